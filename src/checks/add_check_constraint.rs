@@ -0,0 +1,128 @@
+//! Detection for ADD CHECK constraints without NOT VALID.
+//!
+//! This check identifies `ALTER TABLE ... ADD CHECK` statements that validate
+//! existing rows immediately.
+//!
+//! Adding a CHECK constraint scans the entire table to verify every existing row while
+//! holding an ACCESS EXCLUSIVE lock, blocking all reads and writes for the duration.
+//!
+//! Adding the constraint with NOT VALID skips the scan (new rows are still checked),
+//! and a separate VALIDATE CONSTRAINT verifies existing rows without blocking reads
+//! or writes.
+
+use crate::checks::overlap::constraint_target;
+use crate::checks::Check;
+use crate::violation::Violation;
+use sqlparser::ast::{AlterTable, AlterTableOperation, Statement, TableConstraint};
+
+pub struct AddCheckConstraintCheck;
+
+impl Check for AddCheckConstraintCheck {
+    fn check(&self, stmt: &Statement) -> Vec<Violation> {
+        let Statement::AlterTable(AlterTable {
+            name, operations, ..
+        }) = stmt
+        else {
+            return vec![];
+        };
+
+        let table_name = name.to_string();
+
+        operations
+            .iter()
+            .filter_map(|op| {
+                let AlterTableOperation::AddConstraint {
+                    constraint: constraint @ TableConstraint::Check(check),
+                    not_valid: false,
+                } = op
+                else {
+                    return None;
+                };
+
+                let constraint_name = check
+                    .name
+                    .as_ref()
+                    .map(|n| n.to_string())
+                    .unwrap_or_else(|| "<unnamed>".to_string());
+                let suggested_name = check
+                    .name
+                    .as_ref()
+                    .map(|n| n.to_string())
+                    .unwrap_or_else(|| format!("{}_check", table_name));
+                let expr = check.expr.to_string();
+
+                Some(Violation::new(
+                    "ADD CHECK constraint",
+                    format!(
+                        "Adding CHECK constraint '{constraint}' on table '{table}' scans the entire table to validate existing rows, \
+                        acquiring an ACCESS EXCLUSIVE lock and blocking all operations. Duration depends on table size.",
+                        constraint = constraint_name,
+                        table = table_name
+                    ),
+                    format!(
+                        r#"Add the constraint without validating existing rows, then validate separately:
+
+1. Add the CHECK constraint with NOT VALID (new rows are checked immediately):
+   ALTER TABLE {table} ADD CONSTRAINT {constraint_name} CHECK ({expr}) NOT VALID;
+
+2. Validate the constraint separately (uses SHARE UPDATE EXCLUSIVE lock):
+   ALTER TABLE {table} VALIDATE CONSTRAINT {constraint_name};
+
+Note: The VALIDATE step allows concurrent reads and writes, only blocking other schema changes."#,
+                        table = table_name,
+                        constraint_name = suggested_name,
+                        expr = expr
+                    ),
+                ).with_target(constraint_target(name, constraint)))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{assert_allows, assert_detects_violation};
+
+    #[test]
+    fn test_detects_add_check_constraint() {
+        assert_detects_violation!(
+            AddCheckConstraintCheck,
+            "ALTER TABLE users ADD CONSTRAINT users_age_check CHECK (age >= 0);",
+            "ADD CHECK constraint"
+        );
+    }
+
+    #[test]
+    fn test_detects_unnamed_check_constraint() {
+        assert_detects_violation!(
+            AddCheckConstraintCheck,
+            "ALTER TABLE users ADD CHECK (age >= 0);",
+            "ADD CHECK constraint"
+        );
+    }
+
+    #[test]
+    fn test_allows_not_valid_check_constraint() {
+        assert_allows!(
+            AddCheckConstraintCheck,
+            "ALTER TABLE users ADD CONSTRAINT users_age_check CHECK (age >= 0) NOT VALID;"
+        );
+    }
+
+    #[test]
+    fn test_allows_validate_constraint() {
+        assert_allows!(
+            AddCheckConstraintCheck,
+            "ALTER TABLE users VALIDATE CONSTRAINT users_age_check;"
+        );
+    }
+
+    #[test]
+    fn test_ignores_check_in_create_table() {
+        assert_allows!(
+            AddCheckConstraintCheck,
+            "CREATE TABLE users (id BIGINT PRIMARY KEY, age INT CHECK (age >= 0));"
+        );
+    }
+}
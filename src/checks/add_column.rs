@@ -6,15 +6,40 @@
 //! On PostgreSQL versions before 11, adding a column with a DEFAULT value requires
 //! a full table rewrite to backfill the default value for existing rows. This acquires
 //! an ACCESS EXCLUSIVE lock and blocks all operations. Duration depends on table size.
+//!
+//! From PostgreSQL 11 on, a constant default is stored in the catalog and adding the
+//! column is instant, so nothing is reported when `postgres_version` is 11 or later.
+//! Volatile defaults still rewrite the table there; they're reported by
+//! `VolatileDefaultCheck`. When `postgres_version` isn't set, every default is reported.
 
+use crate::checks::overlap::column_target;
 use crate::checks::Check;
 use crate::violation::Violation;
 use sqlparser::ast::{AlterTable, AlterTableOperation, ColumnOption, Statement};
 
-pub struct AddColumnCheck;
+/// First major version in which a constant default doesn't rewrite the table
+const INSTANT_DEFAULT_SINCE: u32 = 11;
+
+#[derive(Default)]
+pub struct AddColumnCheck {
+    postgres_version: Option<u32>,
+}
+
+impl AddColumnCheck {
+    pub fn new(postgres_version: Option<u32>) -> Self {
+        Self { postgres_version }
+    }
+}
 
 impl Check for AddColumnCheck {
     fn check(&self, stmt: &Statement) -> Vec<Violation> {
+        if self
+            .postgres_version
+            .is_some_and(|version| version >= INSTANT_DEFAULT_SINCE)
+        {
+            return vec![];
+        }
+
         let Statement::AlterTable(AlterTable {
             name, operations, ..
         }) = stmt
@@ -42,13 +67,17 @@ impl Check for AddColumnCheck {
                 }
 
                 let column_name = &column_def.name;
+                let versions = match self.postgres_version {
+                    Some(version) => format!("PostgreSQL {}", version),
+                    None => "PostgreSQL < 11".to_string(),
+                };
 
                 Some(Violation::new(
                     "ADD COLUMN with DEFAULT",
                     format!(
-                        "Adding column '{column}' with DEFAULT on table '{table}' requires a full table rewrite on PostgreSQL < 11, \
+                        "Adding column '{column}' with DEFAULT on table '{table}' requires a full table rewrite on {versions}, \
                         which acquires an ACCESS EXCLUSIVE lock and blocks all operations. Duration depends on table size.",
-                        column = column_name, table = table_name
+                        column = column_name, table = table_name, versions = versions
                     ),
                     format!(r#"1. Add the column without a default:
    ALTER TABLE {table} ADD COLUMN {column} {data_type};
@@ -59,12 +88,13 @@ impl Check for AddColumnCheck {
 3. Add default for new rows only:
    ALTER TABLE {table} ALTER COLUMN {column} SET DEFAULT <value>;
 
-Note: For PostgreSQL 11+, this is safe if the default is a constant value."#,
+Note: For PostgreSQL 11+, this is safe if the default is a constant value. Set postgres_version
+in diesel-guard.toml to skip this check on PostgreSQL 11+."#,
                         table = table_name,
                         column = column_name,
                         data_type = column_def.data_type
                     ),
-                ))
+                ).with_target(column_target(name, column_name)))
             })
             .collect()
     }
@@ -73,12 +103,13 @@ Note: For PostgreSQL 11+, this is safe if the default is a constant value."#,
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::checks::test_utils::parse_sql;
     use crate::{assert_allows, assert_detects_violation};
 
     #[test]
     fn test_detects_add_column_with_default() {
         assert_detects_violation!(
-            AddColumnCheck,
+            AddColumnCheck::default(),
             "ALTER TABLE users ADD COLUMN admin BOOLEAN DEFAULT FALSE;",
             "ADD COLUMN with DEFAULT"
         );
@@ -87,15 +118,43 @@ mod tests {
     #[test]
     fn test_allows_add_column_without_default() {
         assert_allows!(
-            AddColumnCheck,
+            AddColumnCheck::default(),
             "ALTER TABLE users ADD COLUMN admin BOOLEAN;"
         );
     }
 
+    #[test]
+    fn test_depends_on_postgres_version() {
+        assert_detects_violation!(
+            AddColumnCheck::new(Some(10)),
+            "ALTER TABLE users ADD COLUMN admin BOOLEAN DEFAULT FALSE;",
+            "ADD COLUMN with DEFAULT"
+        );
+        assert_allows!(
+            AddColumnCheck::new(Some(11)),
+            "ALTER TABLE users ADD COLUMN admin BOOLEAN DEFAULT FALSE;"
+        );
+        assert_allows!(
+            AddColumnCheck::new(Some(16)),
+            "ALTER TABLE users ADD COLUMN token UUID DEFAULT gen_random_uuid();"
+        );
+    }
+
+    #[test]
+    fn test_message_names_postgres_version() {
+        let stmt = parse_sql("ALTER TABLE users ADD COLUMN admin BOOLEAN DEFAULT FALSE;");
+
+        let violations = AddColumnCheck::new(Some(10)).check(&stmt);
+        assert!(violations[0].problem.contains("on PostgreSQL 10,"));
+
+        let violations = AddColumnCheck::default().check(&stmt);
+        assert!(violations[0].problem.contains("on PostgreSQL < 11,"));
+    }
+
     #[test]
     fn test_ignores_other_statements() {
         assert_allows!(
-            AddColumnCheck,
+            AddColumnCheck::default(),
             "CREATE TABLE users (id SERIAL PRIMARY KEY);"
         );
     }
@@ -0,0 +1,136 @@
+//! Detection for ADD EXCLUDE constraint operations.
+//!
+//! This check identifies `ALTER TABLE ... ADD [CONSTRAINT name] EXCLUDE` statements.
+//!
+//! Adding an exclusion constraint builds its index (usually GiST) and checks every
+//! existing row while holding an ACCESS EXCLUSIVE lock, blocking all reads and writes.
+//! Unlike UNIQUE or FOREIGN KEY constraints, there's no safe path: exclusion
+//! constraints support neither NOT VALID nor `USING INDEX` with a prebuilt index.
+//!
+//! **Parser Handling**: sqlparser cannot parse `EXCLUDE` constraints, so the statement
+//! is checked as source text (see [`Check::check_raw`]).
+//!
+//! The recommended approach is a lock timeout and a low-traffic window, or adding the
+//! constraint when the table is created. Since there's no safe rewrite to suggest, the
+//! finding is a warning by default.
+
+use crate::checks::Check;
+use crate::parser::RawStatement;
+use crate::violation::{Severity, Violation};
+use regex::Regex;
+use sqlparser::ast::Statement;
+use std::sync::LazyLock;
+
+/// `ALTER TABLE [IF EXISTS] [ONLY] table ... ADD [CONSTRAINT name] EXCLUDE`
+static ADD_EXCLUDE_PATTERN: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?is)^ALTER\s+TABLE\s+(?:IF\s+EXISTS\s+)?(?:ONLY\s+)?(?P<table>\S+).*?\bADD\s+(?:CONSTRAINT\s+(?P<name>\S+)\s+)?EXCLUDE\b",
+    )
+    .expect("valid regex pattern")
+});
+
+pub struct AddExclusionConstraintCheck;
+
+impl Check for AddExclusionConstraintCheck {
+    fn check(&self, _stmt: &Statement) -> Vec<Violation> {
+        vec![]
+    }
+
+    fn check_raw(&self, stmt: &RawStatement) -> Vec<Violation> {
+        if stmt.keyword != "ALTER" {
+            return vec![];
+        }
+
+        let Some(captures) = ADD_EXCLUDE_PATTERN.captures(&stmt.sql) else {
+            return vec![];
+        };
+
+        let table = &captures["table"];
+        let constraint = captures
+            .name("name")
+            .map(|m| m.as_str())
+            .unwrap_or("<unnamed>");
+
+        vec![Violation::new(
+            "ADD EXCLUDE constraint",
+            format!(
+                "Adding exclusion constraint '{constraint}' on table '{table}' builds its index and checks all existing rows \
+                while holding an ACCESS EXCLUSIVE lock, blocking all reads and writes. Duration depends on table size. \
+                Exclusion constraints support neither NOT VALID nor a prebuilt index, so there is no non-blocking alternative.",
+                constraint = constraint,
+                table = table
+            ),
+            format!(
+                r#"There is no concurrent way to add an exclusion constraint. Limit the impact instead:
+
+1. Fail fast instead of queueing behind long-running queries:
+   SET lock_timeout = '5s';
+   ALTER TABLE {table} ADD CONSTRAINT {constraint} EXCLUDE USING gist (...);
+
+2. Run the migration during a low-traffic window, and retry if the lock times out.
+
+3. For new tables, declare the constraint in CREATE TABLE, before the table has data.
+
+Note: If the table is small or the blocking is acceptable, use a safety-assured block."#,
+                table = table,
+                constraint = constraint
+            ),
+        )]
+    }
+
+    fn default_severity(&self) -> Severity {
+        Severity::Warning
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assert_allows;
+    use crate::checks::test_utils::raw;
+
+    #[test]
+    fn test_detects_named_exclusion_constraint() {
+        let violations = AddExclusionConstraintCheck.check_raw(&raw(
+            "ALTER TABLE bookings ADD CONSTRAINT no_overlap EXCLUDE USING gist (room_id WITH =, during WITH &&)",
+        ));
+
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].operation, "ADD EXCLUDE constraint");
+        assert!(violations[0].problem.contains("'no_overlap'"));
+        assert!(violations[0].problem.contains("'bookings'"));
+    }
+
+    #[test]
+    fn test_detects_unnamed_exclusion_constraint() {
+        let violations = AddExclusionConstraintCheck.check_raw(&raw(
+            "alter table bookings add exclude using gist (during with &&)",
+        ));
+
+        assert_eq!(violations.len(), 1);
+        assert!(violations[0].problem.contains("'<unnamed>'"));
+    }
+
+    #[test]
+    fn test_defaults_to_warning() {
+        assert_eq!(
+            AddExclusionConstraintCheck.default_severity(),
+            Severity::Warning
+        );
+    }
+
+    #[test]
+    fn test_ignores_other_raw_statements() {
+        assert!(AddExclusionConstraintCheck
+            .check_raw(&raw("ALTER TABLE users SET TABLESPACE fast"))
+            .is_empty());
+    }
+
+    #[test]
+    fn test_ignores_parsed_statements() {
+        assert_allows!(
+            AddExclusionConstraintCheck,
+            "ALTER TABLE users ADD CONSTRAINT users_email_key UNIQUE (email);"
+        );
+    }
+}
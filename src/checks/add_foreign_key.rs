@@ -0,0 +1,233 @@
+//! Detection for ADD FOREIGN KEY constraints without NOT VALID.
+//!
+//! This check identifies `ALTER TABLE ... ADD FOREIGN KEY` statements that validate
+//! existing rows immediately.
+//!
+//! Adding a foreign key checks every existing row against the referenced table while
+//! holding a SHARE ROW EXCLUSIVE lock on both tables, blocking writes to both for the
+//! duration of the scan.
+//!
+//! Columns added with an inline REFERENCES (`ADD COLUMN user_id BIGINT REFERENCES users`)
+//! can't be NOT VALID, and are reported as well.
+//!
+//! Adding the constraint with NOT VALID skips the scan (new rows are still checked),
+//! and a separate VALIDATE CONSTRAINT verifies existing rows without blocking writes.
+
+use crate::checks::overlap::constraint_target;
+use crate::checks::Check;
+use crate::violation::Violation;
+use sqlparser::ast::{
+    AlterTable, AlterTableOperation, ColumnDef, ColumnOption, Statement, TableConstraint,
+};
+
+pub struct AddForeignKeyCheck;
+
+impl AddForeignKeyCheck {
+    /// Violation for a column added with an inline REFERENCES option
+    fn inline_reference(table_name: &str, column_def: &ColumnDef) -> Option<Violation> {
+        let (option, fk) = column_def
+            .options
+            .iter()
+            .find_map(|opt| match &opt.option {
+                ColumnOption::ForeignKey(fk) => Some((opt, fk)),
+                _ => None,
+            })?;
+
+        let column = column_def.name.to_string();
+        let constraint_name = option
+            .name
+            .as_ref()
+            .map(|n| n.to_string())
+            .unwrap_or_else(|| format!("{}_{}_fkey", table_name, column));
+        let referred_columns = fk
+            .referred_columns
+            .iter()
+            .map(|c| c.to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+        let foreign_table = fk.foreign_table.to_string();
+
+        Some(Violation::new(
+            "ADD FOREIGN KEY",
+            format!(
+                "Adding column '{column}' with an inline REFERENCES '{foreign_table}' on table '{table}' adds a validated foreign key, \
+                which scans the table while holding a SHARE ROW EXCLUSIVE lock on both tables, blocking writes to both. \
+                Inline references can't be added NOT VALID.",
+                column = column,
+                foreign_table = foreign_table,
+                table = table_name
+            ),
+            format!(
+                r#"Add the column without REFERENCES, then add the foreign key without validating existing rows:
+
+1. Add the column:
+   ALTER TABLE {table} ADD COLUMN {column} {data_type};
+
+2. Add the constraint with NOT VALID (new rows are checked immediately):
+   ALTER TABLE {table} ADD CONSTRAINT {constraint_name} FOREIGN KEY ({column}) REFERENCES {foreign_table}({referred_columns}) NOT VALID;
+
+3. Validate existing rows in a separate migration (uses SHARE UPDATE EXCLUSIVE lock):
+   ALTER TABLE {table} VALIDATE CONSTRAINT {constraint_name};"#,
+                table = table_name,
+                column = column,
+                data_type = column_def.data_type,
+                constraint_name = constraint_name,
+                foreign_table = foreign_table,
+                referred_columns = referred_columns
+            ),
+        ))
+    }
+}
+
+impl Check for AddForeignKeyCheck {
+    fn check(&self, stmt: &Statement) -> Vec<Violation> {
+        let Statement::AlterTable(AlterTable {
+            name, operations, ..
+        }) = stmt
+        else {
+            return vec![];
+        };
+
+        let table_name = name.to_string();
+
+        operations
+            .iter()
+            .filter_map(|op| {
+                let (constraint, fk) = match op {
+                    AlterTableOperation::AddConstraint {
+                        constraint: constraint @ TableConstraint::ForeignKey(fk),
+                        not_valid: false,
+                    } => (constraint, fk),
+                    AlterTableOperation::AddColumn { column_def, .. } => {
+                        return Self::inline_reference(&table_name, column_def);
+                    }
+                    _ => return None,
+                };
+
+                let constraint_name = fk
+                    .name
+                    .as_ref()
+                    .map(|n| n.to_string())
+                    .unwrap_or_else(|| "<unnamed>".to_string());
+                let suggested_name = fk
+                    .name
+                    .as_ref()
+                    .map(|n| n.to_string())
+                    .unwrap_or_else(|| format!("{}_fkey", table_name));
+
+                let columns = fk
+                    .columns
+                    .iter()
+                    .map(|c| c.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                let referred_columns = fk
+                    .referred_columns
+                    .iter()
+                    .map(|c| c.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                let foreign_table = fk.foreign_table.to_string();
+
+                Some(Violation::new(
+                    "ADD FOREIGN KEY",
+                    format!(
+                        "Adding FOREIGN KEY constraint '{constraint}' on table '{table}' ({columns}) referencing '{foreign_table}' \
+                        validates every existing row while holding a SHARE ROW EXCLUSIVE lock on both tables, blocking writes \
+                        to both. Duration depends on table size.",
+                        constraint = constraint_name,
+                        table = table_name,
+                        columns = columns,
+                        foreign_table = foreign_table
+                    ),
+                    format!(
+                        r#"Add the foreign key without validating existing rows, then validate separately:
+
+1. Add the constraint with NOT VALID (new rows are checked immediately):
+   ALTER TABLE {table} ADD CONSTRAINT {constraint_name} FOREIGN KEY ({columns}) REFERENCES {foreign_table}({referred_columns}) NOT VALID;
+
+2. Validate existing rows in a separate migration (uses SHARE UPDATE EXCLUSIVE lock):
+   ALTER TABLE {table} VALIDATE CONSTRAINT {constraint_name};
+
+Note: The VALIDATE step allows concurrent reads and writes, only blocking other schema changes."#,
+                        table = table_name,
+                        constraint_name = suggested_name,
+                        columns = columns,
+                        foreign_table = foreign_table,
+                        referred_columns = referred_columns
+                    ),
+                ).with_target(constraint_target(name, constraint)))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::checks::test_utils::parse_sql;
+    use crate::{assert_allows, assert_detects_violation};
+
+    #[test]
+    fn test_detects_add_foreign_key() {
+        assert_detects_violation!(
+            AddForeignKeyCheck,
+            "ALTER TABLE posts ADD CONSTRAINT posts_user_id_fkey FOREIGN KEY (user_id) REFERENCES users(id);",
+            "ADD FOREIGN KEY"
+        );
+    }
+
+    #[test]
+    fn test_detects_unnamed_foreign_key() {
+        assert_detects_violation!(
+            AddForeignKeyCheck,
+            "ALTER TABLE posts ADD FOREIGN KEY (user_id) REFERENCES users(id);",
+            "ADD FOREIGN KEY"
+        );
+    }
+
+    #[test]
+    fn test_detects_add_column_with_inline_reference() {
+        let violations = AddForeignKeyCheck.check(&parse_sql(
+            "ALTER TABLE posts ADD COLUMN user_id BIGINT REFERENCES users(id);",
+        ));
+
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].operation, "ADD FOREIGN KEY");
+        assert!(violations[0].safe_alternative.contains(
+            "ADD CONSTRAINT posts_user_id_fkey FOREIGN KEY (user_id) REFERENCES users(id) NOT VALID"
+        ));
+    }
+
+    #[test]
+    fn test_allows_add_column_without_reference() {
+        assert_allows!(
+            AddForeignKeyCheck,
+            "ALTER TABLE posts ADD COLUMN user_id BIGINT;"
+        );
+    }
+
+    #[test]
+    fn test_allows_not_valid_foreign_key() {
+        assert_allows!(
+            AddForeignKeyCheck,
+            "ALTER TABLE posts ADD CONSTRAINT posts_user_id_fkey FOREIGN KEY (user_id) REFERENCES users(id) NOT VALID;"
+        );
+    }
+
+    #[test]
+    fn test_allows_validate_constraint() {
+        assert_allows!(
+            AddForeignKeyCheck,
+            "ALTER TABLE posts VALIDATE CONSTRAINT posts_user_id_fkey;"
+        );
+    }
+
+    #[test]
+    fn test_ignores_foreign_key_in_create_table() {
+        assert_allows!(
+            AddForeignKeyCheck,
+            "CREATE TABLE posts (id BIGINT PRIMARY KEY, user_id BIGINT REFERENCES users(id));"
+        );
+    }
+}
@@ -0,0 +1,126 @@
+//! Detection for ADD COLUMN ... GENERATED AS IDENTITY.
+//!
+//! This check identifies `ALTER TABLE` statements that add identity columns
+//! (`GENERATED ALWAYS AS IDENTITY` or `GENERATED BY DEFAULT AS IDENTITY`), the
+//! standard-SQL replacement for SERIAL.
+//!
+//! Like SERIAL, adding an identity column to an existing table requires PostgreSQL to
+//! fill in sequence values for every existing row, rewriting the entire table under an
+//! ACCESS EXCLUSIVE lock. Duration depends on table size and number of indexes.
+//!
+//! The recommended approach is the same staged, sequence-based backfill used for SERIAL.
+
+use crate::checks::Check;
+use crate::violation::Violation;
+use sqlparser::ast::{AlterTable, AlterTableOperation, ColumnOption, Statement};
+
+pub struct AddIdentityColumnCheck;
+
+impl Check for AddIdentityColumnCheck {
+    fn check(&self, stmt: &Statement) -> Vec<Violation> {
+        let Statement::AlterTable(AlterTable {
+            name, operations, ..
+        }) = stmt
+        else {
+            return vec![];
+        };
+
+        let table_name = name.to_string();
+
+        operations
+            .iter()
+            .filter_map(|op| {
+                let AlterTableOperation::AddColumn { column_def, .. } = op else {
+                    return None;
+                };
+
+                // Identity columns are GENERATED without an expression (stored generated columns have one)
+                let is_identity = column_def.options.iter().any(|opt| {
+                    matches!(
+                        opt.option,
+                        ColumnOption::Generated {
+                            generation_expr: None,
+                            ..
+                        }
+                    )
+                });
+
+                if !is_identity {
+                    return None;
+                }
+
+                let column_name = &column_def.name;
+
+                Some(Violation::new(
+                    "ADD COLUMN with IDENTITY",
+                    format!(
+                        "Adding identity column '{column}' on table '{table}' requires a full table rewrite to populate sequence values for existing rows, \
+                        which acquires an ACCESS EXCLUSIVE lock and blocks all operations. Duration depends on table size and number of indexes.",
+                        column = column_name, table = table_name
+                    ),
+                    format!(r#"1. Create a sequence:
+   CREATE SEQUENCE {table}_{column}_seq;
+
+2. Add the column WITHOUT identity or default (fast, no rewrite):
+   ALTER TABLE {table} ADD COLUMN {column} {data_type};
+
+3. Set default for future inserts:
+   ALTER TABLE {table} ALTER COLUMN {column} SET DEFAULT nextval('{table}_{column}_seq');
+
+4. Backfill existing rows in batches (outside migration):
+   UPDATE {table} SET {column} = nextval('{table}_{column}_seq') WHERE {column} IS NULL;
+
+5. Set NOT NULL if needed (PostgreSQL 11+: safe if all values present):
+   ALTER TABLE {table} ALTER COLUMN {column} SET NOT NULL;
+
+6. Set sequence ownership:
+   ALTER SEQUENCE {table}_{column}_seq OWNED BY {table}.{column};"#,
+                        table = table_name,
+                        column = column_name,
+                        data_type = column_def.data_type
+                    ),
+                ))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{assert_allows, assert_detects_violation};
+
+    #[test]
+    fn test_detects_generated_always_as_identity() {
+        assert_detects_violation!(
+            AddIdentityColumnCheck,
+            "ALTER TABLE users ADD COLUMN id BIGINT GENERATED ALWAYS AS IDENTITY;",
+            "ADD COLUMN with IDENTITY"
+        );
+    }
+
+    #[test]
+    fn test_detects_generated_by_default_as_identity() {
+        assert_detects_violation!(
+            AddIdentityColumnCheck,
+            "ALTER TABLE users ADD COLUMN id BIGINT GENERATED BY DEFAULT AS IDENTITY;",
+            "ADD COLUMN with IDENTITY"
+        );
+    }
+
+    #[test]
+    fn test_allows_identity_in_create_table() {
+        assert_allows!(
+            AddIdentityColumnCheck,
+            "CREATE TABLE users (id BIGINT GENERATED ALWAYS AS IDENTITY PRIMARY KEY);"
+        );
+    }
+
+    #[test]
+    fn test_allows_add_column_without_identity() {
+        assert_allows!(
+            AddIdentityColumnCheck,
+            "ALTER TABLE users ADD COLUMN id BIGINT;"
+        );
+    }
+}
@@ -9,8 +9,13 @@
 //!
 //! Using CONCURRENTLY allows the index to be built while permitting concurrent writes,
 //! though it takes longer and cannot be run inside a transaction block.
+//!
+//! Indexes on tables created earlier in the same migration aren't flagged, since an
+//! empty table nothing uses yet can be indexed without blocking anyone. Indexes on
+//! partitioned tables are left to `PartitionedTableIndexCheck`, since CONCURRENTLY
+//! isn't supported there.
 
-use crate::checks::{display_or_default, unique_prefix, Check};
+use crate::checks::{display_or_default, unique_prefix, Check, MigrationContext};
 use crate::violation::Violation;
 use sqlparser::ast::Statement;
 
@@ -53,6 +58,18 @@ Considerations:
 
         violations
     }
+
+    fn check_in_migration(&self, stmt: &Statement, context: &MigrationContext) -> Vec<Violation> {
+        match stmt {
+            Statement::CreateIndex(create_index)
+                if context.is_new_table(&create_index.table_name)
+                    || context.schema().is_partitioned(&create_index.table_name) =>
+            {
+                vec![]
+            }
+            _ => self.check(stmt),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -97,6 +114,20 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_allows_index_on_table_created_in_migration() {
+        let mut context = MigrationContext::default();
+        context.record(&parse_sql(
+            "CREATE TABLE users (id BIGINT PRIMARY KEY, email TEXT);",
+        ));
+
+        let stmt = parse_sql("CREATE INDEX idx_users_email ON users(email);");
+        assert!(AddIndexCheck.check_in_migration(&stmt, &context).is_empty());
+
+        let stmt = parse_sql("CREATE INDEX idx_posts_user_id ON posts(user_id);");
+        assert_eq!(AddIndexCheck.check_in_migration(&stmt, &context).len(), 1);
+    }
+
     #[test]
     fn test_ignores_other_statements() {
         assert_allows!(AddIndexCheck, "CREATE TABLE users (id SERIAL PRIMARY KEY);");
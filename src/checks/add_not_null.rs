@@ -9,15 +9,39 @@
 //!
 //! For large tables, a safer approach is to add a CHECK constraint first, validate it
 //! separately, then add the NOT NULL constraint.
+//!
+//! From PostgreSQL 12 on, `SET NOT NULL` skips the scan when a validated
+//! `CHECK (column IS NOT NULL)` constraint exists, so columns with one, added earlier
+//! in the same migration or in an earlier migration, aren't flagged when
+//! `postgres_version` is 12 or later.
+//!
+//! Tables created earlier in the same migration aren't flagged either: the scan runs
+//! over an empty table. Columns added to an existing table in the same migration still
+//! are, since setting them NOT NULL scans every existing row.
 
-use crate::checks::Check;
+use crate::checks::{Check, MigrationContext};
 use crate::violation::Violation;
-use sqlparser::ast::{AlterColumnOperation, AlterTable, AlterTableOperation, Statement};
+use sqlparser::ast::{AlterColumnOperation, AlterTable, AlterTableOperation, Ident, Statement};
 
-pub struct AddNotNullCheck;
+/// First major version in which SET NOT NULL uses a validated CHECK constraint
+const CHECK_PROVES_NOT_NULL_SINCE: u32 = 12;
 
-impl Check for AddNotNullCheck {
-    fn check(&self, stmt: &Statement) -> Vec<Violation> {
+#[derive(Default)]
+pub struct AddNotNullCheck {
+    postgres_version: Option<u32>,
+}
+
+impl AddNotNullCheck {
+    pub fn new(postgres_version: Option<u32>) -> Self {
+        Self { postgres_version }
+    }
+
+    /// Violations for columns set NOT NULL, except those `is_proven` skips
+    fn not_null_violations(
+        &self,
+        stmt: &Statement,
+        is_proven: impl Fn(&Ident) -> bool,
+    ) -> Vec<Violation> {
         let Statement::AlterTable(AlterTable {
             name, operations, ..
         }) = stmt
@@ -36,6 +60,9 @@ impl Check for AddNotNullCheck {
                 } = op else {
                     return None;
                 };
+                if is_proven(column_name) {
+                    return None;
+                }
 
                 let column_name_str = column_name.to_string();
 
@@ -55,7 +82,7 @@ impl Check for AddNotNullCheck {
 2. Validate the constraint separately (uses SHARE UPDATE EXCLUSIVE lock):
    ALTER TABLE {table} VALIDATE CONSTRAINT {column}_not_null;
 
-3. Add the NOT NULL constraint (instant if CHECK constraint exists):
+3. Add the NOT NULL constraint (instant on PostgreSQL 12+ once the CHECK constraint is validated):
    ALTER TABLE {table} ALTER COLUMN {column} SET NOT NULL;
 
 4. Optionally drop the redundant CHECK constraint:
@@ -71,24 +98,117 @@ Note: The VALIDATE step allows concurrent reads and writes, only blocking other
     }
 }
 
+impl Check for AddNotNullCheck {
+    fn check(&self, stmt: &Statement) -> Vec<Violation> {
+        self.not_null_violations(stmt, |_| false)
+    }
+
+    fn check_in_migration(&self, stmt: &Statement, context: &MigrationContext) -> Vec<Violation> {
+        let Statement::AlterTable(AlterTable { name, .. }) = stmt else {
+            return vec![];
+        };
+        if context.is_new_table(name) {
+            return vec![];
+        }
+        if self
+            .postgres_version
+            .is_none_or(|version| version < CHECK_PROVES_NOT_NULL_SINCE)
+        {
+            return self.check(stmt);
+        }
+
+        self.not_null_violations(stmt, |column| {
+            context.schema().has_validated_not_null_check(name, column)
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::checks::test_utils::check_sequence;
+    use crate::checks::test_utils::parse_sql;
+    use crate::parser::SqlParser;
+    use crate::schema::Schema;
     use crate::{assert_allows, assert_detects_violation};
 
     #[test]
     fn test_detects_add_not_null() {
         assert_detects_violation!(
-            AddNotNullCheck,
+            AddNotNullCheck::default(),
             "ALTER TABLE users ALTER COLUMN email SET NOT NULL;",
             "ADD NOT NULL constraint"
         );
     }
 
+    #[test]
+    fn test_allows_set_not_null_after_validated_check() {
+        let sql = "ALTER TABLE users ADD CONSTRAINT users_email_not_null CHECK (email IS NOT NULL) NOT VALID;
+                   ALTER TABLE users VALIDATE CONSTRAINT users_email_not_null;
+                   ALTER TABLE users ALTER COLUMN email SET NOT NULL;";
+
+        assert!(check_sequence(&AddNotNullCheck::new(Some(12)), sql).is_empty());
+        assert_eq!(
+            check_sequence(&AddNotNullCheck::new(Some(11)), sql).len(),
+            1
+        );
+        // An unknown version is treated as the oldest supported one
+        assert_eq!(check_sequence(&AddNotNullCheck::default(), sql).len(), 1);
+    }
+
+    #[test]
+    fn test_detects_set_not_null_without_validation() {
+        let violations = check_sequence(
+            &AddNotNullCheck::default(),
+            "ALTER TABLE users ADD CONSTRAINT users_email_not_null CHECK (email IS NOT NULL) NOT VALID;
+             ALTER TABLE users ALTER COLUMN email SET NOT NULL, ALTER COLUMN name SET NOT NULL;",
+        );
+
+        assert_eq!(violations.len(), 2);
+    }
+
+    #[test]
+    fn test_allows_set_not_null_after_check_from_earlier_migration() {
+        let parser = SqlParser::new();
+        let mut schema = Schema::default();
+        schema.apply(
+            &parser
+                .parse("ALTER TABLE users ADD CONSTRAINT users_email_not_null CHECK (email IS NOT NULL);")
+                .unwrap(),
+        );
+        let context = MigrationContext::new(&schema);
+        let stmt = parse_sql("ALTER TABLE users ALTER COLUMN email SET NOT NULL;");
+
+        assert!(AddNotNullCheck::new(Some(16))
+            .check_in_migration(&stmt, &context)
+            .is_empty());
+        assert_eq!(AddNotNullCheck::new(Some(16)).check(&stmt).len(), 1);
+    }
+
+    #[test]
+    fn test_allows_set_not_null_on_table_created_in_migration() {
+        let sql = "CREATE TABLE users (id BIGINT PRIMARY KEY, email TEXT);
+                   ALTER TABLE users ALTER COLUMN email SET NOT NULL;";
+
+        assert!(check_sequence(&AddNotNullCheck::default(), sql).is_empty());
+        assert!(check_sequence(&AddNotNullCheck::new(Some(11)), sql).is_empty());
+    }
+
+    #[test]
+    fn test_detects_set_not_null_on_column_added_in_migration() {
+        let violations = check_sequence(
+            &AddNotNullCheck::default(),
+            "ALTER TABLE users ADD COLUMN email TEXT DEFAULT '';
+             ALTER TABLE users ALTER COLUMN email SET NOT NULL;",
+        );
+
+        assert_eq!(violations.len(), 1);
+    }
+
     #[test]
     fn test_ignores_drop_not_null() {
         assert_allows!(
-            AddNotNullCheck,
+            AddNotNullCheck::default(),
             "ALTER TABLE users ALTER COLUMN email DROP NOT NULL;"
         );
     }
@@ -96,7 +216,7 @@ mod tests {
     #[test]
     fn test_ignores_other_alter_column_operations() {
         assert_allows!(
-            AddNotNullCheck,
+            AddNotNullCheck::default(),
             "ALTER TABLE users ALTER COLUMN email SET DEFAULT 'test@example.com';"
         );
     }
@@ -104,7 +224,7 @@ mod tests {
     #[test]
     fn test_ignores_other_operations() {
         assert_allows!(
-            AddNotNullCheck,
+            AddNotNullCheck::default(),
             "ALTER TABLE users ADD COLUMN email VARCHAR(255);"
         );
     }
@@ -112,7 +232,7 @@ mod tests {
     #[test]
     fn test_ignores_other_statements() {
         assert_allows!(
-            AddNotNullCheck,
+            AddNotNullCheck::default(),
             "CREATE TABLE users (id SERIAL PRIMARY KEY);"
         );
     }
@@ -0,0 +1,138 @@
+//! Detection for ADD COLUMN ... NOT NULL without a DEFAULT.
+//!
+//! This check identifies `ALTER TABLE` statements that add a NOT NULL column without
+//! a DEFAULT.
+//!
+//! Existing rows would get NULL in the new column, so PostgreSQL rejects the statement
+//! on any table that has rows. The migration passes against an empty development
+//! database and fails at deploy time. Identity, generated, and SERIAL columns get
+//! values for existing rows and aren't flagged.
+//!
+//! The recommended approach is adding the column as nullable, backfilling it, and
+//! setting NOT NULL afterwards.
+
+use crate::checks::add_serial_column::is_serial_type;
+use crate::checks::Check;
+use crate::violation::Violation;
+use sqlparser::ast::{AlterTable, AlterTableOperation, ColumnOption, Statement};
+
+pub struct AddNotNullColumnCheck;
+
+impl Check for AddNotNullColumnCheck {
+    fn check(&self, stmt: &Statement) -> Vec<Violation> {
+        let Statement::AlterTable(AlterTable {
+            name, operations, ..
+        }) = stmt
+        else {
+            return vec![];
+        };
+
+        let table_name = name.to_string();
+
+        operations
+            .iter()
+            .filter_map(|op| {
+                let AlterTableOperation::AddColumn { column_def, .. } = op else {
+                    return None;
+                };
+
+                let not_null = column_def
+                    .options
+                    .iter()
+                    .any(|opt| matches!(opt.option, ColumnOption::NotNull));
+                // Defaults, identity and generated columns fill in existing rows
+                let filled = column_def.options.iter().any(|opt| {
+                    matches!(
+                        opt.option,
+                        ColumnOption::Default(_) | ColumnOption::Generated { .. }
+                    )
+                }) || is_serial_type(&column_def.data_type.to_string());
+
+                if !not_null || filled {
+                    return None;
+                }
+
+                let column_name = &column_def.name;
+
+                Some(Violation::new(
+                    "ADD COLUMN NOT NULL without DEFAULT",
+                    format!(
+                        "Adding NOT NULL column '{column}' without a DEFAULT on table '{table}' fails if the table has any rows, \
+                        since existing rows would have NULL in the new column. The migration passes on an empty database and \
+                        breaks the deploy.",
+                        column = column_name,
+                        table = table_name
+                    ),
+                    format!(
+                        r#"1. Add the column as nullable:
+   ALTER TABLE {table} ADD COLUMN {column} {data_type};
+
+2. Backfill existing rows in batches (outside migration):
+   UPDATE {table} SET {column} = <value> WHERE {column} IS NULL;
+
+3. Add a NOT NULL check constraint without validating existing rows:
+   ALTER TABLE {table} ADD CONSTRAINT {table}_{column}_not_null CHECK ({column} IS NOT NULL) NOT VALID;
+
+4. Validate it, then set NOT NULL (PostgreSQL 12+ skips the scan thanks to the constraint):
+   ALTER TABLE {table} VALIDATE CONSTRAINT {table}_{column}_not_null;
+   ALTER TABLE {table} ALTER COLUMN {column} SET NOT NULL;
+   ALTER TABLE {table} DROP CONSTRAINT {table}_{column}_not_null;
+
+Note: If every row can take the same value, ADD COLUMN ... NOT NULL DEFAULT <value> works
+in one step on PostgreSQL 11+ without a table rewrite."#,
+                        table = table_name,
+                        column = column_name,
+                        data_type = column_def.data_type
+                    ),
+                ))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{assert_allows, assert_detects_violation};
+
+    #[test]
+    fn test_detects_not_null_without_default() {
+        assert_detects_violation!(
+            AddNotNullColumnCheck,
+            "ALTER TABLE users ADD COLUMN tier TEXT NOT NULL;",
+            "ADD COLUMN NOT NULL without DEFAULT"
+        );
+    }
+
+    #[test]
+    fn test_allows_not_null_with_default() {
+        assert_allows!(
+            AddNotNullColumnCheck,
+            "ALTER TABLE users ADD COLUMN tier TEXT NOT NULL DEFAULT 'free';"
+        );
+    }
+
+    #[test]
+    fn test_allows_columns_filled_for_existing_rows() {
+        assert_allows!(
+            AddNotNullColumnCheck,
+            "ALTER TABLE users ADD COLUMN number BIGINT NOT NULL GENERATED ALWAYS AS IDENTITY;"
+        );
+        assert_allows!(
+            AddNotNullColumnCheck,
+            "ALTER TABLE users ADD COLUMN number BIGSERIAL NOT NULL;"
+        );
+    }
+
+    #[test]
+    fn test_allows_nullable_column_and_create_table() {
+        assert_allows!(
+            AddNotNullColumnCheck,
+            "ALTER TABLE users ADD COLUMN tier TEXT;"
+        );
+        assert_allows!(
+            AddNotNullColumnCheck,
+            "CREATE TABLE users (tier TEXT NOT NULL);"
+        );
+    }
+}
@@ -10,15 +10,87 @@
 //!
 //! The safe alternative is to create a UNIQUE INDEX CONCURRENTLY first, then add the
 //! PRIMARY KEY constraint using that existing index (PostgreSQL 11+).
+//!
+//! Columns added with an inline PRIMARY KEY (`ADD COLUMN id BIGSERIAL PRIMARY KEY`)
+//! build the same index under the same lock, and are reported as well.
+//!
+//! When earlier statements or migrations declare a key column without NOT NULL, the
+//! safe alternative starts by setting NOT NULL through a validated CHECK constraint:
+//! otherwise the final `USING INDEX` step sets it implicitly, scanning the whole table
+//! under the ACCESS EXCLUSIVE lock.
+//!
+//! Primary keys added to tables created earlier in the same migration aren't flagged,
+//! since the table is empty and nothing uses it yet.
 
-use crate::checks::Check;
+use crate::checks::{Check, MigrationContext};
 use crate::violation::Violation;
-use sqlparser::ast::{AlterTable, AlterTableOperation, Statement, TableConstraint};
+use sqlparser::ast::{
+    AlterTable, AlterTableOperation, ColumnDef, ColumnOption, Expr, Ident, ObjectName, Statement,
+    TableConstraint,
+};
 
 pub struct AddPrimaryKeyCheck;
 
-impl Check for AddPrimaryKeyCheck {
-    fn check(&self, stmt: &Statement) -> Vec<Violation> {
+impl AddPrimaryKeyCheck {
+    /// Violation for a column added with an inline PRIMARY KEY option
+    fn inline_primary_key(table_name: &str, column_def: &ColumnDef) -> Option<Violation> {
+        let (option, pk) = column_def
+            .options
+            .iter()
+            .find_map(|opt| match &opt.option {
+                ColumnOption::PrimaryKey(pk) => Some((opt, pk)),
+                _ => None,
+            })?;
+
+        let column = column_def.name.to_string();
+        let constraint_name = option
+            .name
+            .as_ref()
+            .or(pk.name.as_ref())
+            .map(|n| n.to_string())
+            .unwrap_or_else(|| format!("{}_pkey", table_name));
+        let index_name = format!("{}_pkey", table_name);
+
+        Some(Violation::new(
+            "ADD PRIMARY KEY",
+            format!(
+                "Adding column '{column}' with an inline PRIMARY KEY on table '{table}' builds a unique index and validates all rows \
+                while holding an ACCESS EXCLUSIVE lock, blocking all reads and writes. Duration depends on table size.",
+                column = column,
+                table = table_name
+            ),
+            format!(
+                r#"Add the column without PRIMARY KEY, then build the index concurrently:
+
+1. Add the column (no index build):
+   ALTER TABLE {table} ADD COLUMN {column} {data_type};
+
+2. Backfill the column and make it NOT NULL through a validated CHECK constraint.
+
+3. Create the unique index concurrently (metadata.toml with run_in_transaction = false):
+   CREATE UNIQUE INDEX CONCURRENTLY {index_name} ON {table} ({column});
+
+4. Add PRIMARY KEY constraint using the existing index (fast, minimal blocking):
+   ALTER TABLE {table} ADD CONSTRAINT {constraint_name} PRIMARY KEY USING INDEX {index_name};
+
+Considerations:
+- Requires PostgreSQL 11+ for PRIMARY KEY USING INDEX
+- May fail if duplicate or NULL values exist (leaves behind invalid index that should be dropped)"#,
+                table = table_name,
+                column = column,
+                data_type = column_def.data_type,
+                index_name = index_name,
+                constraint_name = constraint_name
+            ),
+        ))
+    }
+
+    /// Violations for each primary key added by `stmt`, given which columns are known
+    /// to accept NULLs
+    fn primary_key_violations(
+        stmt: &Statement,
+        is_nullable: impl Fn(&ObjectName, &Ident) -> bool,
+    ) -> Vec<Violation> {
         let Statement::AlterTable(AlterTable {
             name, operations, ..
         }) = stmt
@@ -31,8 +103,12 @@ impl Check for AddPrimaryKeyCheck {
         operations
             .iter()
             .filter_map(|op| {
-                let AlterTableOperation::AddConstraint { constraint, .. } = op else {
-                    return None;
+                let constraint = match op {
+                    AlterTableOperation::AddConstraint { constraint, .. } => constraint,
+                    AlterTableOperation::AddColumn { column_def, .. } => {
+                        return Self::inline_primary_key(&table_name, column_def);
+                    }
+                    _ => return None,
                 };
 
                 if let TableConstraint::PrimaryKey(pk) = constraint {
@@ -49,24 +125,72 @@ impl Check for AddPrimaryKeyCheck {
                         .collect::<Vec<_>>()
                         .join(", ");
 
+                    let nullable_columns: Vec<&Ident> = pk
+                        .columns
+                        .iter()
+                        .filter_map(|ic| match &ic.column.expr {
+                            Expr::Identifier(ident) if is_nullable(name, ident) => Some(ident),
+                            _ => None,
+                        })
+                        .collect();
+
                     let suggested_index_name = format!("{}_pkey", table_name);
 
+                    let mut problem = format!(
+                        "Adding PRIMARY KEY constraint '{constraint}' on table '{table}' ({columns}) via ALTER TABLE acquires an ACCESS EXCLUSIVE lock, \
+                        blocking all reads and writes. This also implicitly creates a unique index (blocking operation) and validates all rows for uniqueness.",
+                        constraint = constraint_name,
+                        table = table_name,
+                        columns = cols
+                    );
+
+                    let mut not_null_step = String::new();
+                    if !nullable_columns.is_empty() {
+                        let nullable = nullable_columns
+                            .iter()
+                            .map(|ident| ident.to_string())
+                            .collect::<Vec<_>>()
+                            .join(", ");
+                        problem.push_str(&format!(
+                            " Column(s) {nullable} accept NULLs, so adding the primary key USING INDEX still sets NOT NULL implicitly, \
+                            scanning the whole table under the ACCESS EXCLUSIVE lock, and fails if any NULLs exist.",
+                            nullable = nullable
+                        ));
+
+                        let statements = nullable_columns
+                            .iter()
+                            .map(|ident| {
+                                let check_name = format!("{}_{}_not_null", table_name, ident.value);
+                                format!(
+                                    "   ALTER TABLE {table} ADD CONSTRAINT {check_name} CHECK ({column} IS NOT NULL) NOT VALID;\n   \
+                                    ALTER TABLE {table} VALIDATE CONSTRAINT {check_name};\n   \
+                                    ALTER TABLE {table} ALTER COLUMN {column} SET NOT NULL;\n   \
+                                    ALTER TABLE {table} DROP CONSTRAINT {check_name};",
+                                    table = table_name,
+                                    check_name = check_name,
+                                    column = ident
+                                )
+                            })
+                            .collect::<Vec<_>>()
+                            .join("\n\n");
+                        not_null_step = format!(
+                            "1. Make the nullable column(s) NOT NULL through a validated CHECK constraint first \
+                            (SET NOT NULL skips the scan on PostgreSQL 12+):\n{statements}\n\n",
+                            statements = statements
+                        );
+                    }
+                    let first = if not_null_step.is_empty() { 1 } else { 2 };
+
                     Some(Violation::new(
                         "ADD PRIMARY KEY",
-                        format!(
-                            "Adding PRIMARY KEY constraint '{constraint}' on table '{table}' ({columns}) via ALTER TABLE acquires an ACCESS EXCLUSIVE lock, \
-                            blocking all reads and writes. This also implicitly creates a unique index (blocking operation) and validates all rows for uniqueness.",
-                            constraint = constraint_name,
-                            table = table_name,
-                            columns = cols
-                        ),
+                        problem,
                         format!(
                             r#"Use CREATE UNIQUE INDEX CONCURRENTLY first, then add the constraint:
 
-1. Create the unique index concurrently (no blocking):
+{not_null_step}{index_step}. Create the unique index concurrently (no blocking):
    CREATE UNIQUE INDEX CONCURRENTLY {index_name} ON {table} ({columns});
 
-2. Add PRIMARY KEY constraint using the existing index (fast, minimal blocking):
+{constraint_step}. Add PRIMARY KEY constraint using the existing index (fast, minimal blocking):
    ALTER TABLE {table} ADD CONSTRAINT {constraint_name} PRIMARY KEY USING INDEX {index_name};
 
 Benefits:
@@ -82,6 +206,9 @@ Considerations:
 - May fail if duplicate or NULL values exist (leaves behind invalid index that should be dropped)
 
 Note: Ensure all columns in the primary key have NOT NULL constraints before creating the index."#,
+                            not_null_step = not_null_step,
+                            index_step = first,
+                            constraint_step = first + 1,
                             index_name = suggested_index_name,
                             table = table_name,
                             columns = cols,
@@ -96,9 +223,30 @@ Note: Ensure all columns in the primary key have NOT NULL constraints before cre
     }
 }
 
+impl Check for AddPrimaryKeyCheck {
+    fn check(&self, stmt: &Statement) -> Vec<Violation> {
+        Self::primary_key_violations(stmt, |_, _| false)
+    }
+
+    fn check_in_migration(&self, stmt: &Statement, context: &MigrationContext) -> Vec<Violation> {
+        match stmt {
+            Statement::AlterTable(AlterTable { name, .. }) if context.is_new_table(name) => vec![],
+            _ => {
+                let schema = context.schema();
+                Self::primary_key_violations(stmt, |table, column| {
+                    schema.is_nullable(table, column) == Some(true)
+                        && !schema.has_validated_not_null_check(table, column)
+                })
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::checks::test_utils::parse_sql;
+    use crate::schema::Schema;
     use crate::{assert_allows, assert_detects_violation};
 
     #[test]
@@ -128,6 +276,41 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_detects_add_column_with_inline_primary_key() {
+        let violations = AddPrimaryKeyCheck.check(&parse_sql(
+            "ALTER TABLE events ADD COLUMN id BIGSERIAL PRIMARY KEY;",
+        ));
+
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].operation, "ADD PRIMARY KEY");
+        assert!(violations[0].problem.contains("column 'id'"));
+        assert!(violations[0]
+            .safe_alternative
+            .contains("CREATE UNIQUE INDEX CONCURRENTLY events_pkey ON events (id);"));
+        assert!(violations[0].safe_alternative.contains(
+            "ALTER TABLE events ADD CONSTRAINT events_pkey PRIMARY KEY USING INDEX events_pkey;"
+        ));
+
+        let violations = AddPrimaryKeyCheck.check(&parse_sql(
+            "ALTER TABLE events ADD COLUMN id BIGINT CONSTRAINT events_id_pk PRIMARY KEY;",
+        ));
+        assert!(violations[0]
+            .safe_alternative
+            .contains("ADD CONSTRAINT events_id_pk PRIMARY KEY USING INDEX events_pkey"));
+    }
+
+    #[test]
+    fn test_allows_inline_primary_key_on_table_created_in_migration() {
+        let mut context = MigrationContext::default();
+        context.record(&parse_sql("CREATE TABLE events (name TEXT);"));
+
+        let stmt = parse_sql("ALTER TABLE events ADD COLUMN id BIGSERIAL PRIMARY KEY;");
+        assert!(AddPrimaryKeyCheck
+            .check_in_migration(&stmt, &context)
+            .is_empty());
+    }
+
     #[test]
     fn test_allows_create_table_with_primary_key() {
         // Creating a table with PK is fine - only ALTER TABLE is problematic
@@ -137,6 +320,72 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_allows_add_primary_key_on_table_created_in_migration() {
+        let mut context = MigrationContext::default();
+        context.record(&parse_sql(
+            "CREATE TABLE user_roles (user_id BIGINT NOT NULL, role_id BIGINT NOT NULL);",
+        ));
+
+        let stmt = parse_sql("ALTER TABLE user_roles ADD PRIMARY KEY (user_id, role_id);");
+        assert!(AddPrimaryKeyCheck
+            .check_in_migration(&stmt, &context)
+            .is_empty());
+
+        let stmt = parse_sql("ALTER TABLE users ADD PRIMARY KEY (id);");
+        assert_eq!(
+            AddPrimaryKeyCheck.check_in_migration(&stmt, &context).len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_adds_not_null_step_for_nullable_columns() {
+        let mut schema = Schema::default();
+        schema.apply(&[parse_sql("CREATE TABLE users (id BIGINT, email TEXT);")]);
+        let mut context = MigrationContext::new(&schema);
+        context.record(&parse_sql(
+            "ALTER TABLE users ADD CONSTRAINT users_email_check CHECK (email IS NOT NULL) NOT VALID;",
+        ));
+        context.record(&parse_sql(
+            "ALTER TABLE users VALIDATE CONSTRAINT users_email_check;",
+        ));
+
+        let stmt = parse_sql("ALTER TABLE users ADD PRIMARY KEY (id, email);");
+        let violations = AddPrimaryKeyCheck.check_in_migration(&stmt, &context);
+        assert_eq!(violations.len(), 1);
+        assert!(violations[0].problem.contains("Column(s) id accept NULLs"));
+        assert!(violations[0].safe_alternative.contains(
+            "1. Make the nullable column(s) NOT NULL through a validated CHECK constraint first"
+        ));
+        assert!(violations[0].safe_alternative.contains(
+            "ALTER TABLE users ADD CONSTRAINT users_id_not_null CHECK (id IS NOT NULL) NOT VALID;"
+        ));
+        assert!(!violations[0]
+            .safe_alternative
+            .contains("users_email_not_null"));
+        assert!(violations[0]
+            .safe_alternative
+            .contains("3. Add PRIMARY KEY constraint using the existing index"));
+    }
+
+    #[test]
+    fn test_skips_not_null_step_for_not_null_columns() {
+        let mut schema = Schema::default();
+        schema.apply(&[parse_sql(
+            "CREATE TABLE users (id BIGINT NOT NULL, email TEXT);",
+        )]);
+        let context = MigrationContext::new(&schema);
+
+        let stmt = parse_sql("ALTER TABLE users ADD PRIMARY KEY (id);");
+        let violations = AddPrimaryKeyCheck.check_in_migration(&stmt, &context);
+        assert_eq!(violations.len(), 1);
+        assert!(!violations[0].problem.contains("accept NULLs"));
+        assert!(violations[0]
+            .safe_alternative
+            .contains("1. Create the unique index concurrently"));
+    }
+
     #[test]
     fn test_allows_add_unique_constraint() {
         // UNIQUE constraints are handled by AddUniqueConstraintCheck
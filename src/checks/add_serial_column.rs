@@ -81,7 +81,7 @@ impl Check for AddSerialColumnCheck {
 }
 
 /// Helper function to check if a type name is a SERIAL variant
-fn is_serial_type(type_name: &str) -> bool {
+pub(crate) fn is_serial_type(type_name: &str) -> bool {
     matches!(
         type_name.to_uppercase().as_str(),
         "SERIAL" | "SMALLSERIAL" | "BIGSERIAL"
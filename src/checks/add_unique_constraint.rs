@@ -7,14 +7,69 @@
 //! blocking all reads and writes during index creation. This is more restrictive
 //! than CREATE INDEX without CONCURRENTLY (which only blocks writes with a SHARE lock).
 //!
-//! The safe alternative is to use CREATE UNIQUE INDEX CONCURRENTLY instead.
+//! Columns added with an inline UNIQUE (`ADD COLUMN code TEXT UNIQUE`) build the
+//! same index under the same lock, and are reported as well.
+//!
+//! The safe alternative is to use CREATE UNIQUE INDEX CONCURRENTLY instead, optionally
+//! followed by `ADD CONSTRAINT ... UNIQUE USING INDEX`, possibly in a later migration.
+//! sqlparser can't parse that form, so it's kept as source text and isn't flagged:
+//! attaching an existing index only holds the lock briefly.
+//!
+//! Constraints added to tables created earlier in the same migration aren't flagged,
+//! since the table is empty and nothing uses it yet.
 
-use crate::checks::Check;
+use crate::checks::overlap::constraint_target;
+use crate::checks::{Check, MigrationContext};
 use crate::violation::Violation;
-use sqlparser::ast::{AlterTable, AlterTableOperation, Statement, TableConstraint};
+use sqlparser::ast::{
+    AlterTable, AlterTableOperation, ColumnDef, ColumnOption, Statement, TableConstraint,
+};
 
 pub struct AddUniqueConstraintCheck;
 
+impl AddUniqueConstraintCheck {
+    /// Violation for a column added with an inline UNIQUE option
+    fn inline_unique(table_name: &str, column_def: &ColumnDef) -> Option<Violation> {
+        let option = column_def
+            .options
+            .iter()
+            .find(|opt| matches!(opt.option, ColumnOption::Unique(_)))?;
+
+        let column = column_def.name.to_string();
+        let constraint_name = option
+            .name
+            .as_ref()
+            .map(|n| n.to_string())
+            .unwrap_or_else(|| format!("{}_{}_key", table_name, column));
+
+        Some(Violation::new(
+            "ADD UNIQUE constraint",
+            format!(
+                "Adding column '{column}' with an inline UNIQUE constraint on table '{table}' builds a unique index while holding \
+                an ACCESS EXCLUSIVE lock, blocking all reads and writes during index creation. Duration depends on table size.",
+                column = column,
+                table = table_name
+            ),
+            format!(
+                r#"Add the column without UNIQUE, then build the index concurrently:
+
+1. Add the column (fast, no index build):
+   ALTER TABLE {table} ADD COLUMN {column} {data_type};
+
+2. Create the unique index concurrently (metadata.toml with run_in_transaction = false):
+   CREATE UNIQUE INDEX CONCURRENTLY {constraint_name} ON {table} ({column});
+
+3. (Optional) Add constraint using the existing index:
+   ALTER TABLE {table} ADD CONSTRAINT {constraint_name} UNIQUE USING INDEX {constraint_name};"#,
+                table = table_name,
+                column = column,
+                data_type = column_def.data_type,
+                constraint_name = constraint_name
+            ),
+        ))
+    }
+}
+
 impl Check for AddUniqueConstraintCheck {
     fn check(&self, stmt: &Statement) -> Vec<Violation> {
         let Statement::AlterTable(AlterTable {
@@ -29,8 +84,12 @@ impl Check for AddUniqueConstraintCheck {
         operations
             .iter()
             .filter_map(|op| {
-                let AlterTableOperation::AddConstraint { constraint, .. } = op else {
-                    return None;
+                let constraint = match op {
+                    AlterTableOperation::AddConstraint { constraint, .. } => constraint,
+                    AlterTableOperation::AddColumn { column_def, .. } => {
+                        return Self::inline_unique(&table_name, column_def);
+                    }
+                    _ => return None,
                 };
 
                 if let TableConstraint::Unique(unique) = constraint {
@@ -89,18 +148,26 @@ Considerations:
                                 format!("{}_unique_constraint", table_name)
                             }
                         ),
-                    ))
+                    ).with_target(constraint_target(name, constraint)))
                 } else {
                     None
                 }
             })
             .collect()
     }
+
+    fn check_in_migration(&self, stmt: &Statement, context: &MigrationContext) -> Vec<Violation> {
+        match stmt {
+            Statement::AlterTable(AlterTable { name, .. }) if context.is_new_table(name) => vec![],
+            _ => self.check(stmt),
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::checks::test_utils::parse_sql;
     use crate::{assert_allows, assert_detects_violation};
 
     #[test]
@@ -130,6 +197,48 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_detects_add_column_with_inline_unique() {
+        assert_detects_violation!(
+            AddUniqueConstraintCheck,
+            "ALTER TABLE users ADD COLUMN code TEXT UNIQUE;",
+            "ADD UNIQUE constraint"
+        );
+    }
+
+    #[test]
+    fn test_allows_unique_on_table_created_in_migration() {
+        let mut context = MigrationContext::default();
+        context.record(&parse_sql(
+            "CREATE TABLE users (id BIGINT PRIMARY KEY, email TEXT);",
+        ));
+
+        let stmt = parse_sql("ALTER TABLE users ADD CONSTRAINT users_email_key UNIQUE (email);");
+        assert!(AddUniqueConstraintCheck
+            .check_in_migration(&stmt, &context)
+            .is_empty());
+
+        let stmt = parse_sql("ALTER TABLE posts ADD CONSTRAINT posts_slug_key UNIQUE (slug);");
+        assert_eq!(
+            AddUniqueConstraintCheck
+                .check_in_migration(&stmt, &context)
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_inline_unique_uses_constraint_name() {
+        let violations = AddUniqueConstraintCheck.check(&parse_sql(
+            "ALTER TABLE users ADD COLUMN code TEXT CONSTRAINT users_code_uniq UNIQUE;",
+        ));
+
+        assert_eq!(violations.len(), 1);
+        assert!(violations[0]
+            .safe_alternative
+            .contains("CREATE UNIQUE INDEX CONCURRENTLY users_code_uniq ON users (code)"));
+    }
+
     #[test]
     fn test_ignores_create_unique_index() {
         // CREATE UNIQUE INDEX is handled by AddIndexCheck
@@ -9,15 +9,182 @@
 //!
 //! The duration and impact depend heavily on the specific type change and table size.
 //! Type changes with USING clauses always require a full rewrite.
+//!
+//! When the column's current type is known, because it was declared earlier in the same
+//! migration or by an earlier migration in the same directory, metadata-only changes
+//! aren't flagged: widening VARCHAR, VARCHAR to TEXT, and increasing NUMERIC precision
+//! at the same scale. Other binary-coercible conversions, such as XML to TEXT or adding
+//! or removing a domain over the same type, don't rewrite the table either, but may
+//! still validate every row; they're reported as warnings.
+//!
+//! Tables created earlier in the same migration aren't flagged: rewriting them is
+//! instant, since they're still empty.
 
-use crate::checks::Check;
-use crate::violation::Violation;
-use sqlparser::ast::{AlterColumnOperation, AlterTable, AlterTableOperation, Statement};
+use crate::checks::{Check, MigrationContext};
+use crate::config::MIN_POSTGRES_VERSION;
+use crate::schema::{ident_key, Schema};
+use crate::violation::{Severity, Violation};
+use sqlparser::ast::{
+    AlterColumnOperation, AlterTable, AlterTableOperation, CharacterLength, DataType,
+    ExactNumberInfo, ObjectNamePart, Statement, TimezoneInfo,
+};
 
-pub struct AlterColumnTypeCheck;
+/// Operation of type changes that don't rewrite the table
+const BINARY_COERCIBLE: &str = "ALTER COLUMN TYPE (binary-coercible)";
 
-impl Check for AlterColumnTypeCheck {
-    fn check(&self, stmt: &Statement) -> Vec<Violation> {
+/// Conversions PostgreSQL makes without rewriting the table, beyond widenings: (from, to,
+/// first major version that skips the rewrite, caveat)
+const BINARY_COERCIBLE_CASTS: &[(&str, &str, u32, &str)] = &[
+    ("xml", "text", 9, ""),
+    ("cidr", "inet", 9, ""),
+    (
+        "timestamp",
+        "timestamptz",
+        12,
+        " as long as the session time zone is UTC; otherwise the table is rewritten",
+    ),
+];
+
+/// How changing a column's type affects the table
+enum TypeChange {
+    /// Only the catalog is updated
+    MetadataOnly,
+    /// No rewrite, with a caveat to mention
+    BinaryCoercible(&'static str),
+    /// The table may be rewritten
+    Rewrite,
+}
+
+#[derive(Default)]
+pub struct AlterColumnTypeCheck {
+    postgres_version: Option<u32>,
+}
+
+/// Maximum length of a character type, `Some(None)` if unbounded
+fn character_length(data_type: &DataType) -> Option<Option<u64>> {
+    match data_type {
+        DataType::Text => Some(None),
+        DataType::Varchar(length)
+        | DataType::CharacterVarying(length)
+        | DataType::CharVarying(length) => Some(match length {
+            Some(CharacterLength::IntegerLength { length, .. }) => Some(*length),
+            Some(CharacterLength::Max) | None => None,
+        }),
+        _ => None,
+    }
+}
+
+/// Precision and scale of a numeric type, `Some(None)` if unconstrained
+fn numeric_precision(data_type: &DataType) -> Option<Option<(u64, i64)>> {
+    match data_type {
+        DataType::Numeric(info) | DataType::Decimal(info) | DataType::Dec(info) => {
+            Some(match info {
+                ExactNumberInfo::None => None,
+                ExactNumberInfo::Precision(precision) => Some((*precision, 0)),
+                ExactNumberInfo::PrecisionAndScale(precision, scale) => Some((*precision, *scale)),
+            })
+        }
+        _ => None,
+    }
+}
+
+/// Whether changing a column from `old` to `new` only updates the catalog, without
+/// rewriting the table or rebuilding its indexes
+fn is_metadata_only_change(old: &DataType, new: &DataType) -> bool {
+    if old == new {
+        return true;
+    }
+    if let (Some(old), Some(new)) = (character_length(old), character_length(new)) {
+        return match (old, new) {
+            (_, None) => true,
+            (Some(old), Some(new)) => new >= old,
+            (None, Some(_)) => false,
+        };
+    }
+    if let (Some(old), Some(new)) = (numeric_precision(old), numeric_precision(new)) {
+        return match (old, new) {
+            (_, None) => true,
+            (Some((old_precision, old_scale)), Some((new_precision, new_scale))) => {
+                new_precision >= old_precision && new_scale == old_scale
+            }
+            (None, Some(_)) => false,
+        };
+    }
+    false
+}
+
+/// Name of a type for looking up binary-coercible conversions
+fn type_key(data_type: &DataType) -> String {
+    match data_type {
+        DataType::Text
+        | DataType::Varchar(None)
+        | DataType::CharacterVarying(None)
+        | DataType::CharVarying(None) => "text".to_string(),
+        DataType::Timestamp(_, TimezoneInfo::Tz | TimezoneInfo::WithTimeZone) => {
+            "timestamptz".to_string()
+        }
+        DataType::Timestamp(_, _) => "timestamp".to_string(),
+        DataType::Custom(name, _) => name
+            .0
+            .last()
+            .and_then(ObjectNamePart::as_ident)
+            .map(ident_key)
+            .unwrap_or_default(),
+        other => other.to_string().to_lowercase(),
+    }
+}
+
+/// Underlying type of a domain created by the migrations, or the type itself
+fn resolve_domain<'a>(data_type: &'a DataType, schema: &'a Schema) -> &'a DataType {
+    let mut resolved = data_type;
+    // Domains can be based on domains; the bound guards against cycles
+    for _ in 0..8 {
+        match resolved {
+            DataType::Custom(name, modifiers) if modifiers.is_empty() => {
+                match schema.domain_type(name) {
+                    Some(base) => resolved = base,
+                    None => break,
+                }
+            }
+            _ => break,
+        }
+    }
+    resolved
+}
+
+impl AlterColumnTypeCheck {
+    pub fn new(postgres_version: Option<u32>) -> Self {
+        Self { postgres_version }
+    }
+
+    /// How changing a column from `old` to `new` affects the table
+    fn classify(&self, old: &DataType, new: &DataType, schema: &Schema) -> TypeChange {
+        if is_metadata_only_change(old, new) {
+            return TypeChange::MetadataOnly;
+        }
+
+        let (old_base, new_base) = (resolve_domain(old, schema), resolve_domain(new, schema));
+        if (old_base != old || new_base != new) && is_metadata_only_change(old_base, new_base) {
+            return TypeChange::BinaryCoercible(
+                ", though the constraints of the new domain are still checked against every row",
+            );
+        }
+
+        let (old_key, new_key) = (type_key(old_base), type_key(new_base));
+        BINARY_COERCIBLE_CASTS
+            .iter()
+            .find(|(from, to, since, _)| {
+                *from == old_key
+                    && *to == new_key
+                    && self.postgres_version.unwrap_or(MIN_POSTGRES_VERSION) >= *since
+            })
+            .map_or(TypeChange::Rewrite, |(_, _, _, caveat)| {
+                TypeChange::BinaryCoercible(caveat)
+            })
+    }
+
+    /// Violations for type changes, judged against the column types `schema` knows
+    fn type_change_violations(&self, stmt: &Statement, schema: Option<&Schema>) -> Vec<Violation> {
         let Statement::AlterTable(AlterTable {
             name, operations, ..
         }) = stmt
@@ -40,6 +207,39 @@ impl Check for AlterColumnTypeCheck {
                 let column_name_str = column_name.to_string();
                 let new_type = data_type.to_string();
 
+                let current = schema.and_then(|schema| {
+                    schema
+                        .column_type(name, column_name)
+                        .map(|current| (current, self.classify(current, data_type, schema)))
+                });
+                match current {
+                    Some(_) if using.is_some() => {}
+                    Some((_, TypeChange::MetadataOnly)) => return None,
+                    Some((current, TypeChange::BinaryCoercible(caveat))) => {
+                        return Some(Violation::new(
+                            BINARY_COERCIBLE,
+                            format!(
+                                "Changing column '{column}' type from '{old_type}' to '{new_type}' on table '{table}' is binary-coercible, \
+                                so PostgreSQL doesn't rewrite the table{caveat}. It still takes a brief ACCESS EXCLUSIVE lock.",
+                                column = column_name_str,
+                                old_type = current,
+                                new_type = new_type,
+                                table = table_name,
+                                caveat = caveat
+                            ),
+                            format!(
+                                r#"Set a lock timeout so the ALTER doesn't queue behind long-running queries and block others:
+   SET lock_timeout = '2s';
+   ALTER TABLE {table} ALTER COLUMN {column} TYPE {new_type};"#,
+                                table = table_name,
+                                column = column_name_str,
+                                new_type = new_type
+                            ),
+                        ));
+                    }
+                    Some((_, TypeChange::Rewrite)) | None => {}
+                }
+
                 let using_clause = if using.is_some() {
                     "\n\nNote: This migration includes a USING clause, which always triggers a full table rewrite."
                 } else {
@@ -73,6 +273,7 @@ Note: Some type changes are safe:
 - VARCHAR(n) to VARCHAR(m) where m > n (PostgreSQL 9.2+)
 - VARCHAR to TEXT
 - Numeric precision increases
+These aren't flagged when the column's current type is declared by the migrations.
 
 Always test on a production-sized dataset to verify the impact."#,
                         table = table_name,
@@ -85,16 +286,40 @@ Always test on a production-sized dataset to verify the impact."#,
     }
 }
 
+impl Check for AlterColumnTypeCheck {
+    fn check(&self, stmt: &Statement) -> Vec<Violation> {
+        self.type_change_violations(stmt, None)
+    }
+
+    fn check_in_migration(&self, stmt: &Statement, context: &MigrationContext) -> Vec<Violation> {
+        if let Statement::AlterTable(AlterTable { name, .. }) = stmt {
+            if context.is_new_table(name) {
+                return vec![];
+            }
+        }
+        self.type_change_violations(stmt, Some(context.schema()))
+    }
+
+    fn violation_severity(&self, violation: &Violation, configured: Severity) -> Severity {
+        if violation.operation == BINARY_COERCIBLE {
+            Severity::Warning
+        } else {
+            configured
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::checks::test_utils::parse_sql;
+    use crate::parser::SqlParser;
     use crate::{assert_allows, assert_detects_violation};
 
     #[test]
     fn test_detects_alter_column_type() {
         assert_detects_violation!(
-            AlterColumnTypeCheck,
+            AlterColumnTypeCheck::default(),
             "ALTER TABLE users ALTER COLUMN age TYPE BIGINT;",
             "ALTER COLUMN TYPE"
         );
@@ -102,7 +327,7 @@ mod tests {
 
     #[test]
     fn test_detects_alter_column_type_with_using() {
-        let check = AlterColumnTypeCheck;
+        let check = AlterColumnTypeCheck::default();
         let stmt = parse_sql("ALTER TABLE users ALTER COLUMN data TYPE JSONB USING data::JSONB;");
 
         let violations = check.check(&stmt);
@@ -114,16 +339,128 @@ mod tests {
     #[test]
     fn test_detects_set_data_type_variant() {
         assert_detects_violation!(
-            AlterColumnTypeCheck,
+            AlterColumnTypeCheck::default(),
             "ALTER TABLE users ALTER COLUMN email SET DATA TYPE VARCHAR(500);",
             "ALTER COLUMN TYPE"
         );
     }
 
+    /// Check a statement of a migration applied after a migration running `setup`
+    fn check_after(setup: &str, sql: &str) -> Vec<Violation> {
+        let mut schema = Schema::default();
+        schema.apply(&SqlParser::new().parse(setup).unwrap());
+        AlterColumnTypeCheck::default()
+            .check_in_migration(&parse_sql(sql), &MigrationContext::new(&schema))
+    }
+
+    #[test]
+    fn test_allows_metadata_only_changes_of_known_columns() {
+        let setup = "CREATE TABLE users (name VARCHAR(50), bio VARCHAR(100), price NUMERIC(10, 2), code VARCHAR);";
+
+        for sql in [
+            "ALTER TABLE users ALTER COLUMN name TYPE VARCHAR(255);",
+            "ALTER TABLE users ALTER COLUMN name TYPE TEXT;",
+            "ALTER TABLE users ALTER COLUMN bio TYPE VARCHAR;",
+            "ALTER TABLE users ALTER COLUMN price TYPE NUMERIC(12, 2);",
+            "ALTER TABLE users ALTER COLUMN price TYPE NUMERIC;",
+            "ALTER TABLE users ALTER COLUMN code TYPE TEXT;",
+        ] {
+            assert!(check_after(setup, sql).is_empty(), "{}", sql);
+        }
+    }
+
+    #[test]
+    fn test_detects_rewriting_changes_of_known_columns() {
+        let setup =
+            "CREATE TABLE users (name VARCHAR(50), price NUMERIC(10, 2), bio TEXT, age INT);";
+
+        for sql in [
+            "ALTER TABLE users ALTER COLUMN name TYPE VARCHAR(20);",
+            "ALTER TABLE users ALTER COLUMN price TYPE NUMERIC(12, 4);",
+            "ALTER TABLE users ALTER COLUMN bio TYPE VARCHAR(100);",
+            "ALTER TABLE users ALTER COLUMN age TYPE BIGINT;",
+            "ALTER TABLE users ALTER COLUMN name TYPE TEXT USING trim(name);",
+            "ALTER TABLE posts ALTER COLUMN title TYPE TEXT;",
+        ] {
+            assert_eq!(check_after(setup, sql).len(), 1, "{}", sql);
+        }
+    }
+
+    #[test]
+    fn test_reports_binary_coercible_changes_as_warnings() {
+        let setup = "CREATE DOMAIN email AS TEXT;
+                     CREATE TABLE users (doc XML, ip CIDR, address TEXT, created_at TIMESTAMP);";
+        let check = |postgres_version, sql| {
+            let mut schema = Schema::default();
+            schema.apply(&SqlParser::new().parse(setup).unwrap());
+            let context = MigrationContext::new(&schema);
+            let check = AlterColumnTypeCheck::new(postgres_version);
+            check
+                .check_in_migration(&parse_sql(sql), &context)
+                .into_iter()
+                .map(|violation| {
+                    let severity = check.violation_severity(&violation, Severity::Error);
+                    (violation, severity)
+                })
+                .collect::<Vec<_>>()
+        };
+
+        for sql in [
+            "ALTER TABLE users ALTER COLUMN doc TYPE TEXT;",
+            "ALTER TABLE users ALTER COLUMN ip TYPE INET;",
+            "ALTER TABLE users ALTER COLUMN address TYPE email;",
+            "ALTER TABLE users ALTER COLUMN created_at TYPE TIMESTAMPTZ;",
+        ] {
+            let violations = check(Some(16), sql);
+            assert_eq!(violations.len(), 1, "{}", sql);
+            assert_eq!(violations[0].0.operation, BINARY_COERCIBLE, "{}", sql);
+            assert_eq!(violations[0].1, Severity::Warning, "{}", sql);
+        }
+
+        // Before PostgreSQL 12, TIMESTAMP to TIMESTAMPTZ always rewrites the table, and
+        // an unknown version is treated as the oldest supported one
+        for postgres_version in [Some(11), None] {
+            let violations = check(
+                postgres_version,
+                "ALTER TABLE users ALTER COLUMN created_at TYPE TIMESTAMPTZ;",
+            );
+            assert_eq!(violations[0].0.operation, "ALTER COLUMN TYPE");
+            assert_eq!(violations[0].1, Severity::Error);
+        }
+        assert_eq!(
+            check(None, "ALTER TABLE users ALTER COLUMN doc TYPE TEXT;")[0]
+                .0
+                .operation,
+            BINARY_COERCIBLE
+        );
+
+        let violations = check(None, "ALTER TABLE users ALTER COLUMN address TYPE XML;");
+        assert_eq!(violations[0].0.operation, "ALTER COLUMN TYPE");
+    }
+
+    #[test]
+    fn test_allows_type_changes_on_table_created_in_migration() {
+        let mut context = MigrationContext::default();
+        context.record(&parse_sql("CREATE TABLE users (id INT, age INT);"));
+
+        let stmt = parse_sql("ALTER TABLE users ALTER COLUMN age TYPE BIGINT USING age::bigint;");
+        assert!(AlterColumnTypeCheck::default()
+            .check_in_migration(&stmt, &context)
+            .is_empty());
+
+        let stmt = parse_sql("ALTER TABLE posts ALTER COLUMN views TYPE BIGINT;");
+        assert_eq!(
+            AlterColumnTypeCheck::default()
+                .check_in_migration(&stmt, &context)
+                .len(),
+            1
+        );
+    }
+
     #[test]
     fn test_ignores_other_alter_column_operations() {
         assert_allows!(
-            AlterColumnTypeCheck,
+            AlterColumnTypeCheck::default(),
             "ALTER TABLE users ALTER COLUMN email SET NOT NULL;"
         );
     }
@@ -131,7 +468,7 @@ mod tests {
     #[test]
     fn test_ignores_other_operations() {
         assert_allows!(
-            AlterColumnTypeCheck,
+            AlterColumnTypeCheck::default(),
             "ALTER TABLE users ADD COLUMN email VARCHAR(255);"
         );
     }
@@ -139,7 +476,7 @@ mod tests {
     #[test]
     fn test_ignores_other_statements() {
         assert_allows!(
-            AlterColumnTypeCheck,
+            AlterColumnTypeCheck::default(),
             "CREATE TABLE users (id SERIAL PRIMARY KEY);"
         );
     }
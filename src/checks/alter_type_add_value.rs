@@ -0,0 +1,299 @@
+//! Detection for ALTER TYPE ... ADD VALUE.
+//!
+//! This check identifies `ALTER TYPE ... ADD VALUE` statements, which add a label to
+//! an enum type.
+//!
+//! Before PostgreSQL 12, ADD VALUE cannot run inside a transaction block, and Diesel
+//! runs every migration in one unless its `metadata.toml` sets
+//! `run_in_transaction = false`, so the migration fails at deploy time. From
+//! PostgreSQL 12 on it can, but the new value can't be used until the transaction
+//! commits, so the same migration can't insert or default to it.
+//!
+//! Migrations whose `metadata.toml` sets `run_in_transaction = false` aren't flagged.
+//! On PostgreSQL 12+, ADD VALUE is only an error when a later statement of the same
+//! migration uses the new value; otherwise it's reported as a warning.
+//!
+//! The message depends on the `postgres_version` setting; when it isn't set, both
+//! concerns are mentioned.
+//!
+//! The recommended approach is adding the value in its own migration, outside a
+//! transaction.
+
+use crate::checks::Check;
+use crate::parser::{ParsedSql, ParsedStatement};
+use crate::violation::{Severity, Violation};
+use sqlparser::ast::{AlterType, AlterTypeOperation, Statement};
+
+/// First major version that allows ADD VALUE inside a transaction block
+const TRANSACTIONAL_SINCE: u32 = 12;
+
+/// Operation of values added inside a transaction but not used by the migration
+const UNUSED_IN_TRANSACTION: &str = "ALTER TYPE ADD VALUE in transaction";
+
+#[derive(Default)]
+pub struct AlterTypeAddValueCheck {
+    postgres_version: Option<u32>,
+}
+
+impl AlterTypeAddValueCheck {
+    pub fn new(postgres_version: Option<u32>) -> Self {
+        Self { postgres_version }
+    }
+
+    /// Violation for an added value, given whether later statements use it
+    fn violation(&self, type_name: &str, value: &str, used_later: bool) -> Violation {
+        let own_migration = format!(
+            r#"Add the value in its own migration, outside a transaction:
+
+1. Put only the enum change in the migration:
+   ALTER TYPE {type_name} ADD VALUE IF NOT EXISTS '{value}';
+
+2. Create metadata.toml in the migration directory:
+   run_in_transaction = false
+
+3. Use the new value (defaults, backfills) in a later migration."#,
+            type_name = type_name,
+            value = value
+        );
+
+        match self.postgres_version {
+            Some(version) if version < TRANSACTIONAL_SINCE => Violation::new(
+                "ALTER TYPE ADD VALUE",
+                format!(
+                    "Adding value '{value}' to enum '{type_name}' cannot run inside a transaction block on PostgreSQL {version}. \
+                    Diesel runs migrations in a transaction by default, so this migration fails at deploy time.",
+                    value = value,
+                    type_name = type_name,
+                    version = version
+                ),
+                own_migration,
+            ),
+            Some(_) if used_later => Violation::new(
+                "ALTER TYPE ADD VALUE",
+                format!(
+                    "Adding value '{value}' to enum '{type_name}' inside the migration's transaction, and using it later in \
+                    the same migration. The new value can't be used until the transaction commits, so the migration fails \
+                    at deploy time.",
+                    value = value,
+                    type_name = type_name
+                ),
+                format!(
+                    r#"Use the new value in a later migration:
+
+1. Keep only the enum change in this migration:
+   ALTER TYPE {type_name} ADD VALUE IF NOT EXISTS '{value}';
+
+2. Move the statements using '{value}' (defaults, backfills) to a later migration."#,
+                    type_name = type_name,
+                    value = value
+                ),
+            ),
+            Some(_) => Violation::new(
+                UNUSED_IN_TRANSACTION,
+                format!(
+                    "Adding value '{value}' to enum '{type_name}' inside the migration's transaction. This works on \
+                    PostgreSQL 12+, but the new value can't be used until the transaction commits.",
+                    value = value,
+                    type_name = type_name
+                ),
+                format!(
+                    "Use '{value}' only in later migrations, such as for defaults or backfills.\n\n\
+                    Note: To keep the value out of this migration's transaction, add metadata.toml with \
+                    run_in_transaction = false.",
+                    value = value
+                ),
+            ),
+            None => Violation::new(
+                "ALTER TYPE ADD VALUE",
+                format!(
+                    "Adding value '{value}' to enum '{type_name}' inside the migration's transaction. Before PostgreSQL 12, \
+                    ADD VALUE cannot run inside a transaction block and the migration fails at deploy time. From PostgreSQL 12 on, \
+                    it runs, but the new value can't be used until the transaction commits.",
+                    value = value,
+                    type_name = type_name
+                ),
+                format!(
+                    "{own_migration}\n\n\
+                    Note: On PostgreSQL 12+, ADD VALUE can stay in a transaction when the migration doesn't use the new \
+                    value; set postgres_version = 12 or later.",
+                    own_migration = own_migration
+                ),
+            ),
+        }
+    }
+
+    /// Whether a statement mentions `value` as a string literal
+    fn uses_value(stmt: &ParsedStatement, value: &str) -> bool {
+        stmt.stmt
+            .to_string()
+            .contains(&format!("'{}'", value.replace('\'', "''")))
+    }
+}
+
+impl Check for AlterTypeAddValueCheck {
+    fn check(&self, _stmt: &Statement) -> Vec<Violation> {
+        vec![]
+    }
+
+    fn check_parsed(&self, parsed: &ParsedSql) -> Vec<(usize, Violation)> {
+        if parsed.run_in_transaction == Some(false) {
+            return vec![];
+        }
+
+        parsed
+            .statements
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, stmt)| {
+                let Statement::AlterType(AlterType {
+                    name,
+                    operation: AlterTypeOperation::AddValue(add_value),
+                }) = &stmt.stmt
+                else {
+                    return None;
+                };
+
+                let value = &add_value.value.value;
+                let used_later = parsed.statements[idx + 1..]
+                    .iter()
+                    .any(|later| Self::uses_value(later, value));
+
+                Some((
+                    stmt.line,
+                    self.violation(&name.to_string(), value, used_later)
+                        .located(stmt.line, Some(stmt.column)),
+                ))
+            })
+            .collect()
+    }
+
+    fn violation_severity(&self, violation: &Violation, configured: Severity) -> Severity {
+        if violation.operation == UNUSED_IN_TRANSACTION {
+            Severity::Warning
+        } else {
+            configured
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::SqlParser;
+
+    const ADD_VALUE: &str = "ALTER TYPE order_status ADD VALUE 'refunded';";
+
+    fn check_sql(
+        check: &AlterTypeAddValueCheck,
+        sql: &str,
+        run_in_transaction: Option<bool>,
+    ) -> Vec<(usize, Violation)> {
+        let mut parsed = SqlParser::new().parse_with_metadata(sql).unwrap();
+        parsed.run_in_transaction = run_in_transaction;
+        check.check_parsed(&parsed)
+    }
+
+    #[test]
+    fn test_detects_add_value() {
+        let violations = check_sql(&AlterTypeAddValueCheck::default(), ADD_VALUE, Some(true));
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].1.operation, "ALTER TYPE ADD VALUE");
+
+        // Standalone files are assumed to run in a transaction, like Diesel's default
+        assert_eq!(
+            check_sql(&AlterTypeAddValueCheck::default(), ADD_VALUE, None).len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_detects_add_value_with_position() {
+        let violations = check_sql(
+            &AlterTypeAddValueCheck::default(),
+            "ALTER TABLE orders ADD COLUMN note TEXT;\nALTER TYPE order_status ADD VALUE IF NOT EXISTS 'refunded' AFTER 'paid';",
+            None,
+        );
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].0, 2);
+    }
+
+    #[test]
+    fn test_allows_migrations_outside_transaction() {
+        for version in [None, Some(11), Some(16)] {
+            assert!(check_sql(
+                &AlterTypeAddValueCheck::new(version),
+                ADD_VALUE,
+                Some(false)
+            )
+            .is_empty());
+        }
+    }
+
+    #[test]
+    fn test_message_depends_on_postgres_version() {
+        let old = check_sql(
+            &AlterTypeAddValueCheck::new(Some(11)),
+            ADD_VALUE,
+            Some(true),
+        );
+        assert!(old[0]
+            .1
+            .problem
+            .contains("cannot run inside a transaction block on PostgreSQL 11"));
+
+        let unknown = check_sql(&AlterTypeAddValueCheck::default(), ADD_VALUE, Some(true));
+        assert!(unknown[0].1.problem.contains("Before PostgreSQL 12"));
+    }
+
+    #[test]
+    fn test_unused_value_is_a_warning_on_postgres_12() {
+        let check = AlterTypeAddValueCheck::new(Some(16));
+        let violations = check_sql(&check, ADD_VALUE, Some(true));
+
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].1.operation, UNUSED_IN_TRANSACTION);
+        assert!(!violations[0].1.problem.contains("cannot run"));
+        assert_eq!(
+            check.violation_severity(&violations[0].1, Severity::Error),
+            Severity::Warning
+        );
+    }
+
+    #[test]
+    fn test_value_used_later_is_an_error_on_postgres_12() {
+        let check = AlterTypeAddValueCheck::new(Some(16));
+        let violations = check_sql(
+            &check,
+            "ALTER TYPE order_status ADD VALUE 'refunded';
+UPDATE orders SET status = 'refunded' WHERE refunded_at IS NOT NULL;",
+            Some(true),
+        );
+
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].1.operation, "ALTER TYPE ADD VALUE");
+        assert!(violations[0].1.problem.contains("using it later"));
+        assert_eq!(
+            check.violation_severity(&violations[0].1, Severity::Error),
+            Severity::Error
+        );
+
+        // Statements before the ADD VALUE don't count
+        let violations = check_sql(
+            &check,
+            "UPDATE orders SET status = 'refunded' WHERE refunded_at IS NOT NULL;
+ALTER TYPE order_status ADD VALUE 'refunded';",
+            Some(true),
+        );
+        assert_eq!(violations[0].1.operation, UNUSED_IN_TRANSACTION);
+    }
+
+    #[test]
+    fn test_allows_other_alter_type_operations() {
+        assert!(check_sql(
+            &AlterTypeAddValueCheck::default(),
+            "ALTER TYPE order_status RENAME TO order_state;",
+            Some(true),
+        )
+        .is_empty());
+    }
+}
@@ -0,0 +1,202 @@
+//! Detection for foreign keys with ON DELETE CASCADE.
+//!
+//! This check identifies foreign keys declared with `ON DELETE CASCADE`, both in
+//! `CREATE TABLE` (column and table constraints) and in `ALTER TABLE` (ADD CONSTRAINT
+//! and ADD COLUMN ... REFERENCES).
+//!
+//! With a cascade, deleting a single parent row deletes all of its child rows, and
+//! their children in turn, inside one statement. A routine delete can then remove large
+//! amounts of data unexpectedly, holding row locks on every deleted row while it runs.
+//!
+//! Teams that accept cascades can turn this off with
+//! `disable_checks = ["CascadingDeleteCheck"]`.
+//!
+//! The recommended approach is ON DELETE RESTRICT plus explicit cleanup of child rows.
+
+use crate::checks::Check;
+use crate::violation::Violation;
+use sqlparser::ast::{
+    AlterTable, AlterTableOperation, ColumnDef, ColumnOption, ForeignKeyConstraint,
+    ReferentialAction, Statement, TableConstraint,
+};
+
+pub struct CascadingDeleteCheck;
+
+impl CascadingDeleteCheck {
+    /// Foreign keys declared inline on a column, paired with the column name
+    fn column_foreign_keys(
+        column: &ColumnDef,
+    ) -> impl Iterator<Item = (&ForeignKeyConstraint, String)> {
+        column
+            .options
+            .iter()
+            .filter_map(move |opt| match &opt.option {
+                ColumnOption::ForeignKey(fk) => Some((fk, column.name.to_string())),
+                _ => None,
+            })
+    }
+
+    /// Foreign key given as a table constraint, paired with its column list
+    fn constraint_foreign_key(
+        constraint: &TableConstraint,
+    ) -> Option<(&ForeignKeyConstraint, String)> {
+        match constraint {
+            TableConstraint::ForeignKey(fk) => Some((
+                fk,
+                fk.columns
+                    .iter()
+                    .map(|c| c.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            )),
+            _ => None,
+        }
+    }
+
+    fn violation(table: &str, fk: &ForeignKeyConstraint, columns: &str) -> Violation {
+        let constraint = fk
+            .name
+            .as_ref()
+            .map(|name| format!(" '{}'", name))
+            .unwrap_or_default();
+        let parent = fk.foreign_table.to_string();
+
+        Violation::new(
+            "ON DELETE CASCADE",
+            format!(
+                "Foreign key{constraint} on '{table}' ({columns}) references '{parent}' with ON DELETE CASCADE. Deleting a single \
+                row from '{parent}' silently deletes all of its rows in '{table}' (and their dependents) in the same statement, \
+                locking every deleted row.",
+                constraint = constraint,
+                table = table,
+                columns = columns,
+                parent = parent
+            ),
+            format!(
+                r#"Make deletes of child rows explicit:
+
+1. Declare the foreign key with ON DELETE RESTRICT (or leave the default, NO ACTION):
+   FOREIGN KEY ({columns}) REFERENCES {parent} ON DELETE RESTRICT
+
+2. Delete child rows explicitly before the parent, in batches from a cleanup job:
+   DELETE FROM {table} WHERE id IN (
+     SELECT id FROM {table} WHERE <parent_column> = <parent_id> LIMIT 1000
+   );
+
+Note: If cascades are an accepted pattern in your schema, disable this check with
+disable_checks = ["CascadingDeleteCheck"]."#,
+                columns = columns,
+                parent = parent,
+                table = table
+            ),
+        )
+    }
+}
+
+impl Check for CascadingDeleteCheck {
+    fn check(&self, stmt: &Statement) -> Vec<Violation> {
+        let (table, foreign_keys): (String, Vec<(&ForeignKeyConstraint, String)>) = match stmt {
+            Statement::CreateTable(create) => (
+                create.name.to_string(),
+                create
+                    .columns
+                    .iter()
+                    .flat_map(Self::column_foreign_keys)
+                    .chain(
+                        create
+                            .constraints
+                            .iter()
+                            .filter_map(Self::constraint_foreign_key),
+                    )
+                    .collect(),
+            ),
+            Statement::AlterTable(AlterTable {
+                name, operations, ..
+            }) => (
+                name.to_string(),
+                operations
+                    .iter()
+                    .flat_map(|op| match op {
+                        AlterTableOperation::AddConstraint { constraint, .. } => {
+                            Self::constraint_foreign_key(constraint)
+                                .into_iter()
+                                .collect()
+                        }
+                        AlterTableOperation::AddColumn { column_def, .. } => {
+                            Self::column_foreign_keys(column_def).collect()
+                        }
+                        _ => vec![],
+                    })
+                    .collect(),
+            ),
+            _ => return vec![],
+        };
+
+        foreign_keys
+            .into_iter()
+            .filter(|(fk, _)| fk.on_delete == Some(ReferentialAction::Cascade))
+            .map(|(fk, columns)| Self::violation(&table, fk, &columns))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::checks::test_utils::parse_sql;
+    use crate::{assert_allows, assert_detects_violation};
+
+    #[test]
+    fn test_detects_add_constraint_cascade() {
+        assert_detects_violation!(
+            CascadingDeleteCheck,
+            "ALTER TABLE posts ADD CONSTRAINT posts_user_id_fkey FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE NOT VALID;",
+            "ON DELETE CASCADE"
+        );
+    }
+
+    #[test]
+    fn test_detects_inline_reference_in_create_table() {
+        let violations = CascadingDeleteCheck.check(&parse_sql(
+            "CREATE TABLE posts (id BIGINT PRIMARY KEY, user_id BIGINT REFERENCES users (id) ON DELETE CASCADE);",
+        ));
+
+        assert_eq!(violations.len(), 1);
+        assert!(violations[0].problem.contains("'posts' (user_id)"));
+        assert!(violations[0].problem.contains("references 'users'"));
+    }
+
+    #[test]
+    fn test_detects_table_constraint_in_create_table() {
+        assert_detects_violation!(
+            CascadingDeleteCheck,
+            "CREATE TABLE posts (user_id BIGINT, CONSTRAINT posts_user_fk FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE);",
+            "ON DELETE CASCADE"
+        );
+    }
+
+    #[test]
+    fn test_detects_add_column_reference() {
+        assert_detects_violation!(
+            CascadingDeleteCheck,
+            "ALTER TABLE posts ADD COLUMN user_id BIGINT REFERENCES users (id) ON DELETE CASCADE;",
+            "ON DELETE CASCADE"
+        );
+    }
+
+    #[test]
+    fn test_allows_other_actions() {
+        assert_allows!(
+            CascadingDeleteCheck,
+            "CREATE TABLE posts (user_id BIGINT REFERENCES users (id) ON DELETE RESTRICT);"
+        );
+        assert_allows!(
+            CascadingDeleteCheck,
+            "CREATE TABLE posts (user_id BIGINT REFERENCES users (id) ON DELETE SET NULL ON UPDATE CASCADE);"
+        );
+        assert_allows!(
+            CascadingDeleteCheck,
+            "CREATE TABLE posts (user_id BIGINT REFERENCES users (id));"
+        );
+    }
+}
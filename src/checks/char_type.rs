@@ -0,0 +1,96 @@
+//! Detection for CHAR(n) columns.
+//!
+//! This check identifies columns of type `CHAR(n)` / `CHARACTER(n)` in `CREATE TABLE`
+//! and `ALTER TABLE ... ADD COLUMN`. It's opt-in: enable it with
+//! `enable_checks = ["CharTypeCheck"]`.
+//!
+//! CHAR(n) pads values with spaces to the declared length and ignores trailing spaces
+//! in comparisons, which surprises application code, and it's no faster than the
+//! variable-length types. The PostgreSQL documentation recommends text or varchar.
+//!
+//! The recommended approach is `TEXT` (with a CHECK constraint on length if needed)
+//! or `VARCHAR(n)`.
+
+use crate::checks::{new_columns, Check};
+use crate::violation::Violation;
+use sqlparser::ast::{DataType, Statement};
+
+pub struct CharTypeCheck;
+
+impl Check for CharTypeCheck {
+    fn check(&self, stmt: &Statement) -> Vec<Violation> {
+        new_columns(stmt)
+            .into_iter()
+            .filter_map(|(table_name, column_def)| {
+                let (DataType::Char(length) | DataType::Character(length)) = &column_def.data_type
+                else {
+                    return None;
+                };
+
+                let column_name = &column_def.name;
+                let varchar = match length {
+                    Some(length) => format!("VARCHAR({})", length),
+                    None => "VARCHAR(1)".to_string(),
+                };
+
+                Some(Violation::new(
+                    "CHAR(n) column",
+                    format!(
+                        "Column '{column}' on table '{table}' uses {data_type}, which pads values with spaces to the declared \
+                        length and ignores trailing spaces in comparisons. It's no faster than TEXT or VARCHAR.",
+                        column = column_name,
+                        table = table_name,
+                        data_type = column_def.data_type
+                    ),
+                    format!(
+                        r#"Use TEXT or VARCHAR instead:
+
+   {column} TEXT
+
+Or, to keep a length limit:
+
+   {column} {varchar}
+
+Note: CHAR(n) only makes sense for values that always have exactly n characters."#,
+                        column = column_name,
+                        varchar = varchar
+                    ),
+                ))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::checks::test_utils::parse_sql;
+    use crate::{assert_allows, assert_detects_violation};
+
+    #[test]
+    fn test_detects_char_in_create_table() {
+        assert_detects_violation!(
+            CharTypeCheck,
+            "CREATE TABLE countries (code CHAR(2) PRIMARY KEY);",
+            "CHAR(n) column"
+        );
+    }
+
+    #[test]
+    fn test_detects_character_in_add_column() {
+        let violations = CharTypeCheck.check(&parse_sql(
+            "ALTER TABLE users ADD COLUMN locale CHARACTER(5);",
+        ));
+
+        assert_eq!(violations.len(), 1);
+        assert!(violations[0].safe_alternative.contains("locale VARCHAR(5)"));
+    }
+
+    #[test]
+    fn test_allows_varchar_and_text() {
+        assert_allows!(
+            CharTypeCheck,
+            "CREATE TABLE users (name VARCHAR(100), bio TEXT, code CHARACTER VARYING(10));"
+        );
+    }
+}
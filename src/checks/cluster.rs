@@ -0,0 +1,122 @@
+//! Detection for CLUSTER statements.
+//!
+//! This check identifies `CLUSTER` statements, which physically reorder a table
+//! according to an index.
+//!
+//! CLUSTER rewrites the entire table and rebuilds all of its indexes while holding an
+//! ACCESS EXCLUSIVE lock, blocking all reads and writes until it finishes. The order
+//! isn't maintained for new rows, so it's maintenance rather than a schema change.
+//!
+//! **Parser Handling**: sqlparser cannot parse `CLUSTER`, so the statement is checked
+//! as source text (see [`Check::check_raw`]).
+//!
+//! The recommended approach is pg_repack, or a scheduled maintenance window.
+
+use crate::checks::Check;
+use crate::parser::RawStatement;
+use crate::violation::Violation;
+use regex::Regex;
+use sqlparser::ast::Statement;
+use std::sync::LazyLock;
+
+/// Table and optional index of `CLUSTER [VERBOSE] [table [USING index]]`
+static CLUSTER_PATTERN: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?is)^CLUSTER(?:\s+VERBOSE)?(?:\s+(\S+)(?:\s+USING\s+(\S+))?)?\s*$")
+        .expect("valid regex pattern")
+});
+
+pub struct ClusterCheck;
+
+impl Check for ClusterCheck {
+    fn check(&self, _stmt: &Statement) -> Vec<Violation> {
+        vec![]
+    }
+
+    fn check_raw(&self, stmt: &RawStatement) -> Vec<Violation> {
+        if stmt.keyword != "CLUSTER" {
+            return vec![];
+        }
+
+        let captures = CLUSTER_PATTERN.captures(&stmt.sql);
+        let table = captures
+            .as_ref()
+            .and_then(|c| c.get(1))
+            .map(|m| m.as_str().to_string());
+        let index = captures
+            .as_ref()
+            .and_then(|c| c.get(2))
+            .map(|m| m.as_str().to_string());
+
+        let target = match &table {
+            Some(table) => format!("table '{}'", table),
+            None => "every previously clustered table".to_string(),
+        };
+        let table = table.unwrap_or_else(|| "<table>".to_string());
+        let order_by = index
+            .map(|index| format!("--order-by <columns of {}>", index))
+            .unwrap_or_else(|| "--order-by <columns>".to_string());
+
+        vec![Violation::new(
+            "CLUSTER",
+            format!(
+                "CLUSTER on {target} rewrites the table and rebuilds all of its indexes under an ACCESS EXCLUSIVE lock, \
+                blocking all reads and writes until it finishes. Duration depends on table size.",
+                target = target
+            ),
+            format!(
+                r#"Don't run CLUSTER in migrations:
+
+1. Reorder the table without blocking using pg_repack, outside of migrations:
+   pg_repack --table {table} {order_by} <database>
+
+2. Or run CLUSTER by hand during a scheduled maintenance window.
+
+Note: CLUSTER is a one-time reordering; rows written afterwards aren't kept in order."#,
+                table = table,
+                order_by = order_by
+            ),
+        )]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assert_allows;
+    use crate::checks::test_utils::raw;
+
+    #[test]
+    fn test_detects_cluster_using_index() {
+        let violations = ClusterCheck.check_raw(&raw("CLUSTER users USING users_pkey"));
+
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].operation, "CLUSTER");
+        assert!(violations[0].problem.contains("'users'"));
+        assert!(violations[0].safe_alternative.contains("users_pkey"));
+    }
+
+    #[test]
+    fn test_detects_cluster_without_table() {
+        let violations = ClusterCheck.check_raw(&raw("CLUSTER VERBOSE"));
+
+        assert_eq!(violations.len(), 1);
+        assert!(violations[0]
+            .problem
+            .contains("every previously clustered table"));
+    }
+
+    #[test]
+    fn test_ignores_other_raw_statements() {
+        let stmt = RawStatement {
+            keyword: "REINDEX".to_string(),
+            sql: "REINDEX TABLE users".to_string(),
+            line: 1,
+        };
+        assert!(ClusterCheck.check_raw(&stmt).is_empty());
+    }
+
+    #[test]
+    fn test_ignores_parsed_statements() {
+        assert_allows!(ClusterCheck, "ALTER TABLE users ADD COLUMN bio TEXT;");
+    }
+}
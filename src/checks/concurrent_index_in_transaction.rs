@@ -0,0 +1,140 @@
+//! Detection for CONCURRENTLY statements in migrations that run in a transaction.
+//!
+//! This check identifies `CREATE INDEX CONCURRENTLY`, `DROP INDEX CONCURRENTLY`, and
+//! `REINDEX ... CONCURRENTLY` in migrations that Diesel runs inside a transaction, i.e. whose directory has no
+//! `metadata.toml` with `run_in_transaction = false`.
+//!
+//! PostgreSQL refuses to run these statements inside a transaction block, so the
+//! migration fails at deploy time, after passing every other check.
+//!
+//! The statements are found in the source text, since some of them can't be parsed.
+//! Only files in a migration directory (`up.sql` / `down.sql`) are checked.
+//!
+//! The recommended approach is adding the metadata.toml, e.g. with `diesel-guard fix`.
+
+use crate::checks::Check;
+use crate::fix::strip_line_comments;
+use crate::parser::ParsedSql;
+use crate::violation::Violation;
+use regex::Regex;
+use sqlparser::ast::Statement;
+use std::sync::LazyLock;
+
+/// Statements that can't run inside a transaction block because of CONCURRENTLY
+static CONCURRENTLY_PATTERN: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?isx)
+        \b(?P<statement>
+            CREATE\s+(?:UNIQUE\s+)?INDEX\s+CONCURRENTLY
+            | DROP\s+INDEX\s+CONCURRENTLY
+            | REINDEX\b[^;]*?\bCONCURRENTLY
+        )\b
+        ",
+    )
+    .expect("valid regex pattern")
+});
+
+pub struct ConcurrentIndexInTransactionCheck;
+
+impl Check for ConcurrentIndexInTransactionCheck {
+    fn check(&self, _stmt: &Statement) -> Vec<Violation> {
+        vec![]
+    }
+
+    fn check_parsed(&self, parsed: &ParsedSql) -> Vec<(usize, Violation)> {
+        if parsed.run_in_transaction != Some(true) {
+            return vec![];
+        }
+
+        let sql = strip_line_comments(&parsed.sql);
+
+        CONCURRENTLY_PATTERN
+            .captures_iter(&sql)
+            .map(|captures| {
+                let statement = &captures["statement"];
+                let start = captures.get(0).map_or(0, |m| m.start());
+                let line = sql[..start].matches('\n').count() + 1;
+                let statement = statement.split_whitespace().collect::<Vec<_>>().join(" ");
+
+                (
+                    line,
+                    Violation::new(
+                        "CONCURRENTLY in transaction",
+                        format!(
+                            "'{statement}' can't run inside a transaction block, but this migration runs in one: its \
+                            directory has no metadata.toml with run_in_transaction = false. The migration fails when \
+                            it's deployed.",
+                            statement = statement
+                        ),
+                        r#"Run the migration outside a transaction by adding metadata.toml next to up.sql:
+
+   run_in_transaction = false
+
+Or let diesel-guard write it:
+
+   diesel-guard fix migrations/
+
+Note: Outside a transaction, a failed migration isn't rolled back. Keep statements that
+can't run in a transaction in their own migration."#,
+                    ),
+                )
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::SqlParser;
+
+    fn check_sql(sql: &str, run_in_transaction: Option<bool>) -> Vec<(usize, Violation)> {
+        let mut parsed = SqlParser::new().parse_with_metadata(sql).unwrap();
+        parsed.run_in_transaction = run_in_transaction;
+        ConcurrentIndexInTransactionCheck.check_parsed(&parsed)
+    }
+
+    #[test]
+    fn test_detects_concurrently_in_transaction() {
+        let violations = check_sql(
+            "ALTER TABLE users ADD COLUMN email TEXT;\nCREATE INDEX CONCURRENTLY users_email_idx ON users (email);",
+            Some(true),
+        );
+
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].0, 2);
+        assert_eq!(violations[0].1.operation, "CONCURRENTLY in transaction");
+        assert!(violations[0]
+            .1
+            .problem
+            .contains("'CREATE INDEX CONCURRENTLY'"));
+    }
+
+    #[test]
+    fn test_detects_other_concurrently_statements() {
+        let violations = check_sql(
+            "CREATE UNIQUE INDEX CONCURRENTLY a_idx ON users (a);
+             REINDEX INDEX CONCURRENTLY b_idx;",
+            Some(true),
+        );
+
+        assert_eq!(violations.len(), 2);
+    }
+
+    #[test]
+    fn test_allows_migrations_outside_transaction_or_unknown() {
+        let sql = "CREATE INDEX CONCURRENTLY users_email_idx ON users (email);";
+
+        assert!(check_sql(sql, Some(false)).is_empty());
+        assert!(check_sql(sql, None).is_empty());
+    }
+
+    #[test]
+    fn test_ignores_comments_and_plain_statements() {
+        assert!(check_sql(
+            "-- TODO: CREATE INDEX CONCURRENTLY later\nCREATE INDEX users_email_idx ON users (email);",
+            Some(true),
+        )
+        .is_empty());
+    }
+}
@@ -0,0 +1,270 @@
+//! What the earlier statements of a migration did.
+//!
+//! Some findings don't apply to objects created in the same migration, e.g. indexing
+//! a table created a few lines above can't block anyone, since nothing uses it yet.
+//! The registry records each statement in a [`MigrationContext`] after checking it, and
+//! hands the context to [`Check::check_in_migration`](crate::checks::Check::check_in_migration).
+//!
+//! The context starts from the [`Schema`] left by earlier migrations, and its schema is
+//! updated with every recorded statement. When a database URL is given, it also holds
+//! the primary keys of the live database.
+
+use crate::database::DatabasePrimaryKeys;
+use crate::schema::{ident_key, index_key, object_key, Schema};
+use sqlparser::ast::{
+    AlterIndexOperation, AlterTable, AlterTableOperation, Ident, ObjectName, ObjectType,
+    RenameTableNameKind, Statement,
+};
+use std::collections::HashSet;
+use std::sync::Arc;
+
+/// Objects created by the statements checked so far in a migration
+#[derive(Debug, Clone, Default)]
+pub struct MigrationContext {
+    created_tables: HashSet<String>,
+    /// Columns added to existing tables, keyed by (table, column)
+    added_columns: HashSet<(String, String)>,
+    created_indexes: HashSet<String>,
+    schema: Schema,
+    database_primary_keys: Option<Arc<DatabasePrimaryKeys>>,
+}
+
+impl MigrationContext {
+    /// Context for a migration applied on top of the given schema
+    pub fn new(schema: &Schema) -> Self {
+        Self {
+            created_tables: HashSet::new(),
+            added_columns: HashSet::new(),
+            created_indexes: HashSet::new(),
+            schema: schema.clone(),
+            database_primary_keys: None,
+        }
+    }
+
+    /// Attach the primary keys of the live database
+    pub fn with_database_primary_keys(
+        mut self,
+        database_primary_keys: Option<Arc<DatabasePrimaryKeys>>,
+    ) -> Self {
+        self.database_primary_keys = database_primary_keys;
+        self
+    }
+
+    /// Record the objects a statement creates or drops
+    pub fn record(&mut self, stmt: &Statement) {
+        self.schema.apply(std::slice::from_ref(stmt));
+        match stmt {
+            Statement::CreateTable(create) => {
+                self.created_tables.insert(object_key(&create.name));
+            }
+            Statement::AlterTable(AlterTable {
+                name, operations, ..
+            }) => {
+                let mut table = object_key(name);
+                for op in operations {
+                    match op {
+                        AlterTableOperation::AddColumn { column_def, .. } => {
+                            self.added_columns
+                                .insert((table.clone(), ident_key(&column_def.name)));
+                        }
+                        AlterTableOperation::RenameColumn {
+                            old_column_name,
+                            new_column_name,
+                        } => {
+                            if self
+                                .added_columns
+                                .remove(&(table.clone(), ident_key(old_column_name)))
+                            {
+                                self.added_columns
+                                    .insert((table.clone(), ident_key(new_column_name)));
+                            }
+                        }
+                        AlterTableOperation::RenameTable { table_name } => {
+                            let (RenameTableNameKind::As(new_name)
+                            | RenameTableNameKind::To(new_name)) = table_name;
+                            let new_table = object_key(new_name);
+                            if self.created_tables.remove(&table) {
+                                self.created_tables.insert(new_table.clone());
+                            }
+                            let columns: Vec<_> = self
+                                .added_columns
+                                .extract_if(|(t, _)| *t == table)
+                                .collect();
+                            for (_, column) in columns {
+                                self.added_columns.insert((new_table.clone(), column));
+                            }
+                            table = new_table;
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            Statement::Drop {
+                object_type: ObjectType::Table,
+                names,
+                ..
+            } => {
+                for name in names {
+                    let table = object_key(name);
+                    self.created_tables.remove(&table);
+                    self.added_columns.retain(|(t, _)| *t != table);
+                }
+            }
+            Statement::CreateIndex(index) => {
+                if let Some(name) = &index.name {
+                    self.created_indexes.insert(index_key(name));
+                }
+            }
+            Statement::AlterIndex {
+                name,
+                operation: AlterIndexOperation::RenameIndex { index_name },
+            } => {
+                if self.created_indexes.remove(&index_key(name)) {
+                    self.created_indexes.insert(index_key(index_name));
+                }
+            }
+            Statement::Drop {
+                object_type: ObjectType::Index,
+                names,
+                ..
+            } => {
+                for name in names {
+                    self.created_indexes.remove(&index_key(name));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Whether the table was created earlier in the migration
+    pub fn is_new_table(&self, table: &ObjectName) -> bool {
+        self.created_tables.contains(&object_key(table))
+    }
+
+    /// Whether the column was created earlier in the migration, with its table or on its own
+    pub fn is_new_column(&self, table: &ObjectName, column: &Ident) -> bool {
+        self.is_new_table(table)
+            || self
+                .added_columns
+                .contains(&(object_key(table), ident_key(column)))
+    }
+
+    /// Whether the index was created earlier in the migration
+    pub fn is_new_index(&self, index: &ObjectName) -> bool {
+        self.created_indexes.contains(&index_key(index))
+    }
+
+    /// Schema left by earlier migrations and the statements recorded so far
+    pub fn schema(&self) -> &Schema {
+        &self.schema
+    }
+
+    /// Primary keys of the live database, when a database URL was given
+    pub fn database_primary_keys(&self) -> Option<&DatabasePrimaryKeys> {
+        self.database_primary_keys.as_deref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::SqlParser;
+
+    #[test]
+    fn test_tracks_tables_created_in_migration() {
+        let statements = SqlParser::new()
+            .parse(
+                "CREATE TABLE Users (id BIGINT);
+                 CREATE TABLE posts (id BIGINT);
+                 DROP TABLE posts;",
+            )
+            .unwrap();
+        let users = ObjectName::from(vec![Ident::new("users")]);
+        let posts = ObjectName::from(vec![Ident::new("posts")]);
+
+        let mut context = MigrationContext::default();
+        assert!(!context.is_new_table(&users));
+
+        context.record(&statements[0]);
+        context.record(&statements[1]);
+        assert!(context.is_new_table(&users));
+        assert!(context.is_new_table(&posts));
+
+        context.record(&statements[2]);
+        assert!(!context.is_new_table(&posts));
+    }
+
+    #[test]
+    fn test_tracks_columns_and_renames() {
+        let statements = SqlParser::new()
+            .parse(
+                "CREATE TABLE drafts (id BIGINT);
+                 ALTER TABLE drafts RENAME TO posts;
+                 ALTER TABLE users ADD COLUMN nickname TEXT;
+                 ALTER TABLE users RENAME COLUMN nickname TO handle;",
+            )
+            .unwrap();
+        let table = |name: &str| ObjectName::from(vec![Ident::new(name)]);
+
+        let mut context = MigrationContext::default();
+        for stmt in &statements {
+            context.record(stmt);
+        }
+
+        assert!(!context.is_new_table(&table("drafts")));
+        assert!(context.is_new_table(&table("posts")));
+        assert!(context.is_new_column(&table("posts"), &Ident::new("id")));
+        assert!(context.is_new_column(&table("users"), &Ident::new("handle")));
+        assert!(!context.is_new_column(&table("users"), &Ident::new("nickname")));
+        assert!(!context.is_new_column(&table("users"), &Ident::new("email")));
+    }
+
+    #[test]
+    fn test_tracks_indexes_created_in_migration() {
+        let statements = SqlParser::new()
+            .parse(
+                "CREATE INDEX CONCURRENTLY users_email_new_idx ON users (email);
+                 ALTER INDEX users_email_new_idx RENAME TO users_email_idx;
+                 CREATE INDEX users_name_idx ON users (name);
+                 DROP INDEX users_name_idx;",
+            )
+            .unwrap();
+        let index = |name: &str| ObjectName::from(vec![Ident::new(name)]);
+
+        let mut context = MigrationContext::default();
+        for stmt in &statements {
+            context.record(stmt);
+        }
+
+        assert!(context.is_new_index(&index("users_email_idx")));
+        assert!(!context.is_new_index(&index("users_email_new_idx")));
+        assert!(!context.is_new_index(&index("users_name_idx")));
+    }
+
+    #[test]
+    fn test_quoted_names_keep_their_case() {
+        let statements = SqlParser::new()
+            .parse(
+                r#"CREATE TABLE "Users" (id BIGINT);
+                   ALTER TABLE "Accounts" ADD COLUMN "Email" TEXT;
+                   CREATE INDEX "Users_Email_idx" ON "Users" ("Email");"#,
+            )
+            .unwrap();
+        let quoted = |name: &str| Ident::with_quote('"', name);
+
+        let mut context = MigrationContext::default();
+        for stmt in &statements {
+            context.record(stmt);
+        }
+
+        let users = ObjectName::from(vec![quoted("Users")]);
+        let folded = ObjectName::from(vec![Ident::new("Users")]);
+        assert!(context.is_new_table(&users));
+        assert!(!context.is_new_table(&folded));
+        let accounts = ObjectName::from(vec![quoted("Accounts")]);
+        assert!(context.is_new_column(&accounts, &quoted("Email")));
+        assert!(!context.is_new_column(&accounts, &Ident::new("Email")));
+        assert!(context.is_new_index(&ObjectName::from(vec![quoted("Users_Email_idx")])));
+        assert!(!context.is_new_index(&ObjectName::from(vec![Ident::new("users_email_idx")])));
+    }
+}
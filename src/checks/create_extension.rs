@@ -9,12 +9,103 @@
 //!
 //! Extensions should be installed manually or through infrastructure automation
 //! (Ansible, Terraform, etc.) with appropriate privileges before running migrations.
+//! Extensions the migration role is allowed to create can be listed in
+//! `[checks.CreateExtensionCheck] allowed_extensions`.
+//!
+//! From PostgreSQL 13 on, trusted extensions such as pg_trgm or uuid-ossp can be created by
+//! any role with CREATE privilege on the database. When `postgres_version` is 13 or later,
+//! creating one is reported as a warning, with advice about that privilege instead.
 
 use crate::checks::Check;
-use crate::violation::Violation;
-use sqlparser::ast::{CreateExtension, Statement};
+use crate::config::CreateExtensionConfig;
+use crate::schema::{ident_key, name_key};
+use crate::violation::{Severity, Violation};
+use sqlparser::ast::{CreateExtension, Ident, Statement};
+
+/// First major version in which non-superusers can create trusted extensions
+const TRUSTED_EXTENSIONS_SINCE: u32 = 13;
+
+/// Contrib extensions marked `trusted` in PostgreSQL 13+
+const TRUSTED_EXTENSIONS: &[&str] = &[
+    "btree_gin",
+    "btree_gist",
+    "citext",
+    "cube",
+    "dict_int",
+    "fuzzystrmatch",
+    "hstore",
+    "intarray",
+    "isn",
+    "lo",
+    "ltree",
+    "pg_trgm",
+    "pgcrypto",
+    "plpgsql",
+    "seg",
+    "tablefunc",
+    "tcn",
+    "tsm_system_rows",
+    "tsm_system_time",
+    "unaccent",
+    "uuid-ossp",
+];
+
+/// Operation of the downgraded finding for trusted extensions
+const TRUSTED: &str = "CREATE EXTENSION (trusted)";
+
+#[derive(Default)]
+pub struct CreateExtensionCheck {
+    config: CreateExtensionConfig,
+    postgres_version: Option<u32>,
+}
+
+impl CreateExtensionCheck {
+    pub fn new(config: CreateExtensionConfig, postgres_version: Option<u32>) -> Self {
+        Self {
+            config,
+            postgres_version,
+        }
+    }
+
+    fn is_trusted(name: &Ident) -> bool {
+        TRUSTED_EXTENSIONS.contains(&ident_key(name).as_str())
+    }
 
-pub struct CreateExtensionCheck;
+    fn trusted_violation(extension: &str, if_not_exists: &str, version: u32) -> Violation {
+        Violation::new(
+            TRUSTED,
+            format!(
+                "Extension '{extension}' is trusted on PostgreSQL {version}, so the migration role can create it without \
+                superuser privileges if it has CREATE privilege on the database. Without that privilege, the migration fails \
+                in production.",
+                extension = extension,
+                version = version
+            ),
+            format!(
+                r#"Make sure the migration role may create the extension:
+
+1. Grant CREATE on the database to the role running migrations, if it doesn't have it:
+   GRANT CREATE ON DATABASE <database> TO <migration_role>;
+
+2. Keep IF NOT EXISTS, so environments where the extension is preinstalled don't fail:
+   CREATE EXTENSION {if_not_exists}{extension};
+
+Note: Once the role is allowed to create it, add it to allowed_extensions under
+[checks.CreateExtensionCheck]."#,
+                if_not_exists = if_not_exists,
+                extension = extension
+            ),
+        )
+    }
+
+    /// Whether the extension is in `allowed_extensions`
+    fn is_allowed(&self, name: &Ident) -> bool {
+        self.config
+            .allowed_extensions
+            .iter()
+            .any(|allowed| name_key(allowed) == ident_key(name))
+    }
+}
 
 impl Check for CreateExtensionCheck {
     fn check(&self, stmt: &Statement) -> Vec<Violation> {
@@ -26,9 +117,34 @@ impl Check for CreateExtensionCheck {
             ..
         }) = stmt
         {
+            if self.is_allowed(name) {
+                return violations;
+            }
+
             let extension_name = name.to_string();
             let if_not_exists_str = if *if_not_exists { "IF NOT EXISTS " } else { "" };
 
+            let trusted = Self::is_trusted(name);
+            if let Some(version) = self.postgres_version {
+                if trusted && version >= TRUSTED_EXTENSIONS_SINCE {
+                    violations.push(Self::trusted_violation(
+                        &extension_name,
+                        if_not_exists_str,
+                        version,
+                    ));
+                    return violations;
+                }
+            }
+            let trusted_note = if trusted && self.postgres_version.is_none() {
+                format!(
+                    "\n\nOn PostgreSQL {}+, '{}' is a trusted extension that roles with CREATE privilege on the database \
+                    can create. Set postgres_version to get advice specific to your server.",
+                    TRUSTED_EXTENSIONS_SINCE, extension_name
+                )
+            } else {
+                String::new()
+            };
+
             violations.push(Violation::new(
                 "CREATE EXTENSION",
                 format!(
@@ -51,15 +167,25 @@ impl Check for CreateExtensionCheck {
 3. Document required extensions in your project README
 
 Note: Common extensions like pg_trgm, uuid-ossp, hstore, and postgis should be
-installed by your DBA or infrastructure team before application deployment."#,
+installed by your DBA or infrastructure team before application deployment. If the migration
+role may create this extension, add it to allowed_extensions under [checks.CreateExtensionCheck].{trusted_note}"#,
                     if_not_exists = if_not_exists_str,
-                    extension = extension_name
+                    extension = extension_name,
+                    trusted_note = trusted_note
                 ),
             ));
         }
 
         violations
     }
+
+    fn violation_severity(&self, violation: &Violation, configured: Severity) -> Severity {
+        if violation.operation == TRUSTED {
+            Severity::Warning
+        } else {
+            configured
+        }
+    }
 }
 
 #[cfg(test)]
@@ -70,7 +196,7 @@ mod tests {
     #[test]
     fn test_detects_create_extension() {
         assert_detects_violation!(
-            CreateExtensionCheck,
+            CreateExtensionCheck::default(),
             "CREATE EXTENSION pg_trgm;",
             "CREATE EXTENSION"
         );
@@ -79,16 +205,72 @@ mod tests {
     #[test]
     fn test_detects_create_extension_if_not_exists() {
         assert_detects_violation!(
-            CreateExtensionCheck,
+            CreateExtensionCheck::default(),
             "CREATE EXTENSION IF NOT EXISTS uuid_ossp;",
             "CREATE EXTENSION"
         );
     }
 
+    #[test]
+    fn test_allows_allowed_extensions() {
+        let check = || {
+            CreateExtensionCheck::new(
+                CreateExtensionConfig {
+                    allowed_extensions: vec!["pg_trgm".to_string(), "uuid-ossp".to_string()],
+                },
+                None,
+            )
+        };
+
+        assert_allows!(check(), "CREATE EXTENSION IF NOT EXISTS pg_trgm;");
+        assert_allows!(check(), "CREATE EXTENSION \"uuid-ossp\";");
+        assert_detects_violation!(check(), "CREATE EXTENSION postgis;", "CREATE EXTENSION");
+    }
+
+    #[test]
+    fn test_trusted_extensions_depend_on_postgres_version() {
+        use crate::checks::test_utils::parse_sql;
+
+        let stmt = parse_sql("CREATE EXTENSION IF NOT EXISTS \"uuid-ossp\";");
+
+        let violations =
+            CreateExtensionCheck::new(CreateExtensionConfig::default(), Some(13)).check(&stmt);
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].operation, TRUSTED);
+        assert!(violations[0].problem.contains("trusted on PostgreSQL 13"));
+        assert!(violations[0]
+            .safe_alternative
+            .contains("GRANT CREATE ON DATABASE"));
+        assert_eq!(
+            CreateExtensionCheck::default().violation_severity(&violations[0], Severity::Error),
+            Severity::Warning
+        );
+
+        let violations =
+            CreateExtensionCheck::new(CreateExtensionConfig::default(), Some(12)).check(&stmt);
+        assert_eq!(violations[0].operation, "CREATE EXTENSION");
+        assert!(!violations[0].safe_alternative.contains("trusted"));
+
+        let violations = CreateExtensionCheck::default().check(&stmt);
+        assert_eq!(violations[0].operation, "CREATE EXTENSION");
+        assert!(violations[0]
+            .safe_alternative
+            .contains("'\"uuid-ossp\"' is a trusted extension"));
+    }
+
+    #[test]
+    fn test_untrusted_extensions_require_superuser_on_any_version() {
+        assert_detects_violation!(
+            CreateExtensionCheck::new(CreateExtensionConfig::default(), Some(16)),
+            "CREATE EXTENSION postgis;",
+            "CREATE EXTENSION"
+        );
+    }
+
     #[test]
     fn test_ignores_other_create_statements() {
         assert_allows!(
-            CreateExtensionCheck,
+            CreateExtensionCheck::default(),
             "CREATE TABLE users (id SERIAL PRIMARY KEY);"
         );
     }
@@ -96,7 +278,7 @@ mod tests {
     #[test]
     fn test_ignores_create_index() {
         assert_allows!(
-            CreateExtensionCheck,
+            CreateExtensionCheck::default(),
             "CREATE INDEX idx_users_email ON users(email);"
         );
     }
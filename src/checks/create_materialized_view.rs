@@ -0,0 +1,134 @@
+//! Detection for CREATE MATERIALIZED VIEW that populates the view.
+//!
+//! This check identifies `CREATE MATERIALIZED VIEW` statements without `WITH NO DATA`.
+//! Populating the view (`WITH DATA`, the default) runs its full query inside the
+//! migration.
+//!
+//! The query can scan large tables and take hours, holding the migration's transaction
+//! and the locks of any DDL before it open the whole time, and a failure rolls back all
+//! of the work.
+//!
+//! **Parser Handling**: sqlparser can't parse the `WITH [NO] DATA` clause, so those
+//! forms are checked as source text (see [`Check::check_raw`]); the form without the
+//! clause is parsed normally.
+//!
+//! The recommended approach is `WITH NO DATA`, populating the view after deploy.
+
+use crate::checks::Check;
+use crate::parser::RawStatement;
+use crate::violation::Violation;
+use regex::Regex;
+use sqlparser::ast::Statement;
+use std::sync::LazyLock;
+
+/// `CREATE MATERIALIZED VIEW [IF NOT EXISTS] name ... WITH [NO] DATA`
+static WITH_DATA_PATTERN: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?is)^CREATE\s+MATERIALIZED\s+VIEW\s+(?:IF\s+NOT\s+EXISTS\s+)?(?P<name>[^\s(]+).*\bWITH\s+(?P<no>NO\s+)?DATA\s*$",
+    )
+    .expect("valid regex pattern")
+});
+
+pub struct CreateMaterializedViewCheck;
+
+impl CreateMaterializedViewCheck {
+    fn violation(view: &str) -> Violation {
+        Violation::new(
+            "CREATE MATERIALIZED VIEW WITH DATA",
+            format!(
+                "Creating materialized view '{view}' with data runs its full query inside the migration. The query can scan \
+                large tables for a long time, keeping the migration's transaction and locks open until it finishes. \
+                Duration depends on the cost of the query.",
+                view = view
+            ),
+            format!(
+                r#"Create the view empty and populate it after deploy:
+
+1. Create the view without running its query:
+   CREATE MATERIALIZED VIEW {view} AS SELECT ... WITH NO DATA;
+
+2. Add a unique index, which REFRESH ... CONCURRENTLY requires:
+   CREATE UNIQUE INDEX ON {view} (<columns>);
+
+3. Populate the view outside of migrations, before anything reads it:
+   REFRESH MATERIALIZED VIEW {view};
+
+4. Refresh it concurrently from then on, so readers aren't blocked:
+   REFRESH MATERIALIZED VIEW CONCURRENTLY {view};"#,
+                view = view
+            ),
+        )
+    }
+}
+
+impl Check for CreateMaterializedViewCheck {
+    fn check(&self, stmt: &Statement) -> Vec<Violation> {
+        let Statement::CreateView(create) = stmt else {
+            return vec![];
+        };
+        if !create.materialized {
+            return vec![];
+        }
+
+        // Without a WITH [NO] DATA clause, the view is populated
+        vec![Self::violation(&create.name.to_string())]
+    }
+
+    fn check_raw(&self, stmt: &RawStatement) -> Vec<Violation> {
+        if stmt.keyword != "CREATE" {
+            return vec![];
+        }
+
+        let Some(captures) = WITH_DATA_PATTERN.captures(&stmt.sql) else {
+            return vec![];
+        };
+        if captures.name("no").is_some() {
+            return vec![];
+        }
+
+        vec![Self::violation(&captures["name"])]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::checks::test_utils::raw;
+    use crate::{assert_allows, assert_detects_violation};
+
+    #[test]
+    fn test_detects_materialized_view_without_clause() {
+        assert_detects_violation!(
+            CreateMaterializedViewCheck,
+            "CREATE MATERIALIZED VIEW order_totals AS SELECT user_id, sum(total) FROM orders GROUP BY user_id;",
+            "CREATE MATERIALIZED VIEW WITH DATA"
+        );
+    }
+
+    #[test]
+    fn test_detects_with_data() {
+        let violations = CreateMaterializedViewCheck.check_raw(&raw(
+            "CREATE MATERIALIZED VIEW IF NOT EXISTS order_totals AS SELECT * FROM orders WITH DATA",
+        ));
+
+        assert_eq!(violations.len(), 1);
+        assert!(violations[0].problem.contains("'order_totals'"));
+    }
+
+    #[test]
+    fn test_allows_with_no_data() {
+        assert!(CreateMaterializedViewCheck
+            .check_raw(&raw(
+                "create materialized view order_totals as select * from orders with no data"
+            ))
+            .is_empty());
+    }
+
+    #[test]
+    fn test_ignores_regular_views() {
+        assert_allows!(
+            CreateMaterializedViewCheck,
+            "CREATE VIEW active_users AS SELECT * FROM users WHERE active;"
+        );
+    }
+}
@@ -0,0 +1,187 @@
+//! Detection for CREATE ROLE / CREATE USER in migrations.
+//!
+//! This check identifies `CREATE ROLE`, `CREATE USER`, and `CREATE GROUP` statements.
+//!
+//! Creating a role requires the CREATEROLE privilege (or superuser), which the migration
+//! user usually doesn't have in production, so the migration fails there after passing
+//! everywhere else. Roles are shared by every database of the cluster, and a second run
+//! against another database of the same cluster fails because the role already exists.
+//!
+//! A `PASSWORD` clause is worse: the credential is committed to the repository in plain
+//! text, and ends up in every checkout, CI log, and backup of it.
+//!
+//! **Parser Handling**: sqlparser parses the common forms of these statements, which
+//! are checked on the AST. Forms it fails on, such as `CREATE GROUP` or `CREATE USER`
+//! with options, are checked as source text (see [`Check::check_raw`]).
+//!
+//! The recommended approach is creating roles in infrastructure automation, with
+//! credentials from a secret store.
+
+use crate::checks::Check;
+use crate::parser::RawStatement;
+use crate::violation::Violation;
+use regex::Regex;
+use sqlparser::ast::{CreateRole, CreateUser, Password, Statement};
+use std::sync::LazyLock;
+
+/// `CREATE ROLE|USER|GROUP [IF NOT EXISTS] name`
+static CREATE_ROLE_PATTERN: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?is)^CREATE\s+(?P<kind>ROLE|USER|GROUP)\s+(?:IF\s+NOT\s+EXISTS\s+)?(?P<name>[^\s;]+)",
+    )
+    .expect("valid regex pattern")
+});
+
+/// `[ENCRYPTED] PASSWORD '...'` (but not `PASSWORD NULL`)
+static PASSWORD_PATTERN: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?is)\bPASSWORD\s+'").expect("valid regex pattern"));
+
+pub struct CreateRoleCheck;
+
+impl CreateRoleCheck {
+    fn violation(kind: &str, name: &str, with_password: bool) -> Violation {
+        let problem = format!(
+            "CREATE {kind} '{name}' creates a database role in a migration. This requires the CREATEROLE privilege, \
+            which the migration user usually lacks in production, and roles are shared by every database of the \
+            cluster, so running the migration against a second database fails.",
+            kind = kind,
+            name = name
+        );
+        let steps = r#"Create roles outside of application migrations:
+
+1. Create the role in infrastructure automation (Ansible, Terraform, etc.) or your DBA's
+   provisioning scripts, before deploying the application.
+
+2. Set its password from a secret store, never from a file in the repository."#;
+
+        if with_password {
+            return Violation::new(
+                format!("CREATE {} WITH PASSWORD", kind),
+                format!(
+                    "{problem} The PASSWORD clause also commits a credential to the repository in plain text, where it \
+                    ends up in every checkout, CI log, and backup.",
+                    problem = problem
+                ),
+                format!(
+                    r#"{steps}
+
+Note: Treat the committed password as leaked and rotate it, even after removing it from the migration."#,
+                    steps = steps
+                ),
+            );
+        }
+
+        Violation::new(
+            format!("CREATE {}", kind),
+            problem,
+            format!(
+                r#"{steps}
+
+Note: If the migration user is expected to create roles, use a safety-assured block."#,
+                steps = steps
+            ),
+        )
+    }
+}
+
+impl Check for CreateRoleCheck {
+    fn check(&self, stmt: &Statement) -> Vec<Violation> {
+        match stmt {
+            Statement::CreateRole(CreateRole {
+                names, password, ..
+            }) => {
+                let names = names
+                    .iter()
+                    .map(|name| name.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                let with_password = matches!(password, Some(Password::Password(_)));
+                vec![Self::violation("ROLE", &names, with_password)]
+            }
+            Statement::CreateUser(CreateUser { name, options, .. }) => {
+                let with_password = options
+                    .options
+                    .iter()
+                    .any(|option| option.option_name.eq_ignore_ascii_case("PASSWORD"));
+                vec![Self::violation("USER", &name.to_string(), with_password)]
+            }
+            _ => vec![],
+        }
+    }
+
+    fn check_raw(&self, stmt: &RawStatement) -> Vec<Violation> {
+        if stmt.keyword != "CREATE" {
+            return vec![];
+        }
+
+        let Some(captures) = CREATE_ROLE_PATTERN.captures(&stmt.sql) else {
+            return vec![];
+        };
+
+        vec![Self::violation(
+            &captures["kind"].to_uppercase(),
+            &captures["name"],
+            PASSWORD_PATTERN.is_match(&stmt.sql),
+        )]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::checks::test_utils::parse_sql;
+    use crate::checks::test_utils::raw;
+
+    #[test]
+    fn test_detects_create_role() {
+        let violations = CreateRoleCheck.check_raw(&raw("CREATE ROLE reporting NOLOGIN"));
+
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].operation, "CREATE ROLE");
+        assert!(violations[0].problem.contains("'reporting'"));
+    }
+
+    #[test]
+    fn test_detects_parsed_create_role() {
+        let violations = CreateRoleCheck.check(&parse_sql("CREATE ROLE reporting NOLOGIN;"));
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].operation, "CREATE ROLE");
+        assert!(violations[0].problem.contains("'reporting'"));
+
+        let violations =
+            CreateRoleCheck.check(&parse_sql("CREATE ROLE app WITH LOGIN PASSWORD 'hunter2';"));
+        assert_eq!(violations[0].operation, "CREATE ROLE WITH PASSWORD");
+
+        let violations = CreateRoleCheck.check(&parse_sql("CREATE ROLE app PASSWORD NULL;"));
+        assert_eq!(violations[0].operation, "CREATE ROLE");
+
+        let violations = CreateRoleCheck.check(&parse_sql("CREATE USER app"));
+        assert_eq!(violations[0].operation, "CREATE USER");
+        assert!(violations[0].problem.contains("'app'"));
+    }
+
+    #[test]
+    fn test_detects_create_user_with_password() {
+        let violations =
+            CreateRoleCheck.check_raw(&raw("CREATE USER app WITH LOGIN PASSWORD 'hunter2'"));
+
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].operation, "CREATE USER WITH PASSWORD");
+        assert!(violations[0].problem.contains("plain text"));
+        assert!(violations[0].safe_alternative.contains("rotate"));
+    }
+
+    #[test]
+    fn test_password_null_is_not_a_secret() {
+        let violations = CreateRoleCheck.check_raw(&raw("CREATE ROLE app PASSWORD NULL"));
+
+        assert_eq!(violations[0].operation, "CREATE ROLE");
+    }
+
+    #[test]
+    fn test_ignores_alter_role() {
+        assert!(CreateRoleCheck
+            .check_raw(&raw("ALTER ROLE app PASSWORD 'x'"))
+            .is_empty());
+    }
+}
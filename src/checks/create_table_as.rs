@@ -0,0 +1,106 @@
+//! Detection for CREATE TABLE AS and SELECT INTO in migrations.
+//!
+//! This check identifies `CREATE TABLE ... AS SELECT` (including `AS TABLE`) and
+//! `SELECT ... INTO new_table` statements.
+//!
+//! Both copy the result of a query into a new table within the migration transaction.
+//! Duration depends on the size of the source tables and can be arbitrarily long. While
+//! it runs, the source tables hold ACCESS SHARE locks, blocking DDL on them (and any
+//! queries queued behind that DDL), and every copied row is only committed at the end.
+//!
+//! The recommended approach is creating the table empty and backfilling it in batches.
+
+use crate::checks::Check;
+use crate::violation::Violation;
+use sqlparser::ast::{SetExpr, Statement};
+
+pub struct CreateTableAsCheck;
+
+impl Check for CreateTableAsCheck {
+    fn check(&self, stmt: &Statement) -> Vec<Violation> {
+        let (operation, table) = match stmt {
+            Statement::CreateTable(create) if create.query.is_some() => {
+                ("CREATE TABLE AS", create.name.to_string())
+            }
+            Statement::Query(query) => match query.body.as_ref() {
+                SetExpr::Select(select) => match &select.into {
+                    Some(into) => ("SELECT INTO", into.name.to_string()),
+                    None => return vec![],
+                },
+                _ => return vec![],
+            },
+            _ => return vec![],
+        };
+
+        vec![Violation::new(
+            operation,
+            format!(
+                "{operation} copies the result of a query into '{table}' within the migration transaction. \
+                Duration depends on the size of the source tables; meanwhile their ACCESS SHARE locks block any DDL \
+                on them, and nothing is committed until the whole copy finishes.",
+                operation = operation,
+                table = table
+            ),
+            format!(
+                r#"Create the table empty and backfill it in batches:
+
+1. Create the table with an explicit definition:
+   CREATE TABLE {table} (<columns>);
+
+2. Backfill from a script or background job, in batches:
+   INSERT INTO {table} SELECT <columns> FROM <source>
+   WHERE id >= <start> AND id < <start + 1000>;
+   -- repeat for each batch, committing in between
+
+Note: Copying a small lookup table is fine; use a safety-assured block."#,
+                table = table
+            ),
+        )]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{assert_allows, assert_detects_violation};
+
+    #[test]
+    fn test_detects_create_table_as_select() {
+        assert_detects_violation!(
+            CreateTableAsCheck,
+            "CREATE TABLE users_archive AS SELECT * FROM users WHERE deleted;",
+            "CREATE TABLE AS"
+        );
+    }
+
+    #[test]
+    fn test_detects_create_table_as_table() {
+        assert_detects_violation!(
+            CreateTableAsCheck,
+            "CREATE TABLE users_copy AS TABLE users;",
+            "CREATE TABLE AS"
+        );
+    }
+
+    #[test]
+    fn test_detects_select_into() {
+        assert_detects_violation!(
+            CreateTableAsCheck,
+            "SELECT * INTO users_archive FROM users;",
+            "SELECT INTO"
+        );
+    }
+
+    #[test]
+    fn test_allows_create_table() {
+        assert_allows!(
+            CreateTableAsCheck,
+            "CREATE TABLE users_archive (id BIGINT PRIMARY KEY);"
+        );
+    }
+
+    #[test]
+    fn test_allows_select() {
+        assert_allows!(CreateTableAsCheck, "SELECT * FROM users;");
+    }
+}
@@ -0,0 +1,96 @@
+//! Detection for CREATE TRIGGER in migrations.
+//!
+//! This check identifies `CREATE [OR REPLACE] [CONSTRAINT] TRIGGER` statements.
+//! Its violations are warnings by default; raise them with
+//! `[severity] CreateTriggerCheck = "error"`.
+//!
+//! Creating a trigger takes a SHARE ROW EXCLUSIVE lock on the table, blocking writes
+//! while the migration waits for and holds it, which lasts until the migration commits
+//! when other DDL follows. Once created, the trigger runs on every matching write and
+//! silently changes the cost of writes to the table.
+//!
+//! The recommended approach is deploying the trigger function first and creating the
+//! trigger in a separate, small migration.
+
+use crate::checks::Check;
+use crate::violation::{Severity, Violation};
+use sqlparser::ast::Statement;
+
+pub struct CreateTriggerCheck;
+
+impl Check for CreateTriggerCheck {
+    fn check(&self, stmt: &Statement) -> Vec<Violation> {
+        let Statement::CreateTrigger(trigger) = stmt else {
+            return vec![];
+        };
+
+        let trigger_name = trigger.name.to_string();
+        let table_name = trigger.table_name.to_string();
+
+        vec![Violation::new(
+            "CREATE TRIGGER",
+            format!(
+                "Creating trigger '{trigger}' on table '{table}' acquires a SHARE ROW EXCLUSIVE lock, blocking writes \
+                until the migration commits. Afterwards, the trigger runs on every matching write, adding to the cost \
+                of writes to '{table}'.",
+                trigger = trigger_name,
+                table = table_name
+            ),
+            format!(
+                r#"Roll out triggers in small steps:
+
+1. Deploy the trigger function in its own migration (no table lock):
+   CREATE FUNCTION <function>() RETURNS trigger AS $$ ... $$ LANGUAGE plpgsql;
+
+2. Create the trigger in a separate migration without other DDL, failing fast if the
+   table is busy:
+   SET lock_timeout = '2s';
+   CREATE TRIGGER {trigger} ... ON {table} ... EXECUTE FUNCTION <function>();
+
+3. Measure the write overhead on '{table}' before relying on the trigger.
+
+Note: If the trigger is expected, use a safety-assured block."#,
+                trigger = trigger_name,
+                table = table_name
+            ),
+        )]
+    }
+
+    fn default_severity(&self) -> Severity {
+        Severity::Warning
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{assert_allows, assert_detects_violation};
+
+    #[test]
+    fn test_detects_create_trigger() {
+        assert_detects_violation!(
+            CreateTriggerCheck,
+            "CREATE TRIGGER set_updated_at BEFORE UPDATE ON users FOR EACH ROW EXECUTE FUNCTION set_updated_at();",
+            "CREATE TRIGGER"
+        );
+    }
+
+    #[test]
+    fn test_detects_create_or_replace_trigger() {
+        assert_detects_violation!(
+            CreateTriggerCheck,
+            "CREATE OR REPLACE TRIGGER audit AFTER INSERT OR UPDATE ON orders FOR EACH ROW EXECUTE PROCEDURE audit();",
+            "CREATE TRIGGER"
+        );
+    }
+
+    #[test]
+    fn test_defaults_to_warning() {
+        assert_eq!(CreateTriggerCheck.default_severity(), Severity::Warning);
+    }
+
+    #[test]
+    fn test_ignores_other_statements() {
+        assert_allows!(CreateTriggerCheck, "CREATE TABLE users (id BIGINT);");
+    }
+}
@@ -0,0 +1,141 @@
+//! Detection for DELETE without a WHERE clause.
+//!
+//! This check identifies `DELETE` statements that have no WHERE clause, which remove
+//! every row of the table.
+//!
+//! Inside a migration, such a delete runs in a single transaction: every row stays
+//! locked until the migration commits, the table bloats with dead rows, and a mistake
+//! wipes the table in every environment the migration reaches. Flagging every DELETE,
+//! filtered or not, is opt-in via
+//! `[checks.DeleteWithoutWhereCheck] include_with_where = true`.
+//!
+//! The recommended approach is a batched delete run outside of migrations, or TRUNCATE
+//! in a safety-assured block when emptying the table is intended.
+
+use crate::checks::overlap::table_target;
+use crate::checks::Check;
+use crate::config::DeleteWithoutWhereConfig;
+use crate::violation::Violation;
+use sqlparser::ast::{FromTable, Statement};
+
+#[derive(Default)]
+pub struct DeleteWithoutWhereCheck {
+    config: DeleteWithoutWhereConfig,
+}
+
+impl DeleteWithoutWhereCheck {
+    pub fn new(config: DeleteWithoutWhereConfig) -> Self {
+        Self { config }
+    }
+}
+
+impl Check for DeleteWithoutWhereCheck {
+    fn check(&self, stmt: &Statement) -> Vec<Violation> {
+        let Statement::Delete(delete) = stmt else {
+            return vec![];
+        };
+
+        let tables = match &delete.from {
+            FromTable::WithFromKeyword(tables) | FromTable::WithoutKeyword(tables) => tables,
+        };
+        let table = tables
+            .first()
+            .map(|t| t.relation.to_string())
+            .unwrap_or_else(|| "<table>".to_string());
+
+        if delete.selection.is_none() {
+            return vec![Violation::new(
+                "DELETE without WHERE",
+                format!(
+                    "DELETE on '{table}' has no WHERE clause and removes every row of the table in a single transaction. \
+                    Every row stays locked until the migration commits, the table bloats with dead rows, and the data is \
+                    gone in every environment the migration runs in.",
+                    table = table
+                ),
+                format!(
+                    r#"Delete deliberately and in small steps:
+
+1. If only some rows should go, add the missing WHERE clause.
+
+2. For large deletes, run them in batches outside of migrations:
+   DELETE FROM {table} WHERE id IN (
+     SELECT id FROM {table} WHERE <condition> LIMIT 1000
+   );
+   -- repeat until no rows are deleted, committing in between
+
+3. If emptying the table is intended, use TRUNCATE in a safety-assured block:
+   -- safety-assured:start
+   TRUNCATE {table};
+   -- safety-assured:end"#,
+                    table = table
+                ),
+            ).with_target(table_target(&table))];
+        }
+
+        if !self.config.include_with_where {
+            return vec![];
+        }
+
+        vec![Violation::new(
+            "DELETE in migration",
+            format!(
+                "DELETE on '{table}' runs inside the migration's transaction. Every deleted row stays locked until the \
+                migration commits. Duration depends on the number of rows matched.",
+                table = table
+            ),
+            format!(
+                r#"Run deletes in batches outside of migrations:
+
+   DELETE FROM {table} WHERE id IN (
+     SELECT id FROM {table} WHERE <condition> LIMIT 1000
+   );
+   -- repeat until no rows are deleted, committing in between"#,
+                table = table
+            ),
+        ).with_target(table_target(&table))]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{assert_allows, assert_detects_violation};
+
+    #[test]
+    fn test_detects_delete_without_where() {
+        assert_detects_violation!(
+            DeleteWithoutWhereCheck::default(),
+            "DELETE FROM events;",
+            "DELETE without WHERE"
+        );
+    }
+
+    #[test]
+    fn test_detects_delete_using_without_where() {
+        assert_detects_violation!(
+            DeleteWithoutWhereCheck::default(),
+            "DELETE FROM events USING archived_events;",
+            "DELETE without WHERE"
+        );
+    }
+
+    #[test]
+    fn test_allows_delete_with_where() {
+        assert_allows!(
+            DeleteWithoutWhereCheck::default(),
+            "DELETE FROM events WHERE created_at < '2020-01-01';"
+        );
+    }
+
+    #[test]
+    fn test_detects_delete_with_where_when_configured() {
+        let check = DeleteWithoutWhereCheck::new(DeleteWithoutWhereConfig {
+            include_with_where: true,
+        });
+        assert_detects_violation!(
+            check,
+            "DELETE FROM events WHERE created_at < '2020-01-01';",
+            "DELETE in migration"
+        );
+    }
+}
@@ -0,0 +1,207 @@
+//! Detection for ALTER TABLE ... DISABLE TRIGGER.
+//!
+//! This check identifies `ALTER TABLE ... DISABLE TRIGGER` operations. Its violations
+//! are warnings by default, except for `DISABLE TRIGGER ALL`, which is always an error.
+//!
+//! A disabled trigger silently stops doing its work (auditing, denormalized counters,
+//! cache invalidation) for every session until it's re-enabled, and a migration that
+//! fails or forgets to re-enable it leaves it off. `DISABLE TRIGGER ALL` also disables
+//! the internal triggers that enforce foreign keys, so rows violating referential
+//! integrity can be written without any error.
+//!
+//! The recommended approach is not disabling triggers in migrations; if one must be
+//! bypassed for a backfill, do it in the backfill's own transaction.
+
+use crate::checks::Check;
+use crate::violation::{Severity, Violation};
+use sqlparser::ast::{AlterTable, AlterTableOperation, Statement};
+
+/// Operation of the escalated finding
+const DISABLE_ALL: &str = "DISABLE TRIGGER ALL";
+
+pub struct DisableTriggerCheck;
+
+impl Check for DisableTriggerCheck {
+    fn check(&self, stmt: &Statement) -> Vec<Violation> {
+        let Statement::AlterTable(AlterTable {
+            name, operations, ..
+        }) = stmt
+        else {
+            return vec![];
+        };
+
+        let table_name = name.to_string();
+
+        operations
+            .iter()
+            .filter_map(|op| {
+                let AlterTableOperation::DisableTrigger { name: trigger } = op else {
+                    return None;
+                };
+
+                let trigger_name = trigger.to_string();
+                // ALL and USER are keywords unless quoted
+                let keyword = trigger
+                    .quote_style
+                    .is_none()
+                    .then(|| trigger.value.to_uppercase());
+                let violation = match keyword.as_deref() {
+                    Some("ALL") => Violation::new(
+                        DISABLE_ALL,
+                        format!(
+                            "Disabling all triggers on table '{table}' includes the internal triggers that enforce foreign keys. \
+                            Until they're re-enabled, rows violating referential integrity can be written to or deleted from \
+                            '{table}' without any error, and user triggers silently stop running.",
+                            table = table_name
+                        ),
+                        format!(
+                            r#"Don't disable foreign key enforcement in migrations:
+
+1. Keep foreign keys active. If a bulk load is slow, drop and re-add the constraint
+   explicitly with NOT VALID, then validate it:
+   ALTER TABLE {table} ADD CONSTRAINT <name> FOREIGN KEY (...) REFERENCES ... NOT VALID;
+   ALTER TABLE {table} VALIDATE CONSTRAINT <name>;
+
+2. To bypass a specific user trigger, disable only that trigger (DISABLE TRIGGER <name>)."#,
+                            table = table_name
+                        ),
+                    ),
+                    Some("USER") => Violation::new(
+                        "DISABLE TRIGGER USER",
+                        format!(
+                            "Disabling all user triggers on table '{table}' stops every one of them for every session until \
+                            they're re-enabled. Whatever they maintain (audit rows, counters, derived data) silently goes stale, \
+                            and stays that way if the migration fails or never re-enables them.",
+                            table = table_name
+                        ),
+                        format!(
+                            r#"Avoid disabling triggers in migrations:
+
+1. Disable only the trigger that must be bypassed, not all of them, and inside the
+   backfill's own transaction, outside of migrations:
+   BEGIN;
+   ALTER TABLE {table} DISABLE TRIGGER <name>;
+   -- backfill
+   ALTER TABLE {table} ENABLE TRIGGER <name>;
+   COMMIT;
+
+2. Or give the triggers WHEN conditions, so they skip the rows the backfill touches."#,
+                            table = table_name
+                        ),
+                    ),
+                    _ => Violation::new(
+                        "DISABLE TRIGGER",
+                        format!(
+                            "Disabling trigger '{trigger}' on table '{table}' stops it for every session until it's re-enabled. \
+                            Whatever it maintains (audit rows, counters, derived data) silently goes stale, and stays that way \
+                            if the migration fails or never re-enables it.",
+                            trigger = trigger_name,
+                            table = table_name
+                        ),
+                        format!(
+                            r#"Avoid disabling triggers in migrations:
+
+1. If the trigger must be bypassed for a backfill, disable and re-enable it inside the
+   backfill's own transaction, outside of migrations:
+   BEGIN;
+   ALTER TABLE {table} DISABLE TRIGGER {trigger};
+   -- backfill
+   ALTER TABLE {table} ENABLE TRIGGER {trigger};
+   COMMIT;
+
+2. Or give the trigger a WHEN condition, so it skips the rows the backfill touches."#,
+                            table = table_name,
+                            trigger = trigger_name
+                        ),
+                    ),
+                };
+
+                Some(violation)
+            })
+            .collect()
+    }
+
+    fn default_severity(&self) -> Severity {
+        Severity::Warning
+    }
+
+    fn violation_severity(&self, violation: &Violation, configured: Severity) -> Severity {
+        if violation.operation == DISABLE_ALL {
+            Severity::Error
+        } else {
+            configured
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::checks::test_utils::parse_sql;
+    use crate::{assert_allows, assert_detects_violation};
+
+    #[test]
+    fn test_detects_disable_named_trigger() {
+        assert_detects_violation!(
+            DisableTriggerCheck,
+            "ALTER TABLE users DISABLE TRIGGER audit_users;",
+            "DISABLE TRIGGER"
+        );
+    }
+
+    #[test]
+    fn test_detects_disable_trigger_all() {
+        assert_detects_violation!(
+            DisableTriggerCheck,
+            "ALTER TABLE users DISABLE TRIGGER ALL;",
+            "DISABLE TRIGGER ALL"
+        );
+    }
+
+    #[test]
+    fn test_detects_disable_trigger_user() {
+        let violations =
+            DisableTriggerCheck.check(&parse_sql("ALTER TABLE users DISABLE TRIGGER USER;"));
+
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].operation, "DISABLE TRIGGER USER");
+        assert!(violations[0].problem.contains("all user triggers"));
+        assert!(!violations[0].problem.contains("'USER'"));
+        assert_eq!(
+            DisableTriggerCheck.violation_severity(&violations[0], Severity::Warning),
+            Severity::Warning
+        );
+    }
+
+    #[test]
+    fn test_quoted_keywords_are_trigger_names() {
+        let violations =
+            DisableTriggerCheck.check(&parse_sql(r#"ALTER TABLE users DISABLE TRIGGER "user";"#));
+
+        assert_eq!(violations[0].operation, "DISABLE TRIGGER");
+        assert!(violations[0].problem.contains(r#"'"user"'"#));
+    }
+
+    #[test]
+    fn test_escalates_disable_trigger_all() {
+        let all = Violation::new(DISABLE_ALL, "", "");
+        let named = Violation::new("DISABLE TRIGGER", "", "");
+
+        assert_eq!(
+            DisableTriggerCheck.violation_severity(&all, Severity::Warning),
+            Severity::Error
+        );
+        assert_eq!(
+            DisableTriggerCheck.violation_severity(&named, Severity::Warning),
+            Severity::Warning
+        );
+    }
+
+    #[test]
+    fn test_allows_enable_trigger() {
+        assert_allows!(
+            DisableTriggerCheck,
+            "ALTER TABLE users ENABLE TRIGGER audit_users;"
+        );
+    }
+}
@@ -0,0 +1,140 @@
+//! Detection for data manipulation in schema migrations.
+//!
+//! This check identifies `INSERT`, `UPDATE`, and `DELETE` statements. It's opt-in:
+//! enable it with `enable_checks = ["DmlInMigrationCheck"]`.
+//!
+//! Diesel runs each migration in a single transaction, so a backfill holds its row
+//! locks (and the locks of any DDL before it) until the whole migration commits. On
+//! large tables that can be hours, and a failure rolls back all of the work.
+//!
+//! Which statements are flagged is configurable via
+//! `[checks.DmlInMigrationCheck] statements = ["INSERT", "UPDATE", "DELETE"]`.
+//!
+//! The recommended approach is a batched backfill run outside of migrations.
+
+use crate::checks::overlap::table_target;
+use crate::checks::Check;
+use crate::config::{DmlInMigrationConfig, DmlStatement};
+use crate::violation::Violation;
+use sqlparser::ast::{FromTable, Statement};
+
+#[derive(Default)]
+pub struct DmlInMigrationCheck {
+    config: DmlInMigrationConfig,
+}
+
+impl DmlInMigrationCheck {
+    pub fn new(config: DmlInMigrationConfig) -> Self {
+        Self { config }
+    }
+}
+
+impl Check for DmlInMigrationCheck {
+    fn check(&self, stmt: &Statement) -> Vec<Violation> {
+        let (kind, table) = match stmt {
+            Statement::Insert(insert) => (DmlStatement::Insert, insert.table.to_string()),
+            Statement::Update(update) => (DmlStatement::Update, update.table.relation.to_string()),
+            Statement::Delete(delete) => {
+                let tables = match &delete.from {
+                    FromTable::WithFromKeyword(tables) | FromTable::WithoutKeyword(tables) => {
+                        tables
+                    }
+                };
+                let table = tables
+                    .first()
+                    .map(|t| t.relation.to_string())
+                    .unwrap_or_else(|| "<table>".to_string());
+                (DmlStatement::Delete, table)
+            }
+            _ => return vec![],
+        };
+
+        if !self.config.statements.contains(&kind) {
+            return vec![];
+        }
+
+        let keyword = match kind {
+            DmlStatement::Insert => "INSERT",
+            DmlStatement::Update => "UPDATE",
+            DmlStatement::Delete => "DELETE",
+        };
+
+        vec![Violation::new(
+            format!("{} in migration", keyword),
+            format!(
+                "{keyword} on '{table}' changes data inside a schema migration. Diesel runs the migration in a single \
+                transaction, so every touched row stays locked until it commits, along with the locks of any DDL in \
+                the same migration. On large tables this can take hours, and a failure rolls back all of the work.",
+                keyword = keyword,
+                table = table
+            ),
+            format!(
+                r#"Move data changes out of schema migrations:
+
+1. Keep the migration to schema changes only.
+
+2. Run the data change as a batched backfill from a script or background job:
+   {keyword} ... WHERE id >= <start> AND id < <start + 1000>;
+   -- repeat for each batch, committing in between
+
+3. If the data change is small and must ship with the schema change (e.g. seeding a
+   lookup table), use a safety-assured block."#,
+                keyword = keyword
+            ),
+        ).with_target(table_target(&table))]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::checks::test_utils::parse_sql;
+    use crate::{assert_allows, assert_detects_violation};
+
+    #[test]
+    fn test_detects_update() {
+        assert_detects_violation!(
+            DmlInMigrationCheck::default(),
+            "UPDATE users SET active = true;",
+            "UPDATE in migration"
+        );
+    }
+
+    #[test]
+    fn test_detects_insert() {
+        assert_detects_violation!(
+            DmlInMigrationCheck::default(),
+            "INSERT INTO roles (name) SELECT name FROM legacy_roles;",
+            "INSERT in migration"
+        );
+    }
+
+    #[test]
+    fn test_detects_delete() {
+        let violations =
+            DmlInMigrationCheck::default().check(&parse_sql("DELETE FROM sessions WHERE expired;"));
+
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].operation, "DELETE in migration");
+        assert!(violations[0].problem.contains("'sessions'"));
+    }
+
+    #[test]
+    fn test_only_flags_configured_statements() {
+        let check = DmlInMigrationCheck::new(DmlInMigrationConfig {
+            statements: vec![DmlStatement::Update],
+        });
+
+        assert_allows!(check, "INSERT INTO roles (name) VALUES ('admin');");
+        assert_allows!(check, "DELETE FROM sessions;");
+        assert_eq!(check.check(&parse_sql("UPDATE users SET a = 1;")).len(), 1);
+    }
+
+    #[test]
+    fn test_ignores_ddl() {
+        assert_allows!(
+            DmlInMigrationCheck::default(),
+            "ALTER TABLE users ADD COLUMN active BOOLEAN;"
+        );
+    }
+}
@@ -10,45 +10,311 @@
 //! PostgreSQL does not support a CONCURRENTLY option for dropping columns.
 //! The recommended approach is to stage the removal: mark the column as unused
 //! in application code, deploy without references, and drop in a later migration.
+//!
+//! Indexes and constraints on the column are dropped along with it, so the check looks
+//! at the whole migration: creating such an object before the DROP COLUMN is wasted
+//! work, creating it after fails, and dropping it after fails unless it uses IF EXISTS.
+//! The violation lists these objects in statement order.
 
-use crate::checks::{if_exists_clause, Check};
+use crate::checks::{display_or_default, if_exists_clause, Check};
+use crate::schema::{ident_key, index_key, object_key};
 use crate::violation::Violation;
-use sqlparser::ast::{AlterTable, AlterTableOperation, Statement};
+use sqlparser::ast::{
+    AlterTable, AlterTableOperation, DropBehavior, Ident, ObjectName, ObjectType, Statement,
+    TableConstraint,
+};
 
 pub struct DropColumnCheck;
 
-impl Check for DropColumnCheck {
-    fn check(&self, stmt: &Statement) -> Vec<Violation> {
+/// Position of an operation in the migration, as (statement index, operation index)
+type Position = (usize, usize);
+
+/// A DROP COLUMN found in the migration
+struct DroppedColumn {
+    position: Position,
+    table_name: String,
+    column_name: String,
+    /// Normalized table and column names for matching
+    table: String,
+    column: String,
+    if_exists: bool,
+    cascade: bool,
+}
+
+/// An object in the migration that depends on a dropped column
+enum Dependent {
+    /// Index or constraint created on the column; `referencing` is set for foreign keys
+    /// on other tables, which block the DROP COLUMN unless it uses CASCADE
+    Created { object: String, referencing: bool },
+    /// Index or constraint whose name suggests it covers the column
+    Dropped { object: String, if_exists: bool },
+}
+
+/// Whether an expression's SQL mentions the column as an identifier
+fn mentions(sql: &str, column: &str) -> bool {
+    sql.split(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .any(|token| token.eq_ignore_ascii_case(column))
+}
+
+/// Whether an index or constraint name, as a lookup key, contains the column, e.g.
+/// `users_email_idx`
+fn name_mentions(name: &str, column: &str) -> bool {
+    format!("_{}_", name).contains(&format!("_{}_", column))
+}
+
+fn dropped_columns(stmts: &[Statement]) -> Vec<DroppedColumn> {
+    let mut dropped = vec![];
+
+    for (stmt_idx, stmt) in stmts.iter().enumerate() {
         let Statement::AlterTable(AlterTable {
             name, operations, ..
         }) = stmt
         else {
-            return vec![];
+            continue;
         };
 
-        let table_name = name.to_string();
+        for (op_idx, op) in operations.iter().enumerate() {
+            let AlterTableOperation::DropColumn {
+                column_names,
+                if_exists,
+                drop_behavior,
+                ..
+            } = op
+            else {
+                continue;
+            };
 
-        operations
-            .iter()
-            .filter_map(|op| {
-                let AlterTableOperation::DropColumn { column_names, if_exists, .. } = op else {
-                    return None;
-                };
+            dropped.extend(column_names.iter().map(|column| DroppedColumn {
+                position: (stmt_idx, op_idx),
+                table_name: name.to_string(),
+                column_name: column.to_string(),
+                table: object_key(name),
+                column: ident_key(column),
+                if_exists: *if_exists,
+                cascade: matches!(drop_behavior, Some(DropBehavior::Cascade)),
+            }));
+        }
+    }
+
+    dropped
+}
+
+/// Describe a constraint added to `table` if it involves `column` of that table, or if
+/// it's a foreign key of another table referencing `dropped_table(column)`
+fn constraint_dependent(
+    table: &ObjectName,
+    constraint: &TableConstraint,
+    dropped: &DroppedColumn,
+) -> Option<Dependent> {
+    let on_table = object_key(table) == dropped.table;
+    let column = dropped.column.as_str();
+    let created = |kind: &str, name: Option<&Ident>, referencing: bool| {
+        Some(Dependent::Created {
+            object: format!(
+                "{} constraint '{}' on '{}'",
+                kind,
+                display_or_default(name, "<unnamed>"),
+                table
+            ),
+            referencing,
+        })
+    };
 
-                // Report a violation for each column being dropped
-                let violations: Vec<_> = column_names
+    match constraint {
+        TableConstraint::Unique(unique)
+            if on_table
+                && unique
+                    .columns
+                    .iter()
+                    .any(|c| mentions(&c.column.expr.to_string(), column)) =>
+        {
+            created("UNIQUE", unique.name.as_ref(), false)
+        }
+        TableConstraint::PrimaryKey(pk)
+            if on_table
+                && pk
+                    .columns
                     .iter()
-                    .map(|column_name| {
-                        let column_name_str = column_name.to_string();
-
-                        Violation::new(
-                            "DROP COLUMN",
-                            format!(
-                                "Dropping column '{column}' from table '{table}' requires an ACCESS EXCLUSIVE lock, blocking all operations. \
-                                This typically triggers a table rewrite with duration depending on table size.",
-                                column = column_name_str, table = table_name
-                            ),
-                            format!(r#"1. Mark the column as unused in your application code first.
+                    .any(|c| mentions(&c.column.expr.to_string(), column)) =>
+        {
+            created("PRIMARY KEY", pk.name.as_ref(), false)
+        }
+        TableConstraint::Check(check) if on_table && mentions(&check.expr.to_string(), column) => {
+            created("CHECK", check.name.as_ref(), false)
+        }
+        TableConstraint::ForeignKey(fk)
+            if on_table && fk.columns.iter().any(|c| ident_key(c) == column) =>
+        {
+            created("FOREIGN KEY", fk.name.as_ref(), false)
+        }
+        TableConstraint::ForeignKey(fk)
+            if object_key(&fk.foreign_table) == dropped.table
+                && fk.referred_columns.iter().any(|c| ident_key(c) == column) =>
+        {
+            created("FOREIGN KEY", fk.name.as_ref(), true)
+        }
+        _ => None,
+    }
+}
+
+/// Objects in `stmt` that depend on the dropped column, with their positions
+fn dependents(
+    stmt_idx: usize,
+    stmt: &Statement,
+    dropped: &DroppedColumn,
+) -> Vec<(Position, Dependent)> {
+    let column = dropped.column.as_str();
+
+    match stmt {
+        Statement::CreateIndex(index) if object_key(&index.table_name) == dropped.table => {
+            let covers_column = index
+                .columns
+                .iter()
+                .any(|c| mentions(&c.column.expr.to_string(), column))
+                || index.include.iter().any(|c| ident_key(c) == column)
+                || index
+                    .predicate
+                    .as_ref()
+                    .is_some_and(|predicate| mentions(&predicate.to_string(), column));
+            if !covers_column {
+                return vec![];
+            }
+
+            vec![(
+                (stmt_idx, 0),
+                Dependent::Created {
+                    object: format!(
+                        "index '{}'",
+                        display_or_default(index.name.as_ref(), "<unnamed>")
+                    ),
+                    referencing: false,
+                },
+            )]
+        }
+        Statement::Drop {
+            object_type: ObjectType::Index,
+            names,
+            if_exists,
+            ..
+        } => names
+            .iter()
+            .filter(|name| name_mentions(&index_key(name), column))
+            .map(|name| {
+                (
+                    (stmt_idx, 0),
+                    Dependent::Dropped {
+                        object: format!("index '{}'", name),
+                        if_exists: *if_exists,
+                    },
+                )
+            })
+            .collect(),
+        Statement::AlterTable(AlterTable {
+            name, operations, ..
+        }) => operations
+            .iter()
+            .enumerate()
+            .filter_map(|(op_idx, op)| {
+                let dependent = match op {
+                    AlterTableOperation::AddConstraint { constraint, .. } => {
+                        constraint_dependent(name, constraint, dropped)
+                    }
+                    AlterTableOperation::DropConstraint {
+                        name: constraint,
+                        if_exists,
+                        ..
+                    } if object_key(name) == dropped.table
+                        && name_mentions(&ident_key(constraint), column) =>
+                    {
+                        Some(Dependent::Dropped {
+                            object: format!("constraint '{}' on '{}'", constraint, name),
+                            if_exists: *if_exists,
+                        })
+                    }
+                    _ => None,
+                }?;
+                Some(((stmt_idx, op_idx), dependent))
+            })
+            .collect(),
+        _ => vec![],
+    }
+}
+
+/// Advice for one dependent object, depending on where it appears relative to the DROP COLUMN
+fn dependent_advice(position: Position, dependent: &Dependent, dropped: &DroppedColumn) -> String {
+    let before = position < dropped.position;
+
+    match dependent {
+        Dependent::Created {
+            object,
+            referencing: true,
+        } if before && !dropped.cascade => format!(
+            "{} is created earlier and references the column, so the DROP COLUMN fails without CASCADE. \
+            Remove it from this migration.",
+            object
+        ),
+        Dependent::Created { object, .. } if before => format!(
+            "{} is created earlier and dropped again along with the column. Remove it from this migration.",
+            object
+        ),
+        Dependent::Created { object, .. } => format!(
+            "{} is created after the DROP COLUMN and fails because the column no longer exists. \
+            Remove it from this migration.",
+            object
+        ),
+        Dependent::Dropped { object, .. } if before => format!(
+            "{} is dropped before the column, which is the right order.",
+            object
+        ),
+        Dependent::Dropped {
+            object,
+            if_exists: true,
+        } => format!(
+            "{} is dropped after the column, which likely already dropped it. Drop it before the DROP COLUMN instead.",
+            object
+        ),
+        Dependent::Dropped { object, .. } => format!(
+            "{} is dropped after the column, which likely already dropped it, so the DROP fails. \
+            Drop it before the DROP COLUMN instead.",
+            object
+        ),
+    }
+}
+
+fn drop_column_violation(dropped: &DroppedColumn, stmts: &[Statement]) -> Violation {
+    let (table, column) = (&dropped.table_name, &dropped.column_name);
+
+    let mut dependents: Vec<_> = stmts
+        .iter()
+        .enumerate()
+        .flat_map(|(stmt_idx, stmt)| dependents(stmt_idx, stmt, dropped))
+        .collect();
+    dependents.sort_by_key(|(position, _)| *position);
+
+    let dependent_notes = if dependents.is_empty() {
+        String::new()
+    } else {
+        format!(
+            "Indexes and constraints on '{column}' are dropped along with it. This migration also touches:\n{notes}\n\n",
+            column = column,
+            notes = dependents
+                .iter()
+                .map(|(position, dependent)| format!("   - {}", dependent_advice(*position, dependent, dropped)))
+                .collect::<Vec<_>>()
+                .join("\n")
+        )
+    };
+
+    Violation::new(
+        "DROP COLUMN",
+        format!(
+            "Dropping column '{column}' from table '{table}' requires an ACCESS EXCLUSIVE lock, blocking all operations. \
+            This typically triggers a table rewrite with duration depending on table size.",
+            column = column,
+            table = table
+        ),
+        format!(
+            r#"1. Mark the column as unused in your application code first.
 
 2. Deploy the application without the column references.
 
@@ -57,20 +323,26 @@ impl Check for DropColumnCheck {
    UPDATE {table} SET {column} = NULL;
 
 4. Drop the column in a later migration after confirming it's unused:
-   ALTER TABLE {table} DROP COLUMN {column}{if_exists};
-
-Note: PostgreSQL doesn't support DROP COLUMN CONCURRENTLY. The rewrite is unavoidable but staging the removal reduces risk."#,
-                                table = table_name,
-                                column = column_name_str,
-                                if_exists = if_exists_clause(*if_exists)
-                            ),
-                        )
-                    })
-                    .collect();
-
-                Some(violations)
-            })
-            .flatten()
+   ALTER TABLE {table} DROP COLUMN{if_exists} {column};
+
+{dependent_notes}Note: PostgreSQL doesn't support DROP COLUMN CONCURRENTLY. The rewrite is unavoidable but staging the removal reduces risk."#,
+            table = table,
+            column = column,
+            if_exists = if_exists_clause(dropped.if_exists),
+            dependent_notes = dependent_notes
+        ),
+    )
+}
+
+impl Check for DropColumnCheck {
+    fn check(&self, _stmt: &Statement) -> Vec<Violation> {
+        vec![]
+    }
+
+    fn check_migration(&self, stmts: &[Statement]) -> Vec<(usize, Violation)> {
+        dropped_columns(stmts)
+            .iter()
+            .map(|dropped| (dropped.position.0, drop_column_violation(dropped, stmts)))
             .collect()
     }
 }
@@ -78,39 +350,145 @@ Note: PostgreSQL doesn't support DROP COLUMN CONCURRENTLY. The rewrite is unavoi
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{assert_allows, assert_detects_violation};
+    use crate::checks::test_utils::check_sql;
 
     #[test]
     fn test_detects_drop_column() {
-        assert_detects_violation!(
-            DropColumnCheck,
-            "ALTER TABLE users DROP COLUMN email;",
-            "DROP COLUMN"
-        );
+        let violations = check_sql(&DropColumnCheck, "ALTER TABLE users DROP COLUMN email;");
+
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].1.operation, "DROP COLUMN");
+        assert!(!violations[0]
+            .1
+            .safe_alternative
+            .contains("This migration also"));
     }
 
     #[test]
     fn test_detects_drop_column_if_exists() {
-        assert_detects_violation!(
-            DropColumnCheck,
+        let violations = check_sql(
+            &DropColumnCheck,
             "ALTER TABLE users DROP COLUMN IF EXISTS email;",
-            "DROP COLUMN"
+        );
+
+        assert_eq!(violations.len(), 1);
+        assert!(violations[0]
+            .1
+            .safe_alternative
+            .contains("DROP COLUMN IF EXISTS email;"));
+    }
+
+    #[test]
+    fn test_detects_each_dropped_column() {
+        let violations = check_sql(
+            &DropColumnCheck,
+            "CREATE TABLE posts (id BIGINT PRIMARY KEY);
+             ALTER TABLE users DROP COLUMN email, DROP COLUMN name;",
+        );
+
+        assert_eq!(violations.len(), 2);
+        assert!(violations.iter().all(|(idx, _)| *idx == 1));
+    }
+
+    #[test]
+    fn test_mentions_objects_created_before_and_after() {
+        let violations = check_sql(
+            &DropColumnCheck,
+            "CREATE INDEX users_email_idx ON users (lower(email));
+             ALTER TABLE users DROP COLUMN email;
+             ALTER TABLE users ADD CONSTRAINT users_email_check CHECK (email <> '');
+             CREATE INDEX users_name_idx ON users (name);",
+        );
+
+        assert_eq!(violations.len(), 1);
+        let safe_alternative = &violations[0].1.safe_alternative;
+        assert!(safe_alternative.contains(
+            "index 'users_email_idx' is created earlier and dropped again along with the column"
+        ));
+        assert!(safe_alternative.contains(
+            "CHECK constraint 'users_email_check' on 'users' is created after the DROP COLUMN and fails"
+        ));
+        assert!(!safe_alternative.contains("users_name_idx"));
+        assert!(
+            safe_alternative.find("users_email_idx").unwrap()
+                < safe_alternative.find("users_email_check").unwrap()
         );
     }
 
+    #[test]
+    fn test_mentions_dropped_objects_by_order() {
+        let violations = check_sql(
+            &DropColumnCheck,
+            "DROP INDEX users_email_idx;
+             ALTER TABLE users DROP COLUMN email, DROP CONSTRAINT users_email_key;
+             DROP INDEX IF EXISTS users_email_lower_idx;",
+        );
+
+        let safe_alternative = &violations[0].1.safe_alternative;
+        assert!(safe_alternative.contains(
+            "index 'users_email_idx' is dropped before the column, which is the right order."
+        ));
+        assert!(safe_alternative.contains(
+            "constraint 'users_email_key' on 'users' is dropped after the column, which likely already dropped it, so the DROP fails."
+        ));
+        assert!(safe_alternative.contains(
+            "index 'users_email_lower_idx' is dropped after the column, which likely already dropped it. Drop it before"
+        ));
+    }
+
+    #[test]
+    fn test_mentions_foreign_keys_referencing_column() {
+        let violations = check_sql(&DropColumnCheck,
+            "ALTER TABLE orders ADD CONSTRAINT orders_user_code_fkey FOREIGN KEY (user_code) REFERENCES users (code);
+             ALTER TABLE users DROP COLUMN code;",
+        );
+
+        assert!(violations[0].1.safe_alternative.contains(
+            "FOREIGN KEY constraint 'orders_user_code_fkey' on 'orders' is created earlier and references the column, \
+            so the DROP COLUMN fails without CASCADE."
+        ));
+
+        let violations = check_sql(&DropColumnCheck,
+            "ALTER TABLE orders ADD CONSTRAINT orders_user_code_fkey FOREIGN KEY (user_code) REFERENCES users (code);
+             ALTER TABLE users DROP COLUMN code CASCADE;",
+        );
+
+        assert!(violations[0]
+            .1
+            .safe_alternative
+            .contains("is created earlier and dropped again along with the column"));
+    }
+
+    #[test]
+    fn test_ignores_objects_on_other_tables_and_columns() {
+        let violations = check_sql(&DropColumnCheck,
+            "CREATE INDEX posts_email_idx ON posts (email);
+             CREATE INDEX users_email_domain_idx ON users (email_domain);
+             ALTER TABLE users DROP COLUMN email;
+             ALTER TABLE orders ADD CONSTRAINT orders_user_id_fkey FOREIGN KEY (email) REFERENCES users (id);",
+        );
+
+        assert!(!violations[0]
+            .1
+            .safe_alternative
+            .contains("This migration also"));
+    }
+
     #[test]
     fn test_ignores_other_operations() {
-        assert_allows!(
-            DropColumnCheck,
+        assert!(check_sql(
+            &DropColumnCheck,
             "ALTER TABLE users ADD COLUMN email VARCHAR(255);"
-        );
+        )
+        .is_empty());
     }
 
     #[test]
     fn test_ignores_other_statements() {
-        assert_allows!(
-            DropColumnCheck,
+        assert!(check_sql(
+            &DropColumnCheck,
             "CREATE TABLE users (id SERIAL PRIMARY KEY);"
-        );
+        )
+        .is_empty());
     }
 }
@@ -0,0 +1,115 @@
+//! Detection for DROP CONSTRAINT ... CASCADE.
+//!
+//! This check identifies `ALTER TABLE ... DROP CONSTRAINT ... CASCADE` operations.
+//!
+//! CASCADE drops every object that depends on the constraint. For a primary key or
+//! unique constraint, that includes the foreign keys of other tables referencing it,
+//! which are removed silently: the migration succeeds and referential integrity is gone.
+//! Each affected table is locked as well.
+//!
+//! The recommended approach is dropping dependent constraints explicitly, then the
+//! constraint itself without CASCADE.
+
+use crate::checks::overlap::constraint_name_target;
+use crate::checks::Check;
+use crate::violation::Violation;
+use sqlparser::ast::{AlterTable, AlterTableOperation, DropBehavior, Statement};
+
+pub struct DropConstraintCascadeCheck;
+
+impl Check for DropConstraintCascadeCheck {
+    fn check(&self, stmt: &Statement) -> Vec<Violation> {
+        let Statement::AlterTable(AlterTable {
+            name, operations, ..
+        }) = stmt
+        else {
+            return vec![];
+        };
+
+        let table_name = name.to_string();
+
+        operations
+            .iter()
+            .filter_map(|op| {
+                let AlterTableOperation::DropConstraint {
+                    name: constraint_name,
+                    drop_behavior: Some(DropBehavior::Cascade),
+                    ..
+                } = op
+                else {
+                    return None;
+                };
+
+                Some(Violation::new(
+                    "DROP CONSTRAINT CASCADE",
+                    format!(
+                        "Dropping constraint '{constraint}' from table '{table}' with CASCADE also drops every object that depends on it, \
+                        such as foreign keys in other tables referencing a primary key or unique constraint. They are removed \
+                        silently, and each affected table is locked.",
+                        constraint = constraint_name,
+                        table = table_name
+                    ),
+                    format!(
+                        r#"Drop dependent objects explicitly, so the migration shows everything it removes:
+
+1. Find the foreign keys that depend on the constraint:
+   SELECT conrelid::regclass, conname FROM pg_constraint
+   WHERE confrelid = '{table}'::regclass AND contype = 'f';
+
+2. Drop each of them explicitly:
+   ALTER TABLE <referencing_table> DROP CONSTRAINT <foreign_key>;
+
+3. Drop the constraint without CASCADE, so it fails if anything still depends on it:
+   ALTER TABLE {table} DROP CONSTRAINT {constraint};"#,
+                        table = table_name,
+                        constraint = constraint_name
+                    ),
+                ).with_target(constraint_name_target(name, constraint_name)))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{assert_allows, assert_detects_violation};
+
+    #[test]
+    fn test_detects_drop_constraint_cascade() {
+        assert_detects_violation!(
+            DropConstraintCascadeCheck,
+            "ALTER TABLE users DROP CONSTRAINT users_email_key CASCADE;",
+            "DROP CONSTRAINT CASCADE"
+        );
+    }
+
+    #[test]
+    fn test_detects_drop_constraint_if_exists_cascade() {
+        assert_detects_violation!(
+            DropConstraintCascadeCheck,
+            "ALTER TABLE users DROP CONSTRAINT IF EXISTS users_email_key CASCADE;",
+            "DROP CONSTRAINT CASCADE"
+        );
+    }
+
+    #[test]
+    fn test_allows_drop_constraint_without_cascade() {
+        assert_allows!(
+            DropConstraintCascadeCheck,
+            "ALTER TABLE users DROP CONSTRAINT users_email_key;"
+        );
+        assert_allows!(
+            DropConstraintCascadeCheck,
+            "ALTER TABLE users DROP CONSTRAINT users_email_key RESTRICT;"
+        );
+    }
+
+    #[test]
+    fn test_ignores_drop_column_cascade() {
+        assert_allows!(
+            DropConstraintCascadeCheck,
+            "ALTER TABLE users DROP COLUMN email CASCADE;"
+        );
+    }
+}
@@ -0,0 +1,162 @@
+//! Detection for DROP DATABASE and DROP TABLESPACE in migrations.
+//!
+//! This check identifies `DROP DATABASE` and `DROP TABLESPACE` statements.
+//!
+//! Neither belongs in an application migration: DROP DATABASE destroys every table of
+//! the database at once, and a tablespace can only be dropped once it's empty, so the
+//! statement either fails or follows the removal of everything stored in it. Such
+//! statements have slipped into generated rollbacks (down.sql). Violations are always
+//! errors, even when a lower severity is configured for the check.
+//!
+//! **Parser Handling**: `DROP DATABASE` is checked on the AST. sqlparser cannot parse
+//! `DROP TABLESPACE` or `DROP DATABASE ... WITH (FORCE)`, so these statements are
+//! checked as source text (see [`Check::check_raw`]).
+//!
+//! The recommended approach is removing the statement from the migration.
+
+use crate::checks::Check;
+use crate::parser::RawStatement;
+use crate::violation::{Severity, Violation};
+use regex::Regex;
+use sqlparser::ast::{ObjectType, Statement};
+use std::sync::LazyLock;
+
+/// `DROP DATABASE|TABLESPACE [IF EXISTS] name`
+static DROP_DATABASE_PATTERN: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?is)^DROP\s+(?P<object>DATABASE|TABLESPACE)\s+(?:IF\s+EXISTS\s+)?(?P<name>[^\s;]+)",
+    )
+    .expect("valid regex pattern")
+});
+
+pub struct DropDatabaseCheck;
+
+impl DropDatabaseCheck {
+    fn violation(object: &str, name: &str) -> Violation {
+        Violation::new(
+            format!("DROP {}", object),
+            format!(
+                "DROP {object} '{name}' has no place in an application migration. {consequence} \
+                It usually comes from a generated or copied rollback and would run wherever the migrations run, \
+                including production.",
+                object = object,
+                name = name,
+                consequence = if object == "DATABASE" {
+                    "It irreversibly destroys every table and row in the database."
+                } else {
+                    "A tablespace can only be dropped once it's empty, so it fails or follows the removal of everything stored in it."
+                }
+            ),
+            format!(
+                r#"Remove the statement from the migration.
+
+Databases and tablespaces are managed by infrastructure automation or by hand, never by
+application migrations. If a down.sql needs to undo a migration, reverse only the
+schema changes of its up.sql.
+
+Note: This check can't be downgraded to a warning; disable it with
+disable_checks = ["DropDatabaseCheck"] if DROP {object} is really intended."#,
+                object = object
+            ),
+        )
+    }
+}
+
+impl Check for DropDatabaseCheck {
+    fn check(&self, stmt: &Statement) -> Vec<Violation> {
+        match stmt {
+            Statement::Drop {
+                object_type: ObjectType::Database,
+                names,
+                ..
+            } => names
+                .iter()
+                .map(|name| Self::violation("DATABASE", &name.to_string()))
+                .collect(),
+            _ => vec![],
+        }
+    }
+
+    fn check_raw(&self, stmt: &RawStatement) -> Vec<Violation> {
+        if stmt.keyword != "DROP" {
+            return vec![];
+        }
+
+        let Some(captures) = DROP_DATABASE_PATTERN.captures(&stmt.sql) else {
+            return vec![];
+        };
+
+        vec![Self::violation(
+            &captures["object"].to_uppercase(),
+            &captures["name"],
+        )]
+    }
+
+    fn violation_severity(&self, _violation: &Violation, _configured: Severity) -> Severity {
+        Severity::Error
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::checks::test_utils::parse_sql;
+    use crate::checks::test_utils::raw;
+
+    #[test]
+    fn test_detects_drop_database() {
+        let violations =
+            DropDatabaseCheck.check_raw(&raw("DROP DATABASE IF EXISTS app_production"));
+
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].operation, "DROP DATABASE");
+        assert!(violations[0].problem.contains("'app_production'"));
+    }
+
+    #[test]
+    fn test_detects_parsed_drop_database() {
+        let violations =
+            DropDatabaseCheck.check(&parse_sql("DROP DATABASE IF EXISTS app_production;"));
+
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].operation, "DROP DATABASE");
+        assert!(violations[0].problem.contains("'app_production'"));
+
+        assert!(DropDatabaseCheck
+            .check(&parse_sql("DROP TABLE databases;"))
+            .is_empty());
+    }
+
+    #[test]
+    fn test_detects_drop_database_with_force() {
+        let violations = DropDatabaseCheck.check_raw(&raw("DROP DATABASE app WITH (FORCE)"));
+
+        assert_eq!(violations.len(), 1);
+        assert!(violations[0].problem.contains("'app'"));
+    }
+
+    #[test]
+    fn test_detects_drop_tablespace() {
+        let violations = DropDatabaseCheck.check_raw(&raw("drop tablespace fast_ssd"));
+
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].operation, "DROP TABLESPACE");
+    }
+
+    #[test]
+    fn test_always_an_error() {
+        let violation = Violation::new("DROP DATABASE", "", "");
+
+        assert_eq!(
+            DropDatabaseCheck.violation_severity(&violation, Severity::Warning),
+            Severity::Error
+        );
+    }
+
+    #[test]
+    fn test_ignores_other_drops() {
+        assert!(DropDatabaseCheck
+            .check_raw(&raw("DROP TABLE databases"))
+            .is_empty());
+    }
+}
@@ -14,21 +14,103 @@
 //! diesel-guard detects this safe pattern and treats it as valid (returns no violations).
 //! A warning is shown that the file contains this safe pattern. Like CREATE INDEX
 //! CONCURRENTLY, it requires `metadata.toml` with `run_in_transaction = false`.
+//!
+//! `DROP INDEX ... CASCADE` is reported separately: it also drops every object that
+//! depends on the index, such as foreign keys in other tables that reference the indexed
+//! columns through a unique index. CONCURRENTLY doesn't support CASCADE, so dependents
+//! have to be dropped explicitly first.
+//!
+//! Dropping an index that's replaced by one created earlier in the same migration, with the
+//! same table, key columns, uniqueness, access method, predicate and INCLUDE columns, is
+//! the last step of a safe swap. It's reported as a warning
+//! instead, since queries keep an index to use but the drop still takes its lock.
 
-use crate::checks::{if_exists_clause, Check};
-use crate::violation::Violation;
-use sqlparser::ast::{ObjectType, Statement};
+use crate::checks::{if_exists_clause, Check, MigrationContext};
+use crate::violation::{Severity, Violation};
+use sqlparser::ast::{ObjectName, ObjectType, Statement};
+
+/// Operation of the downgraded finding for an index replaced earlier in the migration
+const REPLACED: &str = "DROP INDEX without CONCURRENTLY (replaced)";
 
 pub struct DropIndexCheck;
 
-impl Check for DropIndexCheck {
-    fn check(&self, stmt: &Statement) -> Vec<Violation> {
+impl DropIndexCheck {
+    fn replaced_violation(
+        index_name: &str,
+        if_exists: &str,
+        replacement: &ObjectName,
+    ) -> Violation {
+        Violation::new(
+            REPLACED,
+            format!(
+                "Index '{index}'{if_exists} is replaced by '{replacement}', created earlier in this migration on the same table \
+                and columns, so queries keep an index to use. Dropping it without CONCURRENTLY still acquires an ACCESS EXCLUSIVE \
+                lock, briefly blocking all queries on the table, and longer if it has to wait for running transactions.",
+                index = index_name,
+                if_exists = if_exists,
+                replacement = replacement
+            ),
+            format!(
+                r#"Drop the old index concurrently, like its replacement was built:
+   DROP INDEX CONCURRENTLY{if_exists} {index};
+
+Note: Like CREATE INDEX CONCURRENTLY, this requires run_in_transaction = false in metadata.toml."#,
+                if_exists = if_exists,
+                index = index_name
+            ),
+        )
+    }
+
+    fn cascade_violation(index_name: &str, if_exists: &str) -> Violation {
+        Violation::new(
+            "DROP INDEX CASCADE",
+            format!(
+                "Dropping index '{index}'{if_exists} with CASCADE also drops every object that depends on it, without listing them. \
+                If it's a unique index, foreign keys in other tables that reference the indexed columns are dropped, silently removing \
+                referential integrity. It also acquires an ACCESS EXCLUSIVE lock, blocking all queries on the table until complete.",
+                index = index_name,
+                if_exists = if_exists
+            ),
+            format!(
+                r#"Drop dependent objects explicitly, then drop the index concurrently:
+
+1. Find what depends on the index:
+   SELECT classid::regclass, objid, deptype
+   FROM pg_depend
+   WHERE refobjid = '{index}'::regclass;
+
+   Objects that may be removed by CASCADE:
+   - Foreign key constraints referencing the columns of a unique index
+   - Objects of extensions or custom types built on the index
+
+2. Drop each dependent object in its own statement, e.g.:
+   ALTER TABLE <referencing_table> DROP CONSTRAINT <foreign_key>;
+
+3. Drop the index without CASCADE and without blocking queries:
+   DROP INDEX CONCURRENTLY{if_exists} {index};
+
+Note: DROP INDEX CONCURRENTLY doesn't support CASCADE. Indexes backing a PRIMARY KEY or UNIQUE
+constraint can't be dropped with DROP INDEX at all, even with CASCADE; drop the constraint instead."#,
+                index = index_name,
+                if_exists = if_exists
+            ),
+        )
+    }
+}
+
+impl DropIndexCheck {
+    /// Violations for each dropped index, given a lookup of indexes replacing it
+    fn drop_violations(
+        stmt: &Statement,
+        replacement: impl Fn(&ObjectName) -> Option<ObjectName>,
+    ) -> Vec<Violation> {
         let mut violations = vec![];
 
         if let Statement::Drop {
             object_type,
             if_exists,
             names,
+            cascade,
             ..
         } = stmt
         {
@@ -40,6 +122,19 @@ impl Check for DropIndexCheck {
                     let index_name = name.to_string();
                     let if_exists_str = if_exists_clause(*if_exists);
 
+                    if *cascade {
+                        violations.push(Self::cascade_violation(&index_name, if_exists_str));
+                        continue;
+                    }
+                    if let Some(replacement) = replacement(name) {
+                        violations.push(Self::replaced_violation(
+                            &index_name,
+                            if_exists_str,
+                            &replacement,
+                        ));
+                        continue;
+                    }
+
                     violations.push(Violation::new(
                         "DROP INDEX without CONCURRENTLY",
                         format!(
@@ -77,9 +172,35 @@ Considerations:
     }
 }
 
+impl Check for DropIndexCheck {
+    fn check(&self, stmt: &Statement) -> Vec<Violation> {
+        Self::drop_violations(stmt, |_| None)
+    }
+
+    fn check_in_migration(&self, stmt: &Statement, context: &MigrationContext) -> Vec<Violation> {
+        Self::drop_violations(stmt, |index| {
+            context
+                .schema()
+                .equivalent_indexes(index)
+                .into_iter()
+                .find(|other| context.is_new_index(other))
+        })
+    }
+
+    fn violation_severity(&self, violation: &Violation, configured: Severity) -> Severity {
+        if violation.operation == REPLACED {
+            Severity::Warning
+        } else {
+            configured
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::checks::test_utils::check_sequence;
+    use crate::checks::test_utils::parse_sql;
     use crate::{assert_allows, assert_detects_violation};
 
     #[test]
@@ -102,11 +223,14 @@ mod tests {
 
     #[test]
     fn test_detects_drop_index_cascade() {
-        assert_detects_violation!(
-            DropIndexCheck,
-            "DROP INDEX idx_users_email CASCADE;",
-            "DROP INDEX without CONCURRENTLY"
-        );
+        let violations = DropIndexCheck.check(&parse_sql("DROP INDEX idx_users_email CASCADE;"));
+
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].operation, "DROP INDEX CASCADE");
+        assert!(violations[0].problem.contains("foreign keys"));
+        assert!(violations[0]
+            .safe_alternative
+            .contains("DROP INDEX CONCURRENTLY idx_users_email;"));
     }
 
     #[test]
@@ -120,8 +244,6 @@ mod tests {
 
     #[test]
     fn test_detects_drop_multiple_indexes() {
-        use crate::checks::test_utils::parse_sql;
-
         let check = DropIndexCheck;
         let stmt = parse_sql("DROP INDEX idx1, idx2, idx3;");
 
@@ -137,8 +259,63 @@ mod tests {
         assert_detects_violation!(
             DropIndexCheck,
             "DROP INDEX IF EXISTS idx_users_email CASCADE;",
-            "DROP INDEX without CONCURRENTLY"
+            "DROP INDEX CASCADE"
+        );
+    }
+
+    #[test]
+    fn test_downgrades_drop_of_replaced_index() {
+        let violations = check_sequence(
+            &DropIndexCheck,
+            "CREATE INDEX users_email_idx ON users (email);
+             CREATE INDEX CONCURRENTLY users_email_new_idx ON users (email);
+             DROP INDEX IF EXISTS users_email_idx;",
+        );
+
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].operation, REPLACED);
+        assert!(violations[0]
+            .problem
+            .contains("replaced by 'users_email_new_idx'"));
+        assert!(violations[0]
+            .safe_alternative
+            .contains("DROP INDEX CONCURRENTLY IF EXISTS users_email_idx;"));
+        assert_eq!(
+            DropIndexCheck.violation_severity(&violations[0], Severity::Error),
+            Severity::Warning
+        );
+    }
+
+    #[test]
+    fn test_requires_replacement_on_same_columns_in_migration() {
+        // Different columns
+        let violations = check_sequence(
+            &DropIndexCheck,
+            "CREATE INDEX users_email_idx ON users (email);
+             CREATE INDEX CONCURRENTLY users_email_name_idx ON users (email, name);
+             DROP INDEX users_email_idx;",
+        );
+        assert_eq!(violations[0].operation, "DROP INDEX without CONCURRENTLY");
+
+        // Same columns, but neither unique nor covering the whole table
+        let violations = check_sequence(&DropIndexCheck,
+            "CREATE UNIQUE INDEX users_email_idx ON users (email);
+             CREATE INDEX CONCURRENTLY users_email_new_idx ON users (email) WHERE deleted_at IS NULL;
+             DROP INDEX users_email_idx;",
+        );
+        assert_eq!(violations[0].operation, "DROP INDEX without CONCURRENTLY");
+
+        // Equivalent index from an earlier migration, not a replacement
+        let mut schema = crate::schema::Schema::default();
+        schema.apply(&[
+            parse_sql("CREATE INDEX users_email_idx ON users (email);"),
+            parse_sql("CREATE INDEX users_email_copy_idx ON users (email);"),
+        ]);
+        let violations = DropIndexCheck.check_in_migration(
+            &parse_sql("DROP INDEX users_email_idx;"),
+            &MigrationContext::new(&schema),
         );
+        assert_eq!(violations[0].operation, "DROP INDEX without CONCURRENTLY");
     }
 
     #[test]
@@ -6,17 +6,21 @@
 //! More critically, it breaks foreign key relationships in other tables that reference
 //! this table, and removes the uniqueness constraint that applications may depend on.
 //!
-//! **Limitation:** This check uses heuristic detection based on constraint naming patterns.
-//! It may not detect primary keys with non-standard names, and may occasionally flag
-//! non-primary-key constraints that follow similar naming patterns.
+//! For tables created by earlier migrations of the same directory (or earlier in the same
+//! migration), the primary key constraint is known from the replayed schema, so exactly
+//! that constraint is flagged, whatever its name. Other tables are looked up in the live
+//! database's `pg_constraint` when `--database-url` is given.
 //!
-//! **Future Enhancement:** Future versions of diesel-guard will support optional database
-//! connections to verify constraint types with certainty.
+//! **Limitation:** Without a database, this check falls back to heuristic detection based on
+//! constraint naming patterns for other tables. It may not detect primary keys with
+//! non-standard names, and may occasionally flag non-primary-key constraints that follow
+//! similar naming patterns.
 
-use crate::checks::Check;
+use crate::checks::overlap::constraint_name_target;
+use crate::checks::{Check, MigrationContext};
 use crate::violation::Violation;
 use regex::Regex;
-use sqlparser::ast::{AlterTable, AlterTableOperation, Statement};
+use sqlparser::ast::{AlterTable, AlterTableOperation, Ident, ObjectName, Statement};
 use std::sync::LazyLock;
 
 /// Uses common PostgreSQL naming conventions:
@@ -36,10 +40,12 @@ impl DropPrimaryKeyCheck {
     fn is_likely_primary_key(constraint_name: &str) -> bool {
         PRIMARY_KEY_PATTERN.is_match(constraint_name)
     }
-}
 
-impl Check for DropPrimaryKeyCheck {
-    fn check(&self, stmt: &Statement) -> Vec<Violation> {
+    /// Violations for dropped constraints `is_primary_key` identifies as primary keys
+    fn drop_violations(
+        stmt: &Statement,
+        is_primary_key: impl Fn(&ObjectName, &Ident) -> bool,
+    ) -> Vec<Violation> {
         let Statement::AlterTable(AlterTable {
             name, operations, ..
         }) = stmt
@@ -60,13 +66,12 @@ impl Check for DropPrimaryKeyCheck {
                     return None;
                 };
 
-                let constraint_name_str = constraint_name.to_string();
-
-                // Only flag if the constraint name matches primary key patterns
-                if !Self::is_likely_primary_key(&constraint_name_str) {
+                if !is_primary_key(name, constraint_name) {
                     return None;
                 }
 
+                let constraint_name_str = constraint_name.to_string();
+
                 Some(Violation::new(
                     "DROP PRIMARY KEY",
                     format!(
@@ -95,22 +100,45 @@ impl Check for DropPrimaryKeyCheck {
    - Update application code gradually
    - Drop the old key only after full migration
 
-Note: This check uses naming pattern detection (e.g., '{constraint}' matches '*_pkey' pattern) and may not catch all cases.
-Future versions will support database connections for accurate constraint type verification.
-If this is a false positive, use a safety-assured block."#,
-                        table = table_name,
-                        constraint = constraint_name_str
+Note: For tables not created by earlier migrations, this check uses naming pattern detection (e.g., '*_pkey')
+unless --database-url is given, and may not catch all cases. If this is a false positive, use a safety-assured block."#,
+                        table = table_name
                     ),
-                ))
+                ).with_target(constraint_name_target(name, constraint_name)))
             })
             .collect()
     }
 }
 
+impl Check for DropPrimaryKeyCheck {
+    fn check(&self, stmt: &Statement) -> Vec<Violation> {
+        Self::drop_violations(stmt, |_, constraint| {
+            Self::is_likely_primary_key(&constraint.to_string())
+        })
+    }
+
+    fn check_in_migration(&self, stmt: &Statement, context: &MigrationContext) -> Vec<Violation> {
+        Self::drop_violations(stmt, |table, constraint| {
+            context
+                .schema()
+                .is_primary_key(table, constraint)
+                .or_else(|| {
+                    context
+                        .database_primary_keys()
+                        .and_then(|primary_keys| primary_keys.is_primary_key(table, constraint))
+                })
+                .unwrap_or_else(|| Self::is_likely_primary_key(&constraint.to_string()))
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::checks::test_utils::{check_sequence, check_sequence_in};
+    use crate::database::DatabasePrimaryKeys;
     use crate::{assert_allows, assert_detects_violation};
+    use std::sync::Arc;
 
     #[test]
     fn test_detects_drop_primary_key_pkey_suffix() {
@@ -148,6 +176,70 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_detects_primary_key_with_unconventional_name_from_schema() {
+        let violations = check_sequence(
+            &DropPrimaryKeyCheck,
+            "CREATE TABLE users (id BIGINT, CONSTRAINT users_identity PRIMARY KEY (id));
+             ALTER TABLE users DROP CONSTRAINT users_identity;",
+        );
+
+        assert_eq!(violations.len(), 1);
+        assert!(violations[0].problem.contains("'users_identity'"));
+    }
+
+    #[test]
+    fn test_allows_pk_like_name_that_isnt_primary_key_from_schema() {
+        assert!(check_sequence(&DropPrimaryKeyCheck,
+            "CREATE TABLE users (id BIGINT PRIMARY KEY, api_pk TEXT, CONSTRAINT users_api_pk UNIQUE (api_pk));
+             ALTER TABLE users DROP CONSTRAINT users_api_pk;",
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn test_falls_back_to_names_for_unknown_tables() {
+        assert_eq!(
+            check_sequence(
+                &DropPrimaryKeyCheck,
+                "ALTER TABLE users DROP CONSTRAINT users_pkey;"
+            )
+            .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_uses_database_primary_keys_for_unknown_tables() {
+        let context = || {
+            MigrationContext::default().with_database_primary_keys(Some(Arc::new(
+                DatabasePrimaryKeys::parse("public|users|users_identity"),
+            )))
+        };
+
+        let violations = check_sequence_in(
+            &DropPrimaryKeyCheck,
+            "ALTER TABLE users DROP CONSTRAINT users_identity;",
+            context(),
+        );
+        assert_eq!(violations.len(), 1);
+
+        assert!(check_sequence_in(
+            &DropPrimaryKeyCheck,
+            "ALTER TABLE users DROP CONSTRAINT users_api_pkey;",
+            context()
+        )
+        .is_empty());
+
+        // Tables missing from the database fall back to the constraint name
+        let violations = check_sequence_in(
+            &DropPrimaryKeyCheck,
+            "ALTER TABLE accounts DROP CONSTRAINT accounts_pkey;",
+            context(),
+        );
+        assert_eq!(violations.len(), 1);
+    }
+
     #[test]
     fn test_allows_drop_unique_constraint() {
         assert_allows!(
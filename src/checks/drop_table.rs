@@ -0,0 +1,120 @@
+//! Detection for DROP TABLE operations.
+//!
+//! This check identifies `DROP TABLE` statements, including the `IF EXISTS` and
+//! `CASCADE` forms.
+//!
+//! Dropping a table permanently deletes all of its data and acquires an ACCESS EXCLUSIVE
+//! lock, blocking all operations on the table. Application code that still references
+//! the table will fail immediately after deployment, and CASCADE silently drops dependent
+//! objects such as views and foreign key constraints on other tables.
+//!
+//! The recommended approach is a staged deprecation: stop using the table in code first,
+//! then drop it in a later migration once nothing references it.
+
+use crate::checks::{if_exists_clause, Check};
+use crate::violation::Violation;
+use sqlparser::ast::{ObjectType, Statement};
+
+pub struct DropTableCheck;
+
+impl Check for DropTableCheck {
+    fn check(&self, stmt: &Statement) -> Vec<Violation> {
+        let Statement::Drop {
+            object_type: ObjectType::Table,
+            if_exists,
+            names,
+            cascade,
+            ..
+        } = stmt
+        else {
+            return vec![];
+        };
+
+        names
+            .iter()
+            .map(|name| {
+                let table_name = name.to_string();
+                let cascade_note = if *cascade {
+                    " CASCADE also drops dependent objects such as views and foreign key constraints on other tables."
+                } else {
+                    ""
+                };
+
+                Violation::new(
+                    "DROP TABLE",
+                    format!(
+                        "Dropping table '{table}'{if_exists} permanently deletes all of its data and acquires an ACCESS EXCLUSIVE lock, \
+                        blocking all operations on the table. Running application code that still references the table will fail.{cascade}",
+                        table = table_name,
+                        if_exists = if_exists_clause(*if_exists),
+                        cascade = cascade_note
+                    ),
+                    format!(
+                        r#"Deprecate the table in stages:
+
+1. Remove all references to '{table}' from application code (including schema.rs)
+   and deploy that change.
+
+2. (Optional) Keep a backup of the data if it may be needed later:
+   CREATE TABLE {table}_archive AS TABLE {table};
+
+3. Drop the table in a separate, later migration:
+   DROP TABLE{if_exists} {table};
+
+Note: Drop foreign keys referencing the table explicitly instead of relying on CASCADE.
+Use a safety-assured block once the table is confirmed unused."#,
+                        table = table_name,
+                        if_exists = if_exists_clause(*if_exists)
+                    ),
+                )
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{assert_allows, assert_detects_violation};
+
+    #[test]
+    fn test_detects_drop_table() {
+        assert_detects_violation!(DropTableCheck, "DROP TABLE users;", "DROP TABLE");
+    }
+
+    #[test]
+    fn test_detects_drop_table_if_exists() {
+        assert_detects_violation!(DropTableCheck, "DROP TABLE IF EXISTS users;", "DROP TABLE");
+    }
+
+    #[test]
+    fn test_detects_drop_table_cascade() {
+        use crate::checks::test_utils::parse_sql;
+
+        let stmt = parse_sql("DROP TABLE users CASCADE;");
+        let violations = DropTableCheck.check(&stmt);
+
+        assert_eq!(violations.len(), 1);
+        assert!(violations[0].problem.contains("CASCADE"));
+    }
+
+    #[test]
+    fn test_detects_drop_multiple_tables() {
+        use crate::checks::test_utils::parse_sql;
+
+        let stmt = parse_sql("DROP TABLE users, orders;");
+        let violations = DropTableCheck.check(&stmt);
+
+        assert_eq!(violations.len(), 2, "Expected 2 violations (one per table)");
+    }
+
+    #[test]
+    fn test_ignores_drop_index() {
+        assert_allows!(DropTableCheck, "DROP INDEX idx_users_email;");
+    }
+
+    #[test]
+    fn test_ignores_drop_view() {
+        assert_allows!(DropTableCheck, "DROP VIEW active_users;");
+    }
+}
@@ -0,0 +1,300 @@
+//! Detection for duplicate indexes within a migration.
+//!
+//! This check compares the `CREATE INDEX` statements of a migration with each other and
+//! with the indexes implied by PRIMARY KEY and UNIQUE constraints in the same migration
+//! (in `CREATE TABLE` and `ALTER TABLE ... ADD CONSTRAINT`). An index is a duplicate when
+//! another one covers the same table and columns, in the same order, with the same index
+//! method and predicate.
+//!
+//! A duplicate index is built for nothing: the build scans the table (and locks it unless
+//! created concurrently), and afterwards every write maintains both indexes.
+//!
+//! A unique index isn't considered a duplicate of a non-unique one, since it also
+//! enforces uniqueness; the non-unique index is reported instead.
+//!
+//! The recommended approach is removing the duplicate from the migration.
+
+use crate::checks::{display_or_default, Check};
+use crate::schema::{expr_key, object_key};
+use crate::violation::Violation;
+use sqlparser::ast::{
+    AlterTable, AlterTableOperation, ColumnOption, IndexColumn, IndexType, Statement,
+    TableConstraint,
+};
+
+pub struct DuplicateIndexCheck;
+
+/// An index created or implied by a statement of the migration
+struct Index {
+    stmt_idx: usize,
+    /// Whether the index comes from CREATE INDEX (rather than a constraint)
+    explicit: bool,
+    name: String,
+    table: String,
+    columns: Vec<String>,
+    unique: bool,
+    using: String,
+    predicate: Option<String>,
+}
+
+impl Index {
+    /// Index implied by a PRIMARY KEY or UNIQUE constraint (always a btree)
+    fn implied(stmt_idx: usize, name: String, table: &str, columns: Vec<String>) -> Self {
+        Self {
+            stmt_idx,
+            explicit: false,
+            name,
+            table: table.to_string(),
+            columns,
+            unique: true,
+            using: IndexType::BTree.to_string(),
+            predicate: None,
+        }
+    }
+
+    /// Whether this index makes `other` redundant
+    fn covers(&self, other: &Index) -> bool {
+        self.table == other.table
+            && self.columns == other.columns
+            && self.using == other.using
+            && self.predicate == other.predicate
+            && (self.unique || !other.unique)
+    }
+}
+
+fn index_columns(columns: &[IndexColumn]) -> Vec<String> {
+    columns.iter().map(|c| expr_key(&c.to_string())).collect()
+}
+
+impl DuplicateIndexCheck {
+    /// Index implied by a table constraint, if any
+    fn constraint_index(
+        stmt_idx: usize,
+        table: &str,
+        constraint: &TableConstraint,
+    ) -> Option<Index> {
+        let (kind, name, columns) = match constraint {
+            TableConstraint::PrimaryKey(pk) => ("PRIMARY KEY", &pk.name, &pk.columns),
+            TableConstraint::Unique(unique) => ("UNIQUE", &unique.name, &unique.columns),
+            _ => return None,
+        };
+        let name = name
+            .as_ref()
+            .map(|name| name.to_string())
+            .unwrap_or_else(|| format!("{} constraint", kind));
+        Some(Index::implied(
+            stmt_idx,
+            name,
+            table,
+            index_columns(columns),
+        ))
+    }
+
+    fn indexes(stmts: &[Statement]) -> Vec<Index> {
+        let mut indexes = vec![];
+
+        for (stmt_idx, stmt) in stmts.iter().enumerate() {
+            match stmt {
+                Statement::CreateIndex(index) => indexes.push(Index {
+                    stmt_idx,
+                    explicit: true,
+                    name: display_or_default(index.name.as_ref(), "<unnamed>"),
+                    table: object_key(&index.table_name),
+                    columns: index_columns(&index.columns),
+                    unique: index.unique,
+                    using: index
+                        .using
+                        .as_ref()
+                        .unwrap_or(&IndexType::BTree)
+                        .to_string(),
+                    predicate: index.predicate.as_ref().map(|p| expr_key(&p.to_string())),
+                }),
+                Statement::CreateTable(create) => {
+                    let table = object_key(&create.name);
+                    for column in &create.columns {
+                        for opt in &column.options {
+                            let kind = match opt.option {
+                                ColumnOption::PrimaryKey(_) => "PRIMARY KEY",
+                                ColumnOption::Unique(_) => "UNIQUE",
+                                _ => continue,
+                            };
+                            let name = opt
+                                .name
+                                .as_ref()
+                                .map(|name| name.to_string())
+                                .unwrap_or_else(|| format!("{} constraint", kind));
+                            indexes.push(Index::implied(
+                                stmt_idx,
+                                name,
+                                &table,
+                                vec![expr_key(&column.name.to_string())],
+                            ));
+                        }
+                    }
+                    indexes.extend(
+                        create
+                            .constraints
+                            .iter()
+                            .filter_map(|c| Self::constraint_index(stmt_idx, &table, c)),
+                    );
+                }
+                Statement::AlterTable(AlterTable {
+                    name, operations, ..
+                }) => {
+                    let table = object_key(name);
+                    indexes.extend(operations.iter().filter_map(|op| match op {
+                        AlterTableOperation::AddConstraint { constraint, .. } => {
+                            Self::constraint_index(stmt_idx, &table, constraint)
+                        }
+                        _ => None,
+                    }));
+                }
+                _ => {}
+            }
+        }
+
+        indexes
+    }
+}
+
+impl Check for DuplicateIndexCheck {
+    fn check(&self, _stmt: &Statement) -> Vec<Violation> {
+        vec![]
+    }
+
+    fn check_migration(&self, stmts: &[Statement]) -> Vec<(usize, Violation)> {
+        let indexes = Self::indexes(stmts);
+
+        indexes
+            .iter()
+            .enumerate()
+            .filter(|(_, index)| index.explicit)
+            .filter_map(|(i, index)| {
+                // Constraints and unique indexes take precedence wherever they appear; among
+                // equivalent CREATE INDEX statements, the later one is the duplicate
+                let original = indexes.iter().enumerate().find(|(j, other)| {
+                    *j != i
+                        && (!other.explicit || *j < i || (other.unique && !index.unique))
+                        && other.covers(index)
+                })?;
+                let original = original.1;
+
+                Some((
+                    index.stmt_idx,
+                    Violation::new(
+                        "duplicate index",
+                        format!(
+                            "Index '{index}' on table '{table}' ({columns}) duplicates '{original}' from the same migration. \
+                            Building it scans the table for nothing, and every write to '{table}' then maintains both indexes.",
+                            index = index.name,
+                            table = index.table,
+                            columns = index.columns.join(", "),
+                            original = original.name
+                        ),
+                        format!(
+                            r#"Remove the duplicate index from the migration; '{original}' already covers ({columns}).
+
+Note: If the indexes differ in a way this check doesn't see (e.g. storage parameters), use a
+safety-assured block."#,
+                            original = original.name,
+                            columns = index.columns.join(", ")
+                        ),
+                    ),
+                ))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::checks::test_utils::check_sql;
+
+    #[test]
+    fn test_detects_duplicate_create_index() {
+        let violations = check_sql(
+            &DuplicateIndexCheck,
+            "CREATE INDEX CONCURRENTLY users_email_idx ON users (email);
+             CREATE INDEX CONCURRENTLY index_users_on_email ON users (email);",
+        );
+
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].0, 1);
+        assert_eq!(violations[0].1.operation, "duplicate index");
+        assert!(violations[0].1.problem.contains("'index_users_on_email'"));
+        assert!(violations[0]
+            .1
+            .problem
+            .contains("duplicates 'users_email_idx'"));
+    }
+
+    #[test]
+    fn test_detects_index_duplicating_constraint() {
+        let violations = check_sql(&DuplicateIndexCheck,
+            "CREATE TABLE users (id BIGINT PRIMARY KEY, email TEXT, CONSTRAINT users_email_key UNIQUE (email));
+             CREATE INDEX users_id_idx ON users (id);
+             CREATE UNIQUE INDEX users_email_idx ON users (email);",
+        );
+
+        assert_eq!(violations.len(), 2);
+        assert!(violations[0].1.problem.contains("'PRIMARY KEY constraint'"));
+        assert!(violations[1].1.problem.contains("'users_email_key'"));
+    }
+
+    #[test]
+    fn test_detects_index_before_add_constraint() {
+        let violations = check_sql(
+            &DuplicateIndexCheck,
+            "CREATE INDEX users_email_idx ON users (email);
+             ALTER TABLE users ADD CONSTRAINT users_email_key UNIQUE (email);",
+        );
+
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].0, 0);
+    }
+
+    #[test]
+    fn test_allows_different_columns_order_or_predicate() {
+        assert!(check_sql(
+            &DuplicateIndexCheck,
+            "CREATE INDEX a_idx ON users (email, name);
+             CREATE INDEX b_idx ON users (name, email);
+             CREATE INDEX c_idx ON users (email, name) WHERE deleted_at IS NULL;
+             CREATE INDEX d_idx ON users USING hash (email);
+             CREATE INDEX e_idx ON accounts (email, name);"
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn test_predicates_compare_string_literals_exactly() {
+        assert!(check_sql(
+            &DuplicateIndexCheck,
+            "CREATE INDEX active_idx ON users (email) WHERE status = 'Active';
+             CREATE INDEX lower_active_idx ON users (email) WHERE status = 'active';"
+        )
+        .is_empty());
+
+        // Unquoted identifiers and keywords still fold
+        let violations = check_sql(
+            &DuplicateIndexCheck,
+            "CREATE INDEX active_idx ON users (email) WHERE Status = 'Active';
+             CREATE INDEX active_copy_idx ON users (\"email\") where status = 'Active';",
+        );
+        assert_eq!(violations.len(), 1);
+    }
+
+    #[test]
+    fn test_reports_plain_index_duplicating_unique_one() {
+        let violations = check_sql(
+            &DuplicateIndexCheck,
+            "CREATE INDEX users_email_idx ON users (email);
+             CREATE UNIQUE INDEX users_email_key ON users (email);",
+        );
+
+        // The unique index enforces a constraint; the plain one is the redundant index
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].0, 0);
+    }
+}
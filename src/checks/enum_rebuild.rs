@@ -0,0 +1,182 @@
+//! Detection for rebuilding an enum type to remove or reorder values.
+//!
+//! PostgreSQL can't drop enum values, so a common workaround is to recreate the type
+//! within one migration:
+//!
+//! ```sql
+//! ALTER TYPE status RENAME TO status_old;
+//! CREATE TYPE status AS ENUM ('active', 'archived');
+//! ALTER TABLE orders ALTER COLUMN status TYPE status USING status::text::status;
+//! DROP TYPE status_old;
+//! ```
+//!
+//! This check looks across all statements of a migration for a column changed to a
+//! user-defined type followed by a `DROP TYPE`. Each column change rewrites its table
+//! under an ACCESS EXCLUSIVE lock, and running application instances holding the old
+//! type's OID in prepared statements or caches fail once it is dropped.
+//!
+//! The recommended approach is a text column with a CHECK constraint, or leaving the
+//! unused value in place.
+
+use crate::checks::Check;
+use crate::violation::Violation;
+use sqlparser::ast::{
+    AlterColumnOperation, AlterTable, AlterTableOperation, DataType, ObjectType, Statement,
+};
+
+pub struct EnumRebuildCheck;
+
+impl EnumRebuildCheck {
+    /// Columns changed to a user-defined type, as "table.column"
+    fn retyped_columns(stmt: &Statement) -> Vec<String> {
+        let Statement::AlterTable(AlterTable {
+            name, operations, ..
+        }) = stmt
+        else {
+            return vec![];
+        };
+
+        operations
+            .iter()
+            .filter_map(|op| match op {
+                AlterTableOperation::AlterColumn {
+                    column_name,
+                    op:
+                        AlterColumnOperation::SetDataType {
+                            data_type: DataType::Custom(..),
+                            ..
+                        },
+                } => Some(format!("{}.{}", name, column_name)),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+impl Check for EnumRebuildCheck {
+    fn check(&self, _stmt: &Statement) -> Vec<Violation> {
+        vec![]
+    }
+
+    fn check_migration(&self, stmts: &[Statement]) -> Vec<(usize, Violation)> {
+        let mut columns: Vec<String> = vec![];
+
+        for (idx, stmt) in stmts.iter().enumerate() {
+            columns.extend(Self::retyped_columns(stmt));
+
+            let Statement::Drop {
+                object_type: ObjectType::Type,
+                names,
+                ..
+            } = stmt
+            else {
+                continue;
+            };
+            if columns.is_empty() {
+                continue;
+            }
+
+            let type_name = names
+                .iter()
+                .map(|n| n.to_string())
+                .collect::<Vec<_>>()
+                .join(", ");
+
+            // One violation per migration, on the DROP TYPE completing the rebuild
+            return vec![(
+                idx,
+                Violation::new(
+                    "enum rebuild",
+                    format!(
+                        "Rebuilding enum type '{type_name}': changing the type of {columns} rewrites each table under an \
+                        ACCESS EXCLUSIVE lock, blocking all reads and writes, and dropping '{type_name}' breaks running \
+                        application instances that still reference the old type. Duration depends on table size.",
+                        type_name = type_name,
+                        columns = columns.join(", ")
+                    ),
+                    r#"Avoid recreating enum types on populated tables:
+
+1. To retire a value, leave it in the type and stop writing it. Backfill existing rows
+   in batches (outside migration):
+   UPDATE <table> SET <column> = '<replacement>' WHERE <column> = '<retired>';
+
+2. If the set of values changes often, use a text column with a CHECK constraint instead,
+   which can be replaced without a rewrite:
+   ALTER TABLE <table> ADD CONSTRAINT <column>_values
+     CHECK (<column> IN ('<value1>', '<value2>')) NOT VALID;
+   ALTER TABLE <table> VALIDATE CONSTRAINT <column>_values;
+
+3. To convert an existing column, add the new column, dual-write and backfill it,
+   then switch reads and drop the old column in later migrations."#
+                        .to_string(),
+                ),
+            )];
+        }
+
+        vec![]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::checks::test_utils::check_sql;
+
+    #[test]
+    fn test_detects_enum_rebuild() {
+        let violations = check_sql(
+            &EnumRebuildCheck,
+            "ALTER TYPE status RENAME TO status_old;
+             CREATE TYPE status AS ENUM ('active', 'archived');
+             ALTER TABLE orders ALTER COLUMN status TYPE status USING status::text::status;
+             DROP TYPE status_old;",
+        );
+
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].0, 3);
+        assert_eq!(violations[0].1.operation, "enum rebuild");
+        assert!(violations[0].1.problem.contains("orders.status"));
+        assert!(violations[0].1.problem.contains("'status_old'"));
+    }
+
+    #[test]
+    fn test_reports_once_for_multiple_columns() {
+        let violations = check_sql(
+            &EnumRebuildCheck,
+            "ALTER TABLE orders ALTER COLUMN status TYPE status_new USING status::text::status_new;
+             ALTER TABLE refunds ALTER COLUMN status TYPE status_new USING status::text::status_new;
+             DROP TYPE status;
+             DROP TYPE other;",
+        );
+
+        assert_eq!(violations.len(), 1);
+        assert!(violations[0]
+            .1
+            .problem
+            .contains("orders.status, refunds.status"));
+    }
+
+    #[test]
+    fn test_allows_drop_type_alone() {
+        assert!(check_sql(&EnumRebuildCheck, "DROP TYPE status;").is_empty());
+    }
+
+    #[test]
+    fn test_allows_drop_type_before_column_change() {
+        assert!(check_sql(&EnumRebuildCheck,
+            "DROP TYPE unused_status;
+             ALTER TABLE orders ALTER COLUMN status TYPE status_new USING status::text::status_new;"
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn test_allows_change_to_builtin_type() {
+        assert!(check_sql(
+            &EnumRebuildCheck,
+            "ALTER TABLE orders ALTER COLUMN status TYPE TEXT;
+             DROP TYPE status;"
+        )
+        .is_empty());
+    }
+}
@@ -0,0 +1,336 @@
+//! Detection for tables accumulating many indexes.
+//!
+//! This check counts the indexes each table has once a migration has run: those
+//! created by earlier migrations of the same migrations directory, plus the ones the
+//! migration creates with `CREATE INDEX` or implies with PRIMARY KEY and UNIQUE
+//! constraints (in `CREATE TABLE` and `ALTER TABLE ... ADD CONSTRAINT`). Indexes
+//! dropped with `DROP INDEX` don't count. Each table above the limit is reported once,
+//! with its final count. Its violations are warnings by default.
+//!
+//! Every index is maintained on every write to the table, so each additional one slows
+//! down INSERTs and most UPDATEs, adds WAL volume, and prevents HOT updates of the
+//! indexed columns. The threshold is set with `[checks.ExcessiveIndexesCheck] max_indexes`.
+//!
+//! The recommended approach is consolidating indexes, or dropping the ones queries
+//! don't use.
+
+use crate::checks::Check;
+use crate::config::ExcessiveIndexesConfig;
+use crate::parser::ParsedSql;
+use crate::schema::{index_key, object_key, Schema};
+use crate::violation::{Severity, Violation};
+use sqlparser::ast::{
+    AlterTable, AlterTableOperation, ColumnOption, Ident, ObjectName, ObjectType, Statement,
+    TableConstraint,
+};
+use std::collections::{BTreeMap, HashSet};
+
+#[derive(Default)]
+pub struct ExcessiveIndexesCheck {
+    config: ExcessiveIndexesConfig,
+}
+
+impl ExcessiveIndexesCheck {
+    pub fn new(config: ExcessiveIndexesConfig) -> Self {
+        Self { config }
+    }
+
+    fn violation(&self, indexes: &TableIndexes) -> Violation {
+        let count = indexes.count();
+        let created = indexes.created.len();
+        let table = &indexes.table;
+        Violation::new(
+            "too many indexes",
+            format!(
+                "Table '{table}' has {count} indexes after this migration ({created} created by it), more than the \
+                configured maximum of {max}. Every index is updated on each write to '{table}', slowing down INSERTs \
+                and UPDATEs and adding WAL volume.",
+                table = table,
+                count = count,
+                created = created,
+                max = self.config.max_indexes
+            ),
+            format!(
+                r#"Keep the number of indexes on '{table}' down:
+
+1. Combine indexes that share leading columns into one multi-column index.
+
+2. Use partial indexes (CREATE INDEX ... WHERE ...) for queries on a subset of rows.
+
+3. Check which existing indexes queries actually use, and drop the rest:
+   SELECT indexrelname, idx_scan FROM pg_stat_user_indexes WHERE relname = '{table}';
+
+Note: To allow more indexes, raise [checks.ExcessiveIndexesCheck] max_indexes."#,
+                table = table
+            ),
+        )
+    }
+}
+
+/// Indexes of one table while a migration is replayed
+struct TableIndexes {
+    table: String,
+    /// Indexes created by earlier migrations that are still there
+    existing: usize,
+    /// Names of the indexes created by this migration (None for unnamed ones)
+    created: Vec<Option<String>>,
+    /// Line of the last statement that left the table above the limit
+    line: Option<usize>,
+}
+
+impl TableIndexes {
+    fn count(&self) -> usize {
+        self.existing + self.created.len()
+    }
+}
+
+fn implies_index(constraint: &TableConstraint) -> bool {
+    matches!(
+        constraint,
+        TableConstraint::PrimaryKey(_) | TableConstraint::Unique(_)
+    )
+}
+
+/// Indexes of a table, starting from the ones earlier migrations created
+fn table_indexes<'a>(
+    tables: &'a mut BTreeMap<String, TableIndexes>,
+    schema: &Schema,
+    name: &ObjectName,
+) -> &'a mut TableIndexes {
+    tables
+        .entry(object_key(name))
+        .or_insert_with(|| TableIndexes {
+            table: name.to_string(),
+            existing: schema.index_count(name),
+            created: vec![],
+            line: None,
+        })
+}
+
+/// Table and the indexes a statement creates on it
+fn created_indexes(stmt: &Statement) -> Option<(&ObjectName, Vec<Option<String>>)> {
+    match stmt {
+        Statement::CreateIndex(index) => {
+            Some((&index.table_name, vec![index.name.as_ref().map(index_key)]))
+        }
+        Statement::CreateTable(create) => {
+            let inline = create
+                .columns
+                .iter()
+                .flat_map(|column| &column.options)
+                .filter(|opt| {
+                    matches!(
+                        opt.option,
+                        ColumnOption::PrimaryKey(_) | ColumnOption::Unique(_)
+                    )
+                })
+                .map(|_| None);
+            let constraints = create
+                .constraints
+                .iter()
+                .filter(|c| implies_index(c))
+                .map(|_| None);
+            Some((&create.name, inline.chain(constraints).collect()))
+        }
+        Statement::AlterTable(AlterTable {
+            name, operations, ..
+        }) => Some((
+            name,
+            operations
+                .iter()
+                .filter(|op| match op {
+                    AlterTableOperation::AddConstraint { constraint, .. } => {
+                        implies_index(constraint)
+                    }
+                    _ => false,
+                })
+                .map(|_| None)
+                .collect(),
+        )),
+        _ => None,
+    }
+}
+
+impl Check for ExcessiveIndexesCheck {
+    fn check(&self, _stmt: &Statement) -> Vec<Violation> {
+        vec![]
+    }
+
+    fn check_parsed(&self, parsed: &ParsedSql) -> Vec<(usize, Violation)> {
+        let max = self.config.max_indexes;
+        let mut tables: BTreeMap<String, TableIndexes> = BTreeMap::new();
+        // Indexes of earlier migrations dropped by this one
+        let mut dropped = HashSet::new();
+
+        for parsed_stmt in &parsed.statements {
+            if let Statement::Drop {
+                object_type: ObjectType::Index,
+                names,
+                ..
+            } = &parsed_stmt.stmt
+            {
+                for name in names {
+                    let key = index_key(name);
+                    let created = tables.values_mut().find_map(|table| {
+                        let position = table
+                            .created
+                            .iter()
+                            .position(|index| index.as_ref() == Some(&key))?;
+                        Some((table, position))
+                    });
+                    if let Some((table, position)) = created {
+                        table.created.remove(position);
+                    } else if let Some(index) = parsed.schema.index(name) {
+                        if dropped.insert(key) {
+                            let table = ObjectName::from(vec![Ident::new(index.table.clone())]);
+                            let table = table_indexes(&mut tables, &parsed.schema, &table);
+                            table.existing = table.existing.saturating_sub(1);
+                        }
+                    }
+                }
+                continue;
+            }
+
+            let Some((name, created)) = created_indexes(&parsed_stmt.stmt) else {
+                continue;
+            };
+            if created.is_empty() {
+                continue;
+            }
+
+            let table = table_indexes(&mut tables, &parsed.schema, name);
+            table.created.extend(created);
+            if table.count() > max {
+                table.line = Some(parsed_stmt.line);
+            }
+        }
+
+        tables
+            .into_values()
+            .filter(|table| table.count() > max)
+            .filter_map(|table| Some((table.line?, self.violation(&table))))
+            .collect()
+    }
+
+    fn default_severity(&self) -> Severity {
+        Severity::Warning
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::SqlParser;
+    use std::sync::Arc;
+
+    fn check_sql(max_indexes: usize, sql: &str, schema: Schema) -> Vec<(usize, Violation)> {
+        let mut parsed = SqlParser::new().parse_with_metadata(sql).unwrap();
+        parsed.schema = Arc::new(schema);
+        ExcessiveIndexesCheck::new(ExcessiveIndexesConfig { max_indexes }).check_parsed(&parsed)
+    }
+
+    /// Schema left by an earlier migration
+    fn schema(sql: &str) -> Schema {
+        let mut schema = Schema::default();
+        schema.apply(&SqlParser::new().parse(sql).unwrap());
+        schema
+    }
+
+    const REPORTS: &str = "CREATE TABLE reports (id BIGINT PRIMARY KEY, account_id BIGINT, kind TEXT, created_at TIMESTAMPTZ);
+        CREATE INDEX reports_account_id_idx ON reports (account_id);
+        CREATE INDEX reports_kind_idx ON reports (kind);
+        CREATE INDEX reports_created_at_idx ON reports (created_at);";
+
+    #[test]
+    fn test_detects_indexes_above_threshold() {
+        let violations = check_sql(3, REPORTS, Schema::default());
+
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].0, 4);
+        assert_eq!(violations[0].1.operation, "too many indexes");
+        assert!(violations[0]
+            .1
+            .problem
+            .contains("has 4 indexes after this migration (4 created by it)"));
+    }
+
+    #[test]
+    fn test_reports_each_table_once_with_final_count() {
+        let sql = format!(
+            "{}
+            CREATE INDEX reports_other_idx ON reports (account_id, kind);",
+            REPORTS
+        );
+        let violations = check_sql(3, &sql, Schema::default());
+
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].0, 5);
+        assert!(violations[0].1.problem.contains("has 5 indexes"));
+    }
+
+    #[test]
+    fn test_counts_indexes_of_earlier_migrations() {
+        let earlier = schema(
+            "CREATE TABLE users (id BIGINT PRIMARY KEY, email TEXT, name TEXT);
+             CREATE INDEX users_email_idx ON users (email);
+             CREATE INDEX users_name_idx ON users (name);",
+        );
+
+        let violations = check_sql(
+            3,
+            "CREATE INDEX users_created_at_idx ON users (created_at);",
+            earlier.clone(),
+        );
+        assert_eq!(violations.len(), 1);
+        assert!(violations[0]
+            .1
+            .problem
+            .contains("has 4 indexes after this migration (1 created by it)"));
+
+        // Dropping an older index makes room for the new one
+        assert!(check_sql(
+            3,
+            "DROP INDEX users_name_idx;
+             CREATE INDEX users_created_at_idx ON users (created_at);",
+            earlier.clone(),
+        )
+        .is_empty());
+
+        // Tables the migration doesn't add indexes to aren't reported
+        assert!(check_sql(1, "DROP INDEX users_name_idx;", earlier).is_empty());
+    }
+
+    #[test]
+    fn test_counts_constraints_and_drops() {
+        assert!(check_sql(4, REPORTS, Schema::default()).is_empty());
+
+        let violations = check_sql(
+            2,
+            "ALTER TABLE users ADD CONSTRAINT users_email_key UNIQUE (email);
+             CREATE INDEX users_name_idx ON users (name);
+             DROP INDEX users_name_idx;
+             CREATE INDEX users_created_at_idx ON users (created_at);",
+            Schema::default(),
+        );
+        assert!(violations.is_empty());
+    }
+
+    #[test]
+    fn test_counts_tables_separately() {
+        assert!(check_sql(
+            1,
+            "CREATE INDEX users_email_idx ON users (email);
+             CREATE INDEX posts_user_id_idx ON posts (user_id);",
+            Schema::default(),
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn test_defaults_to_warning() {
+        assert_eq!(
+            ExcessiveIndexesCheck::default().default_severity(),
+            Severity::Warning
+        );
+    }
+}
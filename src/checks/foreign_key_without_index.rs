@@ -0,0 +1,266 @@
+//! Detection for foreign keys without an index on the referencing columns.
+//!
+//! This check looks across all statements of a migration for foreign keys (in
+//! `CREATE TABLE`, `ADD CONSTRAINT`, and `ADD COLUMN ... REFERENCES`) whose referencing
+//! columns aren't the leading columns of an index created in the same migration,
+//! whether by `CREATE INDEX` or implied by a PRIMARY KEY or UNIQUE constraint.
+//!
+//! PostgreSQL doesn't index referencing columns automatically. Without an index, every
+//! delete or key update on the parent table scans the whole child table to check for
+//! references, holding locks for the duration, and joins along the key are slow.
+//!
+//! Violations are warnings by default, since the index may already exist from an
+//! earlier migration.
+//!
+//! The recommended approach is creating an index on the referencing columns.
+
+use crate::checks::Check;
+use crate::schema::{expr_key, object_key};
+use crate::violation::{Severity, Violation};
+use sqlparser::ast::{
+    AlterTable, AlterTableOperation, ColumnDef, ColumnOption, IndexColumn, Statement,
+    TableConstraint,
+};
+
+pub struct ForeignKeyWithoutIndexCheck;
+
+/// A foreign key found in the migration
+struct ForeignKey {
+    stmt_idx: usize,
+    table: String,
+    columns: Vec<String>,
+    foreign_table: String,
+}
+
+fn index_columns(columns: &[IndexColumn]) -> Vec<String> {
+    columns
+        .iter()
+        .map(|c| expr_key(&c.column.expr.to_string()))
+        .collect()
+}
+
+impl ForeignKeyWithoutIndexCheck {
+    /// Foreign keys and indexed column lists declared inline on a column
+    fn column_constraints(
+        table: &str,
+        column: &ColumnDef,
+        foreign_keys: &mut Vec<(String, Vec<String>, String)>,
+        indexes: &mut Vec<(String, Vec<String>)>,
+    ) {
+        let name = expr_key(&column.name.to_string());
+        for opt in &column.options {
+            match &opt.option {
+                ColumnOption::ForeignKey(fk) => foreign_keys.push((
+                    table.to_string(),
+                    vec![name.clone()],
+                    fk.foreign_table.to_string(),
+                )),
+                ColumnOption::PrimaryKey(_) | ColumnOption::Unique(_) => {
+                    indexes.push((table.to_string(), vec![name.clone()]))
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Foreign keys and indexed column lists declared as a table constraint
+    fn table_constraint(
+        table: &str,
+        constraint: &TableConstraint,
+        foreign_keys: &mut Vec<(String, Vec<String>, String)>,
+        indexes: &mut Vec<(String, Vec<String>)>,
+    ) {
+        match constraint {
+            TableConstraint::ForeignKey(fk) => foreign_keys.push((
+                table.to_string(),
+                fk.columns
+                    .iter()
+                    .map(|c| expr_key(&c.to_string()))
+                    .collect(),
+                fk.foreign_table.to_string(),
+            )),
+            TableConstraint::PrimaryKey(pk) => {
+                indexes.push((table.to_string(), index_columns(&pk.columns)))
+            }
+            TableConstraint::Unique(unique) => {
+                indexes.push((table.to_string(), index_columns(&unique.columns)))
+            }
+            _ => {}
+        }
+    }
+
+    /// Whether an index's leading columns are exactly the foreign key's columns
+    fn covers(index: &[String], fk_columns: &[String]) -> bool {
+        index.len() >= fk_columns.len()
+            && fk_columns
+                .iter()
+                .all(|column| index[..fk_columns.len()].contains(column))
+    }
+}
+
+impl Check for ForeignKeyWithoutIndexCheck {
+    fn check(&self, _stmt: &Statement) -> Vec<Violation> {
+        vec![]
+    }
+
+    fn check_migration(&self, stmts: &[Statement]) -> Vec<(usize, Violation)> {
+        let mut foreign_keys: Vec<ForeignKey> = vec![];
+        let mut indexes: Vec<(String, Vec<String>)> = vec![];
+
+        for (stmt_idx, stmt) in stmts.iter().enumerate() {
+            let mut found = vec![];
+            match stmt {
+                Statement::CreateTable(create) => {
+                    let table = object_key(&create.name);
+                    for column in &create.columns {
+                        Self::column_constraints(&table, column, &mut found, &mut indexes);
+                    }
+                    for constraint in &create.constraints {
+                        Self::table_constraint(&table, constraint, &mut found, &mut indexes);
+                    }
+                }
+                Statement::AlterTable(AlterTable {
+                    name, operations, ..
+                }) => {
+                    let table = object_key(name);
+                    for op in operations {
+                        match op {
+                            AlterTableOperation::AddConstraint { constraint, .. } => {
+                                Self::table_constraint(&table, constraint, &mut found, &mut indexes)
+                            }
+                            AlterTableOperation::AddColumn { column_def, .. } => {
+                                Self::column_constraints(
+                                    &table,
+                                    column_def,
+                                    &mut found,
+                                    &mut indexes,
+                                )
+                            }
+                            _ => {}
+                        }
+                    }
+                }
+                Statement::CreateIndex(index) => {
+                    indexes.push((object_key(&index.table_name), index_columns(&index.columns)))
+                }
+                _ => {}
+            }
+
+            foreign_keys.extend(found.into_iter().map(|(table, columns, foreign_table)| {
+                ForeignKey {
+                    stmt_idx,
+                    table,
+                    columns,
+                    foreign_table,
+                }
+            }));
+        }
+
+        foreign_keys
+            .into_iter()
+            .filter(|fk| {
+                !indexes.iter().any(|(table, columns)| {
+                    *table == fk.table && Self::covers(columns, &fk.columns)
+                })
+            })
+            .map(|fk| {
+                let columns = fk.columns.join(", ");
+                let index_name = format!("{}_{}_idx", fk.table, fk.columns.join("_"));
+                (
+                    fk.stmt_idx,
+                    Violation::new(
+                        "FOREIGN KEY without index",
+                        format!(
+                            "Foreign key on '{table}' ({columns}) referencing '{foreign_table}' has no index on its referencing \
+                            columns in this migration. Every delete or key update on '{foreign_table}' then scans all of \
+                            '{table}' to check for references, holding locks while it runs.",
+                            table = fk.table,
+                            columns = columns,
+                            foreign_table = fk.foreign_table
+                        ),
+                        format!(
+                            r#"Index the referencing columns:
+
+   CREATE INDEX CONCURRENTLY {index_name} ON {table} ({columns});
+
+Note: CONCURRENTLY requires metadata.toml with run_in_transaction = false. For a table
+created in the same migration, a plain CREATE INDEX is fine. If the index already exists
+from an earlier migration, ignore this warning."#,
+                            index_name = index_name,
+                            table = fk.table,
+                            columns = columns
+                        ),
+                    ),
+                )
+            })
+            .collect()
+    }
+
+    fn default_severity(&self) -> Severity {
+        Severity::Warning
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::checks::test_utils::check_sql;
+
+    #[test]
+    fn test_detects_foreign_key_in_create_table_without_index() {
+        let violations = check_sql(&ForeignKeyWithoutIndexCheck,
+            "CREATE TABLE posts (id BIGINT PRIMARY KEY, user_id BIGINT NOT NULL REFERENCES users (id));",
+        );
+
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].0, 0);
+        assert_eq!(violations[0].1.operation, "FOREIGN KEY without index");
+        assert!(violations[0]
+            .1
+            .safe_alternative
+            .contains("CREATE INDEX CONCURRENTLY posts_user_id_idx ON posts (user_id)"));
+    }
+
+    #[test]
+    fn test_detects_add_constraint_without_index() {
+        let violations = check_sql(&ForeignKeyWithoutIndexCheck,
+            "ALTER TABLE posts ADD CONSTRAINT posts_user_fk FOREIGN KEY (user_id) REFERENCES users (id) NOT VALID;
+             CREATE INDEX posts_created_at_idx ON posts (created_at);",
+        );
+
+        assert_eq!(violations.len(), 1);
+    }
+
+    #[test]
+    fn test_allows_foreign_key_with_index_in_same_migration() {
+        assert!(check_sql(&ForeignKeyWithoutIndexCheck,
+            "ALTER TABLE posts ADD CONSTRAINT posts_user_fk FOREIGN KEY (user_id) REFERENCES users (id) NOT VALID;
+             CREATE INDEX CONCURRENTLY posts_user_id_idx ON posts (user_id, created_at);"
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn test_allows_foreign_key_covered_by_primary_key_or_unique() {
+        assert!(check_sql(
+            &ForeignKeyWithoutIndexCheck,
+            "CREATE TABLE memberships (
+               user_id BIGINT REFERENCES users (id),
+               team_id BIGINT REFERENCES teams (id),
+               PRIMARY KEY (user_id, team_id),
+               UNIQUE (team_id, user_id)
+             );"
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn test_index_must_lead_with_foreign_key_columns() {
+        let violations = check_sql(&ForeignKeyWithoutIndexCheck,
+            "ALTER TABLE posts ADD CONSTRAINT posts_user_fk FOREIGN KEY (user_id) REFERENCES users (id) NOT VALID;
+             CREATE INDEX posts_created_at_user_id_idx ON posts (created_at, user_id);",
+        );
+
+        assert_eq!(violations.len(), 1);
+    }
+}
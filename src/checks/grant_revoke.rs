@@ -0,0 +1,122 @@
+//! Detection for GRANT and REVOKE in migrations.
+//!
+//! This check identifies `GRANT` and `REVOKE` statements, both for privileges on
+//! objects and for role membership.
+//!
+//! Like CREATE EXTENSION, privilege management usually needs rights the application's
+//! migration user doesn't have in production (it must own the objects or hold the
+//! grant option), and which roles exist differs between environments: a GRANT to a
+//! role that only exists in production fails everywhere else. Privileges are access
+//! control, which belongs with the infrastructure that creates the roles.
+//!
+//! **Parser Handling**: sqlparser parses GRANT and REVOKE of privileges, which are
+//! checked on the AST. Forms it fails on, such as granting role membership, are checked
+//! as source text (see [`Check::check_raw`]).
+//!
+//! The recommended approach is managing privileges in infrastructure automation.
+
+use crate::checks::Check;
+use crate::parser::RawStatement;
+use crate::violation::Violation;
+use sqlparser::ast::Statement;
+
+pub struct GrantRevokeCheck;
+
+impl GrantRevokeCheck {
+    fn violation(keyword: &str, statement: &str) -> Violation {
+        let statement = statement.split_whitespace().collect::<Vec<_>>().join(" ");
+
+        Violation::new(
+            keyword,
+            format!(
+                "'{statement}' manages privileges in a migration. This usually requires owning the objects or holding \
+                the grant option, which the migration user often lacks in production, and fails in environments where \
+                the roles don't exist.",
+                statement = statement
+            ),
+            format!(
+                r#"Manage privileges outside of application migrations:
+
+1. Grant privileges where the roles are created, in infrastructure automation
+   (Ansible, Terraform, etc.) or your DBA's provisioning scripts.
+
+2. For privileges on tables created later, set default privileges once, outside migrations:
+   ALTER DEFAULT PRIVILEGES IN SCHEMA public GRANT SELECT ON TABLES TO <role>;
+
+Note: If the migration user is expected to manage these privileges, use a safety-assured block:
+   -- safety-assured:start
+   {statement};
+   -- safety-assured:end"#,
+                statement = statement
+            ),
+        )
+    }
+}
+
+impl Check for GrantRevokeCheck {
+    fn check(&self, stmt: &Statement) -> Vec<Violation> {
+        match stmt {
+            Statement::Grant { .. } => vec![Self::violation("GRANT", &stmt.to_string())],
+            Statement::Revoke { .. } => vec![Self::violation("REVOKE", &stmt.to_string())],
+            _ => vec![],
+        }
+    }
+
+    fn check_raw(&self, stmt: &RawStatement) -> Vec<Violation> {
+        if stmt.keyword != "GRANT" && stmt.keyword != "REVOKE" {
+            return vec![];
+        }
+
+        vec![Self::violation(&stmt.keyword, &stmt.sql)]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::checks::test_utils::parse_sql;
+    use crate::checks::test_utils::raw;
+
+    #[test]
+    fn test_detects_grant() {
+        let violations = GrantRevokeCheck.check_raw(&raw("GRANT SELECT ON users TO reporting"));
+
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].operation, "GRANT");
+        assert!(violations[0]
+            .problem
+            .contains("'GRANT SELECT ON users TO reporting'"));
+    }
+
+    #[test]
+    fn test_detects_parsed_grant_and_revoke() {
+        let violations = GrantRevokeCheck.check(&parse_sql("GRANT SELECT ON users TO reporting;"));
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].operation, "GRANT");
+        assert!(violations[0]
+            .problem
+            .contains("'GRANT SELECT ON users TO reporting'"));
+
+        let violations = GrantRevokeCheck.check(&parse_sql("REVOKE ALL ON users FROM PUBLIC;"));
+        assert_eq!(violations[0].operation, "REVOKE");
+        assert!(violations[0]
+            .problem
+            .contains("'REVOKE ALL ON users FROM PUBLIC'"));
+
+        assert!(GrantRevokeCheck.check(&parse_sql("SELECT 1;")).is_empty());
+    }
+
+    #[test]
+    fn test_detects_revoke_and_role_membership() {
+        let violations = GrantRevokeCheck.check_raw(&raw("REVOKE ALL ON users FROM PUBLIC"));
+        assert_eq!(violations[0].operation, "REVOKE");
+
+        let violations = GrantRevokeCheck.check_raw(&raw("grant reporting to alice"));
+        assert_eq!(violations.len(), 1);
+    }
+
+    #[test]
+    fn test_ignores_other_statements() {
+        assert!(GrantRevokeCheck.check_raw(&raw("CLUSTER users")).is_empty());
+    }
+}
@@ -0,0 +1,163 @@
+//! Detection for hash indexes.
+//!
+//! This check identifies `CREATE INDEX ... USING hash` statements.
+//!
+//! Before PostgreSQL 10, hash indexes aren't WAL-logged: they aren't replicated to
+//! standbys and must be rebuilt with REINDEX after a crash. From PostgreSQL 10 on they're
+//! safe, but rarely the right choice: they only support equality, can't be unique or
+//! multi-column, and are seldom meaningfully smaller or faster than a btree index. On
+//! those versions the finding is a warning.
+//!
+//! The message depends on the `postgres_version` setting; when it isn't set, both
+//! concerns are mentioned.
+//!
+//! The recommended approach is a btree index.
+
+use crate::checks::{display_or_default, Check};
+use crate::violation::{Severity, Violation};
+use sqlparser::ast::{IndexType, Statement};
+
+/// First major version in which hash indexes are WAL-logged
+const WAL_LOGGED_SINCE: u32 = 10;
+
+#[derive(Default)]
+pub struct HashIndexCheck {
+    postgres_version: Option<u32>,
+}
+
+impl HashIndexCheck {
+    pub fn new(postgres_version: Option<u32>) -> Self {
+        Self { postgres_version }
+    }
+}
+
+impl Check for HashIndexCheck {
+    fn check(&self, stmt: &Statement) -> Vec<Violation> {
+        let Statement::CreateIndex(create_index) = stmt else {
+            return vec![];
+        };
+        if create_index.using != Some(IndexType::Hash) {
+            return vec![];
+        }
+
+        let table_name = create_index.table_name.to_string();
+        let index_name = display_or_default(create_index.name.as_ref(), "<unnamed>");
+        let columns = create_index
+            .columns
+            .iter()
+            .map(|c| c.to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        let limitations = "Hash indexes only support equality comparisons, can't be unique or span multiple columns, \
+            and are rarely smaller or faster than a btree index.";
+        let problem = match self.postgres_version {
+            Some(version) if version < WAL_LOGGED_SINCE => format!(
+                "Hash index '{index}' on table '{table}' is not WAL-logged on PostgreSQL {version}: it isn't replicated \
+                to standbys and must be rebuilt with REINDEX after a crash. {limitations}",
+                index = index_name,
+                table = table_name,
+                version = version,
+                limitations = limitations
+            ),
+            Some(_) => format!(
+                "Creating hash index '{index}' on table '{table}'. {limitations}",
+                index = index_name,
+                table = table_name,
+                limitations = limitations
+            ),
+            None => format!(
+                "Creating hash index '{index}' on table '{table}'. Before PostgreSQL 10, hash indexes are not WAL-logged: \
+                they aren't replicated and must be rebuilt after a crash. {limitations}",
+                index = index_name,
+                table = table_name,
+                limitations = limitations
+            ),
+        };
+
+        vec![Violation::new(
+            "CREATE INDEX USING hash",
+            problem,
+            format!(
+                r#"Use a btree index instead (the default):
+   CREATE INDEX CONCURRENTLY {index} ON {table} ({columns});
+
+Note: If benchmarks show a hash index is worth it on PostgreSQL 10+, use a safety-assured block."#,
+                index = index_name,
+                table = table_name,
+                columns = columns
+            ),
+        )]
+    }
+
+    fn violation_severity(&self, _violation: &Violation, configured: Severity) -> Severity {
+        // Only a design concern once hash indexes are crash-safe
+        if self
+            .postgres_version
+            .is_some_and(|version| version >= WAL_LOGGED_SINCE)
+        {
+            Severity::Warning
+        } else {
+            configured
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::checks::test_utils::parse_sql;
+    use crate::{assert_allows, assert_detects_violation};
+
+    const HASH_INDEX: &str = "CREATE INDEX idx_users_token ON users USING hash (token);";
+
+    #[test]
+    fn test_detects_hash_index() {
+        assert_detects_violation!(
+            HashIndexCheck::default(),
+            HASH_INDEX,
+            "CREATE INDEX USING hash"
+        );
+    }
+
+    #[test]
+    fn test_message_depends_on_postgres_version() {
+        let stmt = parse_sql(HASH_INDEX);
+
+        let old = HashIndexCheck::new(Some(9)).check(&stmt);
+        assert!(old[0].problem.contains("not WAL-logged on PostgreSQL 9"));
+
+        let current = HashIndexCheck::new(Some(16)).check(&stmt);
+        assert!(!current[0].problem.contains("WAL-logged"));
+        assert!(current[0].problem.contains("only support equality"));
+        assert!(current[0].problem.contains("can't be unique"));
+
+        let unknown = HashIndexCheck::default().check(&stmt);
+        assert!(unknown[0].problem.contains("Before PostgreSQL 10"));
+    }
+
+    #[test]
+    fn test_warns_once_hash_indexes_are_wal_logged() {
+        let stmt = parse_sql(HASH_INDEX);
+        let severity = |check: HashIndexCheck| {
+            let violations = check.check(&stmt);
+            check.violation_severity(&violations[0], Severity::Error)
+        };
+
+        assert_eq!(severity(HashIndexCheck::new(Some(9))), Severity::Error);
+        assert_eq!(severity(HashIndexCheck::new(Some(10))), Severity::Warning);
+        assert_eq!(severity(HashIndexCheck::default()), Severity::Error);
+    }
+
+    #[test]
+    fn test_allows_btree_index() {
+        assert_allows!(
+            HashIndexCheck::default(),
+            "CREATE INDEX idx_users_token ON users USING btree (token);"
+        );
+        assert_allows!(
+            HashIndexCheck::default(),
+            "CREATE INDEX idx_users_token ON users (token);"
+        );
+    }
+}
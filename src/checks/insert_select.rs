@@ -0,0 +1,268 @@
+//! Detection for INSERT ... SELECT backfills.
+//!
+//! This check identifies `INSERT INTO ... SELECT` (and `INSERT INTO ... TABLE`)
+//! statements whose source reads from tables, copying an unbounded number of rows.
+//! Sources without tables (`SELECT 1, 'x'`, a `VALUES` list or a set-returning function
+//! like `generate_series`) and queries with a LIMIT are allowed. Common table expressions are resolved to the tables they read from.
+//!
+//! Inside a migration, the copy runs in a single transaction: the inserted rows and the
+//! locks on the target stay held until the migration commits, the source is read in one
+//! long-running query, and a failure rolls back all of the work. Duration depends on the
+//! size of the source tables.
+//!
+//! The recommended approach is a chunked copy run outside of migrations.
+
+use crate::checks::overlap::table_target;
+use crate::checks::Check;
+use crate::schema::ident_key;
+use crate::violation::Violation;
+use sqlparser::ast::{Insert, ObjectNamePart, Query, SetExpr, Statement, TableFactor};
+use std::collections::{HashMap, HashSet};
+
+/// Tables read by each common table expression in scope, keyed by normalized name
+type Ctes = HashMap<String, Vec<String>>;
+
+pub struct InsertSelectCheck;
+
+impl InsertSelectCheck {
+    /// Tables read by a query body
+    fn source_tables(body: &SetExpr, ctes: &Ctes) -> Vec<String> {
+        match body {
+            SetExpr::Select(select) => select
+                .from
+                .iter()
+                .flat_map(|table| Self::relation_tables(&table.relation, ctes))
+                .collect(),
+            SetExpr::Query(query) => Self::unbounded_source_tables(query, ctes),
+            SetExpr::SetOperation { left, right, .. } => {
+                let mut tables = Self::source_tables(left, ctes);
+                tables.extend(Self::source_tables(right, ctes));
+                tables
+            }
+            SetExpr::Table(table) => table.table_name.iter().cloned().collect(),
+            _ => vec![],
+        }
+    }
+
+    /// Tables read by an item of a FROM clause
+    fn relation_tables(relation: &TableFactor, ctes: &Ctes) -> Vec<String> {
+        match relation {
+            // A set-returning function such as generate_series(1, 100) reads no table
+            TableFactor::Table { args: Some(_), .. }
+            | TableFactor::Function { .. }
+            | TableFactor::UNNEST { .. }
+            | TableFactor::TableFunction { .. } => vec![],
+            TableFactor::Table { name, .. } => {
+                let cte = match name.0.as_slice() {
+                    [ObjectNamePart::Identifier(ident)] => ctes.get(&ident_key(ident)),
+                    _ => None,
+                };
+                cte.cloned().unwrap_or_else(|| vec![name.to_string()])
+            }
+            TableFactor::Derived { subquery, .. } => Self::unbounded_source_tables(subquery, ctes),
+            other => vec![other.to_string()],
+        }
+    }
+
+    /// Tables read by a query, unless a LIMIT bounds the number of rows
+    fn unbounded_source_tables(query: &Query, ctes: &Ctes) -> Vec<String> {
+        if query.limit_clause.is_some() || query.fetch.is_some() {
+            return vec![];
+        }
+        Self::source_tables(&query.body, &Self::with_ctes(query, ctes))
+    }
+
+    /// CTEs in scope of a query: those of enclosing queries and its own
+    fn with_ctes(query: &Query, ctes: &Ctes) -> Ctes {
+        let mut ctes = ctes.clone();
+        for cte in query.with.iter().flat_map(|with| &with.cte_tables) {
+            let key = ident_key(&cte.alias.name);
+            // A recursive CTE's reference to itself reads no further tables
+            ctes.insert(key.clone(), vec![]);
+            let tables = Self::unbounded_source_tables(&cte.query, &ctes);
+            ctes.insert(key, tables);
+        }
+        ctes
+    }
+
+    fn check_insert(insert: &Insert, ctes: &Ctes) -> Vec<Violation> {
+        let Some(source) = insert.source.as_deref() else {
+            return vec![];
+        };
+
+        let mut sources = Self::unbounded_source_tables(source, ctes);
+        if sources.is_empty() {
+            return vec![];
+        }
+        let mut seen = HashSet::new();
+        sources.retain(|source| seen.insert(source.clone()));
+
+        let table = insert.table.to_string();
+        let sources = sources
+            .iter()
+            .map(|s| format!("'{}'", s))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        vec![Violation::new(
+            "INSERT ... SELECT",
+            format!(
+                "INSERT into '{table}' copies every matching row from {sources} in a single transaction. The inserted rows \
+                and locks on '{table}' are held until the migration commits, and a failure rolls back all of the work. \
+                Duration depends on the size of {sources}.",
+                table = table,
+                sources = sources
+            ),
+            format!(
+                r#"Copy the data in chunks outside of migrations:
+
+1. Keep the migration to schema changes only.
+
+2. Copy a range of rows at a time from a script or background job:
+   INSERT INTO {table} SELECT ... WHERE id >= <start> AND id < <start + 1000>;
+   -- repeat for each chunk, committing in between
+
+Note: If the source is known to be small (e.g. seeding a lookup table), use a safety-assured block."#,
+                table = table
+            ),
+        ).with_target(table_target(&table))]
+    }
+}
+
+impl Check for InsertSelectCheck {
+    fn check(&self, stmt: &Statement) -> Vec<Violation> {
+        match stmt {
+            Statement::Insert(insert) => Self::check_insert(insert, &Ctes::new()),
+            // WITH ... INSERT INTO ... SELECT
+            Statement::Query(query) => match query.body.as_ref() {
+                SetExpr::Insert(Statement::Insert(insert)) => {
+                    Self::check_insert(insert, &Self::with_ctes(query, &Ctes::new()))
+                }
+                _ => vec![],
+            },
+            _ => vec![],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::checks::test_utils::parse_sql;
+    use crate::{assert_allows, assert_detects_violation};
+
+    #[test]
+    fn test_detects_insert_select() {
+        assert_detects_violation!(
+            InsertSelectCheck,
+            "INSERT INTO events_archive SELECT * FROM events;",
+            "INSERT ... SELECT"
+        );
+    }
+
+    #[test]
+    fn test_detects_insert_select_with_join_and_union() {
+        let violations = InsertSelectCheck.check(&parse_sql(
+            "INSERT INTO contacts (email) SELECT email FROM users JOIN teams ON true UNION SELECT email FROM leads;",
+        ));
+
+        assert_eq!(violations.len(), 1);
+        assert!(violations[0].problem.contains("'users', 'leads'"));
+    }
+
+    #[test]
+    fn test_detects_insert_table() {
+        assert_detects_violation!(
+            InsertSelectCheck,
+            "INSERT INTO events_archive TABLE events;",
+            "INSERT ... SELECT"
+        );
+    }
+
+    #[test]
+    fn test_allows_insert_values_and_constant_select() {
+        assert_allows!(
+            InsertSelectCheck,
+            "INSERT INTO settings (key, value) VALUES ('theme', 'dark');"
+        );
+        assert_allows!(
+            InsertSelectCheck,
+            "INSERT INTO settings (key, value) SELECT 'theme', 'dark';"
+        );
+    }
+
+    #[test]
+    fn test_allows_values_in_from() {
+        assert_allows!(
+            InsertSelectCheck,
+            "INSERT INTO settings (key, value) SELECT * FROM (VALUES ('theme', 'dark'), ('lang', 'en')) AS v(key, value);"
+        );
+
+        // Only the derived VALUES list is skipped
+        let violations = InsertSelectCheck.check(&parse_sql(
+            "INSERT INTO user_settings SELECT u.id, v.key FROM users u, (VALUES ('theme')) AS v(key);",
+        ));
+        assert_eq!(violations.len(), 1);
+        assert!(violations[0].problem.contains("from 'users' in"));
+    }
+
+    #[test]
+    fn test_allows_set_returning_functions_in_from() {
+        assert_allows!(
+            InsertSelectCheck,
+            "INSERT INTO slots (n) SELECT n FROM generate_series(1, 100) AS n;"
+        );
+        assert_allows!(
+            InsertSelectCheck,
+            "INSERT INTO slots (n) SELECT x FROM unnest(ARRAY[1, 2]) x;"
+        );
+        assert_allows!(
+            InsertSelectCheck,
+            "INSERT INTO slots (n) SELECT x FROM LATERAL generate_series(1, 3) AS g(x);"
+        );
+
+        let violations = InsertSelectCheck.check(&parse_sql(
+            "INSERT INTO user_slots SELECT u.id, n FROM users u, generate_series(1, 3) AS n;",
+        ));
+        assert_eq!(violations.len(), 1);
+        assert!(violations[0].problem.contains("from 'users' in"));
+    }
+
+    #[test]
+    fn test_resolves_ctes_to_their_tables() {
+        let violations = InsertSelectCheck.check(&parse_sql(
+            "INSERT INTO events_archive WITH old AS (SELECT * FROM events WHERE created_at < '2024-01-01') SELECT * FROM old;",
+        ));
+        assert_eq!(violations.len(), 1);
+        assert!(violations[0].problem.contains("from 'events' in"));
+        assert!(!violations[0].problem.contains("'old'"));
+
+        let violations = InsertSelectCheck.check(&parse_sql(
+            "WITH old AS (SELECT * FROM events) INSERT INTO events_archive SELECT * FROM OLD;",
+        ));
+        assert_eq!(violations.len(), 1);
+        assert!(violations[0].problem.contains("from 'events' in"));
+
+        // CTEs without tables, or with a LIMIT, are allowed
+        assert_allows!(
+            InsertSelectCheck,
+            "INSERT INTO settings WITH defaults AS (VALUES ('theme', 'dark')) SELECT * FROM defaults;"
+        );
+        assert_allows!(
+            InsertSelectCheck,
+            "INSERT INTO featured_posts WITH top AS (SELECT id FROM posts ORDER BY score DESC LIMIT 10) SELECT id FROM top;"
+        );
+        assert_allows!(
+            InsertSelectCheck,
+            "INSERT INTO numbers WITH RECURSIVE n(x) AS (SELECT 1 UNION ALL SELECT x + 1 FROM n WHERE x < 10) SELECT x FROM n;"
+        );
+    }
+
+    #[test]
+    fn test_allows_limited_select() {
+        assert_allows!(
+            InsertSelectCheck,
+            "INSERT INTO featured_posts SELECT id FROM posts ORDER BY score DESC LIMIT 10;"
+        );
+    }
+}
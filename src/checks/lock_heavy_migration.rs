@@ -0,0 +1,299 @@
+//! Detection for migrations stacking many lock-acquiring statements.
+//!
+//! This check counts the statements of a migration that lock an existing table:
+//! `ALTER TABLE`, `CREATE INDEX` without CONCURRENTLY, `CREATE TRIGGER`, `DROP TABLE`,
+//! `DROP INDEX`, and `TRUNCATE`, as well as statements checked as source text:
+//! `ALTER TABLE` forms sqlparser doesn't support (`SET TABLESPACE`, `ADD ... EXCLUDE`,
+//! ...), `ALTER INDEX ... SET TABLESPACE`, `CLUSTER`, and `REINDEX` without
+//! CONCURRENTLY. Tables created earlier in the same migration don't count, since
+//! nothing else can be waiting on them. Its violations are warnings by default.
+//!
+//! Diesel runs a migration in one transaction, so each lock is held until COMMIT. Every
+//! additional statement extends how long the earlier locks are held, and adds another
+//! table that the migration must wait for, making a lock queue behind a slow query more
+//! likely. The threshold is set with `[checks.LockHeavyMigrationCheck] max_operations`.
+//!
+//! The recommended approach is splitting the migration into several smaller ones.
+
+use crate::checks::Check;
+use crate::config::LockHeavyMigrationConfig;
+use crate::parser::{ParsedSql, RawStatement};
+use crate::schema::{name_key, object_key};
+use crate::violation::{Severity, Violation};
+use regex::Regex;
+use sqlparser::ast::{AlterTable, ObjectName, ObjectType, Statement};
+use std::collections::HashSet;
+use std::sync::LazyLock;
+
+/// Statements checked as source text that lock the table or index they name
+static LOCKING_RAW_PATTERN: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?is)^(?:ALTER\s+(?:TABLE|INDEX|MATERIALIZED\s+VIEW)\s+(?:IF\s+EXISTS\s+)?(?:ONLY\s+)?|CLUSTER(?:\s+VERBOSE)?\b\s*|REINDEX\s*(?:\([^)]*\))?\s*(?:TABLE|INDEX)\s+)(?P<name>ALL\s+IN\s+TABLESPACE\s+\S+|[^\s;(]+)?",
+    )
+    .expect("valid regex pattern")
+});
+
+/// A statement of the migration, in source order
+enum MigrationStatement<'a> {
+    Parsed(&'a Statement),
+    Raw(&'a RawStatement),
+}
+
+/// A statement locking existing tables
+pub(crate) struct Lock {
+    pub line: usize,
+    /// Column of the statement, unless it was checked as source text
+    pub column: Option<usize>,
+    /// Objects the statement locks, as written
+    pub objects: String,
+}
+
+#[derive(Default)]
+pub struct LockHeavyMigrationCheck {
+    config: LockHeavyMigrationConfig,
+}
+
+impl LockHeavyMigrationCheck {
+    pub fn new(config: LockHeavyMigrationConfig) -> Self {
+        Self { config }
+    }
+
+    /// Statements locking existing tables, in source order
+    ///
+    /// Tables created earlier in the migration aren't considered existing.
+    pub(crate) fn locking_statements(parsed: &ParsedSql) -> Vec<Lock> {
+        let mut statements: Vec<(usize, Option<usize>, MigrationStatement)> = parsed
+            .statements
+            .iter()
+            .map(|stmt| {
+                (
+                    stmt.line,
+                    Some(stmt.column),
+                    MigrationStatement::Parsed(&stmt.stmt),
+                )
+            })
+            .chain(
+                parsed
+                    .raw_statements
+                    .iter()
+                    .map(|raw| (raw.line, None, MigrationStatement::Raw(raw))),
+            )
+            .collect();
+        statements.sort_by_key(|(line, column, _)| (*line, column.unwrap_or(1)));
+
+        let mut created = HashSet::new();
+        let mut locking = vec![];
+
+        for (line, column, stmt) in statements {
+            let objects: Vec<String> = match stmt {
+                MigrationStatement::Parsed(Statement::CreateTable(create)) => {
+                    created.insert(object_key(&create.name));
+                    continue;
+                }
+                MigrationStatement::Parsed(stmt) => Self::locked_objects(stmt)
+                    .unwrap_or_default()
+                    .into_iter()
+                    .filter(|name| !created.contains(&object_key(name)))
+                    .map(|name| name.to_string())
+                    .collect(),
+                MigrationStatement::Raw(raw) => Self::raw_locked_object(raw)
+                    .filter(|name| !created.contains(&name_key(name)))
+                    .into_iter()
+                    .collect(),
+            };
+            if !objects.is_empty() {
+                locking.push(Lock {
+                    line,
+                    column,
+                    objects: objects.join(", "),
+                });
+            }
+        }
+
+        locking
+    }
+
+    /// Objects whose lock the statement acquires, or None if it doesn't take a lock
+    /// on existing tables
+    fn locked_objects(stmt: &Statement) -> Option<Vec<&ObjectName>> {
+        match stmt {
+            Statement::AlterTable(AlterTable { name, .. }) => Some(vec![name]),
+            Statement::CreateIndex(index) if !index.concurrently => Some(vec![&index.table_name]),
+            Statement::CreateTrigger(trigger) => Some(vec![&trigger.table_name]),
+            Statement::Drop {
+                object_type: ObjectType::Table | ObjectType::Index,
+                names,
+                ..
+            } => Some(names.iter().collect()),
+            Statement::Truncate(truncate) => {
+                Some(truncate.table_names.iter().map(|t| &t.name).collect())
+            }
+            _ => None,
+        }
+    }
+
+    /// Object a statement checked as source text locks, if it locks one
+    fn raw_locked_object(raw: &RawStatement) -> Option<String> {
+        if raw.keyword == "REINDEX"
+            && raw
+                .sql
+                .split(|c: char| !c.is_ascii_alphabetic())
+                .any(|word| word.eq_ignore_ascii_case("CONCURRENTLY"))
+        {
+            return None;
+        }
+        let captures = LOCKING_RAW_PATTERN.captures(&raw.sql)?;
+        match captures.name("name") {
+            Some(name) => Some(name.as_str().to_string()),
+            // CLUSTER without a table reclusters every table clustered before
+            None if raw.keyword == "CLUSTER" => Some("previously clustered tables".to_string()),
+            None => None,
+        }
+    }
+}
+
+impl Check for LockHeavyMigrationCheck {
+    fn check(&self, _stmt: &Statement) -> Vec<Violation> {
+        vec![]
+    }
+
+    fn check_parsed(&self, parsed: &ParsedSql) -> Vec<(usize, Violation)> {
+        let locking = Self::locking_statements(parsed);
+
+        let max = self.config.max_operations;
+        if locking.len() <= max {
+            return vec![];
+        }
+
+        let mut objects: Vec<&str> = vec![];
+        for lock in &locking {
+            if !objects.contains(&lock.objects.as_str()) {
+                objects.push(&lock.objects);
+            }
+        }
+
+        let Lock { line, column, .. } = locking[max];
+        vec![(
+            line,
+            Violation::new(
+                "multiple locking operations",
+                format!(
+                    "This migration has {count} statements that lock existing tables ({objects}), more than the {max} \
+                    allowed. The migration runs in one transaction, so every lock is held until it commits: each additional \
+                    statement extends how long the earlier locks block other queries, and adds another lock to wait for.",
+                    count = locking.len(),
+                    objects = objects.join("; "),
+                    max = max
+                ),
+                format!(
+                    r#"Split the migration so each one locks as little as possible:
+
+1. Move independent changes into separate migrations, ideally one table per migration.
+
+2. Set a lock timeout in each, so a migration fails fast instead of queueing behind
+   long-running queries:
+   SET lock_timeout = '2s';
+
+Note: Raise the limit with [checks.LockHeavyMigrationCheck] max_operations = {count}, or use a
+safety-assured block if the tables are small."#,
+                    count = locking.len()
+                ),
+            )
+            .located(line, column),
+        )]
+    }
+
+    fn default_severity(&self) -> Severity {
+        Severity::Warning
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::checks::test_utils::check_parsed_sql;
+
+    const FOUR_LOCKS: &str = "ALTER TABLE users ADD COLUMN bio TEXT;
+        ALTER TABLE posts ADD COLUMN draft BOOLEAN;
+        CREATE INDEX posts_draft_idx ON posts (draft);
+        ALTER TABLE comments DROP COLUMN legacy;";
+
+    #[test]
+    fn test_detects_migration_above_threshold() {
+        let violations = check_parsed_sql(&LockHeavyMigrationCheck::default(), FOUR_LOCKS);
+
+        assert_eq!(violations.len(), 1);
+        // Reported on the first statement above the limit
+        assert_eq!(violations[0].0, 4);
+        assert_eq!(violations[0].1.operation, "multiple locking operations");
+        assert!(violations[0].1.problem.contains("4 statements"));
+        assert!(violations[0].1.problem.contains("(users; posts; comments)"));
+    }
+
+    #[test]
+    fn test_threshold_is_configurable() {
+        let check = LockHeavyMigrationCheck::new(LockHeavyMigrationConfig { max_operations: 4 });
+        assert!(check_parsed_sql(&check, FOUR_LOCKS).is_empty());
+
+        let check = LockHeavyMigrationCheck::new(LockHeavyMigrationConfig { max_operations: 1 });
+        let violations = check_parsed_sql(&check, FOUR_LOCKS);
+        assert_eq!(violations[0].0, 2);
+    }
+
+    #[test]
+    fn test_ignores_tables_created_in_migration() {
+        assert!(check_parsed_sql(
+            &LockHeavyMigrationCheck::default(),
+            "CREATE TABLE posts (id BIGINT PRIMARY KEY, user_id BIGINT);
+             CREATE INDEX posts_user_id_idx ON posts (user_id);
+             ALTER TABLE posts ADD COLUMN title TEXT;
+             ALTER TABLE posts ADD COLUMN body TEXT;
+             ALTER TABLE users ADD COLUMN bio TEXT;"
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn test_ignores_non_locking_statements() {
+        assert!(check_parsed_sql(
+            &LockHeavyMigrationCheck::default(),
+            "CREATE INDEX CONCURRENTLY a_idx ON users (a);
+             CREATE INDEX CONCURRENTLY b_idx ON users (b);
+             CREATE INDEX CONCURRENTLY c_idx ON users (c);
+             CREATE INDEX CONCURRENTLY d_idx ON users (d);
+             SELECT 1;"
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn test_counts_statements_checked_as_source_text() {
+        let violations = check_parsed_sql(
+            &LockHeavyMigrationCheck::default(),
+            "ALTER TABLE users SET TABLESPACE fast;
+             CLUSTER posts USING posts_pkey;
+             REINDEX TABLE comments;
+             ALTER TABLE bookings ADD CONSTRAINT no_overlap EXCLUDE USING gist (room WITH =);",
+        );
+
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].0, 4);
+        assert!(violations[0]
+            .1
+            .problem
+            .contains("(users; posts; comments; bookings)"));
+    }
+
+    #[test]
+    fn test_ignores_non_locking_statements_checked_as_source_text() {
+        assert!(check_parsed_sql(
+            &LockHeavyMigrationCheck::default(),
+            "CREATE TABLE events (id BIGINT);
+             ALTER TABLE events SET TABLESPACE fast;
+             REINDEX TABLE CONCURRENTLY users;
+             REINDEX (VERBOSE) INDEX CONCURRENTLY users_email_idx;
+             CLUSTER events;"
+        )
+        .is_empty());
+    }
+}
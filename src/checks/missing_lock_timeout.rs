@@ -0,0 +1,168 @@
+//! Detection for migrations locking existing tables without a lock timeout.
+//!
+//! This check flags migrations whose first statement locking an existing table (see
+//! [`LockHeavyMigrationCheck`](crate::checks::LockHeavyMigrationCheck) for which
+//! statements count) isn't preceded by `SET lock_timeout` or `SET statement_timeout`.
+//!
+//! Without a timeout, a statement waiting for its lock behind a long-running query
+//! queues indefinitely, and every query on the table that arrives afterwards queues
+//! behind it. A short lock timeout makes the migration fail fast instead, so it can be
+//! retried.
+//!
+//! This check is opt-in: enable it with `enable_checks = ["MissingLockTimeoutCheck"]`.
+//!
+//! The recommended approach is a timeout preamble at the top of the migration.
+
+use crate::checks::lock_heavy_migration::Lock;
+use crate::checks::{Check, LockHeavyMigrationCheck};
+use crate::parser::ParsedSql;
+use crate::violation::Violation;
+use sqlparser::ast::{Set, Statement};
+
+/// Settings that bound how long a statement waits for its lock
+const TIMEOUT_SETTINGS: &[&str] = &["lock_timeout", "statement_timeout"];
+
+pub struct MissingLockTimeoutCheck;
+
+impl MissingLockTimeoutCheck {
+    fn sets_timeout(stmt: &Statement) -> bool {
+        let Statement::Set(Set::SingleAssignment { variable, .. }) = stmt else {
+            return false;
+        };
+        let variable = variable.to_string().to_lowercase();
+        TIMEOUT_SETTINGS.contains(&variable.as_str())
+    }
+}
+
+impl Check for MissingLockTimeoutCheck {
+    fn check(&self, _stmt: &Statement) -> Vec<Violation> {
+        vec![]
+    }
+
+    fn check_parsed(&self, parsed: &ParsedSql) -> Vec<(usize, Violation)> {
+        let Some(Lock {
+            line,
+            column,
+            objects,
+        }) = LockHeavyMigrationCheck::locking_statements(parsed)
+            .into_iter()
+            .next()
+        else {
+            return vec![];
+        };
+
+        if parsed
+            .statements
+            .iter()
+            .filter(|stmt| (stmt.line, stmt.column) < (line, column.unwrap_or(1)))
+            .any(|stmt| Self::sets_timeout(&stmt.stmt))
+        {
+            return vec![];
+        }
+
+        vec![(
+            line,
+            Violation::new(
+                "missing lock timeout",
+                format!(
+                    "This migration locks existing tables (first '{objects}') without setting lock_timeout or \
+                    statement_timeout beforehand. If the lock is held by a long-running query, the migration waits \
+                    indefinitely, and every query on the table arriving afterwards queues behind it.",
+                    objects = objects
+                ),
+                r#"Set a timeout at the top of the migration, so a blocked statement fails fast and
+the migration can be retried:
+
+   SET lock_timeout = '2s';
+   SET statement_timeout = '30s';
+
+Note: Both settings last until the end of the session. With run_in_transaction = false,
+reset them at the end of the migration (RESET lock_timeout;)."#,
+            )
+            .located(line, column),
+        )]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::checks::test_utils::check_parsed_sql;
+
+    #[test]
+    fn test_detects_locking_statement_without_timeout() {
+        let violations = check_parsed_sql(
+            &MissingLockTimeoutCheck,
+            "CREATE TABLE tags (id BIGINT PRIMARY KEY);
+             ALTER TABLE users ADD COLUMN bio TEXT;
+             ALTER TABLE posts ADD COLUMN draft BOOLEAN;",
+        );
+
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].0, 2);
+        assert_eq!(violations[0].1.operation, "missing lock timeout");
+        assert!(violations[0].1.problem.contains("first 'users'"));
+    }
+
+    #[test]
+    fn test_allows_timeout_before_locking_statement() {
+        assert!(check_parsed_sql(
+            &MissingLockTimeoutCheck,
+            "SET lock_timeout = '2s';
+             ALTER TABLE users ADD COLUMN bio TEXT;"
+        )
+        .is_empty());
+        assert!(check_parsed_sql(
+            &MissingLockTimeoutCheck,
+            "SET LOCAL statement_timeout TO 5000;
+             ALTER TABLE users ADD COLUMN bio TEXT;"
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn test_timeout_after_locking_statement_is_too_late() {
+        assert_eq!(
+            check_parsed_sql(
+                &MissingLockTimeoutCheck,
+                "ALTER TABLE users ADD COLUMN bio TEXT;
+                 SET lock_timeout = '2s';"
+            )
+            .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_ignores_migrations_without_locks_on_existing_tables() {
+        assert!(check_parsed_sql(
+            &MissingLockTimeoutCheck,
+            "CREATE TABLE tags (id BIGINT PRIMARY KEY, name TEXT);
+             CREATE INDEX tags_name_idx ON tags (name);
+             CREATE INDEX CONCURRENTLY users_email_idx ON users (email);"
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn test_detects_statement_checked_as_source_text_without_timeout() {
+        let violations = check_parsed_sql(
+            &MissingLockTimeoutCheck,
+            "SELECT 1;
+             CLUSTER users USING users_pkey;
+             SET lock_timeout = '2s';
+             ALTER TABLE posts ADD COLUMN draft BOOLEAN;",
+        );
+
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].0, 2);
+        assert!(violations[0].1.problem.contains("first 'users'"));
+
+        assert!(check_parsed_sql(
+            &MissingLockTimeoutCheck,
+            "SET lock_timeout = '2s';
+             ALTER TABLE users SET TABLESPACE fast;"
+        )
+        .is_empty());
+    }
+}
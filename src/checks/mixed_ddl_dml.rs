@@ -0,0 +1,174 @@
+//! Detection for schema changes followed by data backfills in the same migration.
+//!
+//! This check looks across all statements of a migration for DDL on existing tables
+//! (ALTER TABLE, CREATE INDEX, DROP, ...) followed by a potentially large data change
+//! (UPDATE, DELETE, or INSERT ... SELECT).
+//!
+//! Diesel runs each migration in a single transaction, so the locks taken by the DDL
+//! are held until the transaction commits, which is only after the backfill has
+//! finished. A quick `ALTER TABLE` then blocks the table for as long as the backfill runs.
+//!
+//! The recommended approach is to split schema changes and backfills into separate
+//! migrations, or to run the backfill outside of migrations.
+
+use crate::checks::Check;
+use crate::violation::Violation;
+use sqlparser::ast::{SetExpr, Statement};
+
+pub struct MixedDdlDmlCheck;
+
+impl MixedDdlDmlCheck {
+    /// DDL that locks an existing object, with a short description for messages
+    fn ddl_description(stmt: &Statement) -> Option<String> {
+        match stmt {
+            Statement::AlterTable(alter) => Some(format!("ALTER TABLE on '{}'", alter.name)),
+            Statement::CreateIndex(index) => {
+                Some(format!("CREATE INDEX on '{}'", index.table_name))
+            }
+            Statement::AlterIndex { name, .. } => Some(format!("ALTER INDEX '{}'", name)),
+            Statement::Drop {
+                object_type, names, ..
+            } => Some(format!(
+                "DROP {} '{}'",
+                object_type,
+                names
+                    .iter()
+                    .map(|n| n.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )),
+            _ => None,
+        }
+    }
+
+    /// Data change whose size depends on table size, with a short description for messages
+    fn dml_description(stmt: &Statement) -> Option<String> {
+        match stmt {
+            Statement::Update(update) => Some(format!("UPDATE on '{}'", update.table.relation)),
+            Statement::Delete(_) => Some("DELETE".to_string()),
+            // INSERT ... VALUES writes a fixed number of rows; INSERT ... SELECT can copy a table
+            Statement::Insert(insert) => match insert.source.as_deref() {
+                Some(query) if !matches!(*query.body, SetExpr::Values(_)) => {
+                    Some(format!("INSERT ... SELECT into '{}'", insert.table))
+                }
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+}
+
+impl Check for MixedDdlDmlCheck {
+    fn check(&self, _stmt: &Statement) -> Vec<Violation> {
+        vec![]
+    }
+
+    fn check_migration(&self, stmts: &[Statement]) -> Vec<(usize, Violation)> {
+        let Some((ddl_idx, ddl)) = stmts
+            .iter()
+            .enumerate()
+            .find_map(|(idx, stmt)| Self::ddl_description(stmt).map(|ddl| (idx, ddl)))
+        else {
+            return vec![];
+        };
+
+        // One violation per migration, on the first backfill after the DDL
+        let Some((dml_idx, dml)) = stmts
+            .iter()
+            .enumerate()
+            .skip(ddl_idx + 1)
+            .find_map(|(idx, stmt)| Self::dml_description(stmt).map(|dml| (idx, dml)))
+        else {
+            return vec![];
+        };
+
+        vec![(
+            dml_idx,
+            Violation::new(
+                "DDL and DML in one migration",
+                format!(
+                    "{dml} runs in the same transaction as the earlier {ddl}. The locks taken by the DDL are held \
+                    until the migration commits, so the table stays blocked for the entire backfill. \
+                    Duration depends on the number of rows changed.",
+                    dml = dml,
+                    ddl = ddl
+                ),
+                r#"Split the schema change and the backfill:
+
+1. Migration 1 - schema change only:
+   ALTER TABLE <table> ADD COLUMN <column> <type>;
+
+2. Migration 2 (or a script / background job) - backfill in batches:
+   UPDATE <table> SET <column> = <value> WHERE id >= <start> AND id < <start + 1000>;
+   -- repeat for each batch, committing in between
+
+Note: Keeping the backfill out of the DDL's transaction means its locks are released
+as soon as the schema change commits."#
+                    .to_string(),
+            ),
+        )]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::checks::test_utils::check_sql;
+
+    #[test]
+    fn test_detects_alter_followed_by_update() {
+        let violations = check_sql(
+            &MixedDdlDmlCheck,
+            "ALTER TABLE users ADD COLUMN active BOOLEAN;\nUPDATE users SET active = true;",
+        );
+
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].0, 1);
+        assert_eq!(violations[0].1.operation, "DDL and DML in one migration");
+        assert!(violations[0].1.problem.contains("ALTER TABLE on 'users'"));
+    }
+
+    #[test]
+    fn test_detects_insert_select_after_create_index() {
+        let violations = check_sql(
+            &MixedDdlDmlCheck,
+            "CREATE INDEX idx ON users (email);\nINSERT INTO archive SELECT * FROM users;",
+        );
+        assert_eq!(violations.len(), 1);
+    }
+
+    #[test]
+    fn test_reports_once_per_migration() {
+        let violations = check_sql(&MixedDdlDmlCheck,
+            "ALTER TABLE users ADD COLUMN a INT;\nUPDATE users SET a = 1;\nDELETE FROM users WHERE a IS NULL;",
+        );
+        assert_eq!(violations.len(), 1);
+    }
+
+    #[test]
+    fn test_allows_dml_before_ddl() {
+        assert!(check_sql(
+            &MixedDdlDmlCheck,
+            "UPDATE users SET a = 1;\nALTER TABLE users ADD COLUMN b INT;"
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn test_allows_insert_values() {
+        assert!(check_sql(
+            &MixedDdlDmlCheck,
+            "ALTER TABLE roles ADD COLUMN level INT;\nINSERT INTO roles (name) VALUES ('admin');"
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn test_allows_create_table_with_data() {
+        assert!(check_sql(
+            &MixedDdlDmlCheck,
+            "CREATE TABLE archive (id INT);\nINSERT INTO archive SELECT id FROM users;"
+        )
+        .is_empty());
+    }
+}
@@ -1,48 +1,150 @@
+mod add_check_constraint;
 mod add_column;
+mod add_exclusion_constraint;
+mod add_foreign_key;
+mod add_identity_column;
 mod add_index;
 mod add_json_column;
 mod add_not_null;
+mod add_not_null_column;
 mod add_primary_key;
 mod add_serial_column;
 mod add_unique_constraint;
 mod alter_column_type;
+mod alter_type_add_value;
+mod cascading_delete;
+mod char_type;
+mod cluster;
+mod concurrent_index_in_transaction;
+mod context;
 mod create_extension;
+mod create_materialized_view;
+mod create_role;
+mod create_table_as;
+mod create_trigger;
+mod delete_without_where;
+mod disable_trigger;
+mod dml_in_migration;
 mod drop_column;
+mod drop_constraint_cascade;
+mod drop_database;
 mod drop_index;
 mod drop_primary_key;
+mod drop_table;
+mod duplicate_index;
+mod enum_rebuild;
+mod excessive_indexes;
+mod foreign_key_without_index;
+mod grant_revoke;
+mod hash_index;
+mod insert_select;
+mod lock_heavy_migration;
+mod missing_lock_timeout;
+mod mixed_ddl_dml;
+mod money_type;
+mod non_idempotent_migration;
+mod overlap;
+mod partitioned_table_index;
+mod prefer_text_over_varchar;
+mod refresh_materialized_view;
+mod reindex;
 mod rename_column;
+mod rename_enum_value;
 mod rename_table;
+mod role_management;
+mod row_level_security;
+mod serial_vs_identity;
+mod set_logged;
+mod set_tablespace;
 mod short_int_primary_key;
+mod timestamp_without_time_zone;
 mod truncate_table;
+mod unbounded_text_index;
+mod unlogged_table;
 mod unnamed_constraint;
+mod update_without_where;
+mod vacuum_full;
+mod volatile_check_constraint;
+mod volatile_default;
 mod wide_index;
 
 #[cfg(test)]
 mod test_utils;
 
+pub use add_check_constraint::AddCheckConstraintCheck;
 pub use add_column::AddColumnCheck;
+pub use add_exclusion_constraint::AddExclusionConstraintCheck;
+pub use add_foreign_key::AddForeignKeyCheck;
+pub use add_identity_column::AddIdentityColumnCheck;
 pub use add_index::AddIndexCheck;
 pub use add_json_column::AddJsonColumnCheck;
 pub use add_not_null::AddNotNullCheck;
+pub use add_not_null_column::AddNotNullColumnCheck;
 pub use add_primary_key::AddPrimaryKeyCheck;
 pub use add_serial_column::AddSerialColumnCheck;
 pub use add_unique_constraint::AddUniqueConstraintCheck;
 pub use alter_column_type::AlterColumnTypeCheck;
+pub use alter_type_add_value::AlterTypeAddValueCheck;
+pub use cascading_delete::CascadingDeleteCheck;
+pub use char_type::CharTypeCheck;
+pub use cluster::ClusterCheck;
+pub use concurrent_index_in_transaction::ConcurrentIndexInTransactionCheck;
 pub use create_extension::CreateExtensionCheck;
+pub use create_materialized_view::CreateMaterializedViewCheck;
+pub use create_role::CreateRoleCheck;
+pub use create_table_as::CreateTableAsCheck;
+pub use create_trigger::CreateTriggerCheck;
+pub use delete_without_where::DeleteWithoutWhereCheck;
+pub use disable_trigger::DisableTriggerCheck;
+pub use dml_in_migration::DmlInMigrationCheck;
 pub use drop_column::DropColumnCheck;
+pub use drop_constraint_cascade::DropConstraintCascadeCheck;
+pub use drop_database::DropDatabaseCheck;
 pub use drop_index::DropIndexCheck;
 pub use drop_primary_key::DropPrimaryKeyCheck;
+pub use drop_table::DropTableCheck;
+pub use duplicate_index::DuplicateIndexCheck;
+pub use enum_rebuild::EnumRebuildCheck;
+pub use excessive_indexes::ExcessiveIndexesCheck;
+pub use foreign_key_without_index::ForeignKeyWithoutIndexCheck;
+pub use grant_revoke::GrantRevokeCheck;
+pub use hash_index::HashIndexCheck;
+pub use insert_select::InsertSelectCheck;
+pub use lock_heavy_migration::LockHeavyMigrationCheck;
+pub use missing_lock_timeout::MissingLockTimeoutCheck;
+pub use mixed_ddl_dml::MixedDdlDmlCheck;
+pub use money_type::MoneyTypeCheck;
+pub use non_idempotent_migration::NonIdempotentMigrationCheck;
+pub use partitioned_table_index::PartitionedTableIndexCheck;
+pub use prefer_text_over_varchar::PreferTextOverVarcharCheck;
+pub use refresh_materialized_view::RefreshMaterializedViewCheck;
+pub use reindex::ReindexCheck;
 pub use rename_column::RenameColumnCheck;
+pub use rename_enum_value::RenameEnumValueCheck;
 pub use rename_table::RenameTableCheck;
+pub use role_management::RoleManagementCheck;
+pub use row_level_security::RowLevelSecurityCheck;
+pub use serial_vs_identity::SerialVsIdentityCheck;
+pub use set_logged::SetLoggedCheck;
+pub use set_tablespace::SetTablespaceCheck;
 pub use short_int_primary_key::ShortIntegerPrimaryKeyCheck;
+pub use timestamp_without_time_zone::TimestampWithoutTimeZoneCheck;
 pub use truncate_table::TruncateTableCheck;
+pub use unbounded_text_index::UnboundedTextIndexCheck;
+pub use unlogged_table::UnloggedTableCheck;
 pub use unnamed_constraint::UnnamedConstraintCheck;
+pub use update_without_where::UpdateWithoutWhereCheck;
+pub use vacuum_full::VacuumFullCheck;
+pub use volatile_check_constraint::VolatileCheckConstraintCheck;
+pub use volatile_default::VolatileDefaultCheck;
 pub use wide_index::WideIndexCheck;
 
 use crate::config::Config;
+use crate::timings::millis;
 
 /// Helper functions for check implementations
 mod helpers {
+    use sqlparser::ast::{AlterTable, AlterTableOperation, ColumnDef, Statement};
     use std::fmt::Display;
 
     /// Convert an optional displayable value to String, using default if None
@@ -69,23 +171,114 @@ mod helpers {
             ""
         }
     }
+
+    /// Columns defined by CREATE TABLE or ALTER TABLE ... ADD COLUMN, paired with the table name
+    pub fn new_columns(stmt: &Statement) -> Vec<(String, &ColumnDef)> {
+        match stmt {
+            Statement::CreateTable(create) => {
+                let table_name = create.name.to_string();
+                create
+                    .columns
+                    .iter()
+                    .map(|column| (table_name.clone(), column))
+                    .collect()
+            }
+            Statement::AlterTable(AlterTable {
+                name, operations, ..
+            }) => operations
+                .iter()
+                .filter_map(|op| match op {
+                    AlterTableOperation::AddColumn { column_def, .. } => {
+                        Some((name.to_string(), column_def))
+                    }
+                    _ => None,
+                })
+                .collect(),
+            _ => vec![],
+        }
+    }
 }
 
-use crate::parser::IgnoreRange;
-use crate::violation::Violation;
+use crate::parser::{IgnoreRange, ParsedSql, ParsedStatement, RawStatement, SqlParser};
+use crate::violation::{Severity, Violation};
+pub use context::MigrationContext;
 pub use helpers::*;
 use sqlparser::ast::Statement;
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
 
 /// Trait for implementing safety checks on SQL statements
 pub trait Check: Send + Sync {
     /// Run the check on a statement and return any violations found
     fn check(&self, stmt: &Statement) -> Vec<Violation>;
+
+    /// Run the check on a statement, given what earlier statements of its migration did
+    ///
+    /// Defaults to [`Check::check`]. For checks whose findings don't apply to objects
+    /// created earlier in the same migration.
+    fn check_in_migration(&self, stmt: &Statement, _context: &MigrationContext) -> Vec<Violation> {
+        self.check(stmt)
+    }
+
+    /// Run the check on a statement sqlparser can't parse, given as source text
+    fn check_raw(&self, _stmt: &RawStatement) -> Vec<Violation> {
+        vec![]
+    }
+
+    /// Run the check across all statements of a migration
+    ///
+    /// For checks that depend on more than one statement. Each violation is paired
+    /// with the index of the statement it's reported on.
+    fn check_migration(&self, _stmts: &[Statement]) -> Vec<(usize, Violation)> {
+        vec![]
+    }
+
+    /// Run the check on the raw statements of a migration, given its parsed statements
+    ///
+    /// For raw statements whose safety depends on the rest of the migration. Each
+    /// violation is paired with the index of the raw statement it's reported on.
+    fn check_raw_migration(
+        &self,
+        _stmts: &[Statement],
+        _raw_stmts: &[RawStatement],
+    ) -> Vec<(usize, Violation)> {
+        vec![]
+    }
+
+    /// Run the check on a whole migration, with its source text and settings
+    ///
+    /// For checks that need more than the statements, such as whether the migration runs
+    /// inside a transaction. Each violation is paired with the 1-indexed line it's
+    /// reported on.
+    fn check_parsed(&self, _parsed: &ParsedSql) -> Vec<(usize, Violation)> {
+        vec![]
+    }
+
+    /// Severity of violations when the check isn't listed under `[severity]`
+    fn default_severity(&self) -> Severity {
+        Severity::Error
+    }
+
+    /// Severity of a single violation, given the severity configured for the check
+    ///
+    /// Lets a check escalate its most dangerous findings above the configured severity.
+    fn violation_severity(&self, _violation: &Violation, configured: Severity) -> Severity {
+        configured
+    }
 }
 
 /// Registry of all available checks
 pub struct Registry {
     checks: Vec<Box<dyn Check>>,
     names: Vec<&'static str>,
+    severities: Vec<Severity>,
+    /// Accumulated time per check (parallel to `checks`), present only when timings are enabled
+    durations: Option<Mutex<Vec<Duration>>>,
+    /// Whether related findings on the same statement are merged into one
+    merge_overlapping: bool,
+    /// Names of all known checks, including disabled and opt-in ones
+    all_names: Vec<&'static str>,
 }
 
 impl Registry {
@@ -99,6 +292,10 @@ impl Registry {
         let mut registry = Self {
             checks: vec![],
             names: vec![],
+            severities: vec![],
+            durations: None,
+            merge_overlapping: !config.report_overlapping_violations,
+            all_names: vec![],
         };
         registry.register_enabled_checks(config);
         registry
@@ -106,51 +303,276 @@ impl Registry {
 
     /// Register all enabled checks based on configuration
     fn register_enabled_checks(&mut self, config: &Config) {
-        self.register_check(config, AddColumnCheck);
+        self.register_check(config, AddCheckConstraintCheck);
+        self.register_check(config, AddColumnCheck::new(config.postgres_version));
+        self.register_check(config, AddExclusionConstraintCheck);
+        self.register_check(config, AddForeignKeyCheck);
+        self.register_check(config, AddIdentityColumnCheck);
         self.register_check(config, AddIndexCheck);
         self.register_check(config, AddJsonColumnCheck);
-        self.register_check(config, AddNotNullCheck);
+        self.register_check(config, AddNotNullCheck::new(config.postgres_version));
+        self.register_check(config, AddNotNullColumnCheck);
         self.register_check(config, AddPrimaryKeyCheck);
         self.register_check(config, AddSerialColumnCheck);
         self.register_check(config, AddUniqueConstraintCheck);
-        self.register_check(config, AlterColumnTypeCheck);
-        self.register_check(config, CreateExtensionCheck);
+        self.register_check(config, AlterColumnTypeCheck::new(config.postgres_version));
+        self.register_check(config, AlterTypeAddValueCheck::new(config.postgres_version));
+        self.register_check(config, CascadingDeleteCheck);
+        self.register_opt_in_check(config, CharTypeCheck);
+        self.register_check(config, ClusterCheck);
+        self.register_check(config, ConcurrentIndexInTransactionCheck);
+        self.register_check(
+            config,
+            CreateExtensionCheck::new(
+                config.checks.create_extension.clone(),
+                config.postgres_version,
+            ),
+        );
+        self.register_check(config, CreateMaterializedViewCheck);
+        self.register_check(config, CreateRoleCheck);
+        self.register_check(config, CreateTableAsCheck);
+        self.register_check(config, CreateTriggerCheck);
+        self.register_check(
+            config,
+            DeleteWithoutWhereCheck::new(config.checks.delete_without_where.clone()),
+        );
+        self.register_check(config, DisableTriggerCheck);
+        self.register_opt_in_check(
+            config,
+            DmlInMigrationCheck::new(config.checks.dml_in_migration.clone()),
+        );
         self.register_check(config, DropColumnCheck);
+        self.register_check(config, DropConstraintCascadeCheck);
+        self.register_check(config, DropDatabaseCheck);
         self.register_check(config, DropIndexCheck);
         self.register_check(config, DropPrimaryKeyCheck);
+        self.register_check(config, DropTableCheck);
+        self.register_check(config, DuplicateIndexCheck);
+        self.register_check(config, EnumRebuildCheck);
+        self.register_check(
+            config,
+            ExcessiveIndexesCheck::new(config.checks.excessive_indexes.clone()),
+        );
+        self.register_check(config, ForeignKeyWithoutIndexCheck);
+        self.register_check(config, GrantRevokeCheck);
+        self.register_check(config, HashIndexCheck::new(config.postgres_version));
+        self.register_check(config, InsertSelectCheck);
+        self.register_check(
+            config,
+            LockHeavyMigrationCheck::new(config.checks.lock_heavy_migration.clone()),
+        );
+        self.register_opt_in_check(config, MissingLockTimeoutCheck);
+        self.register_check(config, MixedDdlDmlCheck);
+        self.register_opt_in_check(config, MoneyTypeCheck);
+        self.register_opt_in_check(config, NonIdempotentMigrationCheck);
+        self.register_check(config, PartitionedTableIndexCheck);
+        self.register_opt_in_check(
+            config,
+            PreferTextOverVarcharCheck::new(config.checks.prefer_text_over_varchar.clone()),
+        );
+        self.register_check(config, RefreshMaterializedViewCheck);
+        self.register_check(config, ReindexCheck);
         self.register_check(config, RenameColumnCheck);
+        self.register_check(config, RenameEnumValueCheck);
         self.register_check(config, RenameTableCheck);
-        self.register_check(config, ShortIntegerPrimaryKeyCheck);
+        self.register_check(config, RoleManagementCheck);
+        self.register_check(config, RowLevelSecurityCheck);
+        self.register_opt_in_check(config, SerialVsIdentityCheck::new(config.postgres_version));
+        self.register_check(config, SetLoggedCheck);
+        self.register_check(config, SetTablespaceCheck);
+        self.register_check(
+            config,
+            ShortIntegerPrimaryKeyCheck::new(config.checks.short_integer_primary_key.clone()),
+        );
+        self.register_opt_in_check(config, TimestampWithoutTimeZoneCheck);
         self.register_check(config, TruncateTableCheck);
-        self.register_check(config, UnnamedConstraintCheck);
-        self.register_check(config, WideIndexCheck);
+        self.register_check(config, UnboundedTextIndexCheck);
+        self.register_opt_in_check(config, UnloggedTableCheck);
+        self.register_check(
+            config,
+            UnnamedConstraintCheck::new(config.checks.unnamed_constraint.clone()),
+        );
+        self.register_check(config, UpdateWithoutWhereCheck);
+        self.register_check(
+            config,
+            VacuumFullCheck::new(config.checks.vacuum_full.clone()),
+        );
+        self.register_check(config, VolatileCheckConstraintCheck);
+        self.register_check(config, VolatileDefaultCheck);
+        self.register_check(
+            config,
+            WideIndexCheck::new(config.checks.wide_index.clone()),
+        );
     }
 
     /// Register a check if it's enabled in configuration
     fn register_check<C: Check + 'static>(&mut self, config: &Config, check: C) {
-        // Extract just the type name (e.g., "AddColumnCheck" from "diesel_guard::checks::AddColumnCheck")
-        let full_name = std::any::type_name::<C>();
-        let name = full_name.split("::").last().unwrap_or(full_name);
+        let name = Self::check_name::<C>();
+        self.add_check(config, check, config.is_check_enabled(name));
+    }
+
+    /// Register a check that only runs when listed in `enable_checks`
+    fn register_opt_in_check<C: Check + 'static>(&mut self, config: &Config, check: C) {
+        let name = Self::check_name::<C>();
+        self.add_check(config, check, config.is_opt_in_check_enabled(name));
+    }
 
-        if config.is_check_enabled(name) {
+    fn add_check<C: Check + 'static>(&mut self, config: &Config, check: C, enabled: bool) {
+        let name = Self::check_name::<C>();
+        self.all_names.push(name);
+
+        if enabled {
+            let severity = config.severity_for(name, check.default_severity());
             self.checks.push(Box::new(check));
             self.names.push(name);
+            self.severities.push(severity);
         }
     }
 
+    /// Extract just the type name (e.g., "AddColumnCheck" from "diesel_guard::checks::AddColumnCheck")
+    fn check_name<C>() -> &'static str {
+        let full_name = std::any::type_name::<C>();
+        full_name.split("::").last().unwrap_or(full_name)
+    }
+
+    /// Start recording how much time each check spends
+    pub fn enable_timings(&mut self) {
+        self.durations = Some(Mutex::new(vec![Duration::ZERO; self.checks.len()]));
+    }
+
+    /// Time spent in each check so far, in milliseconds (empty unless timings are enabled)
+    pub fn check_timings(&self) -> BTreeMap<String, f64> {
+        let Some(durations) = &self.durations else {
+            return BTreeMap::new();
+        };
+        let durations = durations.lock().unwrap_or_else(|e| e.into_inner());
+
+        self.names
+            .iter()
+            .zip(durations.iter())
+            .map(|(name, duration)| (name.to_string(), millis(*duration)))
+            .collect()
+    }
+
     /// Check a single statement against all registered checks
+    ///
+    /// Related findings of different checks are merged unless
+    /// `report_overlapping_violations` is set.
     pub fn check_statement(&self, stmt: &Statement) -> Vec<Violation> {
+        self.run_checks(|check| check.check(stmt))
+    }
+
+    /// Check a statement of a migration, given what the statements before it did
+    pub fn check_statement_in_migration(
+        &self,
+        stmt: &Statement,
+        context: &MigrationContext,
+    ) -> Vec<Violation> {
+        self.run_checks(|check| check.check_in_migration(stmt, context))
+    }
+
+    /// Check a statement sqlparser can't parse against all registered checks
+    pub fn check_raw_statement(&self, stmt: &RawStatement) -> Vec<Violation> {
+        self.run_checks(|check| check.check_raw(stmt))
+    }
+
+    /// Run every registered check and merge related findings of different checks
+    fn run_checks(&self, run: impl Fn(&dyn Check) -> Vec<Violation>) -> Vec<Violation> {
+        let violations = self
+            .run_labelled(|check| {
+                run(check)
+                    .into_iter()
+                    .map(|violation| ((), violation))
+                    .collect()
+            })
+            .into_iter()
+            .map(|(_, violation)| violation)
+            .collect();
+
+        if self.merge_overlapping {
+            overlap::merge_overlapping(violations)
+        } else {
+            violations
+        }
+    }
+
+    /// Run every registered check across all statements of a migration
+    ///
+    /// Each violation is paired with the index of the statement it's reported on.
+    pub fn check_migration(&self, stmts: &[Statement]) -> Vec<(usize, Violation)> {
+        self.run_labelled(|check| check.check_migration(stmts))
+    }
+
+    /// Run every registered check across the raw statements of a migration
+    ///
+    /// Each violation is paired with the index of the raw statement it's reported on.
+    pub fn check_raw_migration(
+        &self,
+        stmts: &[Statement],
+        raw_stmts: &[RawStatement],
+    ) -> Vec<(usize, Violation)> {
+        self.run_labelled(|check| check.check_raw_migration(stmts, raw_stmts))
+    }
+
+    /// Run every registered check on a whole migration, pairing violations with lines
+    fn check_parsed_migration(&self, parsed: &ParsedSql) -> Vec<(usize, Violation)> {
+        self.run_labelled(|check| check.check_parsed(parsed))
+    }
+
+    /// Run every registered check, labelling violations with check name and severity
+    ///
+    /// Each violation keeps the key the check paired it with, such as a statement index.
+    fn run_labelled<K>(
+        &self,
+        run: impl Fn(&dyn Check) -> Vec<(K, Violation)>,
+    ) -> Vec<(K, Violation)> {
         self.checks
             .iter()
-            .flat_map(|check| check.check(stmt))
+            .zip(&self.names)
+            .zip(&self.severities)
+            .enumerate()
+            .flat_map(|(idx, ((check, name), severity))| {
+                let started = Instant::now();
+                let violations = run(check.as_ref());
+                self.record_duration(idx, started.elapsed());
+
+                violations.into_iter().map(|(key, mut violation)| {
+                    violation.check = name.to_string();
+                    violation.severity = check.violation_severity(&violation, *severity);
+                    (key, violation)
+                })
+            })
             .collect()
     }
 
+    /// Add to the accumulated time of a check when timings are enabled
+    fn record_duration(&self, idx: usize, elapsed: Duration) {
+        if let Some(durations) = &self.durations {
+            let mut durations = durations.lock().unwrap_or_else(|e| e.into_inner());
+            durations[idx] += elapsed;
+        }
+    }
+
     /// Check multiple statements against all registered checks
     pub fn check_statements(&self, stmts: &[Statement]) -> Vec<Violation> {
-        stmts
+        let mut context = MigrationContext::default();
+        let mut violations: Vec<(usize, Violation)> = stmts
             .iter()
-            .flat_map(|stmt| self.check_statement(stmt))
+            .enumerate()
+            .flat_map(|(idx, stmt)| {
+                let violations = self.check_statement_in_migration(stmt, &context);
+                context.record(stmt);
+                violations
+                    .into_iter()
+                    .map(move |violation| (idx, violation))
+            })
+            .chain(self.check_migration(stmts))
+            .collect();
+        violations.sort_by_key(|(idx, _)| *idx);
+
+        violations
+            .into_iter()
+            .map(|(_, violation)| violation)
             .collect()
     }
 
@@ -164,63 +586,137 @@ impl Registry {
         sql: &str,
         ignore_ranges: &[IgnoreRange],
     ) -> Vec<Violation> {
-        // Build set of all ignored line numbers for fast lookup
-        let ignored_lines: std::collections::HashSet<usize> = ignore_ranges
+        self.check_statements_with_lines(statements, sql)
+            .into_iter()
+            .flat_map(|(line, mut violation)| {
+                match ignore_ranges
+                    .iter()
+                    .find(|range| range.suppresses(line, &violation.check))
+                {
+                    Some(range) => {
+                        violation.unmerge_unsuppressed(|check| range.suppresses(line, check))
+                    }
+                    None => vec![violation],
+                }
+            })
+            .collect()
+    }
+
+    /// Check statements and pair each violation with the source line of its statement
+    ///
+    /// No safety-assured filtering is applied; callers decide what to do with each line.
+    pub fn check_statements_with_lines(
+        &self,
+        statements: &[Statement],
+        sql: &str,
+    ) -> Vec<(usize, Violation)> {
+        let statements: Vec<ParsedStatement> = statements
             .iter()
-            .flat_map(|range| (range.start_line + 1)..range.end_line)
+            .zip(Self::statement_locations(statements, sql))
+            .map(|(stmt, (line, column))| ParsedStatement {
+                stmt: stmt.clone(),
+                line,
+                column,
+            })
             .collect();
+        self.check_statements_in_context(&statements, MigrationContext::default())
+    }
 
-        // Track which lines have been matched to handle multiple statements with same keyword
-        let mut matched_lines = std::collections::HashSet::new();
+    /// Check statements of a migration, starting from the given context
+    fn check_statements_in_context(
+        &self,
+        statements: &[ParsedStatement],
+        mut context: MigrationContext,
+    ) -> Vec<(usize, Violation)> {
         let mut violations = Vec::new();
 
         for stmt in statements {
-            // Find where this statement appears in source SQL
-            let stmt_line = Self::find_statement_line(stmt, sql, &matched_lines);
-            matched_lines.insert(stmt_line);
-
-            // Skip checks if statement is in an ignored line
-            if !ignored_lines.contains(&stmt_line) {
-                violations.extend(self.check_statement(stmt));
-            }
+            violations.extend(
+                self.check_statement_in_migration(&stmt.stmt, &context)
+                    .into_iter()
+                    .map(|violation| (stmt.line, violation.located(stmt.line, Some(stmt.column)))),
+            );
+            context.record(&stmt.stmt);
         }
 
+        violations.extend(
+            self.check_migration(&Self::asts(statements))
+                .into_iter()
+                .map(|(idx, violation)| match statements.get(idx) {
+                    Some(stmt) => (stmt.line, violation.located(stmt.line, Some(stmt.column))),
+                    None => (1, violation),
+                }),
+        );
+        violations.sort_by_key(|(line, _)| *line);
+
         violations
     }
 
-    /// Find the first unmatched line where a statement appears in the source SQL
+    /// Check parsed and raw statements, pairing each violation with its source line
     ///
-    /// Uses simple keyword matching to locate the statement, excluding already-matched lines.
-    /// Returns line 1 if the statement cannot be found (safe fallback).
-    fn find_statement_line(
-        stmt: &Statement,
-        sql: &str,
-        matched_lines: &std::collections::HashSet<usize>,
-    ) -> usize {
-        let stmt_str = stmt.to_string().to_uppercase();
-        let first_word = stmt_str.split_whitespace().next().unwrap_or("");
+    /// Violations are ordered by line, and record their location. No safety-assured
+    /// filtering is applied.
+    pub fn check_parsed(&self, parsed: &ParsedSql) -> Vec<(usize, Violation)> {
+        let mut violations = self.check_statements_in_context(
+            &parsed.statements,
+            MigrationContext::new(&parsed.schema)
+                .with_database_primary_keys(parsed.database_primary_keys.clone()),
+        );
+        // Function bodies run after the migration, so they start from its schema
+        // without seeing its statements
+        violations.extend(
+            self.check_statements_in_context(
+                &parsed.function_statements,
+                MigrationContext::new(&parsed.schema)
+                    .with_database_primary_keys(parsed.database_primary_keys.clone()),
+            ),
+        );
+        for stmt in &parsed.raw_statements {
+            violations.extend(
+                self.check_raw_statement(stmt)
+                    .into_iter()
+                    .map(|violation| (stmt.line, violation)),
+            );
+        }
+        violations.extend(
+            self.check_raw_migration(&Self::asts(&parsed.statements), &parsed.raw_statements)
+                .into_iter()
+                .map(|(idx, violation)| (parsed.raw_statements[idx].line, violation)),
+        );
+        violations.extend(self.check_parsed_migration(parsed));
 
-        sql.lines()
-            .enumerate()
-            .find(|(idx, line)| {
-                let line_num = idx + 1; // 1-indexed
-                let trimmed = line.trim();
+        violations.sort_by_key(|(line, _)| *line);
+        violations
+            .into_iter()
+            .map(|(line, violation)| match violation.line {
+                Some(_) => (line, violation),
+                None => (line, violation.located(line, None)),
+            })
+            .collect()
+    }
 
-                // Skip already matched lines and comments
-                if matched_lines.contains(&line_num) || trimmed.starts_with("--") {
-                    return false;
-                }
+    /// Statements without their locations, for checks of a whole migration
+    fn asts(statements: &[ParsedStatement]) -> Vec<Statement> {
+        statements.iter().map(|stmt| stmt.stmt.clone()).collect()
+    }
 
-                // Check if line starts with the statement keyword
-                trimmed.to_uppercase().starts_with(first_word)
-            })
-            .map(|(idx, _)| idx + 1) // 1-indexed
-            .unwrap_or(1) // Fallback to line 1 (won't be in ignore range)
+    /// Source line and column of each statement, in order
+    ///
+    /// The statements are located by parsing `sql` again, so they must have been
+    /// parsed from it. Statements that can't be located get line 1, column 1.
+    pub fn statement_locations(statements: &[Statement], sql: &str) -> Vec<(usize, usize)> {
+        match SqlParser::new().parse_statements(sql) {
+            Ok(located) if located.len() == statements.len() => located
+                .iter()
+                .map(|stmt| (stmt.line, stmt.column))
+                .collect(),
+            _ => vec![(1, 1); statements.len()],
+        }
     }
 
     /// Get all available check names
     pub fn all_check_names() -> Vec<&'static str> {
-        Self::new().names
+        Self::new().all_names
     }
 }
 
@@ -237,7 +733,34 @@ mod tests {
     #[test]
     fn test_registry_creation() {
         let registry = Registry::new();
-        assert_eq!(registry.checks.len(), Registry::all_check_names().len());
+        let opt_in = [
+            "CharTypeCheck",
+            "DmlInMigrationCheck",
+            "MissingLockTimeoutCheck",
+            "MoneyTypeCheck",
+            "NonIdempotentMigrationCheck",
+            "PreferTextOverVarcharCheck",
+            "SerialVsIdentityCheck",
+            "TimestampWithoutTimeZoneCheck",
+            "UnloggedTableCheck",
+        ];
+        assert_eq!(
+            registry.checks.len(),
+            Registry::all_check_names().len() - opt_in.len()
+        );
+        assert!(opt_in.iter().all(|name| !registry.names.contains(name)));
+    }
+
+    #[test]
+    fn test_registry_with_opt_in_checks() {
+        let config = Config {
+            enable_checks: vec!["DmlInMigrationCheck".to_string()],
+            ..Default::default()
+        };
+
+        let registry = Registry::with_config(&config);
+        assert_eq!(registry.checks.len(), Registry::new().checks.len() + 1);
+        assert!(registry.names.contains(&"DmlInMigrationCheck"));
     }
 
     #[test]
@@ -248,7 +771,7 @@ mod tests {
         };
 
         let registry = Registry::with_config(&config);
-        assert_eq!(registry.checks.len(), Registry::all_check_names().len() - 1);
+        assert_eq!(registry.checks.len(), Registry::new().checks.len() - 1);
     }
 
     #[test]
@@ -259,7 +782,7 @@ mod tests {
         };
 
         let registry = Registry::with_config(&config);
-        assert_eq!(registry.checks.len(), Registry::all_check_names().len() - 2);
+        assert_eq!(registry.checks.len(), Registry::new().checks.len() - 2);
     }
 
     #[test]
@@ -292,12 +815,38 @@ ALTER TABLE users DROP COLUMN email;
         let ignore_ranges = vec![IgnoreRange {
             start_line: 2,
             end_line: 4,
+            checks: None,
         }];
 
         let violations = registry.check_statements_with_context(&statements, sql, &ignore_ranges);
         assert_eq!(violations.len(), 0); // Statement is in safety-assured block
     }
 
+    #[test]
+    fn test_scoped_block_keeps_merged_findings_of_other_checks() {
+        use sqlparser::dialect::PostgreSqlDialect;
+        use sqlparser::parser::Parser;
+
+        let registry = Registry::new();
+        let sql = "
+-- safety-assured:start(AddUniqueConstraintCheck)
+ALTER TABLE users ADD UNIQUE (email);
+-- safety-assured:end
+        ";
+
+        let statements = Parser::parse_sql(&PostgreSqlDialect {}, sql).unwrap();
+        let ignore_ranges = vec![IgnoreRange {
+            start_line: 2,
+            end_line: 4,
+            checks: Some(vec!["AddUniqueConstraintCheck".to_string()]),
+        }];
+
+        let violations = registry.check_statements_with_context(&statements, sql, &ignore_ranges);
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].check, "UnnamedConstraintCheck");
+        assert!(violations[0].notes.is_empty());
+    }
+
     #[test]
     fn test_check_without_safety_assured_block() {
         use sqlparser::dialect::PostgreSqlDialect;
@@ -312,4 +861,289 @@ ALTER TABLE users DROP COLUMN email;
         let violations = registry.check_statements_with_context(&statements, sql, &ignore_ranges);
         assert_eq!(violations.len(), 1); // DropColumnCheck should trigger
     }
+
+    #[test]
+    fn test_violations_record_check_name() {
+        use sqlparser::dialect::PostgreSqlDialect;
+        use sqlparser::parser::Parser;
+
+        let registry = Registry::new();
+        let sql = "ALTER TABLE users DROP COLUMN email;";
+
+        let statements = Parser::parse_sql(&PostgreSqlDialect {}, sql).unwrap();
+        let violations = registry.check_statements(&statements);
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].check, "DropColumnCheck");
+    }
+
+    #[test]
+    fn test_check_statements_with_lines() {
+        use sqlparser::dialect::PostgreSqlDialect;
+        use sqlparser::parser::Parser;
+
+        let registry = Registry::new();
+        let sql = r#"
+ALTER TABLE users ADD COLUMN bio TEXT;
+-- safety-assured:start
+ALTER TABLE users DROP COLUMN email;
+-- safety-assured:end
+        "#;
+
+        let statements = Parser::parse_sql(&PostgreSqlDialect {}, sql).unwrap();
+        let violations = registry.check_statements_with_lines(&statements, sql);
+        assert_eq!(violations.len(), 1); // Not filtered by the block
+        assert_eq!(violations[0].0, 4);
+        assert_eq!(violations[0].1.operation, "DROP COLUMN");
+    }
+
+    #[test]
+    fn test_statement_locations_use_source_spans() {
+        use crate::parser::SqlParser;
+
+        // Keyword matching would pick the comment, and the first line for the second
+        // statement sharing it
+        let sql = r#"/*
+ALTER TABLE users DROP COLUMN email;
+*/
+ALTER TABLE users ADD COLUMN bio TEXT; ALTER TABLE users
+  DROP COLUMN email;
+        "#;
+
+        let parsed = SqlParser::new().parse_with_metadata(sql).unwrap();
+        let lines: Vec<usize> = parsed.statements.iter().map(|stmt| stmt.line).collect();
+        assert_eq!(lines, vec![4, 4]);
+        let statements: Vec<Statement> = parsed
+            .statements
+            .iter()
+            .map(|stmt| stmt.stmt.clone())
+            .collect();
+        assert_eq!(
+            Registry::statement_locations(&statements, sql),
+            vec![(4, 1), (4, 40)]
+        );
+        assert_eq!(parsed.statements[1].column, 40);
+
+        let violations = Registry::new().check_parsed(&parsed);
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].0, 4);
+        assert_eq!(violations[0].1.line, Some(4));
+        assert_eq!(violations[0].1.column, Some(40));
+    }
+
+    #[test]
+    fn test_check_parsed_checks_function_bodies_on_their_own() {
+        use crate::parser::SqlParser;
+
+        // The table created when the function runs doesn't exist yet for the index
+        let sql = "CREATE FUNCTION create_audit() RETURNS void AS $$
+  CREATE TABLE audit (id BIGINT, user_id BIGINT);
+  ALTER TABLE users DROP COLUMN email;
+$$ LANGUAGE sql;
+CREATE INDEX audit_user_id_idx ON audit (user_id);";
+        let parsed = SqlParser::new().parse_with_metadata(sql).unwrap();
+        assert_eq!(parsed.function_statements.len(), 2);
+
+        let violations = Registry::new().check_parsed(&parsed);
+        let found: Vec<(usize, &str)> = violations
+            .iter()
+            .map(|(line, violation)| (*line, violation.operation.as_str()))
+            .collect();
+        assert_eq!(
+            found,
+            vec![(3, "DROP COLUMN"), (5, "ADD INDEX without CONCURRENTLY")]
+        );
+    }
+
+    #[test]
+    fn test_check_parsed_includes_raw_statements() {
+        use crate::parser::SqlParser;
+
+        let sql = "CLUSTER users USING users_pkey;\nALTER TABLE users DROP COLUMN email;";
+        let parsed = SqlParser::new().parse_with_metadata(sql).unwrap();
+
+        let violations = Registry::new().check_parsed(&parsed);
+        assert_eq!(violations.len(), 2);
+        assert_eq!(
+            (violations[0].0, violations[0].1.check.as_str()),
+            (1, "ClusterCheck")
+        );
+        assert_eq!(
+            (violations[1].0, violations[1].1.check.as_str()),
+            (2, "DropColumnCheck")
+        );
+    }
+
+    #[test]
+    fn test_check_timings() {
+        use sqlparser::dialect::PostgreSqlDialect;
+        use sqlparser::parser::Parser;
+
+        let mut registry = Registry::new();
+        assert!(registry.check_timings().is_empty());
+
+        registry.enable_timings();
+        let statements = Parser::parse_sql(
+            &PostgreSqlDialect {},
+            "ALTER TABLE users DROP COLUMN email;",
+        )
+        .unwrap();
+        registry.check_statements(&statements);
+
+        let timings = registry.check_timings();
+        assert_eq!(timings.len(), registry.checks.len());
+        assert!(timings.contains_key("DropColumnCheck"));
+    }
+
+    #[test]
+    fn test_violations_record_configured_severity() {
+        use sqlparser::dialect::PostgreSqlDialect;
+        use sqlparser::parser::Parser;
+
+        let config = Config {
+            severity: [("DropColumnCheck".to_string(), Severity::Warning)].into(),
+            ..Default::default()
+        };
+        let registry = Registry::with_config(&config);
+        let sql = "ALTER TABLE users DROP COLUMN email; ALTER TABLE users RENAME TO people;";
+
+        let statements = Parser::parse_sql(&PostgreSqlDialect {}, sql).unwrap();
+        let violations = registry.check_statements(&statements);
+        assert_eq!(violations.len(), 2);
+        assert_eq!(violations[0].severity, Severity::Warning);
+        assert_eq!(violations[1].severity, Severity::Error);
+    }
+
+    #[test]
+    fn test_drop_primary_key_cascade_is_merged() {
+        use sqlparser::dialect::PostgreSqlDialect;
+        use sqlparser::parser::Parser;
+
+        let sql = "ALTER TABLE users DROP CONSTRAINT users_pkey CASCADE;";
+        let statements = Parser::parse_sql(&PostgreSqlDialect {}, sql).unwrap();
+
+        let violations = Registry::new().check_statements(&statements);
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].check, "DropPrimaryKeyCheck");
+        assert!(violations[0].notes[0].starts_with("DROP CONSTRAINT CASCADE"));
+    }
+
+    #[test]
+    fn test_index_on_partitioned_table_is_reported_once() {
+        use crate::parser::SqlParser;
+        use crate::schema::Schema;
+        use std::sync::Arc;
+
+        let sql = "CREATE INDEX events_created_at_idx ON events (created_at);";
+        let mut parsed = SqlParser::new().parse_with_metadata(sql).unwrap();
+        let mut schema = Schema::default();
+        schema.apply(
+            &SqlParser::new()
+                .parse("CREATE TABLE events (id BIGINT, created_at TIMESTAMPTZ) PARTITION BY RANGE (created_at);")
+                .unwrap(),
+        );
+        parsed.schema = Arc::new(schema);
+
+        let violations = Registry::new().check_parsed(&parsed);
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].1.check, "PartitionedTableIndexCheck");
+
+        // Partitioned table created earlier in the same file
+        let sql = "CREATE TABLE events (id BIGINT, created_at TIMESTAMPTZ) PARTITION BY RANGE (created_at);
+CREATE INDEX CONCURRENTLY events_created_at_idx ON events (created_at);";
+        let parsed = SqlParser::new().parse_with_metadata(sql).unwrap();
+
+        let violations = Registry::new().check_parsed(&parsed);
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].1.check, "PartitionedTableIndexCheck");
+    }
+
+    #[test]
+    fn test_delete_without_where_absorbs_dml_in_migration() {
+        use sqlparser::dialect::PostgreSqlDialect;
+        use sqlparser::parser::Parser;
+
+        let statements = Parser::parse_sql(&PostgreSqlDialect {}, "DELETE FROM events;").unwrap();
+        let config = Config {
+            enable_checks: vec!["DmlInMigrationCheck".to_string()],
+            ..Default::default()
+        };
+
+        let violations = Registry::with_config(&config).check_statements(&statements);
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].check, "DeleteWithoutWhereCheck");
+        assert!(violations[0].notes[0].starts_with("DELETE in migration"));
+    }
+
+    #[test]
+    fn test_update_without_where_absorbs_dml_in_migration() {
+        use sqlparser::dialect::PostgreSqlDialect;
+        use sqlparser::parser::Parser;
+
+        let statements =
+            Parser::parse_sql(&PostgreSqlDialect {}, "UPDATE users SET active = true;").unwrap();
+        let config = Config {
+            enable_checks: vec!["DmlInMigrationCheck".to_string()],
+            ..Default::default()
+        };
+
+        let violations = Registry::with_config(&config).check_statements(&statements);
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].check, "UpdateWithoutWhereCheck");
+        assert!(violations[0].notes[0].starts_with("UPDATE in migration"));
+    }
+
+    #[test]
+    fn test_checks_can_escalate_violation_severity() {
+        use sqlparser::dialect::PostgreSqlDialect;
+        use sqlparser::parser::Parser;
+
+        let sql = "ALTER TABLE users DISABLE TRIGGER audit; ALTER TABLE users DISABLE TRIGGER ALL;";
+        let statements = Parser::parse_sql(&PostgreSqlDialect {}, sql).unwrap();
+
+        let violations = Registry::new().check_statements(&statements);
+        assert_eq!(violations[0].severity, Severity::Warning);
+        assert_eq!(violations[1].severity, Severity::Error);
+    }
+
+    #[test]
+    fn test_violations_use_default_severity_of_check() {
+        use sqlparser::dialect::PostgreSqlDialect;
+        use sqlparser::parser::Parser;
+
+        let sql =
+            "CREATE TRIGGER audit AFTER UPDATE ON users FOR EACH ROW EXECUTE FUNCTION audit();";
+        let statements = Parser::parse_sql(&PostgreSqlDialect {}, sql).unwrap();
+
+        let violations = Registry::new().check_statements(&statements);
+        assert_eq!(violations[0].severity, Severity::Warning);
+
+        let config = Config {
+            severity: [("CreateTriggerCheck".to_string(), Severity::Error)].into(),
+            ..Default::default()
+        };
+        let violations = Registry::with_config(&config).check_statements(&statements);
+        assert_eq!(violations[0].severity, Severity::Error);
+    }
+
+    #[test]
+    fn test_overlapping_violations_are_merged() {
+        use sqlparser::dialect::PostgreSqlDialect;
+        use sqlparser::parser::Parser;
+
+        let sql = "ALTER TABLE users ADD UNIQUE (email);";
+        let statements = Parser::parse_sql(&PostgreSqlDialect {}, sql).unwrap();
+
+        let violations = Registry::new().check_statements(&statements);
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].check, "AddUniqueConstraintCheck");
+        assert_eq!(violations[0].notes.len(), 1);
+
+        let config = Config {
+            report_overlapping_violations: true,
+            ..Default::default()
+        };
+        let violations = Registry::with_config(&config).check_statements(&statements);
+        assert_eq!(violations.len(), 2);
+        assert!(violations.iter().all(|v| v.notes.is_empty()));
+    }
 }
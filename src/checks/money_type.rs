@@ -0,0 +1,90 @@
+//! Detection for MONEY columns.
+//!
+//! This check identifies columns of type `MONEY` in `CREATE TABLE` and
+//! `ALTER TABLE ... ADD COLUMN`. It's opt-in: enable it with
+//! `enable_checks = ["MoneyTypeCheck"]`.
+//!
+//! The money type formats and parses values according to the database's `lc_monetary`
+//! setting, so the same data reads differently (or fails to load) on a server with
+//! another locale. It has a fixed fractional precision, can't store a currency, and
+//! converting it to numeric goes through that locale-dependent text.
+//!
+//! The recommended approach is `NUMERIC` with an explicit precision and scale, plus a
+//! currency column if needed.
+
+use crate::checks::{new_columns, Check};
+use crate::violation::Violation;
+use sqlparser::ast::{DataType, Statement};
+
+pub struct MoneyTypeCheck;
+
+impl Check for MoneyTypeCheck {
+    fn check(&self, stmt: &Statement) -> Vec<Violation> {
+        new_columns(stmt)
+            .into_iter()
+            .filter_map(|(table_name, column_def)| {
+                let DataType::Custom(type_name, _) = &column_def.data_type else {
+                    return None;
+                };
+                if !type_name.to_string().eq_ignore_ascii_case("money") {
+                    return None;
+                }
+
+                let column_name = &column_def.name;
+
+                Some(Violation::new(
+                    "MONEY column",
+                    format!(
+                        "Column '{column}' on table '{table}' uses MONEY, which formats and parses values according to the \
+                        server's lc_monetary locale setting. The same data reads differently, or fails to restore, on a server \
+                        with another locale, and the fractional precision is fixed by that locale.",
+                        column = column_name,
+                        table = table_name
+                    ),
+                    format!(
+                        r#"Use NUMERIC with an explicit precision and scale instead:
+
+   {column} NUMERIC(19, 4)
+
+If amounts can be in several currencies, store the currency code alongside:
+
+   {column}_currency TEXT"#,
+                        column = column_name
+                    ),
+                ))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{assert_allows, assert_detects_violation};
+
+    #[test]
+    fn test_detects_money_in_create_table() {
+        assert_detects_violation!(
+            MoneyTypeCheck,
+            "CREATE TABLE invoices (id BIGINT PRIMARY KEY, total MONEY NOT NULL);",
+            "MONEY column"
+        );
+    }
+
+    #[test]
+    fn test_detects_money_in_add_column() {
+        assert_detects_violation!(
+            MoneyTypeCheck,
+            "ALTER TABLE invoices ADD COLUMN tax money;",
+            "MONEY column"
+        );
+    }
+
+    #[test]
+    fn test_allows_numeric() {
+        assert_allows!(
+            MoneyTypeCheck,
+            "CREATE TABLE invoices (total NUMERIC(19, 4), status order_status);"
+        );
+    }
+}
@@ -0,0 +1,271 @@
+//! Detection for CREATE and DROP statements that fail when re-run.
+//!
+//! This check identifies `CREATE` statements without `IF NOT EXISTS` (or `OR REPLACE`
+//! for views, functions, and triggers) and `DROP` statements without `IF EXISTS`. It's
+//! opt-in: enable it with `enable_checks = ["NonIdempotentMigrationCheck"]`.
+//!
+//! Diesel records each migration as applied and never runs it twice, so most projects
+//! don't need this. Teams that re-run migrations against throwaway or partially
+//! migrated environments do: a second run of a plain `CREATE TABLE` fails because the
+//! table already exists.
+//!
+//! The recommended approach is adding `IF NOT EXISTS` / `IF EXISTS` / `OR REPLACE`.
+
+use crate::checks::Check;
+use crate::violation::Violation;
+use sqlparser::ast::{CreateView, Statement};
+
+pub struct NonIdempotentMigrationCheck;
+
+/// How a statement fails when re-run
+enum Rerun {
+    /// CREATE of an object that then already exists, with the clause making it idempotent
+    Create {
+        object: String,
+        name: String,
+        clause: &'static str,
+    },
+    /// DROP of an object that then no longer exists
+    Drop { object: String, names: String },
+}
+
+impl NonIdempotentMigrationCheck {
+    fn rerun_failure(stmt: &Statement) -> Option<Rerun> {
+        let create = |object: &str, name: String, clause| {
+            Some(Rerun::Create {
+                object: object.to_string(),
+                name,
+                clause,
+            })
+        };
+        let drop = |object: &str, names: String| {
+            Some(Rerun::Drop {
+                object: object.to_string(),
+                names,
+            })
+        };
+
+        match stmt {
+            Statement::CreateTable(table) if !table.if_not_exists => {
+                create("TABLE", table.name.to_string(), "IF NOT EXISTS")
+            }
+            Statement::CreateIndex(index) if !index.if_not_exists => create(
+                "INDEX",
+                index
+                    .name
+                    .as_ref()
+                    .map(|name| name.to_string())
+                    .unwrap_or_else(|| format!("<unnamed> on {}", index.table_name)),
+                "IF NOT EXISTS",
+            ),
+            Statement::CreateSchema {
+                schema_name,
+                if_not_exists: false,
+                ..
+            } => create("SCHEMA", schema_name.to_string(), "IF NOT EXISTS"),
+            Statement::CreateExtension(extension) if !extension.if_not_exists => {
+                create("EXTENSION", extension.name.to_string(), "IF NOT EXISTS")
+            }
+            Statement::CreateSequence {
+                name,
+                if_not_exists: false,
+                ..
+            } => create("SEQUENCE", name.to_string(), "IF NOT EXISTS"),
+            Statement::CreateView(CreateView {
+                name,
+                materialized,
+                or_replace,
+                if_not_exists,
+                ..
+            }) if !or_replace && !if_not_exists => {
+                if *materialized {
+                    create("MATERIALIZED VIEW", name.to_string(), "IF NOT EXISTS")
+                } else {
+                    create("VIEW", name.to_string(), "OR REPLACE")
+                }
+            }
+            Statement::CreateFunction(function)
+                if !function.or_replace && !function.if_not_exists =>
+            {
+                create("FUNCTION", function.name.to_string(), "OR REPLACE")
+            }
+            Statement::CreateTrigger(trigger) if !trigger.or_replace => {
+                create("TRIGGER", trigger.name.to_string(), "OR REPLACE")
+            }
+            Statement::Drop {
+                object_type,
+                if_exists: false,
+                names,
+                ..
+            } => drop(
+                &object_type.to_string(),
+                names
+                    .iter()
+                    .map(|name| name.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            ),
+            Statement::DropFunction(function) if !function.if_exists => drop(
+                "FUNCTION",
+                function
+                    .func_desc
+                    .iter()
+                    .map(|desc| desc.name.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            ),
+            Statement::DropExtension(extension) if !extension.if_exists => drop(
+                "EXTENSION",
+                extension
+                    .names
+                    .iter()
+                    .map(|name| name.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            ),
+            Statement::DropTrigger(trigger) if !trigger.if_exists => {
+                drop("TRIGGER", trigger.trigger_name.to_string())
+            }
+            Statement::DropPolicy {
+                if_exists: false,
+                name,
+                ..
+            } => drop("POLICY", name.to_string()),
+            _ => None,
+        }
+    }
+}
+
+impl Check for NonIdempotentMigrationCheck {
+    fn check(&self, stmt: &Statement) -> Vec<Violation> {
+        let Some(rerun) = Self::rerun_failure(stmt) else {
+            return vec![];
+        };
+
+        let violation = match rerun {
+            Rerun::Create {
+                object,
+                name,
+                clause,
+            } => Violation::new(
+                format!("CREATE {} without {}", object, clause),
+                format!(
+                    "CREATE {object} '{name}' fails when the migration runs again, because the {object_lower} already \
+                    exists.",
+                    object = object,
+                    name = name,
+                    object_lower = object.to_lowercase()
+                ),
+                match clause {
+                    "OR REPLACE" => format!(
+                        "Replace the {object_lower} if it exists:\n   CREATE OR REPLACE {object} {name} ...",
+                        object = object,
+                        object_lower = object.to_lowercase(),
+                        name = name
+                    ),
+                    _ => format!(
+                        "Skip creating the {object_lower} if it exists:\n   CREATE {object} IF NOT EXISTS {name} ...",
+                        object = object,
+                        object_lower = object.to_lowercase(),
+                        name = name
+                    ),
+                },
+            ),
+            Rerun::Drop { object, names } => Violation::new(
+                format!("DROP {} without IF EXISTS", object),
+                format!(
+                    "DROP {object} '{names}' fails when the migration runs again, because the {object_lower} no longer \
+                    exists.",
+                    object = object,
+                    names = names,
+                    object_lower = object.to_lowercase()
+                ),
+                format!(
+                    "Skip dropping the {object_lower} if it's gone:\n   DROP {object} IF EXISTS {names};",
+                    object = object,
+                    object_lower = object.to_lowercase(),
+                    names = names
+                ),
+            ),
+        };
+
+        vec![violation]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::checks::test_utils::parse_sql;
+    use crate::{assert_allows, assert_detects_violation};
+
+    #[test]
+    fn test_detects_create_without_if_not_exists() {
+        assert_detects_violation!(
+            NonIdempotentMigrationCheck,
+            "CREATE TABLE users (id BIGINT PRIMARY KEY);",
+            "CREATE TABLE without IF NOT EXISTS"
+        );
+        assert_detects_violation!(
+            NonIdempotentMigrationCheck,
+            "CREATE INDEX CONCURRENTLY users_email_idx ON users (email);",
+            "CREATE INDEX without IF NOT EXISTS"
+        );
+        assert_detects_violation!(
+            NonIdempotentMigrationCheck,
+            "CREATE EXTENSION pg_trgm;",
+            "CREATE EXTENSION without IF NOT EXISTS"
+        );
+    }
+
+    #[test]
+    fn test_detects_create_without_or_replace() {
+        let violations = NonIdempotentMigrationCheck.check(&parse_sql(
+            "CREATE VIEW active_users AS SELECT * FROM users WHERE active;",
+        ));
+
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].operation, "CREATE VIEW without OR REPLACE");
+        assert!(violations[0]
+            .safe_alternative
+            .contains("CREATE OR REPLACE VIEW active_users"));
+    }
+
+    #[test]
+    fn test_detects_drop_without_if_exists() {
+        let violations = NonIdempotentMigrationCheck.check(&parse_sql("DROP TABLE users, posts;"));
+
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].operation, "DROP TABLE without IF EXISTS");
+        assert!(violations[0]
+            .safe_alternative
+            .contains("DROP TABLE IF EXISTS users, posts;"));
+
+        assert_detects_violation!(
+            NonIdempotentMigrationCheck,
+            "DROP INDEX users_email_idx;",
+            "DROP INDEX without IF EXISTS"
+        );
+    }
+
+    #[test]
+    fn test_allows_idempotent_statements() {
+        assert_allows!(
+            NonIdempotentMigrationCheck,
+            "CREATE TABLE IF NOT EXISTS users (id BIGINT PRIMARY KEY);"
+        );
+        assert_allows!(
+            NonIdempotentMigrationCheck,
+            "CREATE INDEX IF NOT EXISTS users_email_idx ON users (email);"
+        );
+        assert_allows!(
+            NonIdempotentMigrationCheck,
+            "CREATE OR REPLACE VIEW active_users AS SELECT * FROM users;"
+        );
+        assert_allows!(NonIdempotentMigrationCheck, "DROP TABLE IF EXISTS users;");
+        assert_allows!(
+            NonIdempotentMigrationCheck,
+            "ALTER TABLE users ADD COLUMN email TEXT;"
+        );
+    }
+}
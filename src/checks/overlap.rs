@@ -0,0 +1,297 @@
+//! Merging of related findings from different checks on the same statement.
+//!
+//! Some statements trip more than one check for what is really a single
+//! change, e.g. `ALTER TABLE users ADD UNIQUE (email)` is both a locking
+//! unique constraint and an unnamed constraint. The secondary finding is
+//! folded into the primary one as a note, so each change is counted once.
+//!
+//! Findings are matched by their target, the object they're about as identified
+//! from the statement's AST, never by their wording.
+
+use crate::violation::Violation;
+use sqlparser::ast::{Ident, ObjectName, TableConstraint};
+use std::fmt::Display;
+
+/// A finding of `secondary` that restates a finding of `primary` with the same target
+struct Overlap {
+    primary: &'static str,
+    secondary: &'static str,
+}
+
+const OVERLAPS: &[Overlap] = &[
+    Overlap {
+        primary: "AddUniqueConstraintCheck",
+        secondary: "UnnamedConstraintCheck",
+    },
+    Overlap {
+        primary: "AddForeignKeyCheck",
+        secondary: "UnnamedConstraintCheck",
+    },
+    Overlap {
+        primary: "AddCheckConstraintCheck",
+        secondary: "UnnamedConstraintCheck",
+    },
+    Overlap {
+        primary: "DropPrimaryKeyCheck",
+        secondary: "DropConstraintCascadeCheck",
+    },
+    Overlap {
+        primary: "DeleteWithoutWhereCheck",
+        secondary: "DmlInMigrationCheck",
+    },
+    Overlap {
+        primary: "InsertSelectCheck",
+        secondary: "DmlInMigrationCheck",
+    },
+    Overlap {
+        primary: "UpdateWithoutWhereCheck",
+        secondary: "DmlInMigrationCheck",
+    },
+    Overlap {
+        primary: "VolatileDefaultCheck",
+        secondary: "AddColumnCheck",
+    },
+];
+
+/// Target of findings about a table, such as the table a DML statement changes
+pub(crate) fn table_target(table: &impl Display) -> String {
+    format!("table {}", table)
+}
+
+/// Target of findings about a column of a table
+pub(crate) fn column_target(table: &ObjectName, column: &Ident) -> String {
+    format!("column {}.{}", table, column)
+}
+
+/// Target of findings about a constraint added to a table
+pub(crate) fn constraint_target(table: &ObjectName, constraint: &TableConstraint) -> String {
+    format!("constraint {} {}", table, constraint)
+}
+
+/// Target of findings about a constraint of a table, known by name
+pub(crate) fn constraint_name_target(table: &ObjectName, name: &Ident) -> String {
+    format!("constraint {} {}", table, name)
+}
+
+/// Fold secondary findings into the primary findings they restate
+///
+/// Expects the violations of a single statement. Each primary finding absorbs at
+/// most one finding per secondary check, in order; the merged violation is an
+/// error if either finding was.
+pub(crate) fn merge_overlapping(mut violations: Vec<Violation>) -> Vec<Violation> {
+    for overlap in OVERLAPS {
+        let mut absorbed = vec![false; violations.len()];
+        let mut merged_into = vec![None; violations.len()];
+
+        for (idx, secondary) in violations.iter().enumerate() {
+            if secondary.check != overlap.secondary {
+                continue;
+            }
+            let primary_idx = violations.iter().enumerate().position(|(p, primary)| {
+                primary.check == overlap.primary
+                    && !absorbed[p]
+                    && primary.target.is_some()
+                    && primary.target == secondary.target
+            });
+            if let Some(p) = primary_idx {
+                absorbed[p] = true;
+                merged_into[idx] = Some(p);
+            }
+        }
+
+        for (idx, target) in merged_into.iter().enumerate() {
+            if let Some(p) = *target {
+                let secondary = violations[idx].clone();
+                violations[p].merge(secondary);
+            }
+        }
+
+        let mut idx = 0;
+        violations.retain(|_| {
+            let keep = merged_into[idx].is_none();
+            idx += 1;
+            keep
+        });
+    }
+
+    violations
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::checks::Registry;
+    use crate::config::Config;
+    use crate::parser::SqlParser;
+    use crate::violation::Severity;
+
+    fn violation(check: &str, problem: &str, target: &str, severity: Severity) -> Violation {
+        let mut violation = Violation::new(check, problem, "").with_target(target);
+        violation.check = check.to_string();
+        violation.severity = severity;
+        violation
+    }
+
+    #[test]
+    fn test_merges_unnamed_unique_constraint() {
+        let violations = vec![
+            violation(
+                "AddUniqueConstraintCheck",
+                "Adding UNIQUE constraint '<unnamed>' on table 'users'",
+                "constraint users UNIQUE (email)",
+                Severity::Warning,
+            ),
+            violation(
+                "UnnamedConstraintCheck",
+                "Adding unnamed UNIQUE constraint on table 'users'",
+                "constraint users UNIQUE (email)",
+                Severity::Error,
+            ),
+        ];
+
+        let merged = merge_overlapping(violations);
+        assert_eq!(merged.len(), 1);
+        assert_eq!(merged[0].check, "AddUniqueConstraintCheck");
+        assert_eq!(merged[0].notes.len(), 1);
+        assert!(merged[0].notes[0].contains("unnamed UNIQUE constraint"));
+        assert_eq!(merged[0].severity, Severity::Error);
+    }
+
+    #[test]
+    fn test_merges_add_column_into_volatile_default() {
+        let violations = vec![
+            violation(
+                "AddColumnCheck",
+                "Adding column 'created_at' with DEFAULT on table 'users'",
+                "column users.created_at",
+                Severity::Error,
+            ),
+            violation(
+                "AddColumnCheck",
+                "Adding column 'token' with DEFAULT on table 'users'",
+                "column users.token",
+                Severity::Error,
+            ),
+            violation(
+                "VolatileDefaultCheck",
+                "Adding column 'token' on table 'users' with volatile DEFAULT gen_random_uuid()",
+                "column users.token",
+                Severity::Error,
+            ),
+        ];
+
+        let merged = merge_overlapping(violations);
+        assert_eq!(merged.len(), 2);
+        assert_eq!(
+            merged[0].problem,
+            "Adding column 'created_at' with DEFAULT on table 'users'"
+        );
+        assert_eq!(merged[1].check, "VolatileDefaultCheck");
+        assert_eq!(merged[1].notes.len(), 1);
+    }
+
+    #[test]
+    fn test_keeps_unrelated_findings() {
+        let violations = vec![
+            violation(
+                "AddUniqueConstraintCheck",
+                "Adding UNIQUE constraint 'users_email_key' on table 'users'",
+                "constraint users CONSTRAINT users_email_key UNIQUE (email)",
+                Severity::Error,
+            ),
+            violation(
+                "UnnamedConstraintCheck",
+                "Adding unnamed CHECK constraint on table 'users'",
+                "constraint users CHECK (age >= 0)",
+                Severity::Error,
+            ),
+        ];
+
+        let merged = merge_overlapping(violations);
+        assert_eq!(merged.len(), 2);
+        assert!(merged.iter().all(|v| v.notes.is_empty()));
+    }
+
+    #[test]
+    fn test_matches_on_target_not_wording() {
+        // Same wording, different targets
+        let violations = vec![
+            violation(
+                "DropPrimaryKeyCheck",
+                "same",
+                "constraint users a",
+                Severity::Error,
+            ),
+            violation(
+                "DropConstraintCascadeCheck",
+                "same",
+                "constraint users b",
+                Severity::Error,
+            ),
+        ];
+        assert_eq!(merge_overlapping(violations).len(), 2);
+
+        // Different wording, same target
+        let violations = vec![
+            violation(
+                "DropPrimaryKeyCheck",
+                "one",
+                "constraint users a",
+                Severity::Error,
+            ),
+            violation(
+                "DropConstraintCascadeCheck",
+                "two",
+                "constraint users a",
+                Severity::Error,
+            ),
+        ];
+        assert_eq!(merge_overlapping(violations).len(), 1);
+
+        // Findings without a target are never merged
+        let mut primary = violation("DropPrimaryKeyCheck", "same", "", Severity::Error);
+        let mut secondary = violation("DropConstraintCascadeCheck", "same", "", Severity::Error);
+        primary.target = None;
+        secondary.target = None;
+        assert_eq!(merge_overlapping(vec![primary, secondary]).len(), 2);
+    }
+
+    #[test]
+    fn test_every_overlap_merges_findings_of_real_checks() {
+        let cases = [
+            "ALTER TABLE users ADD UNIQUE (email);",
+            "ALTER TABLE posts ADD FOREIGN KEY (user_id) REFERENCES users(id);",
+            "ALTER TABLE users ADD CHECK (age >= 0);",
+            "ALTER TABLE users DROP CONSTRAINT users_pkey CASCADE;",
+            "DELETE FROM events;",
+            "INSERT INTO archive SELECT * FROM events;",
+            "UPDATE users SET active = true;",
+            "ALTER TABLE users ADD COLUMN token UUID DEFAULT gen_random_uuid();",
+        ];
+        let config = Config {
+            enable_checks: vec!["DmlInMigrationCheck".to_string()],
+            report_overlapping_violations: true,
+            ..Default::default()
+        };
+        let registry = Registry::with_config(&config);
+
+        for (overlap, sql) in OVERLAPS.iter().zip(cases) {
+            let parsed = SqlParser::new().parse_with_metadata(sql).unwrap();
+            let mut violations: Vec<Violation> = registry
+                .check_parsed(&parsed)
+                .into_iter()
+                .map(|(_, violation)| violation)
+                .filter(|v| v.check == overlap.primary || v.check == overlap.secondary)
+                .collect();
+            assert_eq!(violations.len(), 2, "{}", sql);
+
+            // Rewording the findings doesn't affect merging
+            for violation in &mut violations {
+                violation.problem = format!("Reworded {}", violation.check);
+            }
+            let merged = merge_overlapping(violations);
+            assert_eq!(merged.len(), 1, "{}", sql);
+            assert_eq!(merged[0].check, overlap.primary);
+        }
+    }
+}
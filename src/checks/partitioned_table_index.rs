@@ -0,0 +1,203 @@
+//! Detection for CREATE INDEX on partitioned tables.
+//!
+//! This check identifies `CREATE INDEX` statements on tables created with `PARTITION BY`,
+//! either earlier in the same migration or in an earlier migration of the same
+//! migrations directory.
+//!
+//! `CREATE INDEX CONCURRENTLY` isn't supported on a partitioned table and fails. A plain
+//! `CREATE INDEX` on an existing partitioned table builds the index on every partition
+//! in one statement, blocking writes to all of them until it finishes. Indexes on a
+//! partitioned table created in the same migration aren't flagged unless CONCURRENTLY
+//! is used, since the table has no data yet.
+//!
+//! The recommended approach is creating the index on the parent only, building each
+//! partition's index concurrently, and attaching them with `ALTER INDEX ... ATTACH PARTITION`.
+
+use crate::checks::{display_or_default, Check};
+use crate::parser::ParsedSql;
+use crate::violation::Violation;
+use sqlparser::ast::Statement;
+use std::collections::HashSet;
+
+pub struct PartitionedTableIndexCheck;
+
+impl Check for PartitionedTableIndexCheck {
+    fn check(&self, _stmt: &Statement) -> Vec<Violation> {
+        vec![]
+    }
+
+    fn check_parsed(&self, parsed: &ParsedSql) -> Vec<(usize, Violation)> {
+        // Partitioned tables created earlier in this migration
+        let mut created = HashSet::new();
+        let mut violations = vec![];
+
+        for parsed_stmt in &parsed.statements {
+            let line = parsed_stmt.line;
+            match &parsed_stmt.stmt {
+                Statement::CreateTable(create) if create.partition_by.is_some() => {
+                    created.insert(create.name.to_string());
+                }
+                Statement::CreateIndex(index) => {
+                    let table = index.table_name.to_string();
+                    let is_new = created.contains(&table);
+                    if !is_new && !parsed.schema.is_partitioned(&index.table_name) {
+                        continue;
+                    }
+                    if is_new && !index.concurrently {
+                        continue;
+                    }
+
+                    let index_name = display_or_default(index.name.as_ref(), "<unnamed>");
+                    let columns = index
+                        .columns
+                        .iter()
+                        .map(|c| c.to_string())
+                        .collect::<Vec<_>>()
+                        .join(", ");
+
+                    let (operation, problem) = if index.concurrently {
+                        (
+                            "CREATE INDEX CONCURRENTLY on partitioned table",
+                            format!(
+                                "Index '{index}' is created CONCURRENTLY on partitioned table '{table}'. PostgreSQL doesn't \
+                                support CONCURRENTLY on partitioned tables, so the migration fails.",
+                                index = index_name,
+                                table = table
+                            ),
+                        )
+                    } else {
+                        (
+                            "CREATE INDEX on partitioned table",
+                            format!(
+                                "Creating index '{index}' on partitioned table '{table}' builds the index on every partition \
+                                in one statement, blocking writes to all partitions until it completes. CONCURRENTLY isn't \
+                                supported on partitioned tables.",
+                                index = index_name,
+                                table = table
+                            ),
+                        )
+                    };
+
+                    violations.push((
+                        line,
+                        Violation::new(
+                            operation,
+                            problem,
+                            format!(
+                                r#"Build the index one partition at a time:
+
+1. Create the index on the parent table only (no partition is indexed yet, so this is fast):
+   CREATE {unique}INDEX {index} ON ONLY {table} ({columns});
+
+2. For each partition, build its index concurrently in a migration without a transaction
+   (metadata.toml with run_in_transaction = false):
+   CREATE {unique}INDEX CONCURRENTLY <partition>_{index} ON <partition> ({columns});
+
+3. Attach each partition's index to the parent index:
+   ALTER INDEX {index} ATTACH PARTITION <partition>_{index};
+
+The parent index becomes valid once every partition's index is attached."#,
+                                unique = if index.unique { "UNIQUE " } else { "" },
+                                index = index_name,
+                                table = table,
+                                columns = columns
+                            ),
+                        ),
+                    ));
+                }
+                _ => {}
+            }
+        }
+
+        violations
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::SqlParser;
+    use crate::schema::Schema;
+    use std::sync::Arc;
+
+    fn check_sql(sql: &str, schema: Schema) -> Vec<(usize, Violation)> {
+        let mut parsed = SqlParser::new().parse_with_metadata(sql).unwrap();
+        parsed.schema = Arc::new(schema);
+        PartitionedTableIndexCheck.check_parsed(&parsed)
+    }
+
+    fn events_schema() -> Schema {
+        let mut schema = Schema::default();
+        schema.apply(
+            &SqlParser::new()
+                .parse("CREATE TABLE events (id BIGINT, created_at TIMESTAMPTZ) PARTITION BY RANGE (created_at);")
+                .unwrap(),
+        );
+        schema
+    }
+
+    #[test]
+    fn test_detects_index_on_partitioned_table_from_earlier_migration() {
+        let violations = check_sql(
+            "CREATE INDEX events_created_at_idx ON events (created_at);",
+            events_schema(),
+        );
+
+        assert_eq!(violations.len(), 1);
+        assert_eq!(
+            violations[0].1.operation,
+            "CREATE INDEX on partitioned table"
+        );
+        assert!(violations[0]
+            .1
+            .safe_alternative
+            .contains("CREATE INDEX events_created_at_idx ON ONLY events (created_at);"));
+    }
+
+    #[test]
+    fn test_detects_concurrently_on_partitioned_table() {
+        let violations = check_sql(
+            "CREATE TABLE events (id BIGINT, created_at TIMESTAMPTZ) PARTITION BY RANGE (created_at);
+
+CREATE INDEX CONCURRENTLY events_created_at_idx ON events (created_at);",
+            Schema::default(),
+        );
+
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].0, 3);
+        assert_eq!(
+            violations[0].1.operation,
+            "CREATE INDEX CONCURRENTLY on partitioned table"
+        );
+    }
+
+    #[test]
+    fn test_allows_index_on_new_partitioned_table() {
+        assert!(check_sql(
+            "CREATE TABLE events (id BIGINT, created_at TIMESTAMPTZ) PARTITION BY RANGE (created_at);
+             CREATE INDEX events_created_at_idx ON events (created_at);",
+            Schema::default(),
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn test_allows_per_partition_approach() {
+        assert!(check_sql(
+            "CREATE INDEX events_created_at_idx ON ONLY events (created_at);
+             CREATE INDEX CONCURRENTLY events_2024_created_at_idx ON events_2024 (created_at);
+             ALTER INDEX events_created_at_idx ATTACH PARTITION events_2024_created_at_idx;",
+            events_schema(),
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn test_ignores_regular_tables() {
+        assert!(check_sql(
+            "CREATE INDEX CONCURRENTLY users_email_idx ON users (email);",
+            events_schema(),
+        )
+        .is_empty());
+    }
+}
@@ -0,0 +1,144 @@
+//! Detection for VARCHAR(n) columns.
+//!
+//! This check identifies columns of type `VARCHAR(n)` / `CHARACTER VARYING(n)` in
+//! `CREATE TABLE` and `ALTER TABLE ... ADD COLUMN`. It's opt-in: enable it with
+//! `enable_checks = ["PreferTextOverVarcharCheck"]`.
+//!
+//! A VARCHAR(n) limit is part of the column type, so raising it later takes an
+//! `ALTER COLUMN TYPE`, and lowering it rewrites the table under an ACCESS EXCLUSIVE
+//! lock. A CHECK constraint on TEXT enforces the same limit and can be replaced
+//! without a rewrite (`NOT VALID`, then `VALIDATE CONSTRAINT`).
+//!
+//! Lengths that are intentional can be allowed via
+//! `[checks.PreferTextOverVarcharCheck] allowed_lengths = [255]`.
+//!
+//! The recommended approach is `TEXT` with a CHECK constraint on its length.
+
+use crate::checks::{new_columns, Check};
+use crate::config::PreferTextOverVarcharConfig;
+use crate::violation::Violation;
+use sqlparser::ast::{CharacterLength, DataType, Statement};
+
+#[derive(Default)]
+pub struct PreferTextOverVarcharCheck {
+    config: PreferTextOverVarcharConfig,
+}
+
+impl PreferTextOverVarcharCheck {
+    pub fn new(config: PreferTextOverVarcharConfig) -> Self {
+        Self { config }
+    }
+}
+
+impl Check for PreferTextOverVarcharCheck {
+    fn check(&self, stmt: &Statement) -> Vec<Violation> {
+        new_columns(stmt)
+            .into_iter()
+            .filter_map(|(table_name, column_def)| {
+                let (DataType::Varchar(Some(CharacterLength::IntegerLength { length, .. }))
+                | DataType::CharacterVarying(Some(CharacterLength::IntegerLength {
+                    length,
+                    ..
+                }))
+                | DataType::CharVarying(Some(CharacterLength::IntegerLength { length, .. }))) =
+                    &column_def.data_type
+                else {
+                    return None;
+                };
+
+                if self.config.allowed_lengths.contains(length) {
+                    return None;
+                }
+
+                let column_name = &column_def.name;
+
+                Some(Violation::new(
+                    "VARCHAR(n) column",
+                    format!(
+                        "Column '{column}' on table '{table}' uses {data_type}. The length limit is part of the column type, \
+                        so changing it later requires ALTER COLUMN TYPE, which rewrites the table under an ACCESS EXCLUSIVE \
+                        lock when the limit is lowered.",
+                        column = column_name,
+                        table = table_name,
+                        data_type = column_def.data_type
+                    ),
+                    format!(
+                        r#"Use TEXT with a CHECK constraint instead:
+
+   {column} TEXT CONSTRAINT {table}_{column}_length CHECK (char_length({column}) <= {length})
+
+The limit can then be changed without a rewrite:
+   ALTER TABLE {table} ADD CONSTRAINT {table}_{column}_length_new CHECK (char_length({column}) <= <new_length>) NOT VALID;
+   ALTER TABLE {table} VALIDATE CONSTRAINT {table}_{column}_length_new;
+   ALTER TABLE {table} DROP CONSTRAINT {table}_{column}_length;
+
+Note: If this length is intentional, allow it with
+[checks.PreferTextOverVarcharCheck] allowed_lengths = [{length}]."#,
+                        column = column_name,
+                        table = table_name,
+                        length = length
+                    ),
+                ))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::checks::test_utils::parse_sql;
+    use crate::{assert_allows, assert_detects_violation};
+
+    #[test]
+    fn test_detects_varchar_in_create_table() {
+        assert_detects_violation!(
+            PreferTextOverVarcharCheck::default(),
+            "CREATE TABLE users (id BIGINT PRIMARY KEY, email VARCHAR(255));",
+            "VARCHAR(n) column"
+        );
+    }
+
+    #[test]
+    fn test_detects_character_varying_in_add_column() {
+        let violations = PreferTextOverVarcharCheck::default().check(&parse_sql(
+            "ALTER TABLE users ADD COLUMN nickname CHARACTER VARYING(50);",
+        ));
+
+        assert_eq!(violations.len(), 1);
+        assert!(violations[0].safe_alternative.contains(
+            "nickname TEXT CONSTRAINT users_nickname_length CHECK (char_length(nickname) <= 50)"
+        ));
+    }
+
+    #[test]
+    fn test_allows_configured_lengths() {
+        let check = PreferTextOverVarcharCheck::new(PreferTextOverVarcharConfig {
+            allowed_lengths: vec![255],
+        });
+
+        assert_allows!(check, "CREATE TABLE users (email VARCHAR(255));");
+        assert_eq!(
+            check
+                .check(&parse_sql("CREATE TABLE users (email VARCHAR(100));"))
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_allows_text_and_unbounded_varchar() {
+        assert_allows!(
+            PreferTextOverVarcharCheck::default(),
+            "CREATE TABLE users (bio TEXT, name VARCHAR, code CHAR(2));"
+        );
+    }
+
+    #[test]
+    fn test_ignores_alter_column_type() {
+        assert_allows!(
+            PreferTextOverVarcharCheck::default(),
+            "ALTER TABLE users ALTER COLUMN email TYPE VARCHAR(100);"
+        );
+    }
+}
@@ -0,0 +1,119 @@
+//! Detection for REFRESH MATERIALIZED VIEW without CONCURRENTLY.
+//!
+//! This check identifies `REFRESH MATERIALIZED VIEW` statements that don't use the
+//! CONCURRENTLY option.
+//!
+//! A plain refresh takes an ACCESS EXCLUSIVE lock on the materialized view while its
+//! query reruns, blocking every reader of the view until the refresh finishes.
+//!
+//! **Parser Handling**: sqlparser cannot parse `REFRESH MATERIALIZED VIEW`, so the
+//! statement is checked as source text (see [`Check::check_raw`]).
+//!
+//! The recommended approach is `REFRESH MATERIALIZED VIEW CONCURRENTLY`, which needs a
+//! unique index on the view.
+
+use crate::checks::Check;
+use crate::parser::RawStatement;
+use crate::violation::Violation;
+use regex::Regex;
+use sqlparser::ast::Statement;
+use std::sync::LazyLock;
+
+/// `REFRESH MATERIALIZED VIEW [CONCURRENTLY] name`
+static REFRESH_PATTERN: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?is)^REFRESH\s+MATERIALIZED\s+VIEW\b(?P<concurrently>\s+CONCURRENTLY\b)?\s*(?P<name>[^\s;]+)?",
+    )
+    .expect("valid regex pattern")
+});
+
+pub struct RefreshMaterializedViewCheck;
+
+impl Check for RefreshMaterializedViewCheck {
+    fn check(&self, _stmt: &Statement) -> Vec<Violation> {
+        vec![]
+    }
+
+    fn check_raw(&self, stmt: &RawStatement) -> Vec<Violation> {
+        if stmt.keyword != "REFRESH" {
+            return vec![];
+        }
+
+        let Some(captures) = REFRESH_PATTERN.captures(&stmt.sql) else {
+            return vec![];
+        };
+        if captures.name("concurrently").is_some() {
+            return vec![];
+        }
+
+        let view = captures
+            .name("name")
+            .map(|m| m.as_str().to_string())
+            .unwrap_or_else(|| "<view>".to_string());
+
+        vec![Violation::new(
+            "REFRESH MATERIALIZED VIEW without CONCURRENTLY",
+            format!(
+                "Refreshing materialized view '{view}' without CONCURRENTLY acquires an ACCESS EXCLUSIVE lock on the view, \
+                blocking all reads until its query has rerun. Duration depends on the cost of the query.",
+                view = view
+            ),
+            format!(
+                r#"Refresh the view concurrently so readers keep seeing the old contents:
+
+1. Make sure the view has a unique index covering all rows (required for CONCURRENTLY):
+   CREATE UNIQUE INDEX CONCURRENTLY {view}_unique_idx ON {view} (<columns>);
+
+2. Refresh concurrently:
+   REFRESH MATERIALIZED VIEW CONCURRENTLY {view};
+
+Considerations:
+- CONCURRENTLY is slower than a plain refresh and only works on already populated views
+- Refreshing is data maintenance; consider running it from a scheduled job instead of a migration"#,
+                view = view
+            ),
+        )]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assert_allows;
+    use crate::checks::test_utils::raw;
+
+    #[test]
+    fn test_detects_refresh() {
+        let violations =
+            RefreshMaterializedViewCheck.check_raw(&raw("REFRESH MATERIALIZED VIEW user_stats"));
+
+        assert_eq!(violations.len(), 1);
+        assert_eq!(
+            violations[0].operation,
+            "REFRESH MATERIALIZED VIEW without CONCURRENTLY"
+        );
+        assert!(violations[0].problem.contains("'user_stats'"));
+    }
+
+    #[test]
+    fn test_detects_refresh_with_data() {
+        let violations = RefreshMaterializedViewCheck
+            .check_raw(&raw("refresh materialized view user_stats with data"));
+        assert_eq!(violations.len(), 1);
+    }
+
+    #[test]
+    fn test_allows_refresh_concurrently() {
+        assert!(RefreshMaterializedViewCheck
+            .check_raw(&raw("REFRESH MATERIALIZED VIEW CONCURRENTLY user_stats"))
+            .is_empty());
+    }
+
+    #[test]
+    fn test_ignores_parsed_statements() {
+        assert_allows!(
+            RefreshMaterializedViewCheck,
+            "CREATE MATERIALIZED VIEW user_stats AS SELECT 1;"
+        );
+    }
+}
@@ -0,0 +1,144 @@
+//! Detection for REINDEX without CONCURRENTLY.
+//!
+//! This check identifies `REINDEX` statements that don't use the CONCURRENTLY option.
+//!
+//! A plain REINDEX blocks writes to the table for the whole rebuild and takes an
+//! ACCESS EXCLUSIVE lock on each index being rebuilt, so queries that would use those
+//! indexes block as well. REINDEX TABLE rebuilds every index of the table, which
+//! effectively blocks reads too.
+//!
+//! **Parser Handling**: sqlparser cannot parse `REINDEX`, so the statement is checked
+//! as source text (see [`Check::check_raw`]).
+//!
+//! The recommended approach is `REINDEX ... CONCURRENTLY` (PostgreSQL 12+), or creating
+//! a replacement index concurrently and dropping the old one.
+
+use crate::checks::Check;
+use crate::parser::RawStatement;
+use crate::violation::Violation;
+use regex::Regex;
+use sqlparser::ast::Statement;
+use std::sync::LazyLock;
+
+/// `REINDEX [(options)] kind [CONCURRENTLY] name`
+static REINDEX_PATTERN: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?is)^REINDEX\s*(?:\((?P<options>[^)]*)\))?\s*(?P<kind>INDEX|TABLE|SCHEMA|DATABASE|SYSTEM)\b(?P<concurrently>\s+CONCURRENTLY\b)?\s*(?P<name>\S+)?",
+    )
+    .expect("valid regex pattern")
+});
+
+/// `CONCURRENTLY` given as an option, optionally followed by a true value
+static CONCURRENTLY_OPTION: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)\bCONCURRENTLY\b(?:\s+(?:TRUE|ON|1))?\s*(?:,|$)").expect("valid regex pattern")
+});
+
+pub struct ReindexCheck;
+
+impl Check for ReindexCheck {
+    fn check(&self, _stmt: &Statement) -> Vec<Violation> {
+        vec![]
+    }
+
+    fn check_raw(&self, stmt: &RawStatement) -> Vec<Violation> {
+        if stmt.keyword != "REINDEX" {
+            return vec![];
+        }
+
+        let Some(captures) = REINDEX_PATTERN.captures(&stmt.sql) else {
+            return vec![];
+        };
+
+        let concurrent_option = captures
+            .name("options")
+            .is_some_and(|options| CONCURRENTLY_OPTION.is_match(options.as_str().trim()));
+        if captures.name("concurrently").is_some() || concurrent_option {
+            return vec![];
+        }
+
+        let kind = captures["kind"].to_uppercase();
+        let name = captures
+            .name("name")
+            .map(|m| m.as_str().to_string())
+            .unwrap_or_else(|| "<name>".to_string());
+
+        let blocked = if kind == "INDEX" {
+            "blocking writes to the table and queries that use the index"
+        } else {
+            "blocking writes and, as every index is locked, effectively reads as well"
+        };
+
+        vec![Violation::new(
+            "REINDEX without CONCURRENTLY",
+            format!(
+                "REINDEX {kind} '{name}' without CONCURRENTLY rebuilds indexes while holding locks for the whole rebuild, \
+                {blocked}. Duration depends on index size.",
+                kind = kind,
+                name = name,
+                blocked = blocked
+            ),
+            format!(
+                r#"Rebuild indexes without blocking:
+
+1. Use CONCURRENTLY (PostgreSQL 12+):
+   REINDEX {kind} CONCURRENTLY {name};
+
+2. On older versions, build a replacement and swap it in:
+   CREATE INDEX CONCURRENTLY <new_index> ON <table> (<columns>);
+   DROP INDEX CONCURRENTLY <old_index>;
+   ALTER INDEX <new_index> RENAME TO <old_index>;
+
+Note: CONCURRENTLY cannot run inside a transaction block. Create metadata.toml in your
+migration directory with:
+   run_in_transaction = false"#,
+                kind = kind,
+                name = name
+            ),
+        )]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assert_allows;
+    use crate::checks::test_utils::raw;
+
+    #[test]
+    fn test_detects_reindex_index() {
+        let violations = ReindexCheck.check_raw(&raw("REINDEX INDEX idx_users_email"));
+
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].operation, "REINDEX without CONCURRENTLY");
+        assert!(violations[0].problem.contains("'idx_users_email'"));
+    }
+
+    #[test]
+    fn test_detects_reindex_table() {
+        let violations = ReindexCheck.check_raw(&raw("reindex table users"));
+
+        assert_eq!(violations.len(), 1);
+        assert!(violations[0].problem.contains("effectively reads"));
+    }
+
+    #[test]
+    fn test_detects_reindex_with_other_options() {
+        let violations = ReindexCheck.check_raw(&raw("REINDEX (VERBOSE) TABLE users"));
+        assert_eq!(violations.len(), 1);
+    }
+
+    #[test]
+    fn test_allows_reindex_concurrently() {
+        assert!(ReindexCheck
+            .check_raw(&raw("REINDEX INDEX CONCURRENTLY idx_users_email"))
+            .is_empty());
+        assert!(ReindexCheck
+            .check_raw(&raw("REINDEX (VERBOSE, CONCURRENTLY) TABLE users"))
+            .is_empty());
+    }
+
+    #[test]
+    fn test_ignores_parsed_statements() {
+        assert_allows!(ReindexCheck, "ALTER TABLE users ADD COLUMN bio TEXT;");
+    }
+}
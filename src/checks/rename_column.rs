@@ -11,15 +11,145 @@
 //! The recommended approach is a multi-step migration that maintains compatibility:
 //! add a new column, backfill data, update application code to use the new column,
 //! and finally remove the old column in a subsequent migration.
+//!
+//! Columns created earlier in the same migration, on their own or with their table, can be
+//! renamed freely, since no running code uses them yet.
+//!
+//! A rename is reported as a warning when the migration also keeps the old name readable:
+//! a view selecting the new column under the old name, or a generated column computing
+//! the old name from the new one.
 
-use crate::checks::Check;
-use crate::violation::Violation;
-use sqlparser::ast::{AlterTable, AlterTableOperation, Statement};
+use crate::checks::{Check, MigrationContext};
+use crate::schema::{ident_key, object_key};
+use crate::violation::{Severity, Violation};
+use sqlparser::ast::{
+    AlterTable, AlterTableOperation, ColumnOption, CreateView, Expr, Ident, ObjectName, SelectItem,
+    SetExpr, Statement, TableFactor,
+};
+
+/// Operation of the downgraded finding for renames the migration keeps compatible
+const MITIGATED: &str = "RENAME COLUMN (mitigated)";
 
 pub struct RenameColumnCheck;
 
-impl Check for RenameColumnCheck {
-    fn check(&self, stmt: &Statement) -> Vec<Violation> {
+/// How a migration keeps the old column name available after a rename
+enum Mitigation {
+    /// A view selecting the new column under the old name
+    View(String),
+    /// A generated column computing the old name from the new one
+    GeneratedColumn,
+}
+
+/// Whether the expression is a reference to the column, qualified or not
+fn is_column(expr: &Expr, column: &Ident) -> bool {
+    match expr {
+        Expr::Identifier(ident) => ident_key(ident) == ident_key(column),
+        Expr::CompoundIdentifier(idents) => idents
+            .last()
+            .is_some_and(|ident| ident_key(ident) == ident_key(column)),
+        Expr::Nested(expr) => is_column(expr, column),
+        _ => false,
+    }
+}
+
+impl RenameColumnCheck {
+    /// A statement of the migration that provides `old` as an alias of `new` on `table`
+    fn find_mitigation(
+        stmts: &[Statement],
+        table: &ObjectName,
+        old: &Ident,
+        new: &Ident,
+    ) -> Option<Mitigation> {
+        stmts.iter().find_map(|stmt| match stmt {
+            Statement::CreateView(CreateView { name, query, .. }) => {
+                let SetExpr::Select(select) = query.body.as_ref() else {
+                    return None;
+                };
+                let reads_table = select.from.iter().any(|from| {
+                    matches!(&from.relation, TableFactor::Table { name, .. } if object_key(name) == object_key(table))
+                });
+                let aliases_column = select.projection.iter().any(|item| {
+                    matches!(item, SelectItem::ExprWithAlias { expr, alias }
+                        if ident_key(alias) == ident_key(old) && is_column(expr, new))
+                });
+                (reads_table && aliases_column).then(|| Mitigation::View(name.to_string()))
+            }
+            Statement::AlterTable(AlterTable {
+                name, operations, ..
+            }) if object_key(name) == object_key(table) => operations.iter().find_map(|op| {
+                let AlterTableOperation::AddColumn { column_def, .. } = op else {
+                    return None;
+                };
+                let mirrors_column = column_def.options.iter().any(|opt| {
+                    matches!(&opt.option, ColumnOption::Generated { generation_expr: Some(expr), .. }
+                        if is_column(expr, new))
+                });
+                (ident_key(&column_def.name) == ident_key(old) && mirrors_column)
+                    .then_some(Mitigation::GeneratedColumn)
+            }),
+            _ => None,
+        })
+    }
+
+    fn mitigated_violation(
+        table: &str,
+        old: &str,
+        new: &str,
+        mitigation: &Mitigation,
+    ) -> Violation {
+        let (problem, cleanup) = match mitigation {
+            Mitigation::View(view) => (
+                format!(
+                    "View '{view}' in this migration selects '{new}' as '{old}', so code reading through the view keeps \
+                    working, but queries against '{table}' itself still fail.",
+                    view = view,
+                    new = new,
+                    old = old,
+                    table = table
+                ),
+                format!("DROP VIEW {};", view),
+            ),
+            Mitigation::GeneratedColumn => (
+                format!(
+                    "Generated column '{old}' added in this migration mirrors '{new}', so reads of the old name keep \
+                    working, but writes to it fail, since generated columns can't be written.",
+                    old = old,
+                    new = new
+                ),
+                format!("ALTER TABLE {} DROP COLUMN {};", table, old),
+            ),
+        };
+
+        Violation::new(
+            MITIGATED,
+            format!(
+                "Renaming column '{old}' to '{new}' in table '{table}' breaks running code that uses the old name. {problem}",
+                old = old,
+                new = new,
+                table = table,
+                problem = problem
+            ),
+            format!(
+                r#"1. Make sure running code only reads the old name through the compatibility layer.
+
+2. Update your application code to reference the new column name, and deploy it.
+
+3. Remove the compatibility layer in a later migration:
+   {cleanup}
+
+If running code still writes to '{old}' directly, add a new column and backfill it instead of renaming."#,
+                cleanup = cleanup,
+                old = old
+            ),
+        )
+    }
+
+    /// Violations for renamed columns, unless `is_new` says the column was just created
+    fn rename_violations(
+        stmt: &Statement,
+        stmts: &[Statement],
+        is_new: impl Fn(&ObjectName, &Ident) -> bool,
+    ) -> Vec<Violation> {
         let Statement::AlterTable(AlterTable {
             name, operations, ..
         }) = stmt
@@ -40,9 +170,24 @@ impl Check for RenameColumnCheck {
                     return None;
                 };
 
+                if is_new(name, old_column_name) {
+                    return None;
+                }
+
                 let old_name = old_column_name.to_string();
                 let new_name = new_column_name.to_string();
 
+                if let Some(mitigation) =
+                    Self::find_mitigation(stmts, name, old_column_name, new_column_name)
+                {
+                    return Some(Self::mitigated_violation(
+                        &table_name,
+                        &old_name,
+                        &new_name,
+                        &mitigation,
+                    ));
+                }
+
                 Some(Violation::new(
                     "RENAME COLUMN",
                     format!(
@@ -80,47 +225,147 @@ This approach maintains compatibility with running instances during the transiti
     }
 }
 
+impl Check for RenameColumnCheck {
+    fn check(&self, _stmt: &Statement) -> Vec<Violation> {
+        vec![]
+    }
+
+    fn check_migration(&self, stmts: &[Statement]) -> Vec<(usize, Violation)> {
+        let mut context = MigrationContext::default();
+        let mut violations = vec![];
+
+        for (stmt_idx, stmt) in stmts.iter().enumerate() {
+            violations.extend(
+                Self::rename_violations(stmt, stmts, |table, column| {
+                    context.is_new_column(table, column)
+                })
+                .into_iter()
+                .map(|violation| (stmt_idx, violation)),
+            );
+            context.record(stmt);
+        }
+
+        violations
+    }
+
+    fn violation_severity(&self, violation: &Violation, configured: Severity) -> Severity {
+        if violation.operation == MITIGATED {
+            Severity::Warning
+        } else {
+            configured
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{assert_allows, assert_detects_violation};
+    use crate::checks::test_utils::check_sql;
 
     #[test]
     fn test_detects_rename_column() {
-        assert_detects_violation!(
-            RenameColumnCheck,
+        let violations = check_sql(
+            &RenameColumnCheck,
             "ALTER TABLE users RENAME COLUMN email TO email_address;",
-            "RENAME COLUMN"
         );
+
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].1.operation, "RENAME COLUMN");
     }
 
     #[test]
     fn test_detects_rename_column_with_schema() {
-        assert_detects_violation!(
-            RenameColumnCheck,
+        let violations = check_sql(
+            &RenameColumnCheck,
             "ALTER TABLE public.users RENAME COLUMN old_name TO new_name;",
-            "RENAME COLUMN"
         );
+
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].1.operation, "RENAME COLUMN");
+    }
+
+    #[test]
+    fn test_allows_rename_of_column_created_in_migration() {
+        let violations = check_sql(
+            &RenameColumnCheck,
+            "CREATE TABLE posts (id BIGINT PRIMARY KEY, titel TEXT);
+             ALTER TABLE users ADD COLUMN nickname TEXT;
+             ALTER TABLE posts RENAME COLUMN titel TO title;
+             ALTER TABLE users RENAME COLUMN nickname TO handle;
+             ALTER TABLE users RENAME COLUMN email TO email_address;",
+        );
+
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].0, 4);
+    }
+
+    #[test]
+    fn test_downgrades_rename_with_compatibility_view() {
+        let violations = check_sql(&RenameColumnCheck,
+            "ALTER TABLE users RENAME COLUMN email TO email_address;
+             CREATE OR REPLACE VIEW users_compat AS SELECT id, users.email_address AS email FROM users;",
+        );
+
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].1.operation, MITIGATED);
+        assert!(violations[0]
+            .1
+            .problem
+            .contains("View 'users_compat' in this migration selects 'email_address' as 'email'"));
+        assert!(violations[0]
+            .1
+            .safe_alternative
+            .contains("DROP VIEW users_compat;"));
+        assert_eq!(
+            RenameColumnCheck.violation_severity(&violations[0].1, Severity::Error),
+            Severity::Warning
+        );
+    }
+
+    #[test]
+    fn test_downgrades_rename_with_generated_alias_column() {
+        let violations = check_sql(
+            &RenameColumnCheck,
+            "ALTER TABLE users RENAME COLUMN email TO email_address;
+             ALTER TABLE users ADD COLUMN email TEXT GENERATED ALWAYS AS (email_address) STORED;",
+        );
+
+        assert_eq!(violations[0].1.operation, MITIGATED);
+        assert!(violations[0].1.problem.contains("writes to it fail"));
+    }
+
+    #[test]
+    fn test_ignores_views_not_providing_old_name() {
+        let violations = check_sql(
+            &RenameColumnCheck,
+            "ALTER TABLE users RENAME COLUMN email TO email_address;
+             CREATE VIEW accounts_compat AS SELECT email_address AS email FROM accounts;
+             CREATE VIEW users_emails AS SELECT email_address FROM users;",
+        );
+
+        assert_eq!(violations[0].1.operation, "RENAME COLUMN");
     }
 
     #[test]
     fn test_ignores_other_alter_operations() {
-        assert_allows!(
-            RenameColumnCheck,
+        assert!(check_sql(
+            &RenameColumnCheck,
             "ALTER TABLE users ADD COLUMN email VARCHAR(255);"
-        );
+        )
+        .is_empty());
     }
 
     #[test]
     fn test_ignores_rename_table() {
-        assert_allows!(RenameColumnCheck, "ALTER TABLE users RENAME TO customers;");
+        assert!(check_sql(&RenameColumnCheck, "ALTER TABLE users RENAME TO customers;").is_empty());
     }
 
     #[test]
     fn test_ignores_other_statements() {
-        assert_allows!(
-            RenameColumnCheck,
+        assert!(check_sql(
+            &RenameColumnCheck,
             "CREATE TABLE users (id SERIAL PRIMARY KEY);"
-        );
+        )
+        .is_empty());
     }
 }
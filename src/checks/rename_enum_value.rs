@@ -0,0 +1,102 @@
+//! Detection for ALTER TYPE ... RENAME VALUE.
+//!
+//! This check identifies `ALTER TYPE` statements that rename a label of an enum type.
+//!
+//! Like RENAME COLUMN, the rename itself is fast, but running application instances
+//! that still send the old label fail with "invalid input value for enum" as soon as it
+//! is applied, and those reading it no longer recognize the stored value.
+//!
+//! The recommended approach is a dual-read/dual-write migration: add the new label,
+//! make the application accept both, backfill, then stop using the old label.
+
+use crate::checks::Check;
+use crate::violation::Violation;
+use sqlparser::ast::{AlterType, AlterTypeOperation, Statement};
+
+pub struct RenameEnumValueCheck;
+
+impl Check for RenameEnumValueCheck {
+    fn check(&self, stmt: &Statement) -> Vec<Violation> {
+        let Statement::AlterType(AlterType {
+            name,
+            operation: AlterTypeOperation::RenameValue(rename),
+        }) = stmt
+        else {
+            return vec![];
+        };
+
+        let type_name = name.to_string();
+        let old_value = &rename.from.value;
+        let new_value = &rename.to.value;
+
+        vec![Violation::new(
+            "RENAME enum VALUE",
+            format!(
+                "Renaming value '{old}' to '{new}' of enum '{type_name}' will cause immediate errors in running application instances. \
+                Any code still sending '{old}' fails after the rename is applied, and code reading the column no longer \
+                recognizes the stored value.",
+                old = old_value,
+                new = new_value,
+                type_name = type_name
+            ),
+            format!(
+                r#"1. Add the new value in its own migration (metadata.toml with run_in_transaction = false):
+   ALTER TYPE {type_name} ADD VALUE IF NOT EXISTS '{new}';
+
+2. Update your application code to read both '{old}' and '{new}', and to write '{new}'.
+
+3. Deploy the updated application code.
+
+4. Backfill existing rows in batches (outside migration):
+   UPDATE <table> SET <column> = '{new}' WHERE <column> = '{old}';
+
+5. Update your application code to stop reading '{old}', and deploy it.
+
+The old value stays in the enum unused, since PostgreSQL can't drop enum values.
+This approach maintains compatibility with running instances during the transition."#,
+                type_name = type_name,
+                old = old_value,
+                new = new_value
+            ),
+        )]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::checks::test_utils::parse_sql;
+    use crate::{assert_allows, assert_detects_violation};
+
+    #[test]
+    fn test_detects_rename_value() {
+        assert_detects_violation!(
+            RenameEnumValueCheck,
+            "ALTER TYPE order_status RENAME VALUE 'canceled' TO 'cancelled';",
+            "RENAME enum VALUE"
+        );
+    }
+
+    #[test]
+    fn test_message_names_both_values() {
+        let violations = RenameEnumValueCheck.check(&parse_sql(
+            "ALTER TYPE order_status RENAME VALUE 'canceled' TO 'cancelled';",
+        ));
+
+        assert!(violations[0]
+            .problem
+            .contains("'canceled' to 'cancelled' of enum 'order_status'"));
+    }
+
+    #[test]
+    fn test_allows_other_alter_type_operations() {
+        assert_allows!(
+            RenameEnumValueCheck,
+            "ALTER TYPE order_status ADD VALUE 'refunded';"
+        );
+        assert_allows!(
+            RenameEnumValueCheck,
+            "ALTER TYPE order_status RENAME TO order_state;"
+        );
+    }
+}
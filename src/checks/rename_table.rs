@@ -9,15 +9,19 @@
 //!
 //! The recommended approach is a multi-step dual-write migration that maintains
 //! compatibility with running instances and avoids dangerous locks.
+//!
+//! Tables created earlier in the same migration can be renamed freely, since no running
+//! code uses them yet.
 
-use crate::checks::Check;
+use crate::checks::{Check, MigrationContext};
 use crate::violation::Violation;
-use sqlparser::ast::{AlterTable, AlterTableOperation, Statement};
+use sqlparser::ast::{AlterTable, AlterTableOperation, ObjectName, Statement};
 
 pub struct RenameTableCheck;
 
-impl Check for RenameTableCheck {
-    fn check(&self, stmt: &Statement) -> Vec<Violation> {
+impl RenameTableCheck {
+    /// Violations for renamed tables, unless `is_new` says the table was just created
+    fn rename_violations(stmt: &Statement, is_new: impl Fn(&ObjectName) -> bool) -> Vec<Violation> {
         let Statement::AlterTable(AlterTable {
             name, operations, ..
         }) = stmt
@@ -25,6 +29,10 @@ impl Check for RenameTableCheck {
             return vec![];
         };
 
+        if is_new(name) {
+            return vec![];
+        }
+
         let old_table_name = name.to_string();
 
         operations
@@ -75,9 +83,20 @@ This approach avoids dangerous locks and maintains compatibility with running in
     }
 }
 
+impl Check for RenameTableCheck {
+    fn check(&self, stmt: &Statement) -> Vec<Violation> {
+        Self::rename_violations(stmt, |_| false)
+    }
+
+    fn check_in_migration(&self, stmt: &Statement, context: &MigrationContext) -> Vec<Violation> {
+        Self::rename_violations(stmt, |table| context.is_new_table(table))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::checks::test_utils::parse_sql;
     use crate::{assert_allows, assert_detects_violation};
 
     #[test]
@@ -98,6 +117,23 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_allows_rename_of_table_created_in_migration() {
+        let mut context = MigrationContext::default();
+        context.record(&parse_sql("CREATE TABLE drafts (id BIGINT PRIMARY KEY);"));
+
+        let stmt = parse_sql("ALTER TABLE drafts RENAME TO posts;");
+        assert!(RenameTableCheck
+            .check_in_migration(&stmt, &context)
+            .is_empty());
+
+        let stmt = parse_sql("ALTER TABLE users RENAME TO customers;");
+        assert_eq!(
+            RenameTableCheck.check_in_migration(&stmt, &context).len(),
+            1
+        );
+    }
+
     #[test]
     fn test_ignores_other_alter_operations() {
         assert_allows!(
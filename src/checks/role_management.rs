@@ -0,0 +1,155 @@
+//! Detection for role management in migrations.
+//!
+//! This check identifies `ALTER` and `DROP` statements for roles, users, and groups.
+//! Creating them is covered by [`CreateRoleCheck`](crate::checks::CreateRoleCheck).
+//!
+//! Managing roles requires the CREATEROLE privilege (or superuser), which the
+//! migration user usually doesn't have in production. Roles are also shared by every
+//! database of the cluster, so a migration that creates or drops one reaches beyond
+//! the application's own database.
+//!
+//! **Parser Handling**: sqlparser parses the common forms of these statements, which
+//! are checked on the AST. Forms it fails on, such as `ALTER GROUP` or `ALTER USER`
+//! with options, are checked as source text (see [`Check::check_raw`]).
+//!
+//! The recommended approach is managing roles in infrastructure automation.
+
+use crate::checks::Check;
+use crate::parser::RawStatement;
+use crate::violation::Violation;
+use regex::Regex;
+use sqlparser::ast::{AlterUser, ObjectType, Statement};
+use std::sync::LazyLock;
+
+/// `ALTER|DROP ROLE|USER|GROUP [IF EXISTS] name`
+static ROLE_PATTERN: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?is)^(?P<action>ALTER|DROP)\s+(?P<kind>ROLE|USER|GROUP)\s+(?:IF\s+EXISTS\s+)?(?P<name>[^\s;]+)",
+    )
+    .expect("valid regex pattern")
+});
+
+pub struct RoleManagementCheck;
+
+impl RoleManagementCheck {
+    fn violation(action: &str, kind: &str, name: &str) -> Violation {
+        Violation::new(
+            format!("{} {}", action, kind),
+            format!(
+                "{action} {kind} '{name}' manages a database role in a migration. This requires the CREATEROLE privilege, \
+                which the migration user usually lacks in production, and roles are shared by every database of the \
+                cluster, beyond the application's own.",
+                action = action,
+                kind = kind,
+                name = name
+            ),
+            r#"Manage roles outside of application migrations:
+
+1. Create and configure roles in infrastructure automation (Ansible, Terraform, etc.)
+   or your DBA's provisioning scripts, before deploying the application.
+
+2. Document the roles the application expects in your project README.
+
+Note: If the migration user is expected to manage roles, use a safety-assured block."#,
+        )
+    }
+}
+
+impl Check for RoleManagementCheck {
+    fn check(&self, stmt: &Statement) -> Vec<Violation> {
+        match stmt {
+            Statement::AlterRole { name, .. } => {
+                vec![Self::violation("ALTER", "ROLE", &name.to_string())]
+            }
+            Statement::AlterUser(AlterUser { name, .. }) => {
+                vec![Self::violation("ALTER", "USER", &name.to_string())]
+            }
+            Statement::Drop {
+                object_type: object_type @ (ObjectType::Role | ObjectType::User),
+                names,
+                ..
+            } => {
+                let kind = match object_type {
+                    ObjectType::Role => "ROLE",
+                    _ => "USER",
+                };
+                let names = names
+                    .iter()
+                    .map(|name| name.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                vec![Self::violation("DROP", kind, &names)]
+            }
+            _ => vec![],
+        }
+    }
+
+    fn check_raw(&self, stmt: &RawStatement) -> Vec<Violation> {
+        let Some(captures) = ROLE_PATTERN.captures(&stmt.sql) else {
+            return vec![];
+        };
+
+        vec![Self::violation(
+            &captures["action"].to_uppercase(),
+            &captures["kind"].to_uppercase(),
+            &captures["name"],
+        )]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::checks::test_utils::parse_sql;
+    use crate::checks::test_utils::raw;
+
+    #[test]
+    fn test_detects_alter_and_drop() {
+        let violations =
+            RoleManagementCheck.check_raw(&raw("ALTER ROLE app SET search_path = public"));
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].operation, "ALTER ROLE");
+        assert!(violations[0].problem.contains("'app'"));
+
+        let violations = RoleManagementCheck.check_raw(&raw("drop user if exists app"));
+        assert_eq!(violations[0].operation, "DROP USER");
+        assert!(violations[0].problem.contains("'app'"));
+    }
+
+    #[test]
+    fn test_detects_parsed_statements() {
+        let violations =
+            RoleManagementCheck.check(&parse_sql("ALTER ROLE app SET search_path = public;"));
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].operation, "ALTER ROLE");
+        assert!(violations[0].problem.contains("'app'"));
+
+        let violations = RoleManagementCheck.check(&parse_sql("ALTER USER app RENAME TO app2;"));
+        assert_eq!(violations[0].operation, "ALTER USER");
+
+        let violations = RoleManagementCheck.check(&parse_sql("DROP USER IF EXISTS app;"));
+        assert_eq!(violations[0].operation, "DROP USER");
+        assert!(violations[0].problem.contains("'app'"));
+
+        let violations = RoleManagementCheck.check(&parse_sql("DROP ROLE reporting, app;"));
+        assert_eq!(violations[0].operation, "DROP ROLE");
+        assert!(violations[0].problem.contains("'reporting, app'"));
+
+        assert!(RoleManagementCheck
+            .check(&parse_sql("CREATE ROLE reporting;"))
+            .is_empty());
+        assert!(RoleManagementCheck
+            .check(&parse_sql("DROP TABLE users;"))
+            .is_empty());
+    }
+
+    #[test]
+    fn test_ignores_create_role_and_other_statements() {
+        assert!(RoleManagementCheck
+            .check_raw(&raw("CREATE ROLE reporting"))
+            .is_empty());
+        assert!(RoleManagementCheck
+            .check_raw(&raw("DROP TABLESPACE fast"))
+            .is_empty());
+    }
+}
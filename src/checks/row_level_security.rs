@@ -0,0 +1,194 @@
+//! Detection for enabling row level security without a policy.
+//!
+//! This check identifies `ALTER TABLE ... ENABLE ROW LEVEL SECURITY` and
+//! `ALTER TABLE ... FORCE ROW LEVEL SECURITY` on tables that get no `CREATE POLICY`
+//! in the same migration.
+//!
+//! With row level security enabled and no policy, PostgreSQL applies a default-deny
+//! policy: every role except the table owner and superusers instantly sees no rows and
+//! can't write any. FORCE extends this to the table owner, which is often the role the
+//! application connects as. Either way, the application loses access to the table the
+//! moment the migration commits.
+//!
+//! **Parser Handling**: sqlparser cannot parse `FORCE ROW LEVEL SECURITY`, so that form
+//! is checked as source text (see [`Check::check_raw_migration`]).
+//!
+//! The recommended approach is creating the policies in the same migration.
+
+use crate::checks::Check;
+use crate::parser::RawStatement;
+use crate::schema::{name_key, object_key};
+use crate::violation::Violation;
+use regex::Regex;
+use sqlparser::ast::{AlterTable, AlterTableOperation, Statement};
+use std::collections::HashSet;
+use std::sync::LazyLock;
+
+/// `ALTER TABLE [IF EXISTS] [ONLY] table ... [NO] FORCE ROW LEVEL SECURITY`
+static FORCE_RLS_PATTERN: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?is)^ALTER\s+TABLE\s+(?:IF\s+EXISTS\s+)?(?:ONLY\s+)?(?P<table>\S+).*?(?P<no>\bNO\s+)?\bFORCE\s+ROW\s+LEVEL\s+SECURITY\b",
+    )
+    .expect("valid regex pattern")
+});
+
+pub struct RowLevelSecurityCheck;
+
+impl RowLevelSecurityCheck {
+    /// Tables that get a policy in the migration
+    fn tables_with_policy(stmts: &[Statement]) -> HashSet<String> {
+        stmts
+            .iter()
+            .filter_map(|stmt| match stmt {
+                Statement::CreatePolicy { table_name, .. } => Some(object_key(table_name)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn violation(mode: &str, table: &str) -> Violation {
+        let affected = if mode == "FORCE" {
+            "every role but superusers, including the table owner the application often connects as,"
+        } else {
+            "every role but the table owner and superusers"
+        };
+
+        Violation::new(
+            format!("{} ROW LEVEL SECURITY", mode),
+            format!(
+                "Row level security is turned on for table '{table}' ({mode}) without a CREATE POLICY in this migration. \
+                Without policies, PostgreSQL denies all access: {affected} instantly sees no rows in '{table}' and can't \
+                write any once the migration commits.",
+                table = table,
+                mode = mode,
+                affected = affected
+            ),
+            format!(
+                r#"Create the policies in the same migration, before turning row level security on:
+
+   CREATE POLICY {table}_access ON {table} USING (<condition>);
+   ALTER TABLE {table} {mode} ROW LEVEL SECURITY;
+
+Note: If the policies already exist from an earlier migration, use a safety-assured block."#,
+                table = table,
+                mode = mode
+            ),
+        )
+    }
+}
+
+impl Check for RowLevelSecurityCheck {
+    fn check(&self, _stmt: &Statement) -> Vec<Violation> {
+        vec![]
+    }
+
+    fn check_migration(&self, stmts: &[Statement]) -> Vec<(usize, Violation)> {
+        let with_policy = Self::tables_with_policy(stmts);
+
+        stmts
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, stmt)| {
+                let Statement::AlterTable(AlterTable {
+                    name, operations, ..
+                }) = stmt
+                else {
+                    return None;
+                };
+                let table = name.to_string();
+                let enables = operations
+                    .iter()
+                    .any(|op| matches!(op, AlterTableOperation::EnableRowLevelSecurity));
+
+                (enables && !with_policy.contains(&object_key(name)))
+                    .then(|| (idx, Self::violation("ENABLE", &table)))
+            })
+            .collect()
+    }
+
+    fn check_raw_migration(
+        &self,
+        stmts: &[Statement],
+        raw_stmts: &[RawStatement],
+    ) -> Vec<(usize, Violation)> {
+        let with_policy = Self::tables_with_policy(stmts);
+
+        raw_stmts
+            .iter()
+            .enumerate()
+            .filter(|(_, raw)| raw.keyword == "ALTER")
+            .filter_map(|(idx, raw)| {
+                let captures = FORCE_RLS_PATTERN.captures(&raw.sql)?;
+                let table = &captures["table"];
+                if captures.name("no").is_some() || with_policy.contains(&name_key(table)) {
+                    return None;
+                }
+                Some((idx, Self::violation("FORCE", table)))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::SqlParser;
+
+    fn check_sql(sql: &str) -> Vec<Violation> {
+        let parsed = SqlParser::new().parse_with_metadata(sql).unwrap();
+        let statements: Vec<Statement> = parsed
+            .statements
+            .into_iter()
+            .map(|stmt| stmt.stmt)
+            .collect();
+        let check = RowLevelSecurityCheck;
+        check
+            .check_migration(&statements)
+            .into_iter()
+            .chain(check.check_raw_migration(&statements, &parsed.raw_statements))
+            .map(|(_, violation)| violation)
+            .collect()
+    }
+
+    #[test]
+    fn test_detects_enable_without_policy() {
+        let violations = check_sql("ALTER TABLE documents ENABLE ROW LEVEL SECURITY;");
+
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].operation, "ENABLE ROW LEVEL SECURITY");
+    }
+
+    #[test]
+    fn test_detects_force_without_policy() {
+        let violations = check_sql("ALTER TABLE documents FORCE ROW LEVEL SECURITY;");
+
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].operation, "FORCE ROW LEVEL SECURITY");
+        assert!(violations[0].problem.contains("'documents'"));
+    }
+
+    #[test]
+    fn test_allows_policy_in_same_migration() {
+        assert!(check_sql(
+            "CREATE POLICY documents_owner ON documents USING (owner_id = current_setting('app.user_id')::bigint);
+             ALTER TABLE documents ENABLE ROW LEVEL SECURITY;
+             ALTER TABLE documents FORCE ROW LEVEL SECURITY;"
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn test_policy_must_be_on_same_table() {
+        let violations = check_sql(
+            "CREATE POLICY notes_owner ON notes USING (true);
+             ALTER TABLE documents ENABLE ROW LEVEL SECURITY;",
+        );
+
+        assert_eq!(violations.len(), 1);
+    }
+
+    #[test]
+    fn test_allows_no_force() {
+        assert!(check_sql("ALTER TABLE documents NO FORCE ROW LEVEL SECURITY;").is_empty());
+    }
+}
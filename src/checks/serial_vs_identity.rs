@@ -0,0 +1,145 @@
+//! Detection for SERIAL columns where identity columns are available.
+//!
+//! This check identifies `SERIAL`, `SMALLSERIAL`, and `BIGSERIAL` columns in
+//! `CREATE TABLE`. It's opt-in, and only reports when `postgres_version` is set to 10
+//! or later: enable it with `enable_checks = ["SerialVsIdentityCheck"]`.
+//!
+//! This is a style check. SERIAL is a shorthand for an integer column with a sequence
+//! default: the sequence's permissions and ownership are managed separately, and
+//! nothing stops writes of explicit values that later collide with it. Identity
+//! columns (PostgreSQL 10+) are the standard-SQL replacement without these gaps.
+//!
+//! Adding SERIAL columns to existing tables is covered by AddSerialColumnCheck.
+//!
+//! The recommended approach is `GENERATED ALWAYS AS IDENTITY`.
+
+use crate::checks::add_serial_column::is_serial_type;
+use crate::checks::Check;
+use crate::violation::Violation;
+use sqlparser::ast::{DataType, Statement};
+
+/// First major version with identity columns
+const IDENTITY_SINCE: u32 = 10;
+
+#[derive(Default)]
+pub struct SerialVsIdentityCheck {
+    postgres_version: Option<u32>,
+}
+
+impl SerialVsIdentityCheck {
+    pub fn new(postgres_version: Option<u32>) -> Self {
+        Self { postgres_version }
+    }
+}
+
+impl Check for SerialVsIdentityCheck {
+    fn check(&self, stmt: &Statement) -> Vec<Violation> {
+        // Identity columns don't exist before PostgreSQL 10, and the version must be known
+        if self
+            .postgres_version
+            .is_none_or(|version| version < IDENTITY_SINCE)
+        {
+            return vec![];
+        }
+
+        let Statement::CreateTable(create) = stmt else {
+            return vec![];
+        };
+
+        let table_name = create.name.to_string();
+
+        create
+            .columns
+            .iter()
+            .filter_map(|column_def| {
+                let DataType::Custom(type_name, _) = &column_def.data_type else {
+                    return None;
+                };
+                let type_name = type_name.to_string().to_uppercase();
+                if !is_serial_type(&type_name) {
+                    return None;
+                }
+
+                let integer_type = match type_name.as_str() {
+                    "SMALLSERIAL" => "SMALLINT",
+                    "SERIAL" => "INTEGER",
+                    _ => "BIGINT",
+                };
+                let column_name = &column_def.name;
+
+                Some(Violation::new(
+                    "SERIAL column",
+                    format!(
+                        "Column '{column}' on table '{table}' uses {type_name}. SERIAL creates a separately managed sequence: \
+                        its permissions and ownership must be handled on their own, and explicit values written to the column \
+                        can later collide with it. Identity columns replace SERIAL on PostgreSQL 10+.",
+                        column = column_name,
+                        table = table_name,
+                        type_name = type_name
+                    ),
+                    format!(
+                        r#"Use an identity column instead:
+
+   {column} {integer_type} GENERATED ALWAYS AS IDENTITY
+
+Use GENERATED BY DEFAULT AS IDENTITY if the application needs to write explicit values."#,
+                        column = column_name,
+                        integer_type = integer_type
+                    ),
+                ))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::checks::test_utils::parse_sql;
+    use crate::{assert_allows, assert_detects_violation};
+
+    const SERIAL_TABLE: &str = "CREATE TABLE users (id BIGSERIAL PRIMARY KEY, email TEXT);";
+
+    #[test]
+    fn test_detects_serial_in_create_table() {
+        assert_detects_violation!(
+            SerialVsIdentityCheck::new(Some(16)),
+            SERIAL_TABLE,
+            "SERIAL column"
+        );
+    }
+
+    #[test]
+    fn test_suggests_matching_integer_type() {
+        let violations = SerialVsIdentityCheck::new(Some(16))
+            .check(&parse_sql("CREATE TABLE tags (id serial PRIMARY KEY);"));
+
+        assert_eq!(violations.len(), 1);
+        assert!(violations[0]
+            .safe_alternative
+            .contains("id INTEGER GENERATED ALWAYS AS IDENTITY"));
+    }
+
+    #[test]
+    fn test_requires_postgres_10_or_later() {
+        assert_allows!(SerialVsIdentityCheck::default(), SERIAL_TABLE);
+        assert_allows!(SerialVsIdentityCheck::new(Some(9)), SERIAL_TABLE);
+    }
+
+    #[test]
+    fn test_allows_identity_columns() {
+        assert_allows!(
+            SerialVsIdentityCheck::new(Some(16)),
+            "CREATE TABLE users (id BIGINT GENERATED ALWAYS AS IDENTITY PRIMARY KEY);"
+        );
+    }
+
+    #[test]
+    fn test_ignores_add_column() {
+        // Covered by AddSerialColumnCheck
+        assert_allows!(
+            SerialVsIdentityCheck::new(Some(16)),
+            "ALTER TABLE users ADD COLUMN seq BIGSERIAL;"
+        );
+    }
+}
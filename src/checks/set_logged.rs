@@ -0,0 +1,122 @@
+//! Detection for switching tables between LOGGED and UNLOGGED.
+//!
+//! This check identifies `ALTER TABLE ... SET LOGGED` and `ALTER TABLE ... SET UNLOGGED`
+//! statements.
+//!
+//! Both directions rewrite the entire table (and its indexes) under an ACCESS EXCLUSIVE
+//! lock, blocking all reads and writes until the rewrite finishes. SET LOGGED also
+//! writes the whole table to the WAL. Unlogged tables are truncated after a crash and
+//! aren't replicated, so SET UNLOGGED additionally risks losing data.
+//!
+//! **Parser Handling**: sqlparser cannot parse `SET LOGGED` / `SET UNLOGGED`, so the
+//! statement is checked as source text (see [`Check::check_raw`]).
+//!
+//! The recommended approach is creating a new table with the desired persistence and
+//! migrating data to it, or a maintenance window.
+
+use crate::checks::Check;
+use crate::parser::RawStatement;
+use crate::violation::Violation;
+use regex::Regex;
+use sqlparser::ast::Statement;
+use std::sync::LazyLock;
+
+/// `ALTER TABLE [IF EXISTS] [ONLY] table ... SET LOGGED|UNLOGGED`
+static SET_LOGGED_PATTERN: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?is)^ALTER\s+TABLE\s+(?:IF\s+EXISTS\s+)?(?:ONLY\s+)?(?P<table>\S+).*\bSET\s+(?P<mode>UNLOGGED|LOGGED)\b",
+    )
+    .expect("valid regex pattern")
+});
+
+pub struct SetLoggedCheck;
+
+impl Check for SetLoggedCheck {
+    fn check(&self, _stmt: &Statement) -> Vec<Violation> {
+        vec![]
+    }
+
+    fn check_raw(&self, stmt: &RawStatement) -> Vec<Violation> {
+        if stmt.keyword != "ALTER" {
+            return vec![];
+        }
+
+        let Some(captures) = SET_LOGGED_PATTERN.captures(&stmt.sql) else {
+            return vec![];
+        };
+
+        let table = &captures["table"];
+        let mode = captures["mode"].to_uppercase();
+
+        let consequence = if mode == "UNLOGGED" {
+            "Afterwards the table is truncated after a crash and no longer replicated to standbys, so its data can be lost."
+        } else {
+            "The whole table is also written to the WAL, which can cause replication lag."
+        };
+
+        vec![Violation::new(
+            format!("SET {}", mode),
+            format!(
+                "ALTER TABLE '{table}' SET {mode} rewrites the entire table and its indexes under an ACCESS EXCLUSIVE lock, \
+                blocking all reads and writes. Duration depends on table size. {consequence}",
+                table = table,
+                mode = mode,
+                consequence = consequence
+            ),
+            format!(
+                r#"Avoid changing the persistence of populated tables in migrations:
+
+1. Create a new table with the desired persistence:
+   CREATE {create}TABLE {table}_new (LIKE {table} INCLUDING ALL);
+
+2. Copy data in batches (outside migration), then swap the tables in a short migration:
+   ALTER TABLE {table} RENAME TO {table}_old;
+   ALTER TABLE {table}_new RENAME TO {table};
+
+3. Or run the change by hand during a scheduled maintenance window."#,
+                create = if mode == "UNLOGGED" { "UNLOGGED " } else { "" },
+                table = table
+            ),
+        )]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assert_allows;
+    use crate::checks::test_utils::raw;
+
+    #[test]
+    fn test_detects_set_unlogged() {
+        let violations = SetLoggedCheck.check_raw(&raw("ALTER TABLE events SET UNLOGGED"));
+
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].operation, "SET UNLOGGED");
+        assert!(violations[0].problem.contains("can be lost"));
+    }
+
+    #[test]
+    fn test_detects_set_logged() {
+        let violations = SetLoggedCheck.check_raw(&raw("alter table if exists events set logged"));
+
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].operation, "SET LOGGED");
+        assert!(violations[0].problem.contains("'events'"));
+    }
+
+    #[test]
+    fn test_ignores_other_raw_statements() {
+        assert!(SetLoggedCheck
+            .check_raw(&raw("ALTER TABLE events SET TABLESPACE fast"))
+            .is_empty());
+    }
+
+    #[test]
+    fn test_ignores_parsed_statements() {
+        assert_allows!(
+            SetLoggedCheck,
+            "ALTER TABLE events ADD COLUMN payload TEXT;"
+        );
+    }
+}
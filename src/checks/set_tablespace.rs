@@ -0,0 +1,167 @@
+//! Detection for moving tables and indexes to another tablespace.
+//!
+//! This check identifies `ALTER TABLE/INDEX/MATERIALIZED VIEW ... SET TABLESPACE`
+//! statements.
+//!
+//! Moving a relation to another tablespace copies all of its data files while holding
+//! an ACCESS EXCLUSIVE lock, blocking all reads and writes until the copy finishes.
+//! Moving a table doesn't move its indexes, which need their own (equally blocking) moves.
+//!
+//! **Parser Handling**: sqlparser cannot parse `SET TABLESPACE`, so the statement is
+//! checked as source text (see [`Check::check_raw`]).
+//!
+//! The recommended approach is building new objects in the target tablespace, or
+//! pg_repack / a maintenance window for tables.
+
+use crate::checks::Check;
+use crate::parser::RawStatement;
+use crate::violation::Violation;
+use regex::Regex;
+use sqlparser::ast::Statement;
+use std::sync::LazyLock;
+
+/// `ALTER kind [IF EXISTS] [ONLY] name ... SET TABLESPACE tablespace`
+static SET_TABLESPACE_PATTERN: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?is)^ALTER\s+(?P<kind>TABLE|INDEX|MATERIALIZED\s+VIEW)\s+(?:IF\s+EXISTS\s+)?(?:ONLY\s+)?(?P<name>\S+).*\bSET\s+TABLESPACE\s+(?P<tablespace>[^\s;]+)",
+    )
+    .expect("valid regex pattern")
+});
+
+/// `ALTER kind ALL IN TABLESPACE source ...`
+static ALL_IN_TABLESPACE_PATTERN: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?is)^ALTER\s+\S+(?:\s+VIEW)?\s+ALL\s+IN\s+TABLESPACE\s+(\S+)")
+        .expect("valid regex pattern")
+});
+
+pub struct SetTablespaceCheck;
+
+impl Check for SetTablespaceCheck {
+    fn check(&self, _stmt: &Statement) -> Vec<Violation> {
+        vec![]
+    }
+
+    fn check_raw(&self, stmt: &RawStatement) -> Vec<Violation> {
+        if stmt.keyword != "ALTER" {
+            return vec![];
+        }
+
+        let Some(captures) = SET_TABLESPACE_PATTERN.captures(&stmt.sql) else {
+            return vec![];
+        };
+
+        let kind = captures["kind"]
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ")
+            .to_uppercase();
+        let tablespace = &captures["tablespace"];
+        let target = match ALL_IN_TABLESPACE_PATTERN.captures(&stmt.sql) {
+            Some(all) => format!("every {} in tablespace '{}'", kind.to_lowercase(), &all[1]),
+            None => format!("{} '{}'", kind.to_lowercase(), &captures["name"]),
+        };
+
+        let alternative = if kind == "INDEX" {
+            format!(
+                r#"Build a replacement index in the target tablespace instead of moving it:
+
+1. Create the new index concurrently:
+   CREATE INDEX CONCURRENTLY <new_index> ON <table> (<columns>) TABLESPACE {tablespace};
+
+2. Drop the old index concurrently:
+   DROP INDEX CONCURRENTLY <old_index>;
+
+3. Rename the new index if needed:
+   ALTER INDEX <new_index> RENAME TO <old_index>;
+
+Note: CONCURRENTLY cannot run inside a transaction block. Create metadata.toml in your
+migration directory with:
+   run_in_transaction = false"#,
+                tablespace = tablespace
+            )
+        } else {
+            format!(
+                r#"Don't move populated relations in migrations:
+
+1. Move the table without blocking using pg_repack, outside of migrations:
+   pg_repack --table <table> --tablespace {tablespace} --moveidx <database>
+
+2. Or run the move by hand during a scheduled maintenance window.
+
+Note: New tables can be created directly in the target tablespace:
+   CREATE TABLE <table> (...) TABLESPACE {tablespace};"#,
+                tablespace = tablespace
+            )
+        };
+
+        vec![Violation::new(
+            "SET TABLESPACE",
+            format!(
+                "Moving {target} to tablespace '{tablespace}' copies all of its data under an ACCESS EXCLUSIVE lock, \
+                blocking all reads and writes until the copy finishes. Duration depends on the size of the relation.",
+                target = target,
+                tablespace = tablespace
+            ),
+            alternative,
+        )]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assert_allows;
+    use crate::checks::test_utils::raw;
+
+    #[test]
+    fn test_detects_table_set_tablespace() {
+        let violations =
+            SetTablespaceCheck.check_raw(&raw("ALTER TABLE users SET TABLESPACE fast"));
+
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].operation, "SET TABLESPACE");
+        assert!(violations[0].problem.contains("table 'users'"));
+        assert!(violations[0].safe_alternative.contains("pg_repack"));
+    }
+
+    #[test]
+    fn test_detects_index_set_tablespace() {
+        let violations = SetTablespaceCheck.check_raw(&raw(
+            "alter index if exists idx_users_email set tablespace fast",
+        ));
+
+        assert_eq!(violations.len(), 1);
+        assert!(violations[0].problem.contains("index 'idx_users_email'"));
+        assert!(violations[0]
+            .safe_alternative
+            .contains("CREATE INDEX CONCURRENTLY"));
+    }
+
+    #[test]
+    fn test_detects_all_in_tablespace() {
+        let violations = SetTablespaceCheck.check_raw(&raw(
+            "ALTER TABLE ALL IN TABLESPACE slow SET TABLESPACE fast",
+        ));
+
+        assert_eq!(violations.len(), 1);
+        assert!(violations[0]
+            .problem
+            .contains("every table in tablespace 'slow'"));
+    }
+
+    #[test]
+    fn test_ignores_other_raw_statements() {
+        assert!(SetTablespaceCheck
+            .check_raw(&RawStatement {
+                keyword: "CLUSTER".to_string(),
+                sql: "CLUSTER users".to_string(),
+                line: 1,
+            })
+            .is_empty());
+    }
+
+    #[test]
+    fn test_ignores_parsed_statements() {
+        assert_allows!(SetTablespaceCheck, "ALTER TABLE users ADD COLUMN bio TEXT;");
+    }
+}
@@ -2,27 +2,57 @@
 //!
 //! This check identifies primary key columns that use SMALLINT or INT/INTEGER data types,
 //! which risk ID exhaustion. SMALLINT maxes out at ~32,767 records, and INT at ~2.1 billion.
+//! SMALLSERIAL and SERIAL are the same types with a sequence attached, and are flagged too.
 //!
 //! While 2.1 billion seems large, active applications can exhaust this faster than expected,
 //! especially with high-frequency inserts, soft deletes, or partitioned data.
 //!
 //! Changing the type later requires an ALTER COLUMN TYPE operation that triggers a full
 //! table rewrite with an ACCESS EXCLUSIVE lock, blocking all operations.
+//!
+//! `ALTER TABLE ... ADD CONSTRAINT ... PRIMARY KEY` on columns that already exist is
+//! checked against the column types declared by earlier statements and migrations.
+//!
+//! Tables where a short key is intentional, like small lookup tables, can be listed in
+//! `[checks.ShortIntegerPrimaryKeyCheck] allowed_tables`.
 
-use crate::checks::Check;
+use crate::checks::{Check, MigrationContext};
+use crate::config::ShortIntegerPrimaryKeyConfig;
+use crate::schema::{name_key, object_key, Schema};
 use crate::violation::Violation;
 use sqlparser::ast::{
-    AlterTable, AlterTableOperation, ColumnDef, ColumnOption, DataType, Expr, ObjectName,
+    AlterTable, AlterTableOperation, ColumnDef, ColumnOption, DataType, Expr, Ident, ObjectName,
     Statement, TableConstraint,
 };
 
-pub struct ShortIntegerPrimaryKeyCheck;
+#[derive(Default)]
+pub struct ShortIntegerPrimaryKeyCheck {
+    config: ShortIntegerPrimaryKeyConfig,
+}
+
+impl ShortIntegerPrimaryKeyCheck {
+    pub fn new(config: ShortIntegerPrimaryKeyConfig) -> Self {
+        Self { config }
+    }
+
+    /// Whether the table is listed in `allowed_tables`, with or without its schema
+    fn is_allowed(&self, table_name: &ObjectName) -> bool {
+        let table = object_key(table_name);
+        let unqualified = table.rsplit('.').next().unwrap_or(&table);
+        self.config.allowed_tables.iter().any(|allowed| {
+            let allowed = name_key(allowed);
+            allowed == table || allowed == unqualified
+        })
+    }
+}
 
 impl Check for ShortIntegerPrimaryKeyCheck {
     fn check(&self, stmt: &Statement) -> Vec<Violation> {
         let mut violations = vec![];
 
         match stmt {
+            Statement::CreateTable(create_table) if self.is_allowed(&create_table.name) => {}
+            Statement::AlterTable(AlterTable { name, .. }) if self.is_allowed(name) => {}
             Statement::CreateTable(create_table) => {
                 // Check inline PRIMARY KEY constraints (id INT PRIMARY KEY)
                 violations.extend(check_inline_pk_columns(
@@ -51,6 +81,25 @@ impl Check for ShortIntegerPrimaryKeyCheck {
 
         violations
     }
+
+    fn check_in_migration(&self, stmt: &Statement, context: &MigrationContext) -> Vec<Violation> {
+        let mut violations = self.check(stmt);
+
+        if let Statement::AlterTable(AlterTable {
+            name, operations, ..
+        }) = stmt
+        {
+            if !self.is_allowed(name) {
+                violations.extend(check_alter_add_constraint_pk_on_existing_columns(
+                    name,
+                    operations,
+                    context.schema(),
+                ));
+            }
+        }
+
+        violations
+    }
 }
 
 /// Check if a data type is a short integer, returning (type_name, exhaustion_limit)
@@ -61,22 +110,32 @@ fn is_short_integer_type(data_type: &DataType) -> Option<(&'static str, &'static
         DataType::Integer(_) => Some(("INTEGER", "~2.1 billion")),
         DataType::Int2(_) => Some(("INT2", "~32,767")),
         DataType::Int4(_) => Some(("INT4", "~2.1 billion")),
+        DataType::Custom(name, _) => match name.to_string().to_uppercase().as_str() {
+            "SMALLSERIAL" | "SERIAL2" => Some(("SMALLSERIAL", "~32,767")),
+            "SERIAL" | "SERIAL4" => Some(("SERIAL", "~2.1 billion")),
+            _ => None,
+        },
         _ => None,
     }
 }
 
-/// Extract column name from an index/constraint column expression
-fn extract_column_name(expr: &Expr) -> Option<String> {
+/// Extract column identifier from an index/constraint column expression
+fn extract_column_ident(expr: &Expr) -> Option<&Ident> {
     match expr {
-        Expr::Identifier(ident) => Some(ident.to_string()),
+        Expr::Identifier(ident) => Some(ident),
         Expr::CompoundIdentifier(idents) => {
             // Handle qualified names like schema.table.column - take last part
-            idents.last().map(|i| i.to_string())
+            idents.last()
         }
         _ => None, // Complex expressions in PK - rare, skip
     }
 }
 
+/// Extract column name from an index/constraint column expression
+fn extract_column_name(expr: &Expr) -> Option<String> {
+    extract_column_ident(expr).map(|ident| ident.to_string())
+}
+
 /// Check inline PRIMARY KEY constraints in column definitions
 fn check_inline_pk_columns(table_name: &ObjectName, columns: &[ColumnDef]) -> Vec<Violation> {
     columns
@@ -194,7 +253,7 @@ fn check_alter_add_constraint_pk(
         .collect();
 
     // If no columns are being added, we can't determine types from this statement alone
-    // (the columns would already exist in the table, and we don't track table state)
+    // (existing columns are looked up in the schema by check_in_migration)
     if added_columns.is_empty() {
         return vec![];
     }
@@ -234,6 +293,44 @@ fn check_alter_add_constraint_pk(
     violations
 }
 
+/// Check ALTER TABLE ADD CONSTRAINT PRIMARY KEY on columns not added in the same statement
+///
+/// The column types come from the schema built by earlier statements and migrations;
+/// columns it doesn't know are skipped.
+fn check_alter_add_constraint_pk_on_existing_columns(
+    table_name: &ObjectName,
+    operations: &[AlterTableOperation],
+    schema: &Schema,
+) -> Vec<Violation> {
+    let added_columns: Vec<String> = operations
+        .iter()
+        .filter_map(|op| match op {
+            AlterTableOperation::AddColumn { column_def, .. } => Some(column_def.name.to_string()),
+            _ => None,
+        })
+        .collect();
+
+    operations
+        .iter()
+        .filter_map(|op| match op {
+            AlterTableOperation::AddConstraint {
+                constraint: TableConstraint::PrimaryKey(pk_constraint),
+                ..
+            } => Some(pk_constraint),
+            _ => None,
+        })
+        .flat_map(|pk_constraint| &pk_constraint.columns)
+        .filter_map(|pk_col| extract_column_ident(&pk_col.column.expr))
+        .filter(|column| !added_columns.contains(&column.to_string()))
+        .filter_map(|column| {
+            let data_type = schema.column_type(table_name, column)?;
+            is_short_integer_type(data_type).map(|(type_name, limit)| {
+                create_violation(table_name.to_string(), column.to_string(), type_name, limit)
+            })
+        })
+        .collect()
+}
+
 /// Create a violation for a short integer primary key
 fn create_violation(
     table_name: String,
@@ -241,6 +338,13 @@ fn create_violation(
     type_name: &str,
     limit: &str,
 ) -> Violation {
+    // SERIAL columns are auto-incrementing, so point to the identity equivalent
+    let replacement_type = if type_name.ends_with("SERIAL") {
+        "BIGINT GENERATED ALWAYS AS IDENTITY"
+    } else {
+        "BIGINT"
+    };
+
     Violation::new(
         "Short integer primary key",
         format!(
@@ -260,7 +364,7 @@ Instead of:
    CREATE TABLE {table} ({column} {type_name} PRIMARY KEY);
 
 Use:
-   CREATE TABLE {table} ({column} BIGINT PRIMARY KEY);
+   CREATE TABLE {table} ({column} {replacement_type} PRIMARY KEY);
 
 BIGINT provides 8 bytes (range: -9.2 quintillion to 9.2 quintillion), which is effectively unlimited
 for auto-incrementing IDs. The minimal storage overhead (4 extra bytes per row) is negligible.
@@ -272,7 +376,8 @@ Note: If this is an intentionally small table (e.g., lookup table with <100 entr
 use 'safety-assured' to bypass this check."#,
             table = table_name,
             column = column_name,
-            type_name = type_name
+            type_name = type_name,
+            replacement_type = replacement_type
         ),
     )
 }
@@ -280,6 +385,7 @@ use 'safety-assured' to bypass this check."#,
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::checks::test_utils::check_sequence;
     use crate::{assert_allows, assert_detects_violation};
 
     // === CREATE TABLE with inline PRIMARY KEY ===
@@ -287,7 +393,7 @@ mod tests {
     #[test]
     fn test_detects_create_table_int_primary_key() {
         assert_detects_violation!(
-            ShortIntegerPrimaryKeyCheck,
+            ShortIntegerPrimaryKeyCheck::default(),
             "CREATE TABLE users (id INT PRIMARY KEY);",
             "Short integer primary key"
         );
@@ -296,7 +402,7 @@ mod tests {
     #[test]
     fn test_detects_create_table_integer_primary_key() {
         assert_detects_violation!(
-            ShortIntegerPrimaryKeyCheck,
+            ShortIntegerPrimaryKeyCheck::default(),
             "CREATE TABLE users (id INTEGER PRIMARY KEY);",
             "Short integer primary key"
         );
@@ -305,7 +411,7 @@ mod tests {
     #[test]
     fn test_detects_create_table_smallint_primary_key() {
         assert_detects_violation!(
-            ShortIntegerPrimaryKeyCheck,
+            ShortIntegerPrimaryKeyCheck::default(),
             "CREATE TABLE users (id SMALLINT PRIMARY KEY);",
             "Short integer primary key"
         );
@@ -314,7 +420,7 @@ mod tests {
     #[test]
     fn test_detects_create_table_int2_primary_key() {
         assert_detects_violation!(
-            ShortIntegerPrimaryKeyCheck,
+            ShortIntegerPrimaryKeyCheck::default(),
             "CREATE TABLE users (id INT2 PRIMARY KEY);",
             "Short integer primary key"
         );
@@ -323,7 +429,7 @@ mod tests {
     #[test]
     fn test_detects_create_table_int4_primary_key() {
         assert_detects_violation!(
-            ShortIntegerPrimaryKeyCheck,
+            ShortIntegerPrimaryKeyCheck::default(),
             "CREATE TABLE users (id INT4 PRIMARY KEY);",
             "Short integer primary key"
         );
@@ -334,7 +440,7 @@ mod tests {
     #[test]
     fn test_detects_create_table_separate_pk_constraint() {
         assert_detects_violation!(
-            ShortIntegerPrimaryKeyCheck,
+            ShortIntegerPrimaryKeyCheck::default(),
             "CREATE TABLE users (id INT, name TEXT, PRIMARY KEY (id));",
             "Short integer primary key"
         );
@@ -344,7 +450,7 @@ mod tests {
     fn test_detects_composite_primary_key_with_int() {
         use crate::checks::test_utils::parse_sql;
 
-        let check = ShortIntegerPrimaryKeyCheck;
+        let check = ShortIntegerPrimaryKeyCheck::default();
         let stmt = parse_sql(
             "CREATE TABLE events (tenant_id BIGINT, id INT, PRIMARY KEY (tenant_id, id));",
         );
@@ -360,7 +466,7 @@ mod tests {
     fn test_detects_multiple_short_int_columns_in_composite_pk() {
         use crate::checks::test_utils::parse_sql;
 
-        let check = ShortIntegerPrimaryKeyCheck;
+        let check = ShortIntegerPrimaryKeyCheck::default();
         let stmt = parse_sql(
             "CREATE TABLE data (tenant_id INT, user_id SMALLINT, PRIMARY KEY (tenant_id, user_id));",
         );
@@ -376,7 +482,7 @@ mod tests {
     #[test]
     fn test_detects_alter_add_column_int_primary_key() {
         assert_detects_violation!(
-            ShortIntegerPrimaryKeyCheck,
+            ShortIntegerPrimaryKeyCheck::default(),
             "ALTER TABLE users ADD COLUMN id INT PRIMARY KEY;",
             "Short integer primary key"
         );
@@ -385,7 +491,7 @@ mod tests {
     #[test]
     fn test_detects_alter_add_column_smallint_primary_key() {
         assert_detects_violation!(
-            ShortIntegerPrimaryKeyCheck,
+            ShortIntegerPrimaryKeyCheck::default(),
             "ALTER TABLE users ADD COLUMN id SMALLINT PRIMARY KEY;",
             "Short integer primary key"
         );
@@ -396,7 +502,7 @@ mod tests {
     #[test]
     fn test_allows_bigint_primary_key() {
         assert_allows!(
-            ShortIntegerPrimaryKeyCheck,
+            ShortIntegerPrimaryKeyCheck::default(),
             "CREATE TABLE users (id BIGINT PRIMARY KEY);"
         );
     }
@@ -404,23 +510,46 @@ mod tests {
     #[test]
     fn test_allows_int8_primary_key() {
         assert_allows!(
-            ShortIntegerPrimaryKeyCheck,
+            ShortIntegerPrimaryKeyCheck::default(),
             "CREATE TABLE users (id INT8 PRIMARY KEY);"
         );
     }
 
     #[test]
-    fn test_allows_serial_primary_key() {
-        assert_allows!(
-            ShortIntegerPrimaryKeyCheck,
-            "CREATE TABLE users (id SERIAL PRIMARY KEY);"
-        );
+    fn test_detects_serial_primary_key() {
+        use crate::checks::test_utils::parse_sql;
+
+        let violations = ShortIntegerPrimaryKeyCheck::default()
+            .check(&parse_sql("CREATE TABLE users (id SERIAL PRIMARY KEY);"));
+
+        assert_eq!(violations.len(), 1);
+        assert!(violations[0].problem.contains("Using SERIAL"));
+        assert!(violations[0].problem.contains("~2.1 billion"));
+        assert!(violations[0]
+            .safe_alternative
+            .contains("id BIGINT GENERATED ALWAYS AS IDENTITY PRIMARY KEY"));
+        assert!(violations[0]
+            .safe_alternative
+            .contains("id BIGSERIAL PRIMARY KEY"));
+    }
+
+    #[test]
+    fn test_detects_smallserial_primary_key() {
+        use crate::checks::test_utils::parse_sql;
+
+        let violations = ShortIntegerPrimaryKeyCheck::default().check(&parse_sql(
+            "CREATE TABLE countries (id SMALLSERIAL, name TEXT, PRIMARY KEY (id));",
+        ));
+
+        assert_eq!(violations.len(), 1);
+        assert!(violations[0].problem.contains("Using SMALLSERIAL"));
+        assert!(violations[0].problem.contains("~32,767"));
     }
 
     #[test]
     fn test_allows_bigserial_primary_key() {
         assert_allows!(
-            ShortIntegerPrimaryKeyCheck,
+            ShortIntegerPrimaryKeyCheck::default(),
             "CREATE TABLE users (id BIGSERIAL PRIMARY KEY);"
         );
     }
@@ -428,7 +557,7 @@ mod tests {
     #[test]
     fn test_allows_uuid_primary_key() {
         assert_allows!(
-            ShortIntegerPrimaryKeyCheck,
+            ShortIntegerPrimaryKeyCheck::default(),
             "CREATE TABLE users (id UUID PRIMARY KEY);"
         );
     }
@@ -436,7 +565,7 @@ mod tests {
     #[test]
     fn test_allows_int_column_without_primary_key() {
         assert_allows!(
-            ShortIntegerPrimaryKeyCheck,
+            ShortIntegerPrimaryKeyCheck::default(),
             "CREATE TABLE users (id BIGINT PRIMARY KEY, age INT);"
         );
     }
@@ -444,7 +573,7 @@ mod tests {
     #[test]
     fn test_allows_int_unique_not_primary() {
         assert_allows!(
-            ShortIntegerPrimaryKeyCheck,
+            ShortIntegerPrimaryKeyCheck::default(),
             "CREATE TABLE users (id BIGINT PRIMARY KEY, code INT UNIQUE);"
         );
     }
@@ -452,7 +581,7 @@ mod tests {
     #[test]
     fn test_allows_composite_pk_all_bigint() {
         assert_allows!(
-            ShortIntegerPrimaryKeyCheck,
+            ShortIntegerPrimaryKeyCheck::default(),
             "CREATE TABLE events (tenant_id BIGINT, id BIGINT, PRIMARY KEY (tenant_id, id));"
         );
     }
@@ -460,7 +589,7 @@ mod tests {
     #[test]
     fn test_ignores_other_statements() {
         assert_allows!(
-            ShortIntegerPrimaryKeyCheck,
+            ShortIntegerPrimaryKeyCheck::default(),
             "ALTER TABLE users DROP COLUMN age;"
         );
     }
@@ -468,7 +597,7 @@ mod tests {
     #[test]
     fn test_ignores_alter_add_column_without_pk() {
         assert_allows!(
-            ShortIntegerPrimaryKeyCheck,
+            ShortIntegerPrimaryKeyCheck::default(),
             "ALTER TABLE users ADD COLUMN age INT;"
         );
     }
@@ -478,7 +607,7 @@ mod tests {
     #[test]
     fn test_detects_alter_add_constraint_primary_key() {
         assert_detects_violation!(
-            ShortIntegerPrimaryKeyCheck,
+            ShortIntegerPrimaryKeyCheck::default(),
             "ALTER TABLE users ADD COLUMN id INT, ADD CONSTRAINT pk_users PRIMARY KEY (id);",
             "Short integer primary key"
         );
@@ -487,7 +616,7 @@ mod tests {
     #[test]
     fn test_detects_alter_add_constraint_smallint_pk() {
         assert_detects_violation!(
-            ShortIntegerPrimaryKeyCheck,
+            ShortIntegerPrimaryKeyCheck::default(),
             "ALTER TABLE users ADD COLUMN id SMALLINT, ADD CONSTRAINT pk_users PRIMARY KEY (id);",
             "Short integer primary key"
         );
@@ -497,7 +626,7 @@ mod tests {
     fn test_detects_alter_add_constraint_composite_pk_with_int() {
         use crate::checks::test_utils::parse_sql;
 
-        let check = ShortIntegerPrimaryKeyCheck;
+        let check = ShortIntegerPrimaryKeyCheck::default();
         let stmt = parse_sql(
             "ALTER TABLE events ADD COLUMN tenant_id BIGINT, ADD COLUMN id INT, ADD CONSTRAINT pk_events PRIMARY KEY (tenant_id, id);",
         );
@@ -512,27 +641,114 @@ mod tests {
     #[test]
     fn test_allows_alter_add_constraint_bigint_pk() {
         assert_allows!(
-            ShortIntegerPrimaryKeyCheck,
+            ShortIntegerPrimaryKeyCheck::default(),
             "ALTER TABLE users ADD COLUMN id BIGINT, ADD CONSTRAINT pk_users PRIMARY KEY (id);"
         );
     }
 
     #[test]
     fn test_ignores_alter_add_constraint_on_existing_column() {
-        // Can't detect type when column already exists (not added in same statement)
+        // Without a schema, the type of an existing column is unknown
         assert_allows!(
-            ShortIntegerPrimaryKeyCheck,
+            ShortIntegerPrimaryKeyCheck::default(),
             "ALTER TABLE users ADD CONSTRAINT pk_users PRIMARY KEY (id);"
         );
     }
 
+    #[test]
+    fn test_detects_alter_add_constraint_on_column_from_earlier_statement() {
+        let violations = check_sequence(
+            &ShortIntegerPrimaryKeyCheck::default(),
+            "ALTER TABLE events ADD COLUMN id INTEGER;
+             ALTER TABLE events ADD COLUMN tenant_id BIGINT;
+             ALTER TABLE events ADD CONSTRAINT events_pkey PRIMARY KEY (tenant_id, id);",
+        );
+
+        assert_eq!(violations.len(), 1);
+        assert!(violations[0]
+            .problem
+            .contains("Using INTEGER for primary key column 'id' on table 'events'"));
+    }
+
+    #[test]
+    fn test_detects_alter_add_constraint_on_column_from_earlier_migration() {
+        use crate::checks::test_utils::parse_sql;
+        use crate::parser::SqlParser;
+
+        let mut schema = Schema::default();
+        schema.apply(
+            &SqlParser::new()
+                .parse("CREATE TABLE users (id SERIAL, email TEXT);")
+                .unwrap(),
+        );
+        let context = MigrationContext::new(&schema);
+        let stmt = parse_sql("ALTER TABLE users ADD CONSTRAINT users_pkey PRIMARY KEY (id);");
+
+        let violations = ShortIntegerPrimaryKeyCheck::default().check_in_migration(&stmt, &context);
+        assert_eq!(violations.len(), 1);
+        assert!(violations[0].problem.contains("Using SERIAL"));
+    }
+
+    #[test]
+    fn test_allows_alter_add_constraint_on_known_bigint_or_allowed_column() {
+        let sql = "CREATE TABLE users (id BIGINT, code SMALLINT);
+                   ALTER TABLE users ADD CONSTRAINT users_pkey PRIMARY KEY (id);";
+        assert!(check_sequence(&ShortIntegerPrimaryKeyCheck::default(), sql).is_empty());
+
+        let check = ShortIntegerPrimaryKeyCheck::new(ShortIntegerPrimaryKeyConfig {
+            allowed_tables: vec!["users".to_string()],
+        });
+        let sql = "ALTER TABLE users ADD COLUMN code SMALLINT;
+                   ALTER TABLE users ADD CONSTRAINT users_pkey PRIMARY KEY (code);";
+        assert!(check_sequence(&check, sql).is_empty());
+    }
+
+    #[test]
+    fn test_reports_column_added_in_same_statement_once() {
+        let violations = check_sequence(
+            &ShortIntegerPrimaryKeyCheck::default(),
+            "ALTER TABLE users ADD COLUMN id INT, ADD CONSTRAINT pk_users PRIMARY KEY (id);",
+        );
+
+        assert_eq!(violations.len(), 1);
+    }
+
+    // === Allowed tables ===
+
+    #[test]
+    fn test_allows_listed_tables() {
+        let check = ShortIntegerPrimaryKeyCheck::new(ShortIntegerPrimaryKeyConfig {
+            allowed_tables: vec!["countries".to_string(), "billing.currencies".to_string()],
+        });
+
+        assert_allows!(check, "CREATE TABLE countries (id SMALLINT PRIMARY KEY);");
+        assert_allows!(
+            check,
+            "CREATE TABLE public.countries (id SMALLSERIAL PRIMARY KEY);"
+        );
+        assert_allows!(
+            check,
+            "ALTER TABLE billing.currencies ADD COLUMN id SMALLINT PRIMARY KEY;"
+        );
+        assert_detects_violation!(
+            check,
+            "CREATE TABLE currencies (id SMALLINT PRIMARY KEY);",
+            "Short integer primary key"
+        );
+        assert_detects_violation!(
+            check,
+            "CREATE TABLE users (id INT PRIMARY KEY);",
+            "Short integer primary key"
+        );
+    }
+
     // === Exhaustion limit messages ===
 
     #[test]
     fn test_smallint_shows_correct_limit() {
         use crate::checks::test_utils::parse_sql;
 
-        let check = ShortIntegerPrimaryKeyCheck;
+        let check = ShortIntegerPrimaryKeyCheck::default();
         let stmt = parse_sql("CREATE TABLE users (id SMALLINT PRIMARY KEY);");
         let violations = check.check(&stmt);
 
@@ -544,7 +760,7 @@ mod tests {
     fn test_int_shows_correct_limit() {
         use crate::checks::test_utils::parse_sql;
 
-        let check = ShortIntegerPrimaryKeyCheck;
+        let check = ShortIntegerPrimaryKeyCheck::default();
         let stmt = parse_sql("CREATE TABLE users (id INT PRIMARY KEY);");
         let violations = check.check(&stmt);
 
@@ -42,6 +42,9 @@ pub use test_helpers::*;
 
 #[cfg(test)]
 mod test_helpers {
+    use crate::checks::{Check, MigrationContext};
+    use crate::parser::{RawStatement, SqlParser};
+    use crate::violation::Violation;
     use sqlparser::ast::Statement;
     use sqlparser::dialect::PostgreSqlDialect;
     use sqlparser::parser::Parser;
@@ -58,4 +61,55 @@ mod test_helpers {
             .next()
             .expect("No statements found")
     }
+
+    /// Raw statement on line 1, as the parser would extract it from the SQL.
+    pub fn raw(sql: &str) -> RawStatement {
+        RawStatement::from_source(sql, 1)
+    }
+
+    /// Run a check across all statements of a migration.
+    ///
+    /// # Panics
+    /// Panics if the SQL cannot be parsed.
+    pub fn check_sql(check: &dyn Check, sql: &str) -> Vec<(usize, Violation)> {
+        let stmts = Parser::parse_sql(&PostgreSqlDialect {}, sql).expect("Failed to parse SQL");
+        check.check_migration(&stmts)
+    }
+
+    /// Run a check on a whole migration, including the statements checked as source text.
+    ///
+    /// # Panics
+    /// Panics if the SQL cannot be parsed.
+    pub fn check_parsed_sql(check: &dyn Check, sql: &str) -> Vec<(usize, Violation)> {
+        let parsed = SqlParser::new()
+            .parse_with_metadata(sql)
+            .expect("Failed to parse SQL");
+        check.check_parsed(&parsed)
+    }
+
+    /// Check each statement of a migration, given what the statements before it did.
+    ///
+    /// # Panics
+    /// Panics if the SQL cannot be parsed.
+    pub fn check_sequence(check: &dyn Check, sql: &str) -> Vec<Violation> {
+        check_sequence_in(check, sql, MigrationContext::default())
+    }
+
+    /// Like [`check_sequence`], starting from the given context.
+    pub fn check_sequence_in(
+        check: &dyn Check,
+        sql: &str,
+        mut context: MigrationContext,
+    ) -> Vec<Violation> {
+        SqlParser::new()
+            .parse(sql)
+            .expect("Failed to parse SQL")
+            .iter()
+            .flat_map(|stmt| {
+                let violations = check.check_in_migration(stmt, &context);
+                context.record(stmt);
+                violations
+            })
+            .collect()
+    }
 }
@@ -0,0 +1,103 @@
+//! Detection for TIMESTAMP WITHOUT TIME ZONE columns.
+//!
+//! This check identifies columns of type `TIMESTAMP` (`TIMESTAMP WITHOUT TIME ZONE`)
+//! in `CREATE TABLE` and `ALTER TABLE ... ADD COLUMN`. It's opt-in: enable it with
+//! `enable_checks = ["TimestampWithoutTimeZoneCheck"]`.
+//!
+//! A plain timestamp stores wall-clock time with no time zone. Values written from
+//! sessions or servers in different time zones can't be compared, and daylight saving
+//! transitions make some of them ambiguous.
+//!
+//! The recommended approach is `TIMESTAMPTZ`, which stores an absolute point in time.
+
+use crate::checks::{new_columns, Check};
+use crate::violation::Violation;
+use sqlparser::ast::{DataType, Statement, TimezoneInfo};
+
+pub struct TimestampWithoutTimeZoneCheck;
+
+impl Check for TimestampWithoutTimeZoneCheck {
+    fn check(&self, stmt: &Statement) -> Vec<Violation> {
+        new_columns(stmt)
+            .into_iter()
+            .filter_map(|(table_name, column_def)| {
+                let DataType::Timestamp(precision, TimezoneInfo::None | TimezoneInfo::WithoutTimeZone) =
+                    column_def.data_type
+                else {
+                    return None;
+                };
+
+                let column_name = &column_def.name;
+                let replacement = match precision {
+                    Some(p) => format!("TIMESTAMPTZ({})", p),
+                    None => "TIMESTAMPTZ".to_string(),
+                };
+
+                Some(Violation::new(
+                    "TIMESTAMP WITHOUT TIME ZONE column",
+                    format!(
+                        "Column '{column}' on table '{table}' uses TIMESTAMP WITHOUT TIME ZONE, which stores wall-clock time \
+                        without a time zone. Values written from different time zones can't be compared, and daylight saving \
+                        transitions make some of them ambiguous.",
+                        column = column_name,
+                        table = table_name
+                    ),
+                    format!(
+                        r#"Use TIMESTAMPTZ (TIMESTAMP WITH TIME ZONE) instead:
+
+   {column} {replacement}
+
+TIMESTAMPTZ takes the same 8 bytes and stores an absolute point in time, converting
+to the session time zone on output."#,
+                        column = column_name,
+                        replacement = replacement
+                    ),
+                ))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::checks::test_utils::parse_sql;
+    use crate::{assert_allows, assert_detects_violation};
+
+    #[test]
+    fn test_detects_timestamp_in_create_table() {
+        assert_detects_violation!(
+            TimestampWithoutTimeZoneCheck,
+            "CREATE TABLE events (id BIGINT, created_at TIMESTAMP NOT NULL);",
+            "TIMESTAMP WITHOUT TIME ZONE column"
+        );
+    }
+
+    #[test]
+    fn test_detects_explicit_without_time_zone_in_add_column() {
+        let violations = TimestampWithoutTimeZoneCheck.check(&parse_sql(
+            "ALTER TABLE events ADD COLUMN processed_at TIMESTAMP(3) WITHOUT TIME ZONE;",
+        ));
+
+        assert_eq!(violations.len(), 1);
+        assert!(violations[0]
+            .safe_alternative
+            .contains("processed_at TIMESTAMPTZ(3)"));
+    }
+
+    #[test]
+    fn test_allows_timestamptz() {
+        assert_allows!(
+            TimestampWithoutTimeZoneCheck,
+            "CREATE TABLE events (created_at TIMESTAMPTZ, updated_at TIMESTAMP WITH TIME ZONE);"
+        );
+    }
+
+    #[test]
+    fn test_ignores_other_statements() {
+        assert_allows!(
+            TimestampWithoutTimeZoneCheck,
+            "ALTER TABLE events ALTER COLUMN created_at TYPE TIMESTAMP;"
+        );
+    }
+}
@@ -9,31 +9,99 @@
 //!
 //! The recommended approach is to use DELETE with batching to remove rows incrementally,
 //! allowing concurrent access to the table.
+//!
+//! Tables created earlier in the same migration aren't flagged, e.g. a staging table
+//! that's created, filled, truncated and reloaded while seeding: nothing else uses it yet.
+
+use crate::checks::{Check, MigrationContext};
+use crate::violation::{Severity, Violation};
+use sqlparser::ast::{CascadeOption, ObjectName, Statement, TruncateIdentityOption};
 
-use crate::checks::Check;
-use crate::violation::Violation;
-use sqlparser::ast::Statement;
+/// Operation of a plain TRUNCATE; variants with CASCADE or RESTART IDENTITY are escalated
+const TRUNCATE: &str = "TRUNCATE TABLE";
 
 pub struct TruncateTableCheck;
 
-impl Check for TruncateTableCheck {
-    fn check(&self, stmt: &Statement) -> Vec<Violation> {
-        if let Statement::Truncate(truncate_stmt) = stmt {
-            // Report a violation for each table being truncated
-            return truncate_stmt
-                .table_names
-                .iter()
-                .map(|table_name| {
-                    let table_name_str = table_name.to_string();
-
-                    Violation::new(
-                        "TRUNCATE TABLE",
-                        format!(
-                            "TRUNCATE TABLE on '{table}' acquires an ACCESS EXCLUSIVE lock, blocking all operations (reads and writes). \
-                            Unlike DELETE, TRUNCATE cannot be batched or throttled, making it unsafe for large tables in production.",
-                            table = table_name_str
-                        ),
-                        format!(r#"Use DELETE with batching instead:
+impl TruncateTableCheck {
+    /// Violations for each truncated table, except those `is_new` skips
+    fn truncate_violations(
+        stmt: &Statement,
+        is_new: impl Fn(&ObjectName) -> bool,
+    ) -> Vec<Violation> {
+        let Statement::Truncate(truncate_stmt) = stmt else {
+            return vec![];
+        };
+
+        let cascade = matches!(truncate_stmt.cascade, Some(CascadeOption::Cascade));
+        let restart_identity = matches!(
+            truncate_stmt.identity,
+            Some(TruncateIdentityOption::Restart)
+        );
+
+        // Report a violation for each table being truncated
+        truncate_stmt
+            .table_names
+            .iter()
+            .filter(|table_name| !is_new(&table_name.name))
+            .map(|table_name| {
+                Self::truncate_violation(&table_name.to_string(), cascade, restart_identity)
+            })
+            .collect()
+    }
+
+    fn truncate_violation(table: &str, cascade: bool, restart_identity: bool) -> Violation {
+        let mut operation = TRUNCATE.to_string();
+        let mut blast_radius = String::new();
+        let mut extra_steps = String::new();
+
+        if restart_identity {
+            operation.push_str(" RESTART IDENTITY");
+            blast_radius.push_str(&format!(
+                " RESTART IDENTITY also resets the sequences owned by columns of '{table}', so new rows reuse IDs that \
+                caches, logs, URLs or other systems may still reference.",
+                table = table
+            ));
+            extra_steps.push_str(
+                "\n\nKeep sequences as they are: IDs handed out before must never be reused for different rows.",
+            );
+        }
+        if cascade {
+            operation.push_str(" CASCADE");
+            blast_radius.push_str(&format!(
+                " CASCADE also truncates every table with a foreign key referencing '{table}', and the tables referencing \
+                those in turn, taking an ACCESS EXCLUSIVE lock on each and emptying tables the migration never names.",
+                table = table
+            ));
+            extra_steps.push_str(&format!(
+                "\n\nInstead of CASCADE, delete from the tables referencing {table} first, naming each one, \
+                so the migration shows exactly which data it removes.",
+                table = table
+            ));
+        }
+
+        // Resetting sequences is what RESTART IDENTITY is flagged for, so it's only
+        // offered for a plain TRUNCATE
+        let (reset_sequence, vacuum_step) = if restart_identity {
+            (String::new(), 3)
+        } else {
+            (
+                format!(
+                    "3. (Optional) If you need to reset sequences:\n   ALTER SEQUENCE {table}_id_seq RESTART WITH 1;\n\n",
+                    table = table
+                ),
+                4,
+            )
+        };
+
+        Violation::new(
+            operation,
+            format!(
+                "TRUNCATE TABLE on '{table}' acquires an ACCESS EXCLUSIVE lock, blocking all operations (reads and writes). \
+                Unlike DELETE, TRUNCATE cannot be batched or throttled, making it unsafe for large tables in production.{blast_radius}",
+                table = table,
+                blast_radius = blast_radius
+            ),
+            format!(r#"Use DELETE with batching instead:
 
 1. Delete rows in small batches to allow concurrent access:
    DELETE FROM {table} WHERE id IN (
@@ -42,21 +110,34 @@ impl Check for TruncateTableCheck {
 
 2. Repeat the batched DELETE until all rows are removed.
 
-3. (Optional) If you need to reset sequences:
-   ALTER SEQUENCE {table}_id_seq RESTART WITH 1;
-
-4. (Optional) Run VACUUM to reclaim space:
-   VACUUM {table};
+{reset_sequence}{vacuum_step}. (Optional) Run VACUUM to reclaim space:
+   VACUUM {table};{extra_steps}
 
 Note: If you absolutely must use TRUNCATE (e.g., in a test environment), use a safety-assured block."#,
-                            table = table_name_str
-                        ),
-                    )
-                })
-                .collect();
-        }
+                table = table,
+                reset_sequence = reset_sequence,
+                vacuum_step = vacuum_step,
+                extra_steps = extra_steps
+            ),
+        )
+    }
+}
 
-        vec![]
+impl Check for TruncateTableCheck {
+    fn check(&self, stmt: &Statement) -> Vec<Violation> {
+        Self::truncate_violations(stmt, |_| false)
+    }
+
+    fn check_in_migration(&self, stmt: &Statement, context: &MigrationContext) -> Vec<Violation> {
+        Self::truncate_violations(stmt, |table| context.is_new_table(table))
+    }
+
+    fn violation_severity(&self, violation: &Violation, configured: Severity) -> Severity {
+        if violation.operation == TRUNCATE {
+            configured
+        } else {
+            Severity::Error
+        }
     }
 }
 
@@ -87,15 +168,84 @@ mod tests {
         assert_eq!(violations[1].operation, "TRUNCATE TABLE");
     }
 
+    #[test]
+    fn test_allows_truncate_of_table_created_in_migration() {
+        use crate::checks::test_utils::parse_sql;
+
+        let mut context = MigrationContext::default();
+        context.record(&parse_sql(
+            "CREATE TABLE staging_users (id BIGINT, email TEXT);",
+        ));
+
+        let stmt = parse_sql("TRUNCATE TABLE staging_users, users;");
+        let violations = TruncateTableCheck.check_in_migration(&stmt, &context);
+
+        assert_eq!(violations.len(), 1);
+        assert!(violations[0].problem.contains("'users'"));
+    }
+
     #[test]
     fn test_detects_truncate_with_cascade() {
         assert_detects_violation!(
             TruncateTableCheck,
             "TRUNCATE TABLE users CASCADE;",
+            "TRUNCATE TABLE CASCADE"
+        );
+    }
+
+    #[test]
+    fn test_detects_truncate_with_restart_identity() {
+        use crate::checks::test_utils::parse_sql;
+
+        let stmt = parse_sql("TRUNCATE TABLE users RESTART IDENTITY CASCADE;");
+        let violations = TruncateTableCheck.check(&stmt);
+
+        assert_eq!(violations.len(), 1);
+        assert_eq!(
+            violations[0].operation,
+            "TRUNCATE TABLE RESTART IDENTITY CASCADE"
+        );
+        assert!(violations[0].problem.contains("resets the sequences"));
+        assert!(violations[0]
+            .problem
+            .contains("truncates every table with a foreign key referencing 'users'"));
+        assert!(!violations[0].safe_alternative.contains("ALTER SEQUENCE"));
+        assert!(violations[0]
+            .safe_alternative
+            .contains("3. (Optional) Run VACUUM"));
+
+        let plain = TruncateTableCheck.check(&parse_sql("TRUNCATE TABLE users;"));
+        assert!(plain[0]
+            .safe_alternative
+            .contains("ALTER SEQUENCE users_id_seq RESTART WITH 1;"));
+
+        assert_detects_violation!(
+            TruncateTableCheck,
+            "TRUNCATE users RESTART IDENTITY;",
+            "TRUNCATE TABLE RESTART IDENTITY"
+        );
+        assert_detects_violation!(
+            TruncateTableCheck,
+            "TRUNCATE users CONTINUE IDENTITY RESTRICT;",
             "TRUNCATE TABLE"
         );
     }
 
+    #[test]
+    fn test_escalates_cascade_and_restart_identity() {
+        let plain = Violation::new(TRUNCATE, "", "");
+        let cascade = Violation::new("TRUNCATE TABLE CASCADE", "", "");
+
+        assert_eq!(
+            TruncateTableCheck.violation_severity(&plain, Severity::Warning),
+            Severity::Warning
+        );
+        assert_eq!(
+            TruncateTableCheck.violation_severity(&cascade, Severity::Warning),
+            Severity::Error
+        );
+    }
+
     #[test]
     fn test_ignores_delete_statement() {
         assert_allows!(TruncateTableCheck, "DELETE FROM users;");
@@ -0,0 +1,183 @@
+//! Detection for btree indexes on unbounded text columns.
+//!
+//! This check identifies btree `CREATE INDEX` statements on columns declared as `TEXT`
+//! or `VARCHAR` without a length earlier in the same migration (in `CREATE TABLE` or
+//! `ALTER TABLE ... ADD COLUMN`). Its violations are warnings by default; raise them
+//! with `[severity] UnboundedTextIndexCheck = "error"`.
+//!
+//! A btree index entry can't exceed roughly a third of a page (2704 bytes). Nothing
+//! stops longer values from being written to an unbounded column, and once one is, the
+//! INSERT or UPDATE fails with "index row size exceeds maximum" at runtime, long after
+//! the migration ran.
+//!
+//! The recommended approach is an expression index on a hash or a prefix of the value.
+
+use crate::checks::{display_or_default, new_columns, Check};
+use crate::schema::{ident_key, name_key, object_key};
+use crate::violation::{Severity, Violation};
+use sqlparser::ast::{DataType, Expr, IndexType, Statement};
+use std::collections::HashSet;
+
+pub struct UnboundedTextIndexCheck;
+
+fn is_unbounded_text(data_type: &DataType) -> bool {
+    matches!(
+        data_type,
+        DataType::Text
+            | DataType::Varchar(None)
+            | DataType::CharacterVarying(None)
+            | DataType::CharVarying(None)
+    )
+}
+
+impl Check for UnboundedTextIndexCheck {
+    fn check(&self, _stmt: &Statement) -> Vec<Violation> {
+        vec![]
+    }
+
+    fn check_migration(&self, stmts: &[Statement]) -> Vec<(usize, Violation)> {
+        // (table, column) pairs declared with an unbounded text type
+        let mut text_columns: HashSet<(String, String)> = HashSet::new();
+        let mut violations = vec![];
+
+        for (stmt_idx, stmt) in stmts.iter().enumerate() {
+            for (table, column) in new_columns(stmt) {
+                let key = (name_key(&table), ident_key(&column.name));
+                if is_unbounded_text(&column.data_type) {
+                    text_columns.insert(key);
+                } else {
+                    text_columns.remove(&key);
+                }
+            }
+
+            let Statement::CreateIndex(index) = stmt else {
+                continue;
+            };
+            if !matches!(index.using, None | Some(IndexType::BTree)) {
+                continue;
+            }
+
+            let table = index.table_name.to_string();
+            let table_key = object_key(&index.table_name);
+            let columns: Vec<String> = index
+                .columns
+                .iter()
+                .filter_map(|c| match &c.column.expr {
+                    Expr::Identifier(ident) => Some(ident),
+                    _ => None,
+                })
+                .filter(|ident| text_columns.contains(&(table_key.clone(), ident_key(ident))))
+                .map(|ident| ident.to_string())
+                .collect();
+            let Some(column) = columns.first() else {
+                continue;
+            };
+
+            let index_name = display_or_default(index.name.as_ref(), "<unnamed>");
+
+            violations.push((
+                stmt_idx,
+                Violation::new(
+                    "btree index on unbounded text",
+                    format!(
+                        "Index '{index}' on table '{table}' is a btree index on unbounded text column(s) {columns}. Btree entries \
+                        are limited to about 2704 bytes, so writing a longer value fails at runtime with \"index row size \
+                        exceeds maximum\".",
+                        index = index_name,
+                        table = table,
+                        columns = columns.join(", ")
+                    ),
+                    format!(
+                        r#"Index an expression with a bounded size instead:
+
+1. For equality lookups, index a hash of the value:
+   CREATE INDEX CONCURRENTLY {index} ON {table} (md5({column}));
+   Query with: WHERE md5({column}) = md5($1) AND {column} = $1
+
+2. For prefix and range lookups, index a prefix of the value:
+   CREATE INDEX CONCURRENTLY {index} ON {table} (left({column}, 256));
+   Query with: WHERE left({column}, 256) = left($1, 256) AND {column} = $1
+
+Or bound the column itself with a CHECK constraint on its length.
+
+Note: If values are known to stay short, use a safety-assured block."#,
+                        index = index_name,
+                        table = table,
+                        column = column
+                    ),
+                ),
+            ));
+        }
+
+        violations
+    }
+
+    fn default_severity(&self) -> Severity {
+        Severity::Warning
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::checks::test_utils::check_sql;
+
+    #[test]
+    fn test_detects_index_on_text_column() {
+        let violations = check_sql(
+            &UnboundedTextIndexCheck,
+            "CREATE TABLE pages (id BIGINT PRIMARY KEY, url TEXT NOT NULL);
+             CREATE INDEX pages_url_idx ON pages (url);",
+        );
+
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].0, 1);
+        assert_eq!(violations[0].1.operation, "btree index on unbounded text");
+        assert!(violations[0].1.safe_alternative.contains("(md5(url))"));
+        assert!(violations[0]
+            .1
+            .safe_alternative
+            .contains("(left(url, 256))"));
+    }
+
+    #[test]
+    fn test_detects_added_varchar_without_length() {
+        let violations = check_sql(
+            &UnboundedTextIndexCheck,
+            "ALTER TABLE pages ADD COLUMN title VARCHAR;
+             CREATE UNIQUE INDEX CONCURRENTLY pages_title_idx ON pages USING btree (id, title);",
+        );
+
+        assert_eq!(violations.len(), 1);
+        assert!(violations[0].1.problem.contains("column(s) title"));
+    }
+
+    #[test]
+    fn test_allows_bounded_and_expression_indexes() {
+        assert!(check_sql(
+            &UnboundedTextIndexCheck,
+            "CREATE TABLE pages (url TEXT, slug VARCHAR(100));
+             CREATE INDEX pages_slug_idx ON pages (slug);
+             CREATE INDEX pages_url_idx ON pages (md5(url));
+             CREATE INDEX pages_url_trgm_idx ON pages USING gin (url gin_trgm_ops);"
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn test_ignores_columns_of_unknown_type() {
+        assert!(check_sql(
+            &UnboundedTextIndexCheck,
+            "CREATE INDEX pages_url_idx ON pages (url);"
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn test_defaults_to_warning() {
+        assert_eq!(
+            UnboundedTextIndexCheck.default_severity(),
+            Severity::Warning
+        );
+    }
+}
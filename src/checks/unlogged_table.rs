@@ -0,0 +1,102 @@
+//! Detection for CREATE UNLOGGED TABLE.
+//!
+//! This check identifies `CREATE UNLOGGED TABLE` statements.
+//!
+//! Unlogged tables skip the write-ahead log, which makes writes faster, but they're
+//! truncated during crash recovery and aren't replicated to standbys: after a crash or
+//! a failover, the table is empty. That's fine for caches and scratch data, and a
+//! surprise for anything else.
+//!
+//! This check is opt-in: enable it with `enable_checks = ["UnloggedTableCheck"]`.
+//!
+//! **Parser Handling**: sqlparser cannot parse `CREATE UNLOGGED TABLE`, so the statement
+//! is checked as source text (see [`Check::check_raw`]).
+//!
+//! The recommended approach is a regular table, or a safety-assured block for data
+//! that can be lost.
+
+use crate::checks::Check;
+use crate::parser::RawStatement;
+use crate::violation::Violation;
+use regex::Regex;
+use sqlparser::ast::Statement;
+use std::sync::LazyLock;
+
+/// `CREATE UNLOGGED TABLE [IF NOT EXISTS] table`
+static UNLOGGED_TABLE_PATTERN: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?is)^CREATE\s+UNLOGGED\s+TABLE\s+(?:IF\s+NOT\s+EXISTS\s+)?(?P<table>[^\s(]+)")
+        .expect("valid regex pattern")
+});
+
+pub struct UnloggedTableCheck;
+
+impl Check for UnloggedTableCheck {
+    fn check(&self, _stmt: &Statement) -> Vec<Violation> {
+        vec![]
+    }
+
+    fn check_raw(&self, stmt: &RawStatement) -> Vec<Violation> {
+        if stmt.keyword != "CREATE" {
+            return vec![];
+        }
+
+        let Some(captures) = UNLOGGED_TABLE_PATTERN.captures(&stmt.sql) else {
+            return vec![];
+        };
+
+        let table = &captures["table"];
+
+        vec![Violation::new(
+            "CREATE UNLOGGED TABLE",
+            format!(
+                "Table '{table}' is created UNLOGGED. Unlogged tables are truncated during crash recovery and aren't \
+                replicated to standbys, so after a crash or a failover '{table}' is empty.",
+                table = table
+            ),
+            format!(
+                r#"Use a regular table for data that must survive a crash:
+   CREATE TABLE {table} (...);
+
+Note: If losing the data is acceptable (caches, scratch tables), use a safety-assured block:
+   -- safety-assured:start
+   CREATE UNLOGGED TABLE {table} (...);
+   -- safety-assured:end"#,
+                table = table
+            ),
+        )]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::checks::test_utils::raw;
+
+    #[test]
+    fn test_detects_create_unlogged_table() {
+        let violations =
+            UnloggedTableCheck.check_raw(&raw("CREATE UNLOGGED TABLE page_views(id BIGINT)"));
+
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].operation, "CREATE UNLOGGED TABLE");
+        assert!(violations[0].problem.contains("'page_views'"));
+    }
+
+    #[test]
+    fn test_detects_if_not_exists() {
+        let violations = UnloggedTableCheck.check_raw(&raw(
+            "create unlogged table if not exists page_views (id BIGINT)",
+        ));
+
+        assert!(violations[0].problem.contains("'page_views'"));
+    }
+
+    #[test]
+    fn test_ignores_other_create_statements() {
+        assert!(UnloggedTableCheck
+            .check_raw(&raw(
+                "CREATE MATERIALIZED VIEW unlogged_totals AS SELECT 1 WITH NO DATA"
+            ))
+            .is_empty());
+    }
+}
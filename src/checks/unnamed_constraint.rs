@@ -1,124 +1,262 @@
 //! Detection for unnamed constraints in migrations.
 //!
-//! This check identifies constraints added without explicit names (UNIQUE, FOREIGN KEY, CHECK).
+//! This check identifies constraints added without explicit names (UNIQUE, FOREIGN KEY, CHECK),
+//! both in `ALTER TABLE ... ADD` and in `CREATE TABLE` column and table constraints.
 //!
 //! Unnamed constraints receive auto-generated names from PostgreSQL (like "users_email_key"
 //! or "posts_user_id_fkey"), which can vary between databases and make future migrations
 //! difficult. When you need to modify or drop the constraint later, you'll need to query
 //! the database to find the generated name, which is error-prone and environment-specific.
 //!
+//! Constraints declared in `CREATE TABLE` are only flagged with
+//! `[checks.UnnamedConstraintCheck] include_create_table = true`, since most schemas
+//! rely on PostgreSQL's default names for constraints of new tables.
+//!
 //! Always name constraints explicitly for maintainable migrations.
 
+use crate::checks::overlap::constraint_target;
 use crate::checks::Check;
+use crate::config::UnnamedConstraintConfig;
 use crate::violation::Violation;
-use sqlparser::ast::{AlterTable, AlterTableOperation, Statement, TableConstraint};
+use sqlparser::ast::{
+    AlterTable, AlterTableOperation, ColumnOption, ColumnOptionDef, Statement, TableConstraint,
+};
 
-pub struct UnnamedConstraintCheck;
+#[derive(Default)]
+pub struct UnnamedConstraintCheck {
+    config: UnnamedConstraintConfig,
+}
 
-impl Check for UnnamedConstraintCheck {
-    fn check(&self, stmt: &Statement) -> Vec<Violation> {
-        let Statement::AlterTable(AlterTable {
-            name, operations, ..
-        }) = stmt
-        else {
-            return vec![];
-        };
+impl UnnamedConstraintCheck {
+    pub fn new(config: UnnamedConstraintConfig) -> Self {
+        Self { config }
+    }
 
-        let table_name = name.to_string();
+    /// Constraint type and definition of an unnamed table constraint
+    fn unnamed_table_constraint(constraint: &TableConstraint) -> Option<(&'static str, String)> {
+        match constraint {
+            TableConstraint::Unique(unique) if unique.name.is_none() => {
+                let cols = unique
+                    .columns
+                    .iter()
+                    .map(|ic| ic.column.expr.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                Some(("UNIQUE", format!("({})", cols)))
+            }
+            TableConstraint::ForeignKey(fk) if fk.name.is_none() => {
+                let cols = fk
+                    .columns
+                    .iter()
+                    .map(|c| c.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                let foreign_cols = fk
+                    .referred_columns
+                    .iter()
+                    .map(|c| c.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                Some((
+                    "FOREIGN KEY",
+                    format!(
+                        "({}) REFERENCES {}({})",
+                        cols, fk.foreign_table, foreign_cols
+                    ),
+                ))
+            }
+            TableConstraint::Check(check) if check.name.is_none() => {
+                Some(("CHECK", format!("({})", check.expr)))
+            }
+            _ => None, // Ignore other constraint types
+        }
+    }
 
-        operations
-            .iter()
-            .filter_map(|op| {
-                let AlterTableOperation::AddConstraint { constraint, .. } = op else {
-                    return None;
-                };
+    /// Constraint type and clause of an unnamed inline column constraint
+    fn unnamed_column_constraint(option: &ColumnOptionDef) -> Option<(&'static str, String)> {
+        if option.name.is_some() {
+            return None;
+        }
+        match &option.option {
+            ColumnOption::Unique(_) => Some(("UNIQUE", "UNIQUE".to_string())),
+            ColumnOption::ForeignKey(fk) => {
+                let foreign_cols = fk
+                    .referred_columns
+                    .iter()
+                    .map(|c| c.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                Some((
+                    "FOREIGN KEY",
+                    format!("REFERENCES {}({})", fk.foreign_table, foreign_cols),
+                ))
+            }
+            ColumnOption::Check(check) => Some(("CHECK", format!("CHECK ({})", check.expr))),
+            _ => None,
+        }
+    }
 
-                // Check if constraint has a name
-                let (constraint_type, columns_desc) = match constraint {
-                    TableConstraint::Unique(unique) => {
-                        if unique.name.is_some() {
-                            return None;
-                        }
-                        let cols = unique
-                            .columns
-                            .iter()
-                            .map(|ic| ic.column.expr.to_string())
-                            .collect::<Vec<_>>()
-                            .join(", ");
-                        ("UNIQUE", cols)
-                    }
-                    TableConstraint::ForeignKey(fk) => {
-                        if fk.name.is_some() {
-                            return None;
-                        }
-                        let cols = fk
-                            .columns
-                            .iter()
-                            .map(|c| c.to_string())
-                            .collect::<Vec<_>>()
-                            .join(", ");
-                        let foreign_cols = fk
-                            .referred_columns
-                            .iter()
-                            .map(|c| c.to_string())
-                            .collect::<Vec<_>>()
-                            .join(", ");
-                        (
-                            "FOREIGN KEY",
-                            format!(
-                                "({}) REFERENCES {}({})",
-                                cols, fk.foreign_table, foreign_cols
-                            ),
-                        )
-                    }
-                    TableConstraint::Check(check) => {
-                        if check.name.is_some() {
-                            return None;
-                        }
-                        ("CHECK", format!("({})", check.expr))
-                    }
-                    _ => return None, // Ignore other constraint types
-                };
+    /// Suffix of the conventional name for a constraint type
+    fn name_suffix(constraint_type: &str) -> &'static str {
+        match constraint_type {
+            "UNIQUE" => "key",
+            "FOREIGN KEY" => "fkey",
+            "CHECK" => "check",
+            _ => "constraint",
+        }
+    }
 
-                Some(Violation::new(
-                    "Unnamed constraint",
-                    format!(
-                        "Adding unnamed {constraint_type} constraint on table '{table}' will receive an auto-generated name from PostgreSQL. \
-                        This makes future migrations difficult, as the generated name varies between databases and requires querying \
-                        the database to find the constraint name before modifying or dropping it.",
-                        constraint_type = constraint_type,
-                        table = table_name
-                    ),
-                    format!(
-                        r#"Always name constraints explicitly using the CONSTRAINT keyword:
+    fn alter_table_violation(table: &str, constraint_type: &str, columns: &str) -> Violation {
+        Violation::new(
+            "Unnamed constraint",
+            format!(
+                "Adding unnamed {constraint_type} constraint on table '{table}' will receive an auto-generated name from PostgreSQL. \
+                This makes future migrations difficult, as the generated name varies between databases and requires querying \
+                the database to find the constraint name before modifying or dropping it.",
+                constraint_type = constraint_type,
+                table = table
+            ),
+            format!(
+                r#"Always name constraints explicitly using the CONSTRAINT keyword:
 
 Instead of:
    ALTER TABLE {table} ADD {constraint_type} {columns};
 
 Use:
-   ALTER TABLE {table} ADD CONSTRAINT {table}_{suggested_name} {constraint_type} {columns};
+   ALTER TABLE {table} ADD CONSTRAINT {table}_column_{suffix} {constraint_type} {columns};
 
 Named constraints make future migrations predictable and maintainable:
    -- Easy to reference in later migrations
-   ALTER TABLE {table} DROP CONSTRAINT {table}_{suggested_name};
+   ALTER TABLE {table} DROP CONSTRAINT {table}_column_{suffix};
 
 Note: Choose descriptive names that indicate the table, columns, and constraint type.
 Common patterns:
   - UNIQUE: {table}_<column>_key or {table}_<column1>_<column2>_key
   - FOREIGN KEY: {table}_<column>_fkey
   - CHECK: {table}_<column>_check or {table}_<description>_check"#,
-                        table = table_name,
-                        constraint_type = constraint_type,
-                        columns = columns_desc,
-                        suggested_name = match constraint_type {
-                            "UNIQUE" => "column_key",
-                            "FOREIGN KEY" => "column_fkey",
-                            "CHECK" => "column_check",
-                            _ => "constraint",
-                        }
+                table = table,
+                constraint_type = constraint_type,
+                columns = columns,
+                suffix = Self::name_suffix(constraint_type)
+            ),
+        )
+    }
+
+    /// Violation for an unnamed constraint declared in CREATE TABLE
+    ///
+    /// `declaration` is the constraint as written and `named` the same constraint with a name.
+    fn create_table_violation(
+        table: &str,
+        constraint_type: &str,
+        declaration: &str,
+        named: &str,
+    ) -> Violation {
+        Violation::new(
+            "Unnamed constraint",
+            format!(
+                "Creating table '{table}' with unnamed {constraint_type} constraint gives it an auto-generated name from PostgreSQL. \
+                This makes future migrations difficult, as the generated name varies between databases and requires querying \
+                the database to find the constraint name before modifying or dropping it.",
+                constraint_type = constraint_type,
+                table = table
+            ),
+            format!(
+                r#"Always name constraints explicitly using the CONSTRAINT keyword:
+
+Instead of:
+   CREATE TABLE {table} (
+     ...
+     {declaration}
+   );
+
+Use:
+   CREATE TABLE {table} (
+     ...
+     {named}
+   );
+
+Note: If your team accepts default names for constraints of new tables, remove
+include_create_table = true under [checks.UnnamedConstraintCheck] in diesel-guard.toml."#,
+                table = table,
+                declaration = declaration,
+                named = named
+            ),
+        )
+    }
+
+    fn create_table_violations(&self, stmt: &Statement) -> Vec<Violation> {
+        let Statement::CreateTable(create) = stmt else {
+            return vec![];
+        };
+        if !self.config.include_create_table {
+            return vec![];
+        }
+
+        let table = create.name.to_string();
+        let column_violations = create.columns.iter().flat_map(|column| {
+            let table = &table;
+            column.options.iter().filter_map(move |option| {
+                let (constraint_type, clause) = Self::unnamed_column_constraint(option)?;
+                Some(Self::create_table_violation(
+                    table,
+                    constraint_type,
+                    &format!("{} {} ... {}", column.name, column.data_type, clause),
+                    &format!(
+                        "{} {} ... CONSTRAINT {}_{}_{} {}",
+                        column.name,
+                        column.data_type,
+                        table,
+                        column.name,
+                        Self::name_suffix(constraint_type),
+                        clause
                     ),
                 ))
             })
+        });
+        let table_violations = create.constraints.iter().filter_map(|constraint| {
+            let (constraint_type, columns) = Self::unnamed_table_constraint(constraint)?;
+            Some(Self::create_table_violation(
+                &table,
+                constraint_type,
+                &format!("{} {}", constraint_type, columns),
+                &format!(
+                    "CONSTRAINT {}_column_{} {} {}",
+                    table,
+                    Self::name_suffix(constraint_type),
+                    constraint_type,
+                    columns
+                ),
+            ))
+        });
+
+        column_violations.chain(table_violations).collect()
+    }
+}
+
+impl Check for UnnamedConstraintCheck {
+    fn check(&self, stmt: &Statement) -> Vec<Violation> {
+        let Statement::AlterTable(AlterTable {
+            name, operations, ..
+        }) = stmt
+        else {
+            return self.create_table_violations(stmt);
+        };
+
+        let table_name = name.to_string();
+
+        operations
+            .iter()
+            .filter_map(|op| {
+                let AlterTableOperation::AddConstraint { constraint, .. } = op else {
+                    return None;
+                };
+
+                let (constraint_type, columns) = Self::unnamed_table_constraint(constraint)?;
+                Some(
+                    Self::alter_table_violation(&table_name, constraint_type, &columns)
+                        .with_target(constraint_target(name, constraint)),
+                )
+            })
             .collect()
     }
 }
@@ -126,12 +264,19 @@ Common patterns:
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::checks::test_utils::parse_sql;
     use crate::{assert_allows, assert_detects_violation};
 
+    fn create_table_check() -> UnnamedConstraintCheck {
+        UnnamedConstraintCheck::new(UnnamedConstraintConfig {
+            include_create_table: true,
+        })
+    }
+
     #[test]
     fn test_detects_unnamed_unique_constraint() {
         assert_detects_violation!(
-            UnnamedConstraintCheck,
+            UnnamedConstraintCheck::default(),
             "ALTER TABLE users ADD UNIQUE (email);",
             "Unnamed constraint"
         );
@@ -140,7 +285,7 @@ mod tests {
     #[test]
     fn test_detects_unnamed_foreign_key_constraint() {
         assert_detects_violation!(
-            UnnamedConstraintCheck,
+            UnnamedConstraintCheck::default(),
             "ALTER TABLE posts ADD FOREIGN KEY (user_id) REFERENCES users(id);",
             "Unnamed constraint"
         );
@@ -149,7 +294,7 @@ mod tests {
     #[test]
     fn test_detects_unnamed_check_constraint() {
         assert_detects_violation!(
-            UnnamedConstraintCheck,
+            UnnamedConstraintCheck::default(),
             "ALTER TABLE users ADD CHECK (age >= 0);",
             "Unnamed constraint"
         );
@@ -158,7 +303,7 @@ mod tests {
     #[test]
     fn test_allows_named_unique_constraint() {
         assert_allows!(
-            UnnamedConstraintCheck,
+            UnnamedConstraintCheck::default(),
             "ALTER TABLE users ADD CONSTRAINT users_email_key UNIQUE (email);"
         );
     }
@@ -166,7 +311,7 @@ mod tests {
     #[test]
     fn test_allows_named_foreign_key_constraint() {
         assert_allows!(
-            UnnamedConstraintCheck,
+            UnnamedConstraintCheck::default(),
             "ALTER TABLE posts ADD CONSTRAINT posts_user_id_fkey FOREIGN KEY (user_id) REFERENCES users(id);"
         );
     }
@@ -174,15 +319,69 @@ mod tests {
     #[test]
     fn test_allows_named_check_constraint() {
         assert_allows!(
-            UnnamedConstraintCheck,
+            UnnamedConstraintCheck::default(),
             "ALTER TABLE users ADD CONSTRAINT users_age_check CHECK (age >= 0);"
         );
     }
 
+    #[test]
+    fn test_detects_unnamed_column_constraints_in_create_table() {
+        let violations = create_table_check().check(&parse_sql(
+            "CREATE TABLE posts (
+                id BIGINT PRIMARY KEY,
+                slug TEXT UNIQUE,
+                user_id BIGINT REFERENCES users(id),
+                score INT CHECK (score >= 0)
+            );",
+        ));
+
+        assert_eq!(violations.len(), 3);
+        assert!(violations[0].problem.contains("unnamed UNIQUE constraint"));
+        assert!(violations[0]
+            .safe_alternative
+            .contains("slug TEXT ... CONSTRAINT posts_slug_key UNIQUE"));
+        assert!(violations[1]
+            .safe_alternative
+            .contains("CONSTRAINT posts_user_id_fkey REFERENCES users(id)"));
+        assert!(violations[2]
+            .safe_alternative
+            .contains("CONSTRAINT posts_score_check CHECK (score >= 0)"));
+    }
+
+    #[test]
+    fn test_detects_unnamed_table_constraint_in_create_table() {
+        assert_detects_violation!(
+            create_table_check(),
+            "CREATE TABLE memberships (user_id BIGINT, team_id BIGINT, UNIQUE (user_id, team_id));",
+            "Unnamed constraint"
+        );
+    }
+
+    #[test]
+    fn test_allows_named_constraints_in_create_table() {
+        assert_allows!(
+            create_table_check(),
+            "CREATE TABLE posts (
+                id BIGINT PRIMARY KEY,
+                slug TEXT CONSTRAINT posts_slug_key UNIQUE,
+                user_id BIGINT,
+                CONSTRAINT posts_user_id_fkey FOREIGN KEY (user_id) REFERENCES users(id)
+            );"
+        );
+    }
+
+    #[test]
+    fn test_allows_create_table_by_default() {
+        assert_allows!(
+            UnnamedConstraintCheck::default(),
+            "CREATE TABLE posts (slug TEXT UNIQUE, UNIQUE (slug));"
+        );
+    }
+
     #[test]
     fn test_ignores_other_alter_operations() {
         assert_allows!(
-            UnnamedConstraintCheck,
+            UnnamedConstraintCheck::default(),
             "ALTER TABLE users ADD COLUMN email TEXT;"
         );
     }
@@ -190,7 +389,7 @@ mod tests {
     #[test]
     fn test_ignores_other_statements() {
         assert_allows!(
-            UnnamedConstraintCheck,
+            UnnamedConstraintCheck::default(),
             "CREATE TABLE users (id SERIAL PRIMARY KEY);"
         );
     }
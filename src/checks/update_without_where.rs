@@ -0,0 +1,94 @@
+//! Detection for UPDATE without a WHERE clause.
+//!
+//! This check identifies `UPDATE` statements that have no WHERE clause, which rewrite
+//! every row of the table.
+//!
+//! Inside a migration, such an update runs in a single transaction: every row stays
+//! locked until the migration commits, blocking concurrent writes to them, and since
+//! PostgreSQL writes a new version of each updated row, the table can double in size
+//! until vacuum catches up.
+//!
+//! The recommended approach is a batched backfill run outside of migrations.
+
+use crate::checks::overlap::table_target;
+use crate::checks::Check;
+use crate::violation::Violation;
+use sqlparser::ast::Statement;
+
+pub struct UpdateWithoutWhereCheck;
+
+impl Check for UpdateWithoutWhereCheck {
+    fn check(&self, stmt: &Statement) -> Vec<Violation> {
+        let Statement::Update(update) = stmt else {
+            return vec![];
+        };
+        if update.selection.is_some() {
+            return vec![];
+        }
+
+        let table = update.table.relation.to_string();
+        let assignments = update
+            .assignments
+            .iter()
+            .map(|a| a.to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        vec![Violation::new(
+            "UPDATE without WHERE",
+            format!(
+                "UPDATE on '{table}' has no WHERE clause and rewrites every row of the table in a single transaction. \
+                Every row stays locked until the migration commits, blocking concurrent writes, and each updated row \
+                leaves a dead copy behind, bloating the table. Duration depends on table size.",
+                table = table
+            ),
+            format!(
+                r#"Backfill in batches outside of migrations:
+
+1. Keep the migration to schema changes only.
+
+2. Update a batch of rows at a time from a script or background job, skipping rows
+   that are already done:
+   UPDATE {table} SET {assignments}
+   WHERE id >= <start> AND id < <start + 1000>;
+   -- repeat for each batch, committing in between
+
+Note: If the table is known to be small, use a safety-assured block."#,
+                table = table,
+                assignments = assignments
+            ),
+        ).with_target(table_target(&table))]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{assert_allows, assert_detects_violation};
+
+    #[test]
+    fn test_detects_update_without_where() {
+        assert_detects_violation!(
+            UpdateWithoutWhereCheck,
+            "UPDATE users SET active = true;",
+            "UPDATE without WHERE"
+        );
+    }
+
+    #[test]
+    fn test_detects_update_from_without_where() {
+        assert_detects_violation!(
+            UpdateWithoutWhereCheck,
+            "UPDATE users SET plan = defaults.plan FROM defaults;",
+            "UPDATE without WHERE"
+        );
+    }
+
+    #[test]
+    fn test_allows_update_with_where() {
+        assert_allows!(
+            UpdateWithoutWhereCheck,
+            "UPDATE users SET active = true WHERE active IS NULL;"
+        );
+    }
+}
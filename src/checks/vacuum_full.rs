@@ -0,0 +1,134 @@
+//! Detection for VACUUM FULL in migrations.
+//!
+//! This check identifies `VACUUM FULL` statements, which rewrite the entire table
+//! under an ACCESS EXCLUSIVE lock.
+//!
+//! VACUUM FULL copies the table into a new file and rebuilds every index, blocking all
+//! reads and writes until it finishes. On large tables this takes minutes to hours.
+//! Plain VACUUM doesn't block reads or writes, but it can't run inside a transaction
+//! and is maintenance rather than a schema change; flagging it is opt-in via
+//! `[checks.VacuumFullCheck] include_plain_vacuum = true`.
+//!
+//! The recommended approach is pg_repack, or running maintenance outside of migrations.
+
+use crate::checks::Check;
+use crate::config::VacuumFullConfig;
+use crate::violation::Violation;
+use sqlparser::ast::Statement;
+
+#[derive(Default)]
+pub struct VacuumFullCheck {
+    config: VacuumFullConfig,
+}
+
+impl VacuumFullCheck {
+    pub fn new(config: VacuumFullConfig) -> Self {
+        Self { config }
+    }
+}
+
+impl Check for VacuumFullCheck {
+    fn check(&self, stmt: &Statement) -> Vec<Violation> {
+        let Statement::Vacuum(vacuum) = stmt else {
+            return vec![];
+        };
+
+        let table = vacuum
+            .table_name
+            .as_ref()
+            .map(|name| name.to_string())
+            .unwrap_or_else(|| "<table>".to_string());
+        let target = match &vacuum.table_name {
+            Some(name) => format!("table '{}'", name),
+            None => "every table in the database".to_string(),
+        };
+
+        if vacuum.full {
+            return vec![Violation::new(
+                "VACUUM FULL",
+                format!(
+                    "VACUUM FULL on {target} rewrites the table and rebuilds all of its indexes under an ACCESS EXCLUSIVE lock, \
+                    blocking all reads and writes until it finishes. Duration depends on table size and can reach hours.",
+                    target = target
+                ),
+                format!(
+                    r#"Don't run VACUUM FULL in migrations:
+
+1. To reclaim space without blocking, use pg_repack outside of migrations:
+   pg_repack --table {table} <database>
+
+2. Or schedule VACUUM FULL during a maintenance window, run by hand or by a job.
+
+Note: Plain VACUUM (without FULL) doesn't block reads or writes and is usually enough;
+autovacuum normally takes care of it."#,
+                    table = table
+                ),
+            )];
+        }
+
+        if self.config.include_plain_vacuum {
+            return vec![Violation::new(
+                "VACUUM",
+                format!(
+                    "VACUUM on {target} is database maintenance, not a schema change. It cannot run inside a transaction \
+                    block and can take a long time on large tables, delaying the deployment.",
+                    target = target
+                ),
+                format!(
+                    r#"Run maintenance outside of migrations:
+
+1. Rely on autovacuum, or run VACUUM from a scheduled job:
+   VACUUM (ANALYZE) {table};
+
+2. If it must run during deployment, put it in its own migration with metadata.toml:
+   run_in_transaction = false"#,
+                    table = table
+                ),
+            )];
+        }
+
+        vec![]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::checks::test_utils::parse_sql;
+    use crate::{assert_allows, assert_detects_violation};
+
+    #[test]
+    fn test_detects_vacuum_full() {
+        assert_detects_violation!(
+            VacuumFullCheck::default(),
+            "VACUUM FULL big_table;",
+            "VACUUM FULL"
+        );
+    }
+
+    #[test]
+    fn test_detects_vacuum_full_without_table() {
+        assert_detects_violation!(VacuumFullCheck::default(), "VACUUM FULL;", "VACUUM FULL");
+    }
+
+    #[test]
+    fn test_allows_plain_vacuum_by_default() {
+        assert_allows!(VacuumFullCheck::default(), "VACUUM big_table;");
+    }
+
+    #[test]
+    fn test_detects_plain_vacuum_when_enabled() {
+        let check = VacuumFullCheck::new(VacuumFullConfig {
+            include_plain_vacuum: true,
+        });
+        let violations = check.check(&parse_sql("VACUUM big_table;"));
+
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].operation, "VACUUM");
+    }
+
+    #[test]
+    fn test_ignores_other_statements() {
+        assert_allows!(VacuumFullCheck::default(), "ANALYZE big_table;");
+    }
+}
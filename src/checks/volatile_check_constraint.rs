@@ -0,0 +1,190 @@
+//! Detection for CHECK constraints calling volatile or time-dependent functions.
+//!
+//! This check identifies CHECK constraints, in `CREATE TABLE` (column and table
+//! constraints) and `ALTER TABLE` (ADD CONSTRAINT and ADD COLUMN), whose expression calls
+//! a function such as `now()`, `CURRENT_DATE`, `random()`, or `nextval()`.
+//!
+//! PostgreSQL assumes CHECK expressions are immutable: a row is checked only when it's
+//! written, never again. With a time-dependent or random expression, whether a row
+//! passes depends on when it was written, so an UPDATE of an unrelated column, or
+//! restoring a dump, can fail on rows that were accepted before.
+//!
+//! The recommended approach is comparing against fixed values, and enforcing
+//! time-dependent rules in a trigger or in the application.
+
+use crate::checks::Check;
+use crate::violation::Violation;
+use regex::Regex;
+use sqlparser::ast::{
+    AlterTable, AlterTableOperation, ColumnDef, ColumnOption, Statement, TableConstraint,
+};
+use std::sync::LazyLock;
+
+/// Calls of built-in functions whose result changes between evaluations
+static NON_IMMUTABLE_CALL: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?i)\b(?:(now|random|gen_random_uuid|uuid_generate_v1|uuid_generate_v1mc|uuid_generate_v4|clock_timestamp|statement_timestamp|transaction_timestamp|timeofday|nextval)\s*\(|(current_timestamp|current_date|current_time|localtimestamp|localtime)\b)",
+    )
+    .expect("valid regex pattern")
+});
+
+pub struct VolatileCheckConstraintCheck;
+
+impl VolatileCheckConstraintCheck {
+    /// CHECK constraints declared inline on a column, as (name, expression)
+    fn column_checks(column: &ColumnDef) -> impl Iterator<Item = (Option<String>, String)> + '_ {
+        column.options.iter().filter_map(|opt| match &opt.option {
+            ColumnOption::Check(check) => Some((
+                opt.name
+                    .as_ref()
+                    .or(check.name.as_ref())
+                    .map(|name| name.to_string()),
+                check.expr.to_string(),
+            )),
+            _ => None,
+        })
+    }
+
+    /// CHECK constraint given as a table constraint, as (name, expression)
+    fn constraint_check(constraint: &TableConstraint) -> Option<(Option<String>, String)> {
+        match constraint {
+            TableConstraint::Check(check) => Some((
+                check.name.as_ref().map(|name| name.to_string()),
+                check.expr.to_string(),
+            )),
+            _ => None,
+        }
+    }
+}
+
+impl Check for VolatileCheckConstraintCheck {
+    fn check(&self, stmt: &Statement) -> Vec<Violation> {
+        let (table, checks): (String, Vec<(Option<String>, String)>) = match stmt {
+            Statement::CreateTable(create) => (
+                create.name.to_string(),
+                create
+                    .columns
+                    .iter()
+                    .flat_map(Self::column_checks)
+                    .chain(create.constraints.iter().filter_map(Self::constraint_check))
+                    .collect(),
+            ),
+            Statement::AlterTable(AlterTable {
+                name, operations, ..
+            }) => (
+                name.to_string(),
+                operations
+                    .iter()
+                    .flat_map(|op| match op {
+                        AlterTableOperation::AddConstraint { constraint, .. } => {
+                            Self::constraint_check(constraint).into_iter().collect()
+                        }
+                        AlterTableOperation::AddColumn { column_def, .. } => {
+                            Self::column_checks(column_def).collect()
+                        }
+                        _ => vec![],
+                    })
+                    .collect(),
+            ),
+            _ => return vec![],
+        };
+
+        checks
+            .into_iter()
+            .filter_map(|(name, expr)| {
+                let captures = NON_IMMUTABLE_CALL.captures(&expr)?;
+                let function = captures
+                    .get(1)
+                    .map(|m| format!("{}()", m.as_str().to_lowercase()))
+                    .or_else(|| captures.get(2).map(|m| m.as_str().to_uppercase()))?;
+                let constraint = name.unwrap_or_else(|| "<unnamed>".to_string());
+
+                Some(Violation::new(
+                    "CHECK constraint with volatile function",
+                    format!(
+                        "CHECK constraint '{constraint}' on table '{table}' calls {function}, whose result changes between \
+                        evaluations. PostgreSQL checks a row only when it's written, so rows accepted earlier can later fail \
+                        an unrelated UPDATE or a restore from a dump.",
+                        constraint = constraint,
+                        table = table,
+                        function = function
+                    ),
+                    format!(
+                        r#"Keep CHECK expressions deterministic:
+
+1. Compare against fixed values instead:
+   CHECK (starts_at >= '2024-01-01')
+
+2. Enforce time-dependent or random rules where they're evaluated once, at write time:
+   a BEFORE INSERT trigger, or the application.
+
+Constraint expression: {expr}"#,
+                        expr = expr
+                    ),
+                ))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::checks::test_utils::parse_sql;
+    use crate::{assert_allows, assert_detects_violation};
+
+    #[test]
+    fn test_detects_now_in_table_constraint() {
+        let violations = VolatileCheckConstraintCheck.check(&parse_sql(
+            "CREATE TABLE events (starts_at TIMESTAMPTZ, CONSTRAINT events_future CHECK (starts_at > now()));",
+        ));
+
+        assert_eq!(violations.len(), 1);
+        assert!(violations[0].problem.contains("'events_future'"));
+        assert!(violations[0].problem.contains("calls now()"));
+    }
+
+    #[test]
+    fn test_detects_current_date_in_column_constraint() {
+        let violations = VolatileCheckConstraintCheck.check(&parse_sql(
+            "CREATE TABLE users (birthday DATE CHECK (birthday <= CURRENT_DATE));",
+        ));
+
+        assert_eq!(violations.len(), 1);
+        assert!(violations[0].problem.contains("calls CURRENT_DATE"));
+    }
+
+    #[test]
+    fn test_detects_add_constraint_and_add_column() {
+        assert_detects_violation!(
+            VolatileCheckConstraintCheck,
+            "ALTER TABLE samples ADD CONSTRAINT samples_sampled CHECK (random() < 0.5) NOT VALID;",
+            "CHECK constraint with volatile function"
+        );
+        assert_detects_violation!(
+            VolatileCheckConstraintCheck,
+            "ALTER TABLE users ADD COLUMN expires_at TIMESTAMPTZ CHECK (expires_at > clock_timestamp());",
+            "CHECK constraint with volatile function"
+        );
+    }
+
+    #[test]
+    fn test_allows_deterministic_checks() {
+        assert_allows!(
+            VolatileCheckConstraintCheck,
+            "CREATE TABLE events (starts_at TIMESTAMPTZ, ends_at TIMESTAMPTZ, CHECK (ends_at > starts_at), CHECK (starts_at >= '2024-01-01'));"
+        );
+        assert_allows!(
+            VolatileCheckConstraintCheck,
+            "ALTER TABLE users ADD CONSTRAINT users_known_at CHECK (known_at IS NOT NULL) NOT VALID;"
+        );
+    }
+
+    #[test]
+    fn test_ignores_defaults() {
+        assert_allows!(
+            VolatileCheckConstraintCheck,
+            "CREATE TABLE events (created_at TIMESTAMPTZ NOT NULL DEFAULT now());"
+        );
+    }
+}
@@ -0,0 +1,152 @@
+//! Detection for ADD COLUMN with a volatile DEFAULT.
+//!
+//! This check identifies `ALTER TABLE ... ADD COLUMN` statements whose DEFAULT calls a
+//! volatile function such as `random()`, `gen_random_uuid()`, or `clock_timestamp()`.
+//!
+//! Since PostgreSQL 11, a non-volatile default is evaluated once and stored in the
+//! catalog, so adding the column is instant. A volatile default must be evaluated for
+//! every existing row, so PostgreSQL still rewrites the whole table under an ACCESS
+//! EXCLUSIVE lock, on any version.
+//!
+//! `now()` and `CURRENT_TIMESTAMP` are stable, not volatile: they're evaluated once and
+//! don't cause a rewrite, so they aren't flagged.
+//!
+//! The recommended approach is adding the column without a default, setting the default
+//! for new rows, and backfilling existing rows in batches.
+
+use crate::checks::overlap::column_target;
+use crate::checks::Check;
+use crate::violation::Violation;
+use regex::Regex;
+use sqlparser::ast::{AlterTable, AlterTableOperation, ColumnOption, Statement};
+use std::sync::LazyLock;
+
+/// Calls of built-in volatile functions commonly used as defaults
+static VOLATILE_CALL: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?i)\b(random|gen_random_uuid|uuid_generate_v1|uuid_generate_v1mc|uuid_generate_v4|clock_timestamp|timeofday|nextval)\s*\(",
+    )
+    .expect("valid regex pattern")
+});
+
+pub struct VolatileDefaultCheck;
+
+impl Check for VolatileDefaultCheck {
+    fn check(&self, stmt: &Statement) -> Vec<Violation> {
+        let Statement::AlterTable(AlterTable {
+            name, operations, ..
+        }) = stmt
+        else {
+            return vec![];
+        };
+
+        let table_name = name.to_string();
+
+        operations
+            .iter()
+            .filter_map(|op| {
+                let AlterTableOperation::AddColumn { column_def, .. } = op else {
+                    return None;
+                };
+
+                let default = column_def.options.iter().find_map(|opt| match &opt.option {
+                    ColumnOption::Default(expr) => Some(expr.to_string()),
+                    _ => None,
+                })?;
+                let function = VOLATILE_CALL
+                    .captures(&default)
+                    .map(|c| c[1].to_lowercase())?;
+
+                let column_name = &column_def.name;
+
+                Some(Violation::new(
+                    "ADD COLUMN with volatile DEFAULT",
+                    format!(
+                        "Adding column '{column}' on table '{table}' with volatile DEFAULT {default} requires a full table rewrite, \
+                        even on PostgreSQL 11+: {function}() must be evaluated for every existing row. The rewrite holds an \
+                        ACCESS EXCLUSIVE lock, blocking all reads and writes. Duration depends on table size.",
+                        column = column_name,
+                        table = table_name,
+                        default = default,
+                        function = function
+                    ),
+                    format!(
+                        r#"1. Add the column without a default:
+   ALTER TABLE {table} ADD COLUMN {column} {data_type};
+
+2. Set the default for new rows only (no rewrite):
+   ALTER TABLE {table} ALTER COLUMN {column} SET DEFAULT {default};
+
+3. Backfill existing rows in batches (outside migration):
+   UPDATE {table} SET {column} = {default} WHERE {column} IS NULL AND id >= <start> AND id < <start + 1000>;"#,
+                        table = table_name,
+                        column = column_name,
+                        data_type = column_def.data_type,
+                        default = default
+                    ),
+                ).with_target(column_target(name, column_name)))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{assert_allows, assert_detects_violation};
+
+    #[test]
+    fn test_detects_gen_random_uuid() {
+        assert_detects_violation!(
+            VolatileDefaultCheck,
+            "ALTER TABLE users ADD COLUMN token UUID DEFAULT gen_random_uuid();",
+            "ADD COLUMN with volatile DEFAULT"
+        );
+    }
+
+    #[test]
+    fn test_detects_volatile_call_in_expression() {
+        assert_detects_violation!(
+            VolatileDefaultCheck,
+            "ALTER TABLE users ADD COLUMN score INT DEFAULT floor(random() * 100);",
+            "ADD COLUMN with volatile DEFAULT"
+        );
+    }
+
+    #[test]
+    fn test_detects_clock_timestamp() {
+        assert_detects_violation!(
+            VolatileDefaultCheck,
+            "ALTER TABLE users ADD COLUMN seen_at TIMESTAMPTZ DEFAULT clock_timestamp();",
+            "ADD COLUMN with volatile DEFAULT"
+        );
+    }
+
+    #[test]
+    fn test_allows_stable_defaults() {
+        assert_allows!(
+            VolatileDefaultCheck,
+            "ALTER TABLE users ADD COLUMN created_at TIMESTAMPTZ DEFAULT now();"
+        );
+        assert_allows!(
+            VolatileDefaultCheck,
+            "ALTER TABLE users ADD COLUMN created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP;"
+        );
+    }
+
+    #[test]
+    fn test_allows_constant_default() {
+        assert_allows!(
+            VolatileDefaultCheck,
+            "ALTER TABLE users ADD COLUMN admin BOOLEAN DEFAULT FALSE;"
+        );
+    }
+
+    #[test]
+    fn test_ignores_create_table() {
+        assert_allows!(
+            VolatileDefaultCheck,
+            "CREATE TABLE users (id UUID DEFAULT gen_random_uuid());"
+        );
+    }
+}
@@ -1,8 +1,12 @@
 //! Detection for wide indexes (indexes with 4+ columns).
 //!
-//! This check identifies `CREATE INDEX` statements with more than 3 columns.
+//! This check identifies `CREATE INDEX` statements with more than 3 columns by default, and
+//! `UNIQUE` and `PRIMARY KEY` table constraints, which build the same index. The
+//! threshold is set with `[checks.WideIndexCheck] max_columns`. Only key columns count:
+//! columns in an `INCLUDE (...)` clause are stored in the index but aren't part of the
+//! btree key, so they don't make an index wide.
 //!
-//! Wide indexes (with 4+ columns) are often ineffective because PostgreSQL can only use
+//! Wide indexes are often ineffective because PostgreSQL can only use
 //! the index efficiently when filtering on the leftmost columns in order. They also
 //! consume more storage and slow down write operations.
 //!
@@ -10,41 +14,60 @@
 //! query patterns instead.
 
 use crate::checks::{display_or_default, Check};
+use crate::config::WideIndexConfig;
 use crate::violation::Violation;
-use sqlparser::ast::Statement;
+use sqlparser::ast::{
+    AlterTable, AlterTableOperation, CreateIndex, ObjectName, Statement, TableConstraint,
+};
 
-pub struct WideIndexCheck;
+#[derive(Default)]
+pub struct WideIndexCheck {
+    config: WideIndexConfig,
+}
 
-impl Check for WideIndexCheck {
-    fn check(&self, stmt: &Statement) -> Vec<Violation> {
-        let mut violations = vec![];
-
-        if let Statement::CreateIndex(create_index) = stmt {
-            let column_count = create_index.columns.len();
-
-            // Only flag if MORE than 3 columns (i.e., 4 or more)
-            if column_count > 3 {
-                let table_name = create_index.table_name.to_string();
-                let index_name = display_or_default(create_index.name.as_ref(), "<unnamed>");
-                let column_names: Vec<String> = create_index
-                    .columns
-                    .iter()
-                    .map(|col| col.to_string())
-                    .collect();
-                let columns_list = column_names.join(", ");
-
-                violations.push(Violation::new(
-                    "Wide index",
-                    format!(
-                        "Index '{index}' on table '{table}' has {count} columns ({columns}). \
-                        Wide indexes (4+ columns) are rarely effective because PostgreSQL can only use them efficiently \
-                        when filtering on leftmost columns in order. They also increase storage costs and slow down writes.",
-                        index = index_name,
-                        table = table_name,
-                        count = column_count,
-                        columns = columns_list
-                    ),
-                    format!(r#"Consider these alternatives:
+impl WideIndexCheck {
+    pub fn new(config: WideIndexConfig) -> Self {
+        Self { config }
+    }
+
+    /// Violation for a CREATE INDEX with too many key columns
+    fn index_violation(&self, create_index: &CreateIndex) -> Option<Violation> {
+        // Key columns only; INCLUDE columns are kept apart by the parser
+        let column_count = create_index.columns.len();
+
+        // Only flag if MORE than max_columns columns (4 or more by default)
+        if column_count <= self.config.max_columns {
+            return None;
+        }
+
+        let table_name = create_index.table_name.to_string();
+        let index_name = display_or_default(create_index.name.as_ref(), "<unnamed>");
+        let column_names: Vec<String> = create_index
+            .columns
+            .iter()
+            .map(|col| col.to_string())
+            .collect();
+        let columns_list = column_names.join(", ");
+        let covering_cols: Vec<String> = column_names
+            .iter()
+            .skip(1)
+            .cloned()
+            .chain(create_index.include.iter().map(|col| col.to_string()))
+            .collect();
+
+        Some(Violation::new(
+            "Wide index",
+            format!(
+                "Index '{index}' on table '{table}' has {count} key columns ({columns}). \
+                Wide indexes ({wide}+ columns) are rarely effective because PostgreSQL can only use them efficiently \
+                when filtering on leftmost columns in order. They also increase storage costs and slow down writes.",
+                index = index_name,
+                table = table_name,
+                count = column_count,
+                columns = columns_list,
+                wide = self.config.max_columns + 1
+            ),
+            format!(r#"Consider these alternatives:
 
 1. Use a partial index for specific query patterns:
    CREATE INDEX {index} ON {table}({first_col})
@@ -61,31 +84,104 @@ impl Check for WideIndexCheck {
    INCLUDE ({other_cols});
 
 Note: Multi-column indexes are occasionally useful (e.g., for composite foreign keys or specific query patterns). If you've verified this index is necessary, use a safety-assured block."#,
-                        index = index_name,
-                        table = table_name,
-                        first_col = column_names.first().unwrap_or(&"column1".to_string()),
-                        second_col = column_names.get(1).unwrap_or(&"column2".to_string()),
-                        other_cols = column_names.iter().skip(1).cloned().collect::<Vec<_>>().join(", "),
-                        count = column_count,
-                        condition = "condition"
-                    ),
-                ));
-            }
+                index = index_name,
+                table = table_name,
+                first_col = column_names.first().unwrap_or(&"column1".to_string()),
+                second_col = column_names.get(1).unwrap_or(&"column2".to_string()),
+                other_cols = covering_cols.join(", "),
+                count = column_count,
+                condition = "condition"
+            ),
+        ))
+    }
+
+    /// Violation for a UNIQUE or PRIMARY KEY constraint whose index has too many key columns
+    fn constraint_violation(
+        &self,
+        table_name: &ObjectName,
+        constraint: &TableConstraint,
+    ) -> Option<Violation> {
+        let (kind, name, columns) = match constraint {
+            TableConstraint::Unique(unique) => ("UNIQUE", unique.name.as_ref(), &unique.columns),
+            TableConstraint::PrimaryKey(pk) => ("PRIMARY KEY", pk.name.as_ref(), &pk.columns),
+            _ => return None,
+        };
+        if columns.len() <= self.config.max_columns {
+            return None;
         }
 
-        violations
+        let column_names: Vec<String> = columns.iter().map(|col| col.to_string()).collect();
+
+        Some(Violation::new(
+            "Wide index",
+            format!(
+                "{kind} constraint '{constraint}' on table '{table}' builds an index with {count} key columns ({columns}). \
+                Wide indexes ({wide}+ columns) are rarely effective because PostgreSQL can only use them efficiently \
+                when filtering on leftmost columns in order. They also increase storage costs and slow down writes.",
+                kind = kind,
+                constraint = display_or_default(name, "<unnamed>"),
+                table = table_name,
+                count = columns.len(),
+                columns = column_names.join(", "),
+                wide = self.config.max_columns + 1
+            ),
+            format!(
+                r#"Consider these alternatives:
+
+1. Check whether fewer columns already identify a row, e.g. ({first_col}, {second_col}),
+   and constrain those instead.
+
+2. Use a surrogate key as the {kind} and index only the columns your queries filter on.
+
+3. Rethink your data model - does uniqueness really depend on all {count} columns?
+
+Note: Wide {kind} constraints are occasionally required (e.g., for natural keys). If you've verified this constraint is necessary, use a safety-assured block."#,
+                kind = kind,
+                first_col = column_names.first().map_or("column1", String::as_str),
+                second_col = column_names.get(1).map_or("column2", String::as_str),
+                count = columns.len()
+            ),
+        ))
+    }
+}
+
+impl Check for WideIndexCheck {
+    fn check(&self, stmt: &Statement) -> Vec<Violation> {
+        match stmt {
+            Statement::CreateIndex(create_index) => {
+                self.index_violation(create_index).into_iter().collect()
+            }
+            Statement::CreateTable(create_table) => create_table
+                .constraints
+                .iter()
+                .filter_map(|constraint| self.constraint_violation(&create_table.name, constraint))
+                .collect(),
+            Statement::AlterTable(AlterTable {
+                name, operations, ..
+            }) => operations
+                .iter()
+                .filter_map(|op| match op {
+                    AlterTableOperation::AddConstraint { constraint, .. } => {
+                        self.constraint_violation(name, constraint)
+                    }
+                    _ => None,
+                })
+                .collect(),
+            _ => vec![],
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::checks::test_utils::parse_sql;
     use crate::{assert_allows, assert_detects_violation};
 
     #[test]
     fn test_detects_index_with_four_columns() {
         assert_detects_violation!(
-            WideIndexCheck,
+            WideIndexCheck::default(),
             "CREATE INDEX idx_users_composite ON users(a, b, c, d);",
             "Wide index"
         );
@@ -94,7 +190,7 @@ mod tests {
     #[test]
     fn test_detects_index_with_five_columns() {
         assert_detects_violation!(
-            WideIndexCheck,
+            WideIndexCheck::default(),
             "CREATE INDEX idx_users_composite ON users(a, b, c, d, e);",
             "Wide index"
         );
@@ -103,7 +199,7 @@ mod tests {
     #[test]
     fn test_detects_unique_index_with_four_columns() {
         assert_detects_violation!(
-            WideIndexCheck,
+            WideIndexCheck::default(),
             "CREATE UNIQUE INDEX idx_users_composite ON users(tenant_id, user_id, email, status);",
             "Wide index"
         );
@@ -112,7 +208,7 @@ mod tests {
     #[test]
     fn test_allows_index_with_one_column() {
         assert_allows!(
-            WideIndexCheck,
+            WideIndexCheck::default(),
             "CREATE INDEX idx_users_email ON users(email);"
         );
     }
@@ -120,7 +216,7 @@ mod tests {
     #[test]
     fn test_allows_index_with_two_columns() {
         assert_allows!(
-            WideIndexCheck,
+            WideIndexCheck::default(),
             "CREATE INDEX idx_users_composite ON users(tenant_id, user_id);"
         );
     }
@@ -128,15 +224,106 @@ mod tests {
     #[test]
     fn test_allows_index_with_three_columns() {
         assert_allows!(
-            WideIndexCheck,
+            WideIndexCheck::default(),
             "CREATE INDEX idx_users_composite ON users(email, name, status);"
         );
     }
 
+    #[test]
+    fn test_ignores_include_columns() {
+        assert_allows!(
+            WideIndexCheck::default(),
+            "CREATE INDEX idx_users_email ON users(email) INCLUDE (name, status, created_at, tenant_id);"
+        );
+    }
+
+    #[test]
+    fn test_include_columns_kept_in_covering_suggestion() {
+        let violations = WideIndexCheck::default().check(&parse_sql(
+            "CREATE INDEX idx_users_composite ON users(a, b, c, d) INCLUDE (e);",
+        ));
+
+        assert_eq!(violations.len(), 1);
+        assert!(violations[0]
+            .problem
+            .contains("has 4 key columns (a, b, c, d)"));
+        assert!(violations[0]
+            .safe_alternative
+            .contains("INCLUDE (b, c, d, e)"));
+    }
+
+    #[test]
+    fn test_respects_max_columns() {
+        let check = WideIndexCheck::new(WideIndexConfig { max_columns: 4 });
+
+        assert_allows!(
+            check,
+            "CREATE INDEX idx_orders_tenant ON orders(tenant_id, region_id, customer_id, created_at);"
+        );
+        assert_detects_violation!(
+            check,
+            "CREATE INDEX idx_orders_tenant ON orders(tenant_id, region_id, customer_id, created_at, status);",
+            "Wide index"
+        );
+    }
+
+    #[test]
+    fn test_detects_wide_unique_constraint() {
+        let violations = WideIndexCheck::default().check(&parse_sql(
+            "ALTER TABLE orders ADD CONSTRAINT orders_natural_key UNIQUE (tenant_id, region_id, customer_id, number);",
+        ));
+
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].operation, "Wide index");
+        assert!(violations[0].problem.contains(
+            "UNIQUE constraint 'orders_natural_key' on table 'orders' builds an index with 4 key columns"
+        ));
+        assert!(violations[0]
+            .safe_alternative
+            .contains("(tenant_id, region_id)"));
+    }
+
+    #[test]
+    fn test_detects_wide_primary_key_in_create_table() {
+        let violations = WideIndexCheck::default().check(&parse_sql(
+            "CREATE TABLE line_items (a BIGINT, b BIGINT, c BIGINT, d BIGINT, UNIQUE (a, b), PRIMARY KEY (a, b, c, d));",
+        ));
+
+        assert_eq!(violations.len(), 1);
+        assert!(violations[0]
+            .problem
+            .contains("PRIMARY KEY constraint '<unnamed>' on table 'line_items'"));
+    }
+
+    #[test]
+    fn test_constraints_respect_max_columns() {
+        let check = WideIndexCheck::new(WideIndexConfig { max_columns: 4 });
+
+        assert_allows!(
+            check,
+            "ALTER TABLE orders ADD CONSTRAINT orders_key PRIMARY KEY (a, b, c, d);"
+        );
+        assert_detects_violation!(
+            check,
+            "ALTER TABLE orders ADD CONSTRAINT orders_key UNIQUE (a, b, c, d, e);",
+            "Wide index"
+        );
+    }
+
+    #[test]
+    fn test_single_column_constraint_over_threshold() {
+        let check = WideIndexCheck::new(WideIndexConfig { max_columns: 0 });
+        let stmt = parse_sql("ALTER TABLE users ADD CONSTRAINT users_email_key UNIQUE (email);");
+
+        let violations = check.check(&stmt);
+        assert_eq!(violations.len(), 1);
+        assert!(violations[0].safe_alternative.contains("(email, column2)"));
+    }
+
     #[test]
     fn test_ignores_other_statements() {
         assert_allows!(
-            WideIndexCheck,
+            WideIndexCheck::default(),
             "CREATE TABLE users (id SERIAL PRIMARY KEY);"
         );
     }
@@ -2,10 +2,13 @@
 //!
 //! This module handles loading and validating diesel-guard.toml configuration files.
 
+use crate::suppression::STALE_SAFETY_ASSURED;
+use crate::violation::Severity;
 use camino::{Utf8Path, Utf8PathBuf};
 use miette::Diagnostic;
 use regex::Regex;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::sync::LazyLock;
 use thiserror::Error;
 
@@ -17,11 +20,28 @@ static MIGRATION_TIMESTAMP_REGEX: LazyLock<Regex> = LazyLock::new(|| {
         .expect("valid regex pattern")
 });
 
+/// Extract the timestamp from a migration directory name, normalized to YYYYMMDDHHMMSS
+pub fn migration_timestamp(migration_dir_name: &str) -> Option<String> {
+    let captures = MIGRATION_TIMESTAMP_REGEX.captures(migration_dir_name)?;
+    Some(captures[1].replace(['_', '-'], ""))
+}
+
+/// Environment variable selecting an `[env.<name>]` profile
+pub const ENV_VAR: &str = "DIESEL_GUARD_ENV";
+
+/// Accepted values for the `dialect` setting
+const SUPPORTED_DIALECTS: &[&str] = &["postgres", "postgresql"];
+
+/// Oldest major version accepted for the `postgres_version` setting, and the one
+/// version-aware checks assume when it's unset
+pub(crate) const MIN_POSTGRES_VERSION: u32 = 9;
+
 /// Generate help text for invalid check names from the registry
 fn valid_check_names_help() -> String {
     format!(
-        "Valid check names: {}",
-        crate::checks::Registry::all_check_names().join(", ")
+        "Valid check names: {}, {}",
+        crate::checks::Registry::all_check_names().join(", "),
+        STALE_SAFETY_ASSURED
     )
 }
 
@@ -38,6 +58,21 @@ pub enum ConfigError {
 
     #[error("Invalid timestamp format: {0}")]
     InvalidTimestampFormat(String),
+
+    #[error("Unsupported dialect: {0}")]
+    UnsupportedDialect(String),
+
+    #[error("Invalid PostgreSQL version: {0}")]
+    InvalidPostgresVersion(u32),
+
+    #[error("Invalid value for {option}: {reason}")]
+    InvalidCheckOption { option: String, reason: String },
+
+    #[error("Unknown environment: {name}")]
+    UnknownEnv {
+        name: String,
+        available: Vec<String>,
+    },
 }
 
 impl Diagnostic for ConfigError {
@@ -49,6 +84,16 @@ impl Diagnostic for ConfigError {
             Self::InvalidTimestampFormat(_) => {
                 Some(Box::new("diesel_guard::config::invalid_timestamp"))
             }
+            Self::UnsupportedDialect(_) => {
+                Some(Box::new("diesel_guard::config::unsupported_dialect"))
+            }
+            Self::InvalidPostgresVersion(_) => {
+                Some(Box::new("diesel_guard::config::invalid_postgres_version"))
+            }
+            Self::InvalidCheckOption { .. } => {
+                Some(Box::new("diesel_guard::config::invalid_check_option"))
+            }
+            Self::UnknownEnv { .. } => Some(Box::new("diesel_guard::config::unknown_env")),
         }
     }
 
@@ -58,6 +103,21 @@ impl Diagnostic for ConfigError {
             Self::InvalidTimestampFormat(_) => Some(Box::new(
                 "Expected format: YYYYMMDDHHMMSS, YYYY_MM_DD_HHMMSS, or YYYY-MM-DD-HHMMSS (e.g., 20240101000000, 2024_01_01_000000, or 2024-01-01-000000)",
             )),
+            Self::UnsupportedDialect(_) => Some(Box::new(format!(
+                "Supported dialects: {}",
+                SUPPORTED_DIALECTS.join(", ")
+            ))),
+            Self::InvalidPostgresVersion(_) => Some(Box::new(format!(
+                "Expected a major version of {} or later, e.g. postgres_version = 16",
+                MIN_POSTGRES_VERSION
+            ))),
+            Self::UnknownEnv { available, .. } if available.is_empty() => Some(Box::new(
+                "Define it as an [env.<name>] section in diesel-guard.toml",
+            )),
+            Self::UnknownEnv { available, .. } => Some(Box::new(format!(
+                "Environments defined in diesel-guard.toml: {}",
+                available.join(", ")
+            ))),
             _ => None,
         }
     }
@@ -78,29 +138,329 @@ pub struct Config {
     /// List of check struct names to disable
     #[serde(default)]
     pub disable_checks: Vec<String>,
+
+    /// List of opt-in check struct names to enable
+    #[serde(default)]
+    pub enable_checks: Vec<String>,
+
+    /// SQL dialect of the migrations (only PostgreSQL is supported)
+    #[serde(default)]
+    pub dialect: Option<String>,
+
+    /// Major version of the PostgreSQL server the migrations target (e.g. 16)
+    #[serde(default)]
+    pub postgres_version: Option<u32>,
+
+    /// Migration roots of a monorepo, each with optional overrides
+    #[serde(default)]
+    pub roots: Vec<RootConfig>,
+
+    /// Severity per check name; checks not listed are errors
+    #[serde(default)]
+    pub severity: BTreeMap<String, Severity>,
+
+    /// Report related findings of different checks on the same statement separately
+    /// instead of merging them into one violation
+    #[serde(default)]
+    pub report_overlapping_violations: bool,
+
+    /// Options of individual checks (`[checks.<CheckName>]`)
+    #[serde(default)]
+    pub checks: ChecksConfig,
+
+    /// Named profiles (`[env.production]`) overriding any of the settings above
+    #[serde(default)]
+    pub env: BTreeMap<String, toml::Table>,
+}
+
+/// Options of individual checks, each under a `[checks.<CheckName>]` table
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+pub struct ChecksConfig {
+    #[serde(default, rename = "CreateExtensionCheck")]
+    pub create_extension: CreateExtensionConfig,
+
+    #[serde(default, rename = "DeleteWithoutWhereCheck")]
+    pub delete_without_where: DeleteWithoutWhereConfig,
+
+    #[serde(default, rename = "DmlInMigrationCheck")]
+    pub dml_in_migration: DmlInMigrationConfig,
+
+    #[serde(default, rename = "ExcessiveIndexesCheck")]
+    pub excessive_indexes: ExcessiveIndexesConfig,
+
+    #[serde(default, rename = "LockHeavyMigrationCheck")]
+    pub lock_heavy_migration: LockHeavyMigrationConfig,
+
+    #[serde(default, rename = "PreferTextOverVarcharCheck")]
+    pub prefer_text_over_varchar: PreferTextOverVarcharConfig,
+
+    #[serde(default, rename = "ShortIntegerPrimaryKeyCheck")]
+    pub short_integer_primary_key: ShortIntegerPrimaryKeyConfig,
+
+    #[serde(default, rename = "UnnamedConstraintCheck")]
+    pub unnamed_constraint: UnnamedConstraintConfig,
+
+    #[serde(default, rename = "VacuumFullCheck")]
+    pub vacuum_full: VacuumFullConfig,
+
+    #[serde(default, rename = "WideIndexCheck")]
+    pub wide_index: WideIndexConfig,
+}
+
+/// Options of `CreateExtensionCheck`
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+pub struct CreateExtensionConfig {
+    /// Extensions the migration role is allowed to create, e.g. `pg_trgm`
+    #[serde(default)]
+    pub allowed_extensions: Vec<String>,
+}
+
+/// Options of `DeleteWithoutWhereCheck`
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+pub struct DeleteWithoutWhereConfig {
+    /// Also flag DELETE statements that have a WHERE clause
+    #[serde(default)]
+    pub include_with_where: bool,
+}
+
+/// Options of `DmlInMigrationCheck`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DmlInMigrationConfig {
+    /// Kinds of data manipulation to flag
+    #[serde(default = "DmlStatement::all")]
+    pub statements: Vec<DmlStatement>,
+}
+
+impl Default for DmlInMigrationConfig {
+    fn default() -> Self {
+        Self {
+            statements: DmlStatement::all(),
+        }
+    }
+}
+
+/// A kind of data manipulation statement
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum DmlStatement {
+    Insert,
+    Update,
+    Delete,
+}
+
+impl DmlStatement {
+    fn all() -> Vec<Self> {
+        vec![Self::Insert, Self::Update, Self::Delete]
+    }
+}
+
+/// Options of `ExcessiveIndexesCheck`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ExcessiveIndexesConfig {
+    /// Number of indexes a table may have after a migration before it's flagged
+    #[serde(default = "ExcessiveIndexesConfig::default_max_indexes")]
+    pub max_indexes: usize,
+}
+
+impl ExcessiveIndexesConfig {
+    fn default_max_indexes() -> usize {
+        8
+    }
+}
+
+impl Default for ExcessiveIndexesConfig {
+    fn default() -> Self {
+        Self {
+            max_indexes: Self::default_max_indexes(),
+        }
+    }
+}
+
+/// Options of `LockHeavyMigrationCheck`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LockHeavyMigrationConfig {
+    /// Number of lock-acquiring statements a migration may contain before it's flagged
+    #[serde(default = "LockHeavyMigrationConfig::default_max_operations")]
+    pub max_operations: usize,
+}
+
+impl LockHeavyMigrationConfig {
+    fn default_max_operations() -> usize {
+        3
+    }
+}
+
+impl Default for LockHeavyMigrationConfig {
+    fn default() -> Self {
+        Self {
+            max_operations: Self::default_max_operations(),
+        }
+    }
+}
+
+/// Options of `PreferTextOverVarcharCheck`
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+pub struct PreferTextOverVarcharConfig {
+    /// VARCHAR lengths that aren't flagged
+    #[serde(default)]
+    pub allowed_lengths: Vec<u64>,
+}
+
+/// Options of `ShortIntegerPrimaryKeyCheck`
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+pub struct ShortIntegerPrimaryKeyConfig {
+    /// Tables whose short integer primary keys are intentional, e.g. small lookup tables
+    #[serde(default)]
+    pub allowed_tables: Vec<String>,
+}
+
+/// Options of `UnnamedConstraintCheck`
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+pub struct UnnamedConstraintConfig {
+    /// Also flag unnamed constraints declared in CREATE TABLE
+    #[serde(default)]
+    pub include_create_table: bool,
+}
+
+/// Options of `VacuumFullCheck`
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+pub struct VacuumFullConfig {
+    /// Also flag plain VACUUM, not just VACUUM FULL
+    #[serde(default)]
+    pub include_plain_vacuum: bool,
+}
+
+/// Options of `WideIndexCheck`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct WideIndexConfig {
+    /// Number of columns an index may have before it's flagged
+    #[serde(default = "WideIndexConfig::default_max_columns")]
+    pub max_columns: usize,
+}
+
+impl WideIndexConfig {
+    fn default_max_columns() -> usize {
+        3
+    }
+}
+
+impl Default for WideIndexConfig {
+    fn default() -> Self {
+        Self {
+            max_columns: Self::default_max_columns(),
+        }
+    }
+}
+
+/// A migration root with settings that override the top-level configuration
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct RootConfig {
+    /// Path to the migrations directory, relative to the working directory
+    pub path: String,
+
+    #[serde(default)]
+    pub start_after: Option<String>,
+
+    #[serde(default)]
+    pub check_down: Option<bool>,
+
+    /// Replaces the top-level `disable_checks` for this root
+    #[serde(default)]
+    pub disable_checks: Option<Vec<String>>,
+
+    #[serde(default)]
+    pub dialect: Option<String>,
 }
 
 impl Config {
     /// Load config from diesel-guard.toml in current directory
     /// Returns default config if file doesn't exist
+    ///
+    /// Applies the environment profile named by `DIESEL_GUARD_ENV`, if set.
     pub fn load() -> Result<Self, ConfigError> {
+        Self::load_for_env(std::env::var(ENV_VAR).ok().as_deref())
+    }
+
+    /// Load config from diesel-guard.toml in current directory with an environment profile applied
+    pub fn load_for_env(env: Option<&str>) -> Result<Self, ConfigError> {
         let config_path = Utf8PathBuf::from("diesel-guard.toml");
 
         if !config_path.exists() {
-            return Ok(Self::default());
+            return match env {
+                Some(name) => Err(ConfigError::UnknownEnv {
+                    name: name.to_string(),
+                    available: vec![],
+                }),
+                None => Ok(Self::default()),
+            };
         }
 
-        Self::load_from_path(&config_path)
+        Self::load_from_path_for_env(&config_path, env)
     }
 
     /// Load config from specific path (useful for testing)
     pub fn load_from_path(path: &Utf8Path) -> Result<Self, ConfigError> {
+        Self::load_from_path_for_env(path, None)
+    }
+
+    /// Load config from specific path with an environment profile applied
+    pub fn load_from_path_for_env(path: &Utf8Path, env: Option<&str>) -> Result<Self, ConfigError> {
         let contents = std::fs::read_to_string(path)?;
-        let config: Config = toml::from_str(&contents)?;
+        Self::from_toml_for_env(&contents, env)
+    }
+
+    /// Parse and validate config, merging the `[env.<name>]` profile over the top-level settings
+    fn from_toml_for_env(contents: &str, env: Option<&str>) -> Result<Self, ConfigError> {
+        let mut table: toml::Table = toml::from_str(contents)?;
+        let config: Config = table.clone().try_into()?;
+
+        // Every profile must be valid, not just the selected one
+        for name in config.env.keys() {
+            let mut merged = table.clone();
+            Self::apply_env(&mut merged, &config.env, name)?;
+            let profile: Config = merged.try_into()?;
+            profile.validate()?;
+        }
+
+        let Some(name) = env else {
+            config.validate()?;
+            return Ok(config);
+        };
+
+        Self::apply_env(&mut table, &config.env, name)?;
+        let config: Config = table.try_into()?;
         config.validate()?;
         Ok(config)
     }
 
+    /// Merge the named profile over the top-level settings of a raw config table
+    fn apply_env(
+        table: &mut toml::Table,
+        profiles: &BTreeMap<String, toml::Table>,
+        name: &str,
+    ) -> Result<(), ConfigError> {
+        let Some(profile) = profiles.get(name) else {
+            return Err(ConfigError::UnknownEnv {
+                name: name.to_string(),
+                available: profiles.keys().cloned().collect(),
+            });
+        };
+
+        merge_tables(table, profile.clone());
+        Ok(())
+    }
+
     /// Validate configuration values
     fn validate(&self) -> Result<(), ConfigError> {
         // Validate timestamp format if present
@@ -108,9 +468,38 @@ impl Config {
             Self::validate_timestamp(timestamp)?;
         }
 
+        if let Some(ref dialect) = self.dialect {
+            Self::validate_dialect(dialect)?;
+        }
+
+        if let Some(version) = self.postgres_version {
+            if version < MIN_POSTGRES_VERSION {
+                return Err(ConfigError::InvalidPostgresVersion(version));
+            }
+        }
+
+        if self.checks.wide_index.max_columns == 0 {
+            return Err(ConfigError::InvalidCheckOption {
+                option: "checks.WideIndexCheck.max_columns".to_string(),
+                reason: "an index needs at least one column, so it must be 1 or more".to_string(),
+            });
+        }
+
+        // Each root is validated with its overrides applied
+        for root in &self.roots {
+            self.for_root(root).validate()?;
+        }
+
         // Validate check names against the central registry
-        for check_name in &self.disable_checks {
-            if !crate::checks::Registry::all_check_names().contains(&check_name.as_str()) {
+        for check_name in self
+            .disable_checks
+            .iter()
+            .chain(&self.enable_checks)
+            .chain(self.severity.keys())
+        {
+            if !crate::checks::Registry::all_check_names().contains(&check_name.as_str())
+                && check_name != STALE_SAFETY_ASSURED
+            {
                 return Err(ConfigError::InvalidCheckName {
                     invalid_name: check_name.clone(),
                 });
@@ -120,6 +509,35 @@ impl Config {
         Ok(())
     }
 
+    /// Validate that the dialect is one we can parse
+    fn validate_dialect(dialect: &str) -> Result<(), ConfigError> {
+        if SUPPORTED_DIALECTS.contains(&dialect.to_lowercase().as_str()) {
+            Ok(())
+        } else {
+            Err(ConfigError::UnsupportedDialect(dialect.to_string()))
+        }
+    }
+
+    /// Configuration for a single migration root, with its overrides applied
+    pub fn for_root(&self, root: &RootConfig) -> Config {
+        Config {
+            start_after: root.start_after.clone().or(self.start_after.clone()),
+            check_down: root.check_down.unwrap_or(self.check_down),
+            disable_checks: root
+                .disable_checks
+                .clone()
+                .unwrap_or(self.disable_checks.clone()),
+            enable_checks: self.enable_checks.clone(),
+            dialect: root.dialect.clone().or(self.dialect.clone()),
+            postgres_version: self.postgres_version,
+            roots: vec![],
+            severity: self.severity.clone(),
+            report_overlapping_violations: self.report_overlapping_violations,
+            checks: self.checks.clone(),
+            env: BTreeMap::new(),
+        }
+    }
+
     /// Validate timestamp format: YYYY_MM_DD_HHMMSS, YYYY-MM-DD-HHMMSS, or YYYYMMDDHHMMSS
     fn validate_timestamp(timestamp: &str) -> Result<(), ConfigError> {
         let Some(captures) = MIGRATION_TIMESTAMP_REGEX.captures(timestamp) else {
@@ -134,11 +552,21 @@ impl Config {
         }
     }
 
+    /// Severity of violations reported by a check, or `default` if it isn't configured
+    pub fn severity_for(&self, check_name: &str, default: Severity) -> Severity {
+        self.severity.get(check_name).copied().unwrap_or(default)
+    }
+
     /// Check if a specific check is enabled
     pub fn is_check_enabled(&self, check_name: &str) -> bool {
         !self.disable_checks.iter().any(|c| c == check_name)
     }
 
+    /// Check if an opt-in check is enabled (listed in `enable_checks` and not disabled)
+    pub fn is_opt_in_check_enabled(&self, check_name: &str) -> bool {
+        self.enable_checks.iter().any(|c| c == check_name) && self.is_check_enabled(check_name)
+    }
+
     /// Check if migration should be checked based on start_after
     /// Returns true if migration timestamp is AFTER start_after (or if no filter set)
     pub fn should_check_migration(&self, migration_dir_name: &str) -> bool {
@@ -147,21 +575,32 @@ impl Config {
         };
 
         // Extract timestamp from migration directory name using regex
-        let Some(captures) = MIGRATION_TIMESTAMP_REGEX.captures(migration_dir_name) else {
+        let Some(migration_normalized) = migration_timestamp(migration_dir_name) else {
             return true; // If can't extract timestamp, default to checking it
         };
 
-        let migration_timestamp = &captures[1];
-
-        // Normalize both timestamps by removing separators for comparison
+        // Normalize start_after by removing separators for comparison
         let start_normalized = start_after.replace(['_', '-'], "");
-        let migration_normalized = migration_timestamp.replace(['_', '-'], "");
 
         // String comparison works because all formats are lexicographically ordered
         migration_normalized > start_normalized
     }
 }
 
+/// Recursively merge `overlay` into `base`; nested tables are merged, other values replaced
+fn merge_tables(base: &mut toml::Table, overlay: toml::Table) {
+    for (key, value) in overlay {
+        match (base.get_mut(&key), value) {
+            (Some(toml::Value::Table(base_table)), toml::Value::Table(overlay_table)) => {
+                merge_tables(base_table, overlay_table);
+            }
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -291,6 +730,23 @@ mod tests {
         assert!(!config_no_sep.should_check_migration("2024-01-01-000000_exact_match"));
     }
 
+    #[test]
+    fn test_migration_timestamp() {
+        assert_eq!(
+            migration_timestamp("2024_01_02_030405_create_users"),
+            Some("20240102030405".to_string())
+        );
+        assert_eq!(
+            migration_timestamp("2024-01-02-030405_create_users"),
+            Some("20240102030405".to_string())
+        );
+        assert_eq!(
+            migration_timestamp("20240102030405_create_users"),
+            Some("20240102030405".to_string())
+        );
+        assert_eq!(migration_timestamp("create_users"), None);
+    }
+
     #[test]
     fn test_is_check_enabled() {
         let config = Config {
@@ -304,6 +760,23 @@ mod tests {
         assert!(config.is_check_enabled("AddNotNullCheck"));
     }
 
+    #[test]
+    fn test_is_opt_in_check_enabled() {
+        let config = Config {
+            enable_checks: vec!["DmlInMigrationCheck".to_string()],
+            ..Default::default()
+        };
+        assert!(config.is_opt_in_check_enabled("DmlInMigrationCheck"));
+        assert!(!Config::default().is_opt_in_check_enabled("DmlInMigrationCheck"));
+
+        // disable_checks wins over enable_checks
+        let config = Config {
+            disable_checks: vec!["DmlInMigrationCheck".to_string()],
+            ..config
+        };
+        assert!(!config.is_opt_in_check_enabled("DmlInMigrationCheck"));
+    }
+
     #[test]
     fn test_invalid_check_name() {
         let config_str = r#"
@@ -369,4 +842,280 @@ disable_checks = ["AddColumnCheck"]
         assert!(config.check_down);
         assert_eq!(config.disable_checks, vec!["AddColumnCheck".to_string()]);
     }
+
+    #[test]
+    fn test_for_root_overrides() {
+        let config: Config = toml::from_str(
+            r#"
+            start_after = "2024_01_01_000000"
+            disable_checks = ["AddColumnCheck"]
+
+            [[roots]]
+            path = "services/billing/migrations"
+            check_down = true
+            disable_checks = ["DropColumnCheck"]
+
+            [[roots]]
+            path = "services/users/migrations"
+            start_after = "2025_01_01_000000"
+            "#,
+        )
+        .unwrap();
+        assert!(config.validate().is_ok());
+
+        let billing = config.for_root(&config.roots[0]);
+        assert_eq!(billing.start_after, Some("2024_01_01_000000".to_string()));
+        assert!(billing.check_down);
+        assert_eq!(billing.disable_checks, vec!["DropColumnCheck".to_string()]);
+
+        let users = config.for_root(&config.roots[1]);
+        assert_eq!(users.start_after, Some("2025_01_01_000000".to_string()));
+        assert!(!users.check_down);
+        assert_eq!(users.disable_checks, vec!["AddColumnCheck".to_string()]);
+    }
+
+    #[test]
+    fn test_invalid_root_override() {
+        let config: Config = toml::from_str(
+            r#"
+            [[roots]]
+            path = "migrations"
+            disable_checks = ["InvalidCheckName"]
+            "#,
+        )
+        .unwrap();
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_dialect() {
+        let config: Config = toml::from_str(r#"dialect = "postgres""#).unwrap();
+        assert!(config.validate().is_ok());
+
+        let config: Config = toml::from_str(r#"dialect = "mysql""#).unwrap();
+        assert!(matches!(
+            config.validate(),
+            Err(ConfigError::UnsupportedDialect(_))
+        ));
+    }
+
+    #[test]
+    fn test_env_profile_overrides_settings() {
+        let contents = r#"
+            disable_checks = ["AddIndexCheck"]
+
+            [severity]
+            DropColumnCheck = "warning"
+
+            [env.production]
+            disable_checks = []
+
+            [env.production.severity]
+            DropColumnCheck = "error"
+            RenameTableCheck = "warning"
+        "#;
+
+        let config = Config::from_toml_for_env(contents, None).unwrap();
+        assert!(!config.is_check_enabled("AddIndexCheck"));
+        assert_eq!(
+            config.severity_for("DropColumnCheck", Severity::Error),
+            Severity::Warning
+        );
+
+        let production = Config::from_toml_for_env(contents, Some("production")).unwrap();
+        assert!(production.is_check_enabled("AddIndexCheck"));
+        assert_eq!(
+            production.severity_for("DropColumnCheck", Severity::Error),
+            Severity::Error
+        );
+        assert_eq!(
+            production.severity_for("RenameTableCheck", Severity::Error),
+            Severity::Warning
+        );
+        assert_eq!(
+            production.severity_for("AddColumnCheck", Severity::Error),
+            Severity::Error
+        );
+    }
+
+    #[test]
+    fn test_unknown_env() {
+        let contents = r#"
+            [env.production]
+            check_down = true
+        "#;
+
+        let Err(ConfigError::UnknownEnv { name, available }) =
+            Config::from_toml_for_env(contents, Some("staging"))
+        else {
+            panic!("Expected UnknownEnv error");
+        };
+        assert_eq!(name, "staging");
+        assert_eq!(available, vec!["production".to_string()]);
+    }
+
+    #[test]
+    fn test_invalid_env_profile() {
+        let contents = r#"
+            [env.production]
+            disable_checks = ["InvalidCheckName"]
+        "#;
+
+        // Invalid profiles are reported even when not selected
+        assert!(Config::from_toml_for_env(contents, None).is_err());
+    }
+
+    #[test]
+    fn test_postgres_version() {
+        let config = Config::from_toml_for_env("postgres_version = 16", None).unwrap();
+        assert_eq!(config.postgres_version, Some(16));
+        assert_eq!(Config::default().postgres_version, None);
+
+        assert!(matches!(
+            Config::from_toml_for_env("postgres_version = 8", None),
+            Err(ConfigError::InvalidPostgresVersion(8))
+        ));
+    }
+
+    #[test]
+    fn test_rejects_zero_max_columns() {
+        assert!(matches!(
+            Config::from_toml_for_env("[checks.WideIndexCheck]\nmax_columns = 0", None),
+            Err(ConfigError::InvalidCheckOption { .. })
+        ));
+    }
+
+    #[test]
+    fn test_invalid_severity() {
+        assert!(
+            Config::from_toml_for_env("[severity]\nDropColumnCheck = \"fatal\"", None).is_err()
+        );
+        assert!(Config::from_toml_for_env("[severity]\nFooCheck = \"warning\"", None).is_err());
+    }
+
+    #[test]
+    fn test_check_options() {
+        let contents = r#"
+            [checks.VacuumFullCheck]
+            include_plain_vacuum = true
+        "#;
+        let config = Config::from_toml_for_env(contents, None).unwrap();
+        assert!(config.checks.vacuum_full.include_plain_vacuum);
+        assert!(!Config::default().checks.vacuum_full.include_plain_vacuum);
+
+        let config = Config::from_toml_for_env(
+            "[checks.CreateExtensionCheck]\nallowed_extensions = [\"pg_trgm\", \"uuid-ossp\"]",
+            None,
+        )
+        .unwrap();
+        assert_eq!(
+            config.checks.create_extension.allowed_extensions,
+            vec!["pg_trgm", "uuid-ossp"]
+        );
+
+        let config = Config::from_toml_for_env(
+            "[checks.DeleteWithoutWhereCheck]\ninclude_with_where = true",
+            None,
+        )
+        .unwrap();
+        assert!(config.checks.delete_without_where.include_with_where);
+
+        let config =
+            Config::from_toml_for_env("[checks.LockHeavyMigrationCheck]\nmax_operations = 5", None)
+                .unwrap();
+        assert_eq!(config.checks.lock_heavy_migration.max_operations, 5);
+        assert_eq!(
+            Config::default().checks.lock_heavy_migration.max_operations,
+            3
+        );
+
+        let config =
+            Config::from_toml_for_env("[checks.ExcessiveIndexesCheck]\nmax_indexes = 12", None)
+                .unwrap();
+        assert_eq!(config.checks.excessive_indexes.max_indexes, 12);
+        assert_eq!(Config::default().checks.excessive_indexes.max_indexes, 8);
+
+        let config = Config::from_toml_for_env(
+            "[checks.PreferTextOverVarcharCheck]\nallowed_lengths = [2, 255]",
+            None,
+        )
+        .unwrap();
+        assert_eq!(
+            config.checks.prefer_text_over_varchar.allowed_lengths,
+            vec![2, 255]
+        );
+        assert!(Config::default()
+            .checks
+            .prefer_text_over_varchar
+            .allowed_lengths
+            .is_empty());
+
+        let config = Config::from_toml_for_env(
+            "[checks.ShortIntegerPrimaryKeyCheck]\nallowed_tables = [\"countries\"]",
+            None,
+        )
+        .unwrap();
+        assert_eq!(
+            config.checks.short_integer_primary_key.allowed_tables,
+            vec!["countries"]
+        );
+
+        let config = Config::from_toml_for_env(
+            "[checks.UnnamedConstraintCheck]\ninclude_create_table = true",
+            None,
+        )
+        .unwrap();
+        assert!(config.checks.unnamed_constraint.include_create_table);
+        assert!(
+            !Config::default()
+                .checks
+                .unnamed_constraint
+                .include_create_table
+        );
+
+        let config =
+            Config::from_toml_for_env("[checks.WideIndexCheck]\nmax_columns = 4", None).unwrap();
+        assert_eq!(config.checks.wide_index.max_columns, 4);
+        assert_eq!(Config::default().checks.wide_index.max_columns, 3);
+
+        // Unknown checks and options are rejected
+        assert!(Config::from_toml_for_env(
+            "[checks.FooCheck]
+bar = 1",
+            None
+        )
+        .is_err());
+        assert!(Config::from_toml_for_env(
+            "[checks.VacuumFullCheck]
+bar = 1",
+            None
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_dml_statements_option() {
+        let contents = r#"
+            enable_checks = ["DmlInMigrationCheck"]
+
+            [checks.DmlInMigrationCheck]
+            statements = ["UPDATE", "DELETE"]
+        "#;
+        let config = Config::from_toml_for_env(contents, None).unwrap();
+        assert_eq!(
+            config.checks.dml_in_migration.statements,
+            vec![DmlStatement::Update, DmlStatement::Delete]
+        );
+        assert_eq!(
+            Config::default().checks.dml_in_migration.statements,
+            DmlStatement::all()
+        );
+
+        assert!(Config::from_toml_for_env(
+            "[checks.DmlInMigrationCheck]
+statements = [\"MERGE\"]",
+            None
+        )
+        .is_err());
+    }
 }
@@ -0,0 +1,277 @@
+//! Access to the target database.
+//!
+//! Queries run through the `psql` client, so no database driver is linked into
+//! the binary. `psql` must be installed wherever database features are used.
+
+use crate::error::{DieselGuardError, Result};
+use crate::schema::{ident_key, object_key};
+use sqlparser::ast::{Ident, ObjectName};
+use std::collections::{HashMap, HashSet};
+use std::process::Command;
+
+/// Versions of migrations Diesel has already applied to the database
+pub fn applied_migration_versions(database_url: &str) -> Result<HashSet<String>> {
+    let output = query(
+        database_url,
+        "SELECT version FROM __diesel_schema_migrations",
+    )?;
+    Ok(parse_versions(&output))
+}
+
+/// Primary key constraints of the tables in the database
+#[derive(Debug, Clone, Default)]
+pub struct DatabasePrimaryKeys {
+    /// Primary key constraint of each table, if it has one; tables in `public` are
+    /// also listed unqualified
+    primary_keys: HashMap<String, Option<String>>,
+}
+
+impl DatabasePrimaryKeys {
+    /// Query the tables and their primary key constraints from the catalog
+    pub fn load(database_url: &str) -> Result<Self> {
+        let output = query(
+            database_url,
+            "SELECT n.nspname, c.relname, con.conname \
+             FROM pg_class c \
+             JOIN pg_namespace n ON n.oid = c.relnamespace \
+             LEFT JOIN pg_constraint con ON con.conrelid = c.oid AND con.contype = 'p' \
+             WHERE c.relkind IN ('r', 'p')",
+        )?;
+        Ok(Self::parse(&output))
+    }
+
+    /// Parse `schema|table|constraint` rows, with an empty constraint for tables
+    /// without a primary key
+    pub(crate) fn parse(output: &str) -> Self {
+        let mut primary_keys = HashMap::new();
+        for line in output.lines() {
+            let mut fields = line.trim().split('|');
+            let (Some(schema), Some(table), Some(constraint)) =
+                (fields.next(), fields.next(), fields.next())
+            else {
+                continue;
+            };
+            let constraint = Some(constraint.to_string()).filter(|c| !c.is_empty());
+            if schema == "public" {
+                primary_keys.insert(table.to_string(), constraint.clone());
+            }
+            primary_keys.insert(format!("{}.{}", schema, table), constraint);
+        }
+        Self { primary_keys }
+    }
+
+    /// Whether the constraint is the table's primary key in the database, or None when
+    /// the table isn't in the database
+    pub fn is_primary_key(&self, table: &ObjectName, constraint: &Ident) -> Option<bool> {
+        self.primary_keys
+            .get(&object_key(table))
+            .map(|primary_key| primary_key.as_deref() == Some(ident_key(constraint).as_str()))
+    }
+}
+
+/// Run a query with `psql` and return its unaligned, tuples-only output
+pub fn query(database_url: &str, sql: &str) -> Result<String> {
+    let (database_url, password) = split_password(database_url);
+    let mut command = Command::new("psql");
+    command
+        .args([
+            database_url.as_str(),
+            "--no-psqlrc",
+            "--tuples-only",
+            "--no-align",
+        ])
+        .args(["--command", sql]);
+    if let Some(password) = password {
+        command.env("PGPASSWORD", password);
+    }
+    let output = command
+        .output()
+        .map_err(|e| DieselGuardError::DatabaseError {
+            msg: format!("failed to run psql: {}", e),
+        })?;
+
+    if !output.status.success() {
+        return Err(DieselGuardError::DatabaseError {
+            msg: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        });
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Connection URL without its password, and the password
+///
+/// The password is handed to `psql` through PGPASSWORD, so it doesn't show up in
+/// the process list. Connection strings other than URLs are returned unchanged.
+fn split_password(database_url: &str) -> (String, Option<String>) {
+    let Some(scheme_end) = database_url.find("://").map(|idx| idx + 3) else {
+        return (database_url.to_string(), None);
+    };
+    let authority_end = database_url[scheme_end..]
+        .find(['/', '?'])
+        .map_or(database_url.len(), |idx| scheme_end + idx);
+    let Some(at) = database_url[scheme_end..authority_end].rfind('@') else {
+        return (database_url.to_string(), None);
+    };
+    let userinfo = &database_url[scheme_end..scheme_end + at];
+    let Some(colon) = userinfo.find(':') else {
+        return (database_url.to_string(), None);
+    };
+
+    let url = format!(
+        "{}{}",
+        &database_url[..scheme_end + colon],
+        &database_url[scheme_end + at..]
+    );
+    (url, Some(percent_decode(&userinfo[colon + 1..])))
+}
+
+/// Decode `%XX` escapes of a URL component
+fn percent_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut idx = 0;
+    while idx < bytes.len() {
+        let escaped = (bytes[idx] == b'%')
+            .then(|| text.get(idx + 1..idx + 3))
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                idx += 3;
+            }
+            None => {
+                decoded.push(bytes[idx]);
+                idx += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// Version Diesel records for a migration directory
+///
+/// Diesel stores the directory name up to the first underscore, with dashes removed:
+/// `2024-01-01-000000_create_users` is recorded as `20240101000000`, while
+/// `2024_01_01_000000_create_users` is recorded as `2024`.
+pub fn migration_version(migration_dir_name: &str) -> String {
+    migration_dir_name
+        .split('_')
+        .next()
+        .unwrap_or(migration_dir_name)
+        .replace('-', "")
+}
+
+fn parse_versions(output: &str) -> HashSet<String> {
+    output
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_versions() {
+        let versions = parse_versions("20240101000000\n20240102000000\n\n");
+        assert_eq!(versions.len(), 2);
+        assert!(versions.contains("20240101000000"));
+    }
+
+    #[test]
+    fn test_parse_primary_keys() {
+        let primary_keys = DatabasePrimaryKeys::parse(
+            "public|users|users_identity\nbilling|invoices|invoices_pkey\npublic|events|\npublic|Accounts|Accounts_pkey\n\n",
+        );
+        let table = |name: &str| ObjectName::from(vec![Ident::new(name)]);
+
+        assert_eq!(
+            primary_keys.is_primary_key(&table("users"), &Ident::new("users_identity")),
+            Some(true)
+        );
+        assert_eq!(
+            primary_keys.is_primary_key(
+                &ObjectName::from(vec![Ident::new("public"), Ident::new("Users")]),
+                &Ident::new("USERS_IDENTITY")
+            ),
+            Some(true)
+        );
+        assert_eq!(
+            primary_keys.is_primary_key(&table("users"), &Ident::new("users_pkey")),
+            Some(false)
+        );
+        assert_eq!(
+            primary_keys.is_primary_key(&table("events"), &Ident::new("events_pkey")),
+            Some(false)
+        );
+        assert_eq!(
+            primary_keys.is_primary_key(&table("invoices"), &Ident::new("invoices_pkey")),
+            None
+        );
+        assert_eq!(
+            primary_keys.is_primary_key(
+                &ObjectName::from(vec![Ident::new("billing"), Ident::new("invoices")]),
+                &Ident::new("invoices_pkey")
+            ),
+            Some(true)
+        );
+        assert_eq!(
+            primary_keys.is_primary_key(
+                &ObjectName::from(vec![Ident::with_quote('"', "Accounts")]),
+                &Ident::with_quote('"', "Accounts_pkey")
+            ),
+            Some(true)
+        );
+        assert_eq!(
+            primary_keys.is_primary_key(&table("Accounts"), &Ident::new("accounts_pkey")),
+            None
+        );
+    }
+
+    #[test]
+    fn test_migration_version() {
+        assert_eq!(
+            migration_version("2024-01-01-000000_create_users"),
+            "20240101000000"
+        );
+        // Diesel keeps only the first field of underscore-separated timestamps
+        assert_eq!(migration_version("2024_01_01_000000_create_users"), "2024");
+        assert_eq!(
+            migration_version("20240101000000_create_users"),
+            "20240101000000"
+        );
+        assert_eq!(
+            migration_version("00000000000000_diesel_initial_setup"),
+            "00000000000000"
+        );
+        assert_eq!(migration_version("v1_create_users"), "v1");
+    }
+
+    #[test]
+    fn test_split_password() {
+        assert_eq!(
+            split_password("postgres://app:s%40cret:x@db.example.com:5432/myapp?sslmode=require"),
+            (
+                "postgres://app@db.example.com:5432/myapp?sslmode=require".to_string(),
+                Some("s@cret:x".to_string())
+            )
+        );
+        assert_eq!(
+            split_password("postgresql://app@localhost/myapp"),
+            ("postgresql://app@localhost/myapp".to_string(), None)
+        );
+        assert_eq!(
+            split_password("postgres://localhost/user@example"),
+            ("postgres://localhost/user@example".to_string(), None)
+        );
+        assert_eq!(
+            split_password("host=localhost dbname=myapp"),
+            ("host=localhost dbname=myapp".to_string(), None)
+        );
+    }
+}
@@ -0,0 +1,213 @@
+//! Comparison of two structured reports.
+//!
+//! Violations are matched by check, file, and fingerprint, so line shifts and
+//! wording changes in the safe alternative don't show up as new violations.
+//! Duplicates are matched one-to-one: two identical violations in the new report
+//! against one in the old report yield one unchanged and one added.
+
+use crate::error::{DieselGuardError, Result};
+use crate::violation::Violation;
+use camino::Utf8Path;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+
+/// Results as written by `diesel-guard check --format json`
+pub type Report = Vec<(String, Vec<Violation>)>;
+
+/// A violation together with the file it was found in
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReportEntry {
+    pub file: String,
+    pub fingerprint: String,
+    #[serde(flatten)]
+    pub violation: Violation,
+}
+
+/// Difference between an old and a new report
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ReportDiff {
+    pub added: Vec<ReportEntry>,
+    pub removed: Vec<ReportEntry>,
+    pub unchanged: Vec<ReportEntry>,
+}
+
+impl ReportDiff {
+    /// Compare two reports
+    pub fn new(old: &Report, new: &Report) -> Self {
+        let mut old_entries = group_entries(old);
+        let mut diff = Self::default();
+
+        for entry in entries(new) {
+            let key = entry_key(&entry);
+            match old_entries.get_mut(&key).and_then(|matches| matches.pop()) {
+                Some(_) => diff.unchanged.push(entry),
+                None => diff.added.push(entry),
+            }
+        }
+
+        diff.removed = old_entries.into_values().flatten().collect();
+        diff.removed.sort_by_key(entry_key);
+        diff
+    }
+
+    /// Whether the new report contains violations that the old one didn't
+    pub fn has_new_violations(&self) -> bool {
+        !self.added.is_empty()
+    }
+}
+
+/// Load a report written by `diesel-guard check --format json`
+///
+/// Accepts the plain results array, the object form produced with extra
+/// sections such as `--timings`, and results grouped per migration root.
+pub fn load_report(path: &Utf8Path) -> Result<Report> {
+    let content = fs::read_to_string(path)?;
+    let report_error = |msg: String| DieselGuardError::ReportError {
+        path: path.to_string(),
+        msg,
+    };
+
+    let mut value: serde_json::Value =
+        serde_json::from_str(&content).map_err(|e| report_error(e.to_string()))?;
+
+    // Multi-root runs group results per root; a diff compares all of them
+    if let Some(roots) = value
+        .get_mut("roots")
+        .and_then(|roots| roots.as_array_mut())
+    {
+        let mut report = Report::new();
+        for root in roots {
+            let results = root.get_mut("results").map(|results| results.take());
+            let results: Report = serde_json::from_value(results.unwrap_or_default())
+                .map_err(|e| report_error(e.to_string()))?;
+            report.extend(results);
+        }
+        return Ok(report);
+    }
+
+    if let Some(results) = value.get_mut("results") {
+        value = results.take();
+    }
+
+    serde_json::from_value(value).map_err(|e| report_error(e.to_string()))
+}
+
+type EntryKey = (String, String, String);
+
+fn entry_key(entry: &ReportEntry) -> EntryKey {
+    (
+        entry.file.clone(),
+        entry.violation.check.clone(),
+        entry.fingerprint.clone(),
+    )
+}
+
+fn entries(report: &Report) -> Vec<ReportEntry> {
+    report
+        .iter()
+        .flat_map(|(file, violations)| {
+            violations.iter().map(|violation| ReportEntry {
+                file: file.clone(),
+                fingerprint: violation.fingerprint(),
+                violation: violation.clone(),
+            })
+        })
+        .collect()
+}
+
+fn group_entries(report: &Report) -> BTreeMap<EntryKey, Vec<ReportEntry>> {
+    let mut grouped: BTreeMap<EntryKey, Vec<ReportEntry>> = BTreeMap::new();
+    for entry in entries(report) {
+        grouped.entry(entry_key(&entry)).or_default().push(entry);
+    }
+    grouped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn violation(check: &str, problem: &str) -> Violation {
+        let mut violation = Violation::new("OPERATION", problem, "");
+        violation.check = check.to_string();
+        violation
+    }
+
+    #[test]
+    fn test_diff_added_removed_unchanged() {
+        let old = vec![(
+            "a/up.sql".to_string(),
+            vec![
+                violation("DropColumnCheck", "drop email"),
+                violation("AddIndexCheck", "index users"),
+            ],
+        )];
+        let new = vec![
+            (
+                "a/up.sql".to_string(),
+                vec![violation("DropColumnCheck", "drop email")],
+            ),
+            (
+                "b/up.sql".to_string(),
+                vec![violation("RenameTableCheck", "rename users")],
+            ),
+        ];
+
+        let diff = ReportDiff::new(&old, &new);
+        assert_eq!(diff.unchanged.len(), 1);
+        assert_eq!(diff.added.len(), 1);
+        assert_eq!(diff.added[0].file, "b/up.sql");
+        assert_eq!(diff.removed.len(), 1);
+        assert_eq!(diff.removed[0].violation.check, "AddIndexCheck");
+        assert!(diff.has_new_violations());
+    }
+
+    #[test]
+    fn test_diff_matches_duplicates_one_to_one() {
+        let old = vec![(
+            "a/up.sql".to_string(),
+            vec![violation("DropColumnCheck", "drop email")],
+        )];
+        let new = vec![(
+            "a/up.sql".to_string(),
+            vec![
+                violation("DropColumnCheck", "drop email"),
+                violation("DropColumnCheck", "drop email"),
+            ],
+        )];
+
+        let diff = ReportDiff::new(&old, &new);
+        assert_eq!(diff.unchanged.len(), 1);
+        assert_eq!(diff.added.len(), 1);
+        assert!(diff.removed.is_empty());
+    }
+
+    #[test]
+    fn test_diff_same_problem_in_other_file_is_new() {
+        let old = vec![(
+            "a/up.sql".to_string(),
+            vec![violation("DropColumnCheck", "drop email")],
+        )];
+        let new = vec![(
+            "b/up.sql".to_string(),
+            vec![violation("DropColumnCheck", "drop email")],
+        )];
+
+        let diff = ReportDiff::new(&old, &new);
+        assert_eq!(diff.added.len(), 1);
+        assert_eq!(diff.removed.len(), 1);
+    }
+
+    #[test]
+    fn test_no_new_violations() {
+        let old = vec![(
+            "a/up.sql".to_string(),
+            vec![violation("DropColumnCheck", "drop email")],
+        )];
+
+        let diff = ReportDiff::new(&old, &vec![]);
+        assert!(!diff.has_new_violations());
+        assert_eq!(diff.removed.len(), 1);
+    }
+}
@@ -0,0 +1,195 @@
+//! Discovery of migration directories from Diesel's own configuration.
+//!
+//! Reads `[migrations_directory] dir` from `diesel.toml` in the project root and
+//! in every Cargo workspace member, so running without a path checks exactly
+//! what Diesel itself would run.
+
+use crate::config::ConfigError;
+use camino::{Utf8Path, Utf8PathBuf};
+use std::fs;
+
+/// Migrations directory Diesel uses when `diesel.toml` doesn't set one
+const DEFAULT_MIGRATIONS_DIR: &str = "migrations";
+
+/// Find migration directories configured via `diesel.toml` under `root`
+///
+/// Looks at `root/diesel.toml` and at `diesel.toml` of each workspace member
+/// listed in `root/Cargo.toml`. Returns existing directories only, in order.
+pub fn discover_migration_dirs(root: &Utf8Path) -> Result<Vec<Utf8PathBuf>, ConfigError> {
+    let mut project_dirs = vec![root.to_owned()];
+    project_dirs.extend(workspace_members(root)?);
+
+    let mut migration_dirs: Vec<Utf8PathBuf> = vec![];
+    for project_dir in project_dirs {
+        if let Some(dir) = migrations_dir(&project_dir)? {
+            if dir.is_dir() && !migration_dirs.contains(&dir) {
+                migration_dirs.push(dir);
+            }
+        }
+    }
+
+    Ok(migration_dirs)
+}
+
+/// Migrations directory configured by `diesel.toml` in a project, if the file exists
+fn migrations_dir(project_dir: &Utf8Path) -> Result<Option<Utf8PathBuf>, ConfigError> {
+    let Some(diesel_toml) = read_toml(&project_dir.join("diesel.toml"))? else {
+        return Ok(None);
+    };
+
+    let dir = diesel_toml
+        .get("migrations_directory")
+        .and_then(|section| section.get("dir"))
+        .and_then(|dir| dir.as_str())
+        .unwrap_or(DEFAULT_MIGRATIONS_DIR);
+
+    // Relative paths in diesel.toml are relative to the file itself
+    Ok(Some(join_relative(project_dir, dir)))
+}
+
+/// Directories of Cargo workspace members, with trailing `*` globs expanded
+fn workspace_members(root: &Utf8Path) -> Result<Vec<Utf8PathBuf>, ConfigError> {
+    let Some(cargo_toml) = read_toml(&root.join("Cargo.toml"))? else {
+        return Ok(vec![]);
+    };
+
+    let members = cargo_toml
+        .get("workspace")
+        .and_then(|workspace| workspace.get("members"))
+        .and_then(|members| members.as_array())
+        .map(|members| members.iter().filter_map(|m| m.as_str()).collect())
+        .unwrap_or_else(Vec::new);
+
+    let mut dirs = vec![];
+    for member in members {
+        match member.strip_suffix("/*") {
+            Some(parent) => dirs.extend(subdirectories(&join_relative(root, parent))),
+            None => dirs.push(join_relative(root, member)),
+        }
+    }
+
+    Ok(dirs)
+}
+
+/// Sorted subdirectories of a directory (empty if it can't be read)
+fn subdirectories(dir: &Utf8Path) -> Vec<Utf8PathBuf> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return vec![];
+    };
+
+    let mut dirs: Vec<Utf8PathBuf> = entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| Utf8PathBuf::from_path_buf(entry.path()).ok())
+        .collect();
+    dirs.sort();
+    dirs
+}
+
+/// Join a path from a config file onto its base, keeping `.` bases out of the result
+fn join_relative(base: &Utf8Path, path: &str) -> Utf8PathBuf {
+    if base == "." || base.as_str().is_empty() {
+        Utf8PathBuf::from(path)
+    } else {
+        base.join(path)
+    }
+}
+
+/// Parse a TOML file, returning `None` if it doesn't exist
+fn read_toml(path: &Utf8Path) -> Result<Option<toml::Table>, ConfigError> {
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let contents = fs::read_to_string(path)?;
+    Ok(Some(toml::from_str(&contents)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn temp_root() -> (TempDir, Utf8PathBuf) {
+        let temp_dir = TempDir::new().unwrap();
+        let root = Utf8PathBuf::from_path_buf(temp_dir.path().to_path_buf()).unwrap();
+        (temp_dir, root)
+    }
+
+    #[test]
+    fn test_discover_custom_migrations_directory() {
+        let (_temp_dir, root) = temp_root();
+        fs::create_dir_all(root.join("db/migrations")).unwrap();
+        fs::write(
+            root.join("diesel.toml"),
+            "[migrations_directory]\ndir = \"db/migrations\"\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            discover_migration_dirs(&root).unwrap(),
+            vec![root.join("db/migrations")]
+        );
+    }
+
+    #[test]
+    fn test_discover_default_migrations_directory() {
+        let (_temp_dir, root) = temp_root();
+        fs::create_dir_all(root.join("migrations")).unwrap();
+        fs::write(
+            root.join("diesel.toml"),
+            "[print_schema]\nfile = \"src/schema.rs\"\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            discover_migration_dirs(&root).unwrap(),
+            vec![root.join("migrations")]
+        );
+    }
+
+    #[test]
+    fn test_discover_workspace_members() {
+        let (_temp_dir, root) = temp_root();
+        fs::write(
+            root.join("Cargo.toml"),
+            "[workspace]\nmembers = [\"api\", \"services/*\"]\n",
+        )
+        .unwrap();
+
+        for member in ["api", "services/billing", "services/users"] {
+            fs::create_dir_all(root.join(member).join("migrations")).unwrap();
+        }
+        // Only members with a diesel.toml are discovered
+        fs::write(root.join("api/diesel.toml"), "").unwrap();
+        fs::write(
+            root.join("services/users/diesel.toml"),
+            "[migrations_directory]\ndir = \"migrations\"\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            discover_migration_dirs(&root).unwrap(),
+            vec![
+                root.join("api/migrations"),
+                root.join("services/users/migrations"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_discover_without_diesel_toml() {
+        let (_temp_dir, root) = temp_root();
+        fs::create_dir_all(root.join("migrations")).unwrap();
+
+        assert!(discover_migration_dirs(&root).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_discover_invalid_diesel_toml() {
+        let (_temp_dir, root) = temp_root();
+        fs::write(root.join("diesel.toml"), "[migrations_directory").unwrap();
+
+        assert!(discover_migration_dirs(&root).is_err());
+    }
+}
@@ -37,6 +37,27 @@ pub enum DieselGuardError {
         help("Run 'diesel-guard init' to create a valid configuration file")
     )]
     ConfigError(#[from] crate::config::ConfigError),
+
+    #[error("Invalid report '{path}': {msg}")]
+    #[diagnostic(
+        code(diesel_guard::report_error),
+        help("Reports must be produced by 'diesel-guard check --format json'")
+    )]
+    ReportError { path: String, msg: String },
+
+    #[error("Invalid ratchet state '{path}': {msg}")]
+    #[diagnostic(
+        code(diesel_guard::state_error),
+        help("Delete the state file to start ratcheting from the current violation counts")
+    )]
+    StateError { path: String, msg: String },
+
+    #[error("Database query failed: {msg}")]
+    #[diagnostic(
+        code(diesel_guard::database_error),
+        help("Check the database URL and that the psql client is installed")
+    )]
+    DatabaseError { msg: String },
 }
 
 impl DieselGuardError {
@@ -0,0 +1,186 @@
+//! Automatic fixes for migration directories.
+//!
+//! Statements such as `CREATE INDEX CONCURRENTLY` can't run inside a transaction,
+//! and Diesel wraps every migration in one unless its `metadata.toml` sets
+//! `run_in_transaction = false`. Forgetting that file makes safe migrations fail
+//! at deploy time, so these helpers detect such statements and write the file.
+
+use crate::error::Result;
+use camino::Utf8Path;
+use regex::Regex;
+use std::fs;
+use std::sync::LazyLock;
+
+/// Statements PostgreSQL refuses to run inside a transaction block
+static NON_TRANSACTIONAL_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?ix)
+        \b(CREATE|DROP)\s+(UNIQUE\s+)?INDEX\s+CONCURRENTLY\b
+        | \bREINDEX\b[^;]*\bCONCURRENTLY\b
+        | \bDETACH\s+PARTITION\b[^;]*\bCONCURRENTLY\b
+        | (^|;)\s*VACUUM\b
+        | (^|;)\s*(CREATE|DROP)\s+DATABASE\b
+        ",
+    )
+    .expect("valid regex pattern")
+});
+
+static RUN_IN_TRANSACTION_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?m)^\s*run_in_transaction\s*=.*$").expect("valid regex pattern")
+});
+
+/// What happened to a migration's metadata.toml
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MetadataFix {
+    Created,
+    Updated,
+    AlreadySet,
+}
+
+/// Whether SQL contains statements that can't run inside a transaction
+pub fn requires_no_transaction(sql: &str) -> bool {
+    NON_TRANSACTIONAL_REGEX.is_match(&strip_line_comments(sql))
+}
+
+/// Whether Diesel runs the migration in a directory inside a transaction
+///
+/// That's the default, unless its `metadata.toml` sets `run_in_transaction = false`.
+pub fn runs_in_transaction(migration_dir: &Utf8Path) -> bool {
+    fs::read_to_string(migration_dir.join("metadata.toml"))
+        .ok()
+        .and_then(|contents| run_in_transaction_setting(&contents))
+        .unwrap_or(true)
+}
+
+fn run_in_transaction_setting(contents: &str) -> Option<bool> {
+    toml::from_str::<toml::Table>(contents)
+        .ok()
+        .and_then(|table| table.get("run_in_transaction").and_then(|v| v.as_bool()))
+}
+
+/// Create or update `metadata.toml` in a migration directory so it runs outside a transaction
+///
+/// Other settings and comments in an existing file are preserved.
+pub fn ensure_no_transaction_metadata(migration_dir: &Utf8Path) -> Result<MetadataFix> {
+    let metadata_path = migration_dir.join("metadata.toml");
+
+    if !metadata_path.exists() {
+        fs::write(&metadata_path, "run_in_transaction = false\n")?;
+        return Ok(MetadataFix::Created);
+    }
+
+    let contents = fs::read_to_string(&metadata_path)?;
+    if run_in_transaction_setting(&contents) == Some(false) {
+        return Ok(MetadataFix::AlreadySet);
+    }
+
+    let updated = if RUN_IN_TRANSACTION_REGEX.is_match(&contents) {
+        RUN_IN_TRANSACTION_REGEX
+            .replace(&contents, "run_in_transaction = false")
+            .into_owned()
+    } else {
+        // Top-level keys must come before any table header
+        format!("run_in_transaction = false\n{}", contents)
+    };
+    fs::write(&metadata_path, updated)?;
+
+    Ok(MetadataFix::Updated)
+}
+
+pub(crate) fn strip_line_comments(sql: &str) -> String {
+    sql.lines()
+        .map(|line| line.split("--").next().unwrap_or(""))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_requires_no_transaction() {
+        assert!(requires_no_transaction(
+            "CREATE INDEX CONCURRENTLY idx_users_email ON users(email);"
+        ));
+        assert!(requires_no_transaction(
+            "create unique index concurrently idx ON users(email);"
+        ));
+        assert!(requires_no_transaction(
+            "DROP INDEX CONCURRENTLY idx_users_email;"
+        ));
+        assert!(requires_no_transaction(
+            "REINDEX INDEX CONCURRENTLY idx_users_email;"
+        ));
+        assert!(requires_no_transaction(
+            "ALTER TABLE users ADD COLUMN bio TEXT;\nVACUUM users;"
+        ));
+    }
+
+    #[test]
+    fn test_transactional_sql() {
+        assert!(!requires_no_transaction(
+            "CREATE INDEX idx_users_email ON users(email);"
+        ));
+        assert!(!requires_no_transaction(
+            "-- CREATE INDEX CONCURRENTLY later\nALTER TABLE users ADD COLUMN bio TEXT;"
+        ));
+        assert!(!requires_no_transaction(
+            "ALTER TABLE users ADD COLUMN vacuum_count INT;"
+        ));
+    }
+
+    #[test]
+    fn test_runs_in_transaction() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = Utf8Path::from_path(temp_dir.path()).unwrap();
+
+        assert!(runs_in_transaction(dir));
+
+        fs::write(dir.join("metadata.toml"), "# comment\n").unwrap();
+        assert!(runs_in_transaction(dir));
+
+        fs::write(dir.join("metadata.toml"), "run_in_transaction = false\n").unwrap();
+        assert!(!runs_in_transaction(dir));
+    }
+
+    #[test]
+    fn test_creates_metadata() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = Utf8Path::from_path(temp_dir.path()).unwrap();
+
+        assert_eq!(
+            ensure_no_transaction_metadata(dir).unwrap(),
+            MetadataFix::Created
+        );
+        assert_eq!(
+            fs::read_to_string(dir.join("metadata.toml")).unwrap(),
+            "run_in_transaction = false\n"
+        );
+        assert_eq!(
+            ensure_no_transaction_metadata(dir).unwrap(),
+            MetadataFix::AlreadySet
+        );
+    }
+
+    #[test]
+    fn test_updates_existing_metadata() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = Utf8Path::from_path(temp_dir.path()).unwrap();
+        fs::write(
+            dir.join("metadata.toml"),
+            "# keep this comment\nrun_in_transaction = true\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            ensure_no_transaction_metadata(dir).unwrap(),
+            MetadataFix::Updated
+        );
+        assert_eq!(
+            fs::read_to_string(dir.join("metadata.toml")).unwrap(),
+            "# keep this comment\nrun_in_transaction = false\n"
+        );
+    }
+}
@@ -1,11 +1,23 @@
 pub mod checks;
 pub mod config;
+pub mod database;
+pub mod diff;
+pub mod discovery;
 pub mod error;
+pub mod fix;
 pub mod output;
 pub mod parser;
+pub mod ratchet;
 pub mod safety_checker;
+pub mod schema;
+pub mod summary;
+pub mod suppression;
+pub mod timings;
 pub mod violation;
 
 pub use config::{Config, ConfigError};
 pub use safety_checker::SafetyChecker;
-pub use violation::Violation;
+pub use summary::Summary;
+pub use suppression::Suppression;
+pub use timings::Timings;
+pub use violation::{Severity, Violation};
@@ -1,8 +1,14 @@
-use camino::Utf8PathBuf;
+use camino::{Utf8Path, Utf8PathBuf};
 use clap::{Parser, Subcommand};
-use diesel_guard::output::OutputFormatter;
-use diesel_guard::{Config, SafetyChecker};
+use diesel_guard::config::RootConfig;
+use diesel_guard::database::{applied_migration_versions, DatabasePrimaryKeys};
+use diesel_guard::diff::{load_report, ReportDiff};
+use diesel_guard::discovery::discover_migration_dirs;
+use diesel_guard::output::{OutputFormatter, ReportSections, RootResults};
+use diesel_guard::{Config, SafetyChecker, Summary, Violation};
 use miette::{IntoDiagnostic, Result};
+use std::collections::HashSet;
+use std::env;
 use std::fs;
 use std::process::exit;
 
@@ -14,12 +20,55 @@ const CONFIG_TEMPLATE: &str = include_str!("../diesel-guard.toml.example");
 struct Cli {
     #[command(subcommand)]
     command: Commands,
+
+    /// Environment profile from diesel-guard.toml to apply (defaults to DIESEL_GUARD_ENV)
+    #[arg(long, global = true)]
+    env: Option<String>,
 }
 
 #[derive(Subcommand)]
 enum Commands {
     /// Check migrations for unsafe operations
     Check {
+        /// Path to migration file or directory (defaults to configured roots or diesel.toml)
+        path: Option<Utf8PathBuf>,
+
+        /// Output format (text, json, or jsonl)
+        #[arg(long, default_value = "text")]
+        format: String,
+
+        /// Include parse, per-check, and per-file timings in the output
+        #[arg(long)]
+        timings: bool,
+
+        /// Include violations suppressed by safety-assured blocks, start_after, or --skip-applied
+        #[arg(long)]
+        show_suppressed: bool,
+
+        /// Skip migrations already applied to the database (requires psql)
+        #[arg(long)]
+        skip_applied: bool,
+
+        /// Database URL for --skip-applied (defaults to the DATABASE_URL environment variable).
+        /// When given, dropped primary keys are also identified from the database (requires psql)
+        #[arg(long)]
+        database_url: Option<String>,
+
+        /// Fail only if per-check violation counts increased since the last run recorded in this file
+        #[arg(long, value_name = "STATE_FILE")]
+        ratchet: Option<Utf8PathBuf>,
+
+        /// Print only counts per check and severity and the top offending files (text format)
+        #[arg(long)]
+        summary_only: bool,
+
+        /// Number of files listed by --summary-only
+        #[arg(long, value_name = "N", default_value_t = 10)]
+        top_files: usize,
+    },
+
+    /// List safety-assured blocks for auditing
+    Suppressions {
         /// Path to migration file or directory
         path: Utf8PathBuf,
 
@@ -28,6 +77,28 @@ enum Commands {
         format: String,
     },
 
+    /// Apply automatic fixes to migrations
+    ///
+    /// Writes metadata.toml with `run_in_transaction = false` for migrations containing
+    /// statements that can't run inside a transaction, such as CREATE INDEX CONCURRENTLY.
+    Fix {
+        /// Path to migration file or directory
+        path: Utf8PathBuf,
+    },
+
+    /// Compare two JSON reports and fail if new violations appeared
+    Diff {
+        /// Report from the base revision
+        old: Utf8PathBuf,
+
+        /// Report from the current revision
+        new: Utf8PathBuf,
+
+        /// Output format (text or json)
+        #[arg(long, default_value = "text")]
+        format: String,
+    },
+
     /// Initialize diesel-guard configuration file
     Init {
         /// Overwrite existing config file if it exists
@@ -36,6 +107,210 @@ enum Commands {
     },
 }
 
+/// Load configuration, failing on an invalid config file or an unknown profile
+///
+/// The `--env` flag takes precedence over the DIESEL_GUARD_ENV environment variable.
+fn load_config(env: Option<&str>) -> Result<Config> {
+    let config = match env {
+        Some(env) => Config::load_for_env(Some(env)),
+        None => Config::load(),
+    }?;
+    Ok(config)
+}
+
+/// Settings of the check command that apply to every checked root
+struct CheckOptions {
+    timings: bool,
+    show_suppressed: bool,
+    applied_versions: HashSet<String>,
+    /// Primary keys of the database given with --database-url
+    database_primary_keys: Option<DatabasePrimaryKeys>,
+    /// Print a summary instead of each violation (`None` unless --summary-only)
+    summary_top_files: Option<usize>,
+}
+
+/// Create a checker with the command-line options applied
+fn build_checker(config: Config, options: &CheckOptions) -> SafetyChecker {
+    let mut checker =
+        SafetyChecker::with_config(config).with_applied_versions(options.applied_versions.clone());
+    if let Some(primary_keys) = &options.database_primary_keys {
+        checker = checker.with_database_primary_keys(primary_keys.clone());
+    }
+    if options.timings {
+        checker = checker.with_timings();
+    }
+    // The summary counts suppressed violations even when they aren't listed
+    if options.show_suppressed || options.summary_top_files.is_some() {
+        checker = checker.with_suppressed_tracking();
+    }
+    checker
+}
+
+/// Optional report sections collected by a checker
+fn report_sections(checker: &SafetyChecker, options: &CheckOptions) -> ReportSections {
+    ReportSections {
+        timings: checker.timings(),
+        suppressed: checker.suppressed().filter(|_| options.show_suppressed),
+    }
+}
+
+/// Summary of the results of one or more checkers
+fn summarize(results: &[(String, Vec<Violation>)], checkers: &[SafetyChecker]) -> Summary {
+    let mut summary = Summary::from_results(results);
+    for checker in checkers {
+        summary.files_checked += checker.files_checked();
+        summary.files_skipped += checker.files_skipped();
+        summary.suppressed += checker
+            .suppressed()
+            .map_or(0, |suppressed| suppressed.len());
+    }
+    summary
+}
+
+/// Print optional report sections in text format
+fn print_sections_text(sections: &ReportSections) {
+    if let Some(suppressed) = &sections.suppressed {
+        print!("{}", OutputFormatter::format_suppressed_text(suppressed));
+    }
+    if let Some(timings) = &sections.timings {
+        print!("{}", OutputFormatter::format_timings_text(timings));
+    }
+}
+
+/// Check a path, printing violations as JSON Lines as each file is checked
+fn stream_jsonl(
+    checker: &SafetyChecker,
+    path: &Utf8Path,
+    options: &CheckOptions,
+) -> Result<Vec<(String, Vec<Violation>)>> {
+    let mut results = vec![];
+    for file_path in checker.migration_files(path) {
+        let violations = checker.check_file(&file_path)?;
+        print!(
+            "{}",
+            OutputFormatter::format_jsonl(file_path.as_str(), &violations)
+        );
+        if !violations.is_empty() {
+            results.push((file_path.to_string(), violations));
+        }
+    }
+    print!(
+        "{}",
+        OutputFormatter::format_sections_jsonl(&report_sections(checker, options))
+    );
+    Ok(results)
+}
+
+/// Check every migration root, printing results grouped per root
+///
+/// Roots come from the config, or are discovered from `diesel.toml` if none are configured.
+fn check_roots(
+    config: &Config,
+    format: &str,
+    options: &CheckOptions,
+) -> Result<Vec<(String, Vec<Violation>)>> {
+    let roots = if config.roots.is_empty() {
+        // Fall back to the migration directories Diesel itself would use
+        discover_migration_dirs(Utf8Path::new("."))?
+            .into_iter()
+            .map(|dir| RootConfig {
+                path: dir.to_string(),
+                ..Default::default()
+            })
+            .collect()
+    } else {
+        config.roots.clone()
+    };
+
+    if roots.is_empty() {
+        miette::bail!(
+            "No path given, no [[roots]] configured in diesel-guard.toml, and no diesel.toml found"
+        );
+    }
+
+    let mut groups = vec![];
+    let mut checkers = vec![];
+    for root in &roots {
+        let checker = build_checker(config.for_root(root), options);
+        let root_path = Utf8Path::new(&root.path);
+        let results = if format == "jsonl" {
+            stream_jsonl(&checker, root_path, options)?
+        } else {
+            checker.check_path(root_path)?
+        };
+
+        groups.push(RootResults {
+            root: root.path.clone(),
+            results,
+            sections: report_sections(&checker, options),
+        });
+        checkers.push(checker);
+    }
+
+    match format {
+        "jsonl" => {}
+        _ if options.summary_top_files.is_some() => {
+            let results: Vec<_> = groups
+                .iter()
+                .flat_map(|group| group.results.clone())
+                .collect();
+            print_summary_only(&summarize(&results, &checkers), options);
+        }
+        "json" => println!("{}", OutputFormatter::format_roots_json(&groups)),
+        _ => {
+            // text format
+            for group in &groups {
+                print!("{}", OutputFormatter::format_root_header(group));
+                for (file_path, violations) in &group.results {
+                    print!("{}", OutputFormatter::format_text(file_path, violations));
+                }
+                print_sections_text(&group.sections);
+            }
+            let results: Vec<_> = groups
+                .iter()
+                .flat_map(|group| group.results.clone())
+                .collect();
+            OutputFormatter::print_summary(&Summary::from_results(&results));
+        }
+    }
+
+    Ok(groups.into_iter().flat_map(|group| group.results).collect())
+}
+
+/// Print the summary of a run in place of its violations (`--summary-only`)
+fn print_summary_only(summary: &Summary, options: &CheckOptions) {
+    print!(
+        "{}",
+        OutputFormatter::format_summary_text(summary, options.summary_top_files.unwrap_or(0))
+    );
+    OutputFormatter::print_summary(summary);
+}
+
+/// Print check results in text or json format
+fn print_results(results: &[(String, Vec<Violation>)], format: &str, sections: &ReportSections) {
+    if results.is_empty() && sections.is_empty() {
+        OutputFormatter::print_summary(&Summary::default());
+        return;
+    }
+
+    match format {
+        "json" => {
+            println!(
+                "{}",
+                OutputFormatter::format_json_with_sections(results, sections)
+            );
+        }
+        _ => {
+            // text format
+            for (file_path, violations) in results {
+                print!("{}", OutputFormatter::format_text(file_path, violations));
+            }
+            OutputFormatter::print_summary(&Summary::from_results(results));
+            print_sections_text(sections);
+        }
+    }
+}
+
 fn main() -> Result<()> {
     miette::set_hook(Box::new(|_| {
         Box::new(
@@ -50,42 +325,120 @@ fn main() -> Result<()> {
     let cli = Cli::parse();
 
     match cli.command {
-        Commands::Check { path, format } => {
-            // Load configuration with explicit error handling
-            let config = match Config::load() {
-                Ok(config) => config,
-                Err(e) => {
-                    eprintln!("Warning: {}", e);
-                    eprintln!("Using default configuration.");
-                    Config::default()
+        Commands::Check {
+            path,
+            format,
+            timings,
+            show_suppressed,
+            skip_applied,
+            database_url,
+            ratchet,
+            summary_only,
+            top_files,
+        } => {
+            if summary_only && format != "text" {
+                miette::bail!("--summary-only is only supported with --format text");
+            }
+
+            let config = load_config(cli.env.as_deref())?;
+
+            let database_primary_keys = database_url
+                .as_deref()
+                .map(DatabasePrimaryKeys::load)
+                .transpose()?;
+            let applied_versions = if skip_applied {
+                let Some(database_url) = database_url.or_else(|| env::var("DATABASE_URL").ok())
+                else {
+                    miette::bail!("--skip-applied requires --database-url or DATABASE_URL");
+                };
+                applied_migration_versions(&database_url)?
+            } else {
+                HashSet::new()
+            };
+            let options = CheckOptions {
+                timings,
+                show_suppressed,
+                applied_versions,
+                database_primary_keys,
+                summary_top_files: summary_only.then_some(top_files),
+            };
+
+            let results = match path {
+                Some(path) => {
+                    let checker = build_checker(config, &options);
+                    if format == "jsonl" {
+                        stream_jsonl(&checker, &path, &options)?
+                    } else if options.summary_top_files.is_some() {
+                        let results = checker.check_path(&path)?;
+                        print_summary_only(
+                            &summarize(&results, std::slice::from_ref(&checker)),
+                            &options,
+                        );
+                        results
+                    } else {
+                        let results = checker.check_path(&path)?;
+                        print_results(&results, &format, &report_sections(&checker, &options));
+                        results
+                    }
                 }
+                None => check_roots(&config, &format, &options)?,
             };
 
-            let checker = SafetyChecker::with_config(config);
+            // In ratchet mode only increased counts fail the run
+            if let Some(state_path) = ratchet {
+                let outcome = diesel_guard::ratchet::apply(&state_path, &results)?;
+                eprint!(
+                    "{}",
+                    OutputFormatter::format_ratchet_text(&outcome, state_path.as_str())
+                );
+                exit(if outcome.passed() { 0 } else { 1 });
+            }
 
-            let results = checker.check_path(&path)?;
+            // Only error-severity violations fail the run
+            if results
+                .iter()
+                .flat_map(|(_, violations)| violations)
+                .any(Violation::is_error)
+            {
+                exit(1);
+            }
+        }
 
-            if results.is_empty() {
-                OutputFormatter::print_summary(0);
-                exit(0);
+        Commands::Suppressions { path, format } => {
+            let config = load_config(cli.env.as_deref())?;
+
+            let checker = SafetyChecker::with_config(config);
+            let suppressions = checker.collect_suppressions(&path)?;
+
+            match format.as_str() {
+                "json" => println!(
+                    "{}",
+                    OutputFormatter::format_suppressions_json(&suppressions)
+                ),
+                _ => print!(
+                    "{}",
+                    OutputFormatter::format_suppressions_text(&suppressions)
+                ),
             }
+        }
+
+        Commands::Fix { path } => {
+            let config = load_config(cli.env.as_deref())?;
 
-            let total_violations: usize = results.iter().map(|(_, v)| v.len()).sum();
+            let checker = SafetyChecker::with_config(config);
+            let fixes = checker.fix_metadata(&path)?;
+            print!("{}", OutputFormatter::format_fixes_text(&fixes));
+        }
+
+        Commands::Diff { old, new, format } => {
+            let diff = ReportDiff::new(&load_report(&old)?, &load_report(&new)?);
 
             match format.as_str() {
-                "json" => {
-                    println!("{}", OutputFormatter::format_json(&results));
-                }
-                _ => {
-                    // text format
-                    for (file_path, violations) in &results {
-                        print!("{}", OutputFormatter::format_text(file_path, violations));
-                    }
-                    OutputFormatter::print_summary(total_violations);
-                }
+                "json" => println!("{}", OutputFormatter::format_diff_json(&diff)),
+                _ => print!("{}", OutputFormatter::format_diff_text(&diff)),
             }
 
-            if total_violations > 0 {
+            if diff.has_new_violations() {
                 exit(1);
             }
         }
@@ -1,7 +1,39 @@
-use crate::violation::Violation;
+use crate::diff::{ReportDiff, ReportEntry};
+use crate::fix::MetadataFix;
+use crate::ratchet::RatchetOutcome;
+use crate::summary::Summary;
+use crate::suppression::{SuppressedViolation, Suppression, SuppressionMechanism};
+use crate::timings::Timings;
+use crate::violation::{Severity, Violation};
+use camino::Utf8PathBuf;
 use colored::*;
+use serde::Serialize;
 use serde_json;
 
+/// Optional report sections that turn the JSON output into an object
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ReportSections {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timings: Option<Timings>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub suppressed: Option<Vec<SuppressedViolation>>,
+}
+
+impl ReportSections {
+    pub fn is_empty(&self) -> bool {
+        self.timings.is_none() && self.suppressed.is_none()
+    }
+}
+
+/// Results of checking a single migration root
+#[derive(Debug, Clone, Serialize)]
+pub struct RootResults {
+    pub root: String,
+    pub results: Vec<(String, Vec<Violation>)>,
+    #[serde(flatten)]
+    pub sections: ReportSections,
+}
+
 pub struct OutputFormatter;
 
 impl OutputFormatter {
@@ -9,22 +41,54 @@ impl OutputFormatter {
     pub fn format_text(file_path: &str, violations: &[Violation]) -> String {
         let mut output = String::new();
 
-        output.push_str(&format!(
-            "{} {}\n\n",
-            "❌ Unsafe migration detected in".red().bold(),
-            file_path.yellow()
-        ));
-
-        for violation in violations {
+        if violations.iter().any(Violation::is_error) {
             output.push_str(&format!(
                 "{} {}\n\n",
-                "❌",
-                violation.operation.red().bold()
+                "❌ Unsafe migration detected in".red().bold(),
+                file_path.yellow()
+            ));
+        } else {
+            output.push_str(&format!(
+                "{} {}\n\n",
+                "⚠️  Warnings in".yellow().bold(),
+                file_path.yellow()
             ));
+        }
+
+        for violation in violations {
+            let location = match (violation.line, violation.column) {
+                (Some(line), Some(column)) => format!(" line {}:{}", line, column),
+                (Some(line), None) => format!(" line {}", line),
+                _ => String::new(),
+            };
+            if violation.is_error() {
+                output.push_str(&format!(
+                    "{} {}{}\n\n",
+                    "❌",
+                    violation.operation.red().bold(),
+                    location.dimmed()
+                ));
+            } else {
+                output.push_str(&format!(
+                    "{} {} {}{}\n\n",
+                    "⚠️ ",
+                    violation.operation.yellow().bold(),
+                    "(warning)".dimmed(),
+                    location.dimmed()
+                ));
+            }
 
             output.push_str(&format!("{}\n", "Problem:".white().bold()));
             output.push_str(&format!("  {}\n\n", violation.problem));
 
+            if !violation.notes.is_empty() {
+                output.push_str(&format!("{}\n", "Also reported:".white().bold()));
+                for note in &violation.notes {
+                    output.push_str(&format!("  - {}\n", note));
+                }
+                output.push('\n');
+            }
+
             output.push_str(&format!("{}\n", "Safe alternative:".green().bold()));
             for line in violation.safe_alternative.lines() {
                 output.push_str(&format!("  {}\n", line));
@@ -41,16 +105,368 @@ impl OutputFormatter {
         serde_json::to_string_pretty(results).unwrap_or_else(|_| "{}".into())
     }
 
-    /// Print summary
-    pub fn print_summary(total_violations: usize) {
-        if total_violations == 0 {
-            println!("{}", "✅ No unsafe migrations detected!".green().bold());
+    /// Format violations of one file as JSON Lines (one object per violation)
+    pub fn format_jsonl(file_path: &str, violations: &[Violation]) -> String {
+        violations
+            .iter()
+            .map(|violation| {
+                let mut value = serde_json::to_value(violation).unwrap_or_default();
+                value["file"] = file_path.into();
+                format!("{}\n", value)
+            })
+            .collect()
+    }
+
+    /// Format report sections as a final JSON Lines record
+    pub fn format_sections_jsonl(sections: &ReportSections) -> String {
+        if sections.is_empty() {
+            return String::new();
+        }
+        let value = serde_json::to_value(sections).unwrap_or_default();
+        format!("{}\n", value)
+    }
+
+    /// Format results grouped per migration root as JSON
+    pub fn format_roots_json(groups: &[RootResults]) -> String {
+        let output = serde_json::json!({ "roots": groups });
+        serde_json::to_string_pretty(&output).unwrap_or_else(|_| "{}".into())
+    }
+
+    /// Format the heading printed before the results of a migration root
+    pub fn format_root_header(group: &RootResults) -> String {
+        let total_violations: usize = group.results.iter().map(|(_, v)| v.len()).sum();
+        format!(
+            "{} {} ({} violation(s))\n\n",
+            "📁",
+            group.root.cyan().bold(),
+            total_violations
+        )
+    }
+
+    /// Format violations as JSON together with extra sections such as `timings`
+    ///
+    /// Without any sections this is the same array as [`OutputFormatter::format_json`].
+    pub fn format_json_with_sections(
+        results: &[(String, Vec<Violation>)],
+        sections: &ReportSections,
+    ) -> String {
+        if sections.is_empty() {
+            return Self::format_json(results);
+        }
+
+        let mut output = serde_json::to_value(sections).unwrap_or_default();
+        output["results"] = serde_json::to_value(results).unwrap_or_default();
+        serde_json::to_string_pretty(&output).unwrap_or_else(|_| "{}".into())
+    }
+
+    /// Format suppressed violations as colored text for terminal
+    pub fn format_suppressed_text(suppressed: &[SuppressedViolation]) -> String {
+        let mut output = format!(
+            "\n{} {} suppressed violation(s)\n",
+            "🔒",
+            suppressed.len().to_string().bold()
+        );
+
+        for entry in suppressed {
+            let mechanism = match entry.mechanism {
+                SuppressionMechanism::SafetyAssured => "safety-assured",
+                SuppressionMechanism::StartAfter => "start_after",
+                SuppressionMechanism::AlreadyApplied => "already applied",
+            };
+            output.push_str(&format!(
+                "  {} {} ({}) [{}]\n",
+                entry.file.yellow(),
+                entry.violation.operation,
+                entry.violation.check,
+                mechanism.dimmed()
+            ));
+            if let Some(reason) = &entry.reason {
+                output.push_str(&format!("    {}\n", reason.dimmed()));
+            }
+        }
+
+        output
+    }
+
+    /// Format timings as text for terminal, slowest checks first
+    pub fn format_timings_text(timings: &Timings) -> String {
+        let mut output = String::new();
+
+        output.push_str(&format!("\n{}\n", "Timings:".white().bold()));
+        output.push_str(&format!(
+            "  total {:.2}ms, parse {:.2}ms, {} file(s)\n",
+            timings.total_ms,
+            timings.parse_ms,
+            timings.files.len()
+        ));
+
+        let mut checks: Vec<_> = timings.checks.iter().collect();
+        checks.sort_by(|a, b| b.1.total_cmp(a.1));
+        for (name, ms) in checks {
+            output.push_str(&format!("  {:<32} {:.2}ms\n", name, ms));
+        }
+
+        output
+    }
+
+    /// Format a run summary as colored text, listing at most `top_files` files
+    pub fn format_summary_text(summary: &Summary, top_files: usize) -> String {
+        let mut output = String::new();
+
+        output.push_str(&format!("{}\n", "Summary:".white().bold()));
+        output.push_str(&format!(
+            "  {} violation(s) in {} of {} checked file(s)\n",
+            summary.total_violations(),
+            summary.files.len(),
+            summary.files_checked
+        ));
+        output.push_str(&format!(
+            "  {} file(s) skipped, {} violation(s) suppressed\n",
+            summary.files_skipped, summary.suppressed
+        ));
+
+        if !summary.by_severity.is_empty() {
+            output.push_str(&format!("\n{}\n", "By severity:".white().bold()));
+            for (severity, count) in &summary.by_severity {
+                output.push_str(&format!("  {:<32} {}\n", severity, count));
+            }
+        }
+
+        if !summary.by_check.is_empty() {
+            let mut checks: Vec<_> = summary.by_check.iter().collect();
+            checks.sort_by(|a, b| b.1.cmp(a.1));
+            output.push_str(&format!("\n{}\n", "By check:".white().bold()));
+            for (check, count) in checks {
+                output.push_str(&format!("  {:<32} {}\n", check, count));
+            }
+        }
+
+        if !summary.files.is_empty() && top_files > 0 {
+            output.push_str(&format!(
+                "\n{}\n",
+                format!("Top {} file(s):", top_files.min(summary.files.len()))
+                    .white()
+                    .bold()
+            ));
+            for (file, count) in summary.files.iter().take(top_files) {
+                output.push_str(&format!("  {:>5}  {}\n", count, file.yellow()));
+            }
+        }
+
+        output
+    }
+
+    /// Format safety-assured blocks as colored text for terminal
+    pub fn format_suppressions_text(suppressions: &[Suppression]) -> String {
+        let mut output = String::new();
+
+        for suppression in suppressions {
+            output.push_str(&format!(
+                "{} {}\n",
+                "🔒",
+                format!(
+                    "{}:{}-{}",
+                    suppression.file, suppression.start_line, suppression.end_line
+                )
+                .yellow()
+            ));
+
+            let reason = match &suppression.reason {
+                Some(reason) => reason.normal(),
+                None => "(no reason given)".red(),
+            };
+            output.push_str(&format!("  {} {}\n", "Reason:".white().bold(), reason));
+
+            if let Some(checks) = &suppression.checks {
+                output.push_str(&format!(
+                    "  {} {}\n",
+                    "Scope:".white().bold(),
+                    checks.join(", ")
+                ));
+            }
+
+            if let Some(age_days) = suppression.age_days {
+                output.push_str(&format!(
+                    "  {} {} day(s)\n",
+                    "Age:".white().bold(),
+                    age_days
+                ));
+            }
+
+            let checks = if suppression.suppressed_checks.is_empty() {
+                "nothing".dimmed()
+            } else {
+                suppression.suppressed_checks.join(", ").normal()
+            };
+            output.push_str(&format!(
+                "  {} {}\n\n",
+                "Suppresses:".white().bold(),
+                checks
+            ));
+        }
+
+        output.push_str(&format!(
+            "{} safety-assured block(s) found\n",
+            suppressions.len().to_string().bold()
+        ));
+
+        output
+    }
+
+    /// Format safety-assured blocks as JSON
+    pub fn format_suppressions_json(suppressions: &[Suppression]) -> String {
+        serde_json::to_string_pretty(suppressions).unwrap_or_else(|_| "[]".into())
+    }
+
+    /// Format a report diff as colored text for terminal
+    pub fn format_diff_text(diff: &ReportDiff) -> String {
+        let mut output = String::new();
+
+        let format_entry = |entry: &ReportEntry| {
+            format!(
+                "{} {} ({})",
+                entry.file, entry.violation.operation, entry.violation.check
+            )
+        };
+
+        for entry in &diff.added {
+            output.push_str(&format!("{} {}\n", "+".red().bold(), format_entry(entry)));
+        }
+        for entry in &diff.removed {
+            output.push_str(&format!("{} {}\n", "-".green().bold(), format_entry(entry)));
+        }
+
+        output.push_str(&format!(
+            "\n{} added, {} removed, {} unchanged\n",
+            diff.added.len().to_string().red().bold(),
+            diff.removed.len().to_string().green().bold(),
+            diff.unchanged.len()
+        ));
+
+        output
+    }
+
+    /// Format a report diff as JSON
+    pub fn format_diff_json(diff: &ReportDiff) -> String {
+        serde_json::to_string_pretty(diff).unwrap_or_else(|_| "{}".into())
+    }
+
+    /// Format the result of ratchet mode as colored text
+    pub fn format_ratchet_text(outcome: &RatchetOutcome, state_path: &str) -> String {
+        let mut output = String::new();
+
+        if outcome.initialized {
+            output.push_str(&format!(
+                "{} Recorded current violation counts in {}\n",
+                "🔧",
+                state_path.yellow()
+            ));
+        }
+
+        for change in &outcome.regressions {
+            output.push_str(&format!(
+                "{} {} increased from {} to {}\n",
+                "❌",
+                change.check.red().bold(),
+                change.previous,
+                change.current
+            ));
+        }
+
+        for change in &outcome.improvements {
+            output.push_str(&format!(
+                "{} {} decreased from {} to {}\n",
+                "✅",
+                change.check.green().bold(),
+                change.previous,
+                change.current
+            ));
+        }
+
+        if outcome.passed() {
+            output.push_str(&format!(
+                "{}\n",
+                "Ratchet passed: no violation counts increased"
+                    .green()
+                    .bold()
+            ));
+        } else {
+            output.push_str(&format!(
+                "{}\n",
+                "Ratchet failed: violation counts increased since the last successful run"
+                    .red()
+                    .bold()
+            ));
+        }
+
+        output
+    }
+
+    /// Format the result of the fix command as colored text
+    pub fn format_fixes_text(fixes: &[(Utf8PathBuf, MetadataFix)]) -> String {
+        let mut output = String::new();
+        let mut changed = 0;
+
+        for (migration_dir, fix) in fixes {
+            let action = match fix {
+                MetadataFix::Created => "Created",
+                MetadataFix::Updated => "Updated",
+                MetadataFix::AlreadySet => continue,
+            };
+            changed += 1;
+            output.push_str(&format!(
+                "{} {} {}\n",
+                "✓".green(),
+                action,
+                migration_dir.join("metadata.toml").as_str().yellow()
+            ));
+        }
+
+        if changed == 0 {
+            output.push_str(&format!("{}\n", "✅ Nothing to fix".green().bold()));
         } else {
-            println!(
+            output.push_str(&format!(
+                "\n{} migration(s) now run outside a transaction (run_in_transaction = false)\n",
+                changed.to_string().bold()
+            ));
+        }
+
+        output
+    }
+
+    /// Format the closing line of a run; only errors are reported as unsafe
+    pub fn format_summary(summary: &Summary) -> String {
+        let errors = summary.count(Severity::Error);
+        let warnings = summary.count(Severity::Warning);
+        if errors > 0 {
+            let mut line = format!(
                 "\n{} {} unsafe migration(s) detected",
                 "❌".red(),
-                total_violations.to_string().red().bold()
+                errors.to_string().red().bold()
             );
+            if warnings > 0 {
+                line.push_str(&format!(
+                    ", {} warning(s)",
+                    warnings.to_string().yellow().bold()
+                ));
+            }
+            line
+        } else if warnings > 0 {
+            format!(
+                "\n{} {} warning(s), no unsafe migrations detected",
+                "⚠️ ",
+                warnings.to_string().yellow().bold()
+            )
+        } else {
+            "✅ No unsafe migrations detected!"
+                .green()
+                .bold()
+                .to_string()
         }
     }
+
+    /// Print summary
+    pub fn print_summary(summary: &Summary) {
+        println!("{}", Self::format_summary(summary));
+    }
 }
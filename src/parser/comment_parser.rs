@@ -6,10 +6,12 @@ use regex::Regex;
 use std::sync::LazyLock;
 
 /// Regex pattern for matching safety-assured:start directive
-/// Matches: optional whitespace, --, optional whitespace, safety-assured:start, optional whitespace
+/// Matches: optional whitespace, --, optional whitespace, safety-assured:start, an optional
+/// parenthesized list of check names, optional whitespace
 /// Case-insensitive
-static START_DIRECTIVE: LazyLock<Regex> =
-    LazyLock::new(|| Regex::new(r"(?i)^\s*--\s*safety-assured:start\s*$").unwrap());
+static START_DIRECTIVE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)^\s*--\s*safety-assured:start\s*(?:\(([^)]*)\))?\s*$").unwrap()
+});
 
 /// Regex pattern for matching safety-assured:end directive
 /// Matches: optional whitespace, --, optional whitespace, safety-assured:end, optional whitespace
@@ -23,6 +25,24 @@ static END_DIRECTIVE: LazyLock<Regex> =
 pub struct IgnoreRange {
     pub start_line: usize,
     pub end_line: usize,
+    /// Checks named in the start directive, or None when the block covers every check
+    pub checks: Option<Vec<String>>,
+}
+
+impl IgnoreRange {
+    /// Whether a line falls strictly inside the block (between its directives)
+    pub fn contains_line(&self, line: usize) -> bool {
+        line > self.start_line && line < self.end_line
+    }
+
+    /// Whether the block suppresses a violation of `check` reported on `line`
+    pub fn suppresses(&self, line: usize, check: &str) -> bool {
+        self.contains_line(line)
+            && self
+                .checks
+                .as_ref()
+                .is_none_or(|checks| checks.iter().any(|name| name == check))
+    }
 }
 
 pub struct CommentParser;
@@ -32,7 +52,7 @@ impl CommentParser {
     /// Returns: `Vec<IgnoreRange>` and validates matching start/end pairs
     pub fn parse_ignore_ranges(sql: &str) -> Result<Vec<IgnoreRange>> {
         let mut ranges = Vec::new();
-        let mut current_start: Option<usize> = None;
+        let mut current_start: Option<(usize, Option<Vec<String>>)> = None;
 
         for (line_num, line) in sql.lines().enumerate() {
             let line_num = line_num + 1; // 1-indexed
@@ -46,15 +66,21 @@ impl CommentParser {
                         line_num
                     )));
                 }
-                current_start = Some(line_num);
+                let checks = START_DIRECTIVE
+                    .captures(trimmed)
+                    .and_then(|captures| captures.get(1))
+                    .map(|list| Self::parse_check_names(list.as_str(), line_num))
+                    .transpose()?;
+                current_start = Some((line_num, checks));
             }
             // Match end directive
             else if Self::is_end_directive(trimmed) {
                 match current_start.take() {
-                    Some(start_line) => {
+                    Some((start_line, checks)) => {
                         ranges.push(IgnoreRange {
                             start_line,
                             end_line: line_num,
+                            checks,
                         });
                     }
                     None => {
@@ -68,7 +94,7 @@ impl CommentParser {
         }
 
         // Check for unclosed blocks
-        if let Some(start_line) = current_start {
+        if let Some((start_line, _)) = current_start {
             return Err(DieselGuardError::parse_error(format!(
                 "Unclosed 'safety-assured:start' at line {}. Did you forget to add 'safety-assured:end'?",
                 start_line
@@ -78,6 +104,36 @@ impl CommentParser {
         Ok(ranges)
     }
 
+    /// Parse the check names listed in a start directive
+    /// Names must be registered checks, and at least one is required
+    fn parse_check_names(list: &str, line_num: usize) -> Result<Vec<String>> {
+        let names: Vec<String> = list
+            .split(',')
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .map(str::to_string)
+            .collect();
+
+        if names.is_empty() {
+            return Err(DieselGuardError::parse_error(format!(
+                "Empty check list in 'safety-assured:start' at line {}. Name the checks to suppress, or remove the parentheses to suppress every check.",
+                line_num
+            )));
+        }
+
+        let known = crate::checks::Registry::all_check_names();
+        if let Some(unknown) = names.iter().find(|name| !known.contains(&name.as_str())) {
+            return Err(DieselGuardError::parse_error(format!(
+                "Unknown check '{}' in 'safety-assured:start' at line {}. Valid check names: {}",
+                unknown,
+                line_num,
+                known.join(", ")
+            )));
+        }
+
+        Ok(names)
+    }
+
     /// Check if line is a start directive
     fn is_start_directive(line: &str) -> bool {
         START_DIRECTIVE.is_match(line)
@@ -234,6 +290,9 @@ ALTER TABLE posts ADD COLUMN body TEXT;
             "  -- safety-assured:start  "
         ));
         assert!(CommentParser::is_start_directive("-- SAFETY-ASSURED:START"));
+        assert!(CommentParser::is_start_directive(
+            "-- safety-assured:start (DropColumnCheck)"
+        ));
 
         // Not start directives
         assert!(!CommentParser::is_start_directive("-- safety-assured:end"));
@@ -241,6 +300,53 @@ ALTER TABLE posts ADD COLUMN body TEXT;
         assert!(!CommentParser::is_start_directive("-- some comment"));
     }
 
+    #[test]
+    fn test_scoped_block() {
+        let sql = r#"
+-- safety-assured:start(DropColumnCheck, RenameColumnCheck)
+ALTER TABLE users DROP COLUMN email;
+-- safety-assured:end
+        "#;
+
+        let ranges = CommentParser::parse_ignore_ranges(sql).unwrap();
+        assert_eq!(ranges.len(), 1);
+        assert_eq!(
+            ranges[0].checks,
+            Some(vec![
+                "DropColumnCheck".to_string(),
+                "RenameColumnCheck".to_string()
+            ])
+        );
+        assert!(ranges[0].suppresses(3, "DropColumnCheck"));
+        assert!(!ranges[0].suppresses(3, "AddIndexCheck"));
+        assert!(!ranges[0].suppresses(4, "DropColumnCheck"));
+    }
+
+    #[test]
+    fn test_unscoped_block_suppresses_every_check() {
+        let ranges = CommentParser::parse_ignore_ranges(
+            "-- safety-assured:start\nDROP TABLE users;\n-- safety-assured:end",
+        )
+        .unwrap();
+        assert_eq!(ranges[0].checks, None);
+        assert!(ranges[0].suppresses(2, "DropTableCheck"));
+        assert!(ranges[0].suppresses(2, "AddIndexCheck"));
+    }
+
+    #[test]
+    fn test_scoped_block_rejects_unknown_and_empty_check_lists() {
+        let result = CommentParser::parse_ignore_ranges(
+            "-- safety-assured:start(DropColumnCheck, NoSuchCheck)\n-- safety-assured:end",
+        );
+        let error = result.unwrap_err().to_string();
+        assert!(error.contains("Unknown check 'NoSuchCheck'"));
+        assert!(error.contains("line 1"));
+
+        let result =
+            CommentParser::parse_ignore_ranges("-- safety-assured:start( )\n-- safety-assured:end");
+        assert!(result.unwrap_err().to_string().contains("Empty check list"));
+    }
+
     #[test]
     fn test_directive_requires_exact_match() {
         // These should NOT match - no extra characters allowed
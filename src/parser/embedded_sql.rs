@@ -0,0 +1,369 @@
+//! Extraction of SQL embedded in PL/pgSQL code and function bodies.
+//!
+//! sqlparser doesn't parse `DO` blocks, and their bodies are PL/pgSQL rather than
+//! SQL, so the DDL they run would go unchecked. The same goes for the string bodies of
+//! `CREATE FUNCTION` and `CREATE PROCEDURE`. PL/pgSQL statements are found on a best-effort
+//! basis: plain statements, including those nested in `IF`, `CASE`, and loop
+//! constructs, and the SQL string of `EXECUTE`, given as a string literal or as the
+//! format string of `format()` with its placeholders quoted. The caller drops
+//! snippets that don't parse as SQL, such as assignments or `RAISE`.
+
+use super::raw_statement::{dollar_quote_tag, statement_spans};
+use regex::Regex;
+use std::sync::LazyLock;
+
+/// PL/pgSQL control syntax that can precede a statement
+static CONTROL_PREFIX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?is)^(?:(?:IF|ELSIF|ELSEIF|WHEN)\b.*?\bTHEN\b|(?:FOR|FOREACH|WHILE)\b.*?\bLOOP\b|ELSE\b|LOOP\b|BEGIN\b|EXCEPTION\b|END(?:\s+(?:IF|LOOP|CASE))?\b|CASE\b[^;]*?\bWHEN\b.*?\bTHEN\b)\s*",
+    )
+    .expect("valid regex pattern")
+});
+
+/// `DO [LANGUAGE name]` before the body of a DO block
+static DO_PREFIX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?is)^DO\s+(?:LANGUAGE\s+\w+\s+)?").expect("valid regex pattern")
+});
+
+/// `EXECUTE` or `EXECUTE format(` before a dynamic SQL string
+static EXECUTE_PREFIX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?is)^EXECUTE\s+(format\s*\(\s*)?").expect("valid regex pattern")
+});
+
+/// `CREATE [OR REPLACE] { FUNCTION | PROCEDURE } name`
+static FUNCTION_PREFIX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?is)^CREATE\s+(?:OR\s+REPLACE\s+)?(?:FUNCTION|PROCEDURE)\s+([^\s(]+)")
+        .expect("valid regex pattern")
+});
+
+/// `AS` before the body string of a function
+static BODY_PREFIX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?is)\bAS\s+").expect("valid regex pattern"));
+
+/// `LANGUAGE name` of a function
+static LANGUAGE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?is)\bLANGUAGE\s+'?(\w+)'?").expect("valid regex pattern"));
+
+/// `RETURN expression` body of a SQL-standard function, which runs no statements
+static RETURN_BODY: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?is)\bRETURN\s").expect("valid regex pattern"));
+
+/// SQL found in PL/pgSQL code
+#[derive(Debug, Clone, PartialEq)]
+pub struct EmbeddedSql {
+    /// Byte offset of the snippet in the migration
+    pub offset: usize,
+    pub sql: String,
+}
+
+/// Body of a function or procedure created by a migration
+#[derive(Debug, Clone, PartialEq)]
+pub struct FunctionBody {
+    /// Name of the function or procedure
+    pub name: String,
+    /// Byte offset of the CREATE statement in the migration
+    pub offset: usize,
+    pub sql: BodySql,
+}
+
+/// SQL run by a function body
+#[derive(Debug, Clone, PartialEq)]
+pub enum BodySql {
+    /// Statements of a `LANGUAGE sql` body, which should all parse
+    Sql(Vec<EmbeddedSql>),
+    /// Statements of a `LANGUAGE plpgsql` body, found on a best-effort basis
+    PlPgSql(Vec<EmbeddedSql>),
+    /// A body that can't be analyzed, with the reason
+    Unsupported(String),
+}
+
+/// SQL run by the DO blocks of a migration
+pub fn do_block_sql(sql: &str) -> Vec<EmbeddedSql> {
+    statement_spans(sql)
+        .into_iter()
+        .filter_map(|(start, end)| {
+            let text = &sql[start..end];
+            let prefix = DO_PREFIX.find(text)?;
+            let (body_start, body, _) = string_literal(&text[prefix.end()..])?;
+            Some(plpgsql_sql(&body, start + prefix.end() + body_start))
+        })
+        .flatten()
+        .collect()
+}
+
+/// Bodies of the functions and procedures created by a migration
+///
+/// SQL-standard bodies (`RETURN expression`) run no statements and are left out.
+pub fn function_bodies(sql: &str) -> Vec<FunctionBody> {
+    statement_spans(sql)
+        .into_iter()
+        .filter_map(|(start, end)| {
+            let text = &sql[start..end];
+            let prefix = FUNCTION_PREFIX.captures(text)?;
+            let name = prefix[1].to_string();
+            let after_name = prefix.get(0).map_or(0, |m| m.end());
+
+            let body = BODY_PREFIX.find_iter(&text[after_name..]).find_map(|m| {
+                let literal = &text[after_name + m.end()..];
+                let (literal_start, body, literal_end) = string_literal(literal)?;
+                let literal_offset = after_name + m.end();
+                Some((
+                    literal_offset + literal_start,
+                    body,
+                    literal_offset + literal_end,
+                ))
+            });
+            let Some((body_start, body, body_end)) = body else {
+                if RETURN_BODY.is_match(&text[after_name..]) {
+                    return None;
+                }
+                return Some(FunctionBody {
+                    name,
+                    offset: start,
+                    sql: BodySql::Unsupported("no string body".to_string()),
+                });
+            };
+
+            // Look for LANGUAGE outside the body, which could mention it too
+            let outside = format!("{} {}", &text[..body_start], &text[body_end..]);
+            let language = LANGUAGE
+                .captures(&outside)
+                .map_or("sql".to_string(), |captures| captures[1].to_lowercase());
+            let offset = start + body_start;
+
+            let sql = match language.as_str() {
+                "sql" => BodySql::Sql(
+                    statement_spans(&body)
+                        .into_iter()
+                        .map(|(start, end)| EmbeddedSql {
+                            offset: offset + start,
+                            sql: body[start..end].trim_end_matches(';').to_string(),
+                        })
+                        .collect(),
+                ),
+                "plpgsql" => BodySql::PlPgSql(plpgsql_sql(&body, offset)),
+                other => BodySql::Unsupported(format!("LANGUAGE {}", other)),
+            };
+
+            Some(FunctionBody {
+                name,
+                offset: start,
+                sql,
+            })
+        })
+        .collect()
+}
+
+/// SQL run by a PL/pgSQL body starting at the given byte offset
+pub fn plpgsql_sql(body: &str, offset: usize) -> Vec<EmbeddedSql> {
+    statement_spans(body)
+        .into_iter()
+        .filter_map(|(start, end)| {
+            let mut start = start;
+            let text = body[start..end].trim_end_matches(';');
+            let mut rest = text;
+            while let Some(prefix) = CONTROL_PREFIX.find(rest) {
+                if prefix.is_empty() {
+                    break;
+                }
+                rest = &rest[prefix.end()..];
+            }
+            start += text.len() - rest.len();
+            if rest.trim().is_empty() {
+                return None;
+            }
+
+            match EXECUTE_PREFIX.captures(rest) {
+                Some(captures) => {
+                    let prefix_len = captures.get(0).map_or(0, |m| m.end());
+                    let (literal_start, sql, _) = string_literal(&rest[prefix_len..])?;
+                    let sql = if captures.get(1).is_some() {
+                        quote_placeholders(&sql)
+                    } else {
+                        sql
+                    };
+                    Some(EmbeddedSql {
+                        offset: offset + start + prefix_len + literal_start,
+                        sql,
+                    })
+                }
+                None => Some(EmbeddedSql {
+                    offset: offset + start,
+                    sql: rest.to_string(),
+                }),
+            }
+        })
+        .collect()
+}
+
+/// Contents of the string literal at the start of `s`, with the byte offsets they start
+/// at and the literal ends at
+///
+/// Handles single-quoted strings (unescaping `''`) and dollar-quoted strings.
+fn string_literal(s: &str) -> Option<(usize, String, usize)> {
+    if let Some(tag) = dollar_quote_tag(s) {
+        let body = &s[tag.len()..];
+        let end = body.find(tag)?;
+        return Some((tag.len(), body[..end].to_string(), 2 * tag.len() + end));
+    }
+
+    let body = s.strip_prefix('\'')?;
+    let mut contents = String::new();
+    let mut chars = body.char_indices().peekable();
+    while let Some((idx, c)) = chars.next() {
+        if c == '\'' {
+            if chars.peek().map(|(_, next)| *next) == Some('\'') {
+                chars.next();
+            } else {
+                return Some((1, contents, idx + 2));
+            }
+        }
+        contents.push(c);
+    }
+    None
+}
+
+/// Replace `format()` placeholders with quoted names so the SQL parses
+fn quote_placeholders(format: &str) -> String {
+    format
+        .replace("%%", "\u{0}")
+        .replace("%I", "\"%I\"")
+        .replace("%s", "\"%s\"")
+        .replace("%L", "'%L'")
+        .replace('\u{0}', "%")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snippets(sql: &str) -> Vec<String> {
+        do_block_sql(sql).into_iter().map(|e| e.sql).collect()
+    }
+
+    #[test]
+    fn test_extracts_plain_statements() {
+        let sql = "DO $$\nBEGIN\n  ALTER TABLE users ADD COLUMN bio TEXT;\nEND\n$$;";
+        let embedded = do_block_sql(sql);
+
+        assert_eq!(embedded.len(), 1);
+        assert_eq!(embedded[0].sql, "ALTER TABLE users ADD COLUMN bio TEXT");
+        assert!(sql[embedded[0].offset..].starts_with("ALTER TABLE users"));
+    }
+
+    #[test]
+    fn test_extracts_statements_nested_in_control_flow() {
+        let sql = "DO LANGUAGE plpgsql $body$
+DECLARE
+  n integer;
+BEGIN
+  IF NOT EXISTS (SELECT 1 FROM pg_indexes WHERE indexname = 'users_email_idx') THEN
+    CREATE INDEX users_email_idx ON users (email);
+  ELSE
+    RAISE NOTICE 'exists';
+  END IF;
+END
+$body$;";
+
+        assert_eq!(
+            snippets(sql),
+            vec![
+                "DECLARE\n  n integer",
+                "CREATE INDEX users_email_idx ON users (email)",
+                "RAISE NOTICE 'exists'",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_extracts_execute_strings() {
+        let sql = "DO $$ BEGIN
+  EXECUTE 'ALTER TABLE users DROP COLUMN ''legacy''';
+  EXECUTE format('ALTER TABLE %I ADD COLUMN flag BOOLEAN DEFAULT %L', 'users', 'f');
+  EXECUTE stmt;
+END $$;";
+
+        assert_eq!(
+            snippets(sql),
+            vec![
+                "ALTER TABLE users DROP COLUMN 'legacy'",
+                "ALTER TABLE \"%I\" ADD COLUMN flag BOOLEAN DEFAULT '%L'",
+            ]
+        );
+        let embedded = do_block_sql(sql);
+        assert!(sql[embedded[1].offset..].starts_with("ALTER TABLE %I"));
+    }
+
+    #[test]
+    fn test_ignores_other_statements() {
+        assert!(snippets("ALTER TABLE users ADD COLUMN bio TEXT;\nSELECT 'DO $$';").is_empty());
+    }
+
+    #[test]
+    fn test_extracts_sql_function_body() {
+        let sql = "CREATE FUNCTION reset_flags() RETURNS void AS '
+  UPDATE users SET flagged = false;
+  DELETE FROM flags WHERE note = ''stale'';
+' LANGUAGE sql;";
+        let bodies = function_bodies(sql);
+
+        assert_eq!(bodies.len(), 1);
+        assert_eq!(bodies[0].name, "reset_flags");
+        assert_eq!(bodies[0].offset, 0);
+        let BodySql::Sql(statements) = &bodies[0].sql else {
+            panic!("expected a SQL body, got {:?}", bodies[0].sql);
+        };
+        let statements: Vec<&str> = statements.iter().map(|e| e.sql.as_str()).collect();
+        assert_eq!(
+            statements,
+            vec![
+                "UPDATE users SET flagged = false",
+                "DELETE FROM flags WHERE note = 'stale'",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_extracts_plpgsql_procedure_body() {
+        let sql = "SELECT 1;
+CREATE OR REPLACE PROCEDURE add_bio()
+LANGUAGE plpgsql
+AS $$
+BEGIN
+  -- LANGUAGE sql in a comment doesn't change the language
+  ALTER TABLE users ADD COLUMN bio TEXT;
+END
+$$;";
+        let bodies = function_bodies(sql);
+
+        assert_eq!(bodies.len(), 1);
+        assert_eq!(bodies[0].name, "add_bio");
+        assert!(sql[bodies[0].offset..].starts_with("CREATE OR REPLACE PROCEDURE"));
+        let BodySql::PlPgSql(statements) = &bodies[0].sql else {
+            panic!("expected a PL/pgSQL body, got {:?}", bodies[0].sql);
+        };
+        assert_eq!(statements.len(), 1);
+        assert!(sql[statements[0].offset..].starts_with("-- LANGUAGE sql"));
+        assert!(statements[0]
+            .sql
+            .ends_with("ALTER TABLE users ADD COLUMN bio TEXT"));
+    }
+
+    #[test]
+    fn test_reports_bodies_that_cant_be_analyzed() {
+        let bodies = function_bodies(
+            "CREATE FUNCTION add(integer, integer) RETURNS integer AS 'add_ints', 'add' LANGUAGE c;",
+        );
+        assert_eq!(bodies.len(), 1);
+        assert_eq!(
+            bodies[0].sql,
+            BodySql::Unsupported("LANGUAGE c".to_string())
+        );
+
+        // SQL-standard bodies run no statements
+        assert!(function_bodies(
+            "CREATE FUNCTION add(a integer, b integer) RETURNS integer LANGUAGE sql RETURN a + b;"
+        )
+        .is_empty());
+    }
+}
@@ -1,30 +1,83 @@
+use crate::database::DatabasePrimaryKeys;
 use crate::error::{DieselGuardError, Result};
+use crate::schema::Schema;
 use sqlparser::ast::Statement;
 use sqlparser::dialect::PostgreSqlDialect;
 use sqlparser::parser::Parser;
+use sqlparser::tokenizer::{Location, Token};
+use std::sync::Arc;
 
 pub mod comment_parser;
 mod drop_index_concurrently_detector;
-mod primary_key_using_index_detector;
-mod unique_using_index_detector;
+mod embedded_sql;
+mod raw_statement;
 
 pub use comment_parser::IgnoreRange;
+pub use raw_statement::{RawStatement, UsingIndexConstraint};
+
+/// A statement with where it appears in the source SQL
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParsedStatement {
+    pub stmt: Statement,
+    /// 1-indexed line of the statement's first token
+    pub line: usize,
+    /// 1-indexed column of the statement's first token, in characters
+    pub column: usize,
+}
+
+impl ParsedStatement {
+    fn new(stmt: Statement, start: Location) -> Self {
+        Self {
+            stmt,
+            line: start.line as usize,
+            column: start.column as usize,
+        }
+    }
+}
 
 /// Parsed SQL with metadata for safety-assured handling
 pub struct ParsedSql {
-    pub statements: Vec<Statement>,
+    pub statements: Vec<ParsedStatement>,
+    /// Statements sqlparser can't parse, kept as source text
+    pub raw_statements: Vec<RawStatement>,
+    /// Statements that failed to parse on their own and aren't checked
+    pub unparsed_statements: Vec<RawStatement>,
+    /// Statements in the bodies of functions and procedures the migration creates
+    ///
+    /// They run when the function is called rather than during the migration, so they
+    /// don't affect the state later statements are checked against.
+    pub function_statements: Vec<ParsedStatement>,
     pub sql: String,
     pub ignore_ranges: Vec<IgnoreRange>,
+    /// Whether Diesel runs the migration inside a transaction, when known from the
+    /// migration directory's metadata.toml
+    pub run_in_transaction: Option<bool>,
+    /// Schema declared by the migrations before this one, when checked as part of a
+    /// migrations directory
+    pub schema: Arc<Schema>,
+    /// Primary keys of the live database, when a database URL was given
+    pub database_primary_keys: Option<Arc<DatabasePrimaryKeys>>,
 }
 
 pub struct SqlParser {
     dialect: PostgreSqlDialect,
+    /// Don't warn about statements that aren't checked
+    quiet: bool,
 }
 
 impl SqlParser {
     pub fn new() -> Self {
         Self {
             dialect: PostgreSqlDialect {},
+            quiet: false,
+        }
+    }
+
+    /// Parser that doesn't print warnings, for SQL that's parsed without being checked
+    pub fn quiet() -> Self {
+        Self {
+            quiet: true,
+            ..Self::new()
         }
     }
 
@@ -34,56 +87,213 @@ impl SqlParser {
             .map_err(|e| DieselGuardError::parse_error(e.to_string()))
     }
 
+    /// Parse SQL string into AST statements, each with its source location
+    pub fn parse_statements(&self, sql: &str) -> Result<Vec<ParsedStatement>> {
+        let error =
+            |e: sqlparser::parser::ParserError| DieselGuardError::parse_error(e.to_string());
+        let mut parser = Parser::new(&self.dialect)
+            .try_with_sql(sql)
+            .map_err(error)?;
+        let mut statements = vec![];
+        let mut expecting_delimiter = false;
+
+        // Same loop as Parser::parse_statements, noting where each statement starts and ends
+        loop {
+            while parser.consume_token(&Token::SemiColon) {
+                expecting_delimiter = false;
+            }
+            let next = parser.peek_token();
+            match &next.token {
+                Token::EOF => break,
+                Token::Word(word)
+                    if expecting_delimiter && word.keyword == sqlparser::keywords::Keyword::END =>
+                {
+                    break
+                }
+                _ if expecting_delimiter => {
+                    return parser.expected("end of statement", next).map_err(error)
+                }
+                _ => {}
+            }
+
+            let statement = parser.parse_statement().map_err(error)?;
+            statements.push(ParsedStatement::new(statement, next.span.start));
+            expecting_delimiter = true;
+        }
+
+        Ok(statements)
+    }
+
     /// Parse SQL with metadata for safety-assured blocks
-    /// Handles safe patterns that sqlparser can't parse
-    pub fn parse_with_metadata(&self, sql: &str) -> Result<ParsedSql> {
+    /// Statements sqlparser can't parse are skipped rather than failing the whole file
+    pub fn parse_with_metadata(&self, source: &str) -> Result<ParsedSql> {
+        // COPY data isn't SQL, so it's blanked out before anything looks at statements
+        let sql = &raw_statement::blank_copy_data(source);
+
         // Parse ignore ranges first
         let ignore_ranges = comment_parser::CommentParser::parse_ignore_ranges(sql)?;
 
-        // Try to parse SQL
-        match self.parse(sql) {
-            Ok(statements) => Ok(ParsedSql {
-                statements,
-                sql: sql.to_string(),
-                ignore_ranges,
-            }),
-            Err(e) => {
-                // If parsing fails, check for safe patterns that sqlparser can't handle
-                if let Some(pattern_name) = Self::detect_safe_pattern(sql) {
-                    Self::warn_safe_pattern_skipped(pattern_name);
-                    Ok(ParsedSql {
-                        statements: vec![],
-                        sql: sql.to_string(),
-                        ignore_ranges,
-                    })
-                } else {
-                    // Not a known safe pattern - return the original parse error
-                    Err(e)
+        // Statements sqlparser doesn't support are checked as source text
+        let (parseable_sql, mut raw_statements) = raw_statement::extract_raw_statements(sql);
+
+        // Parse the whole file at once, falling back to one statement at a time so a
+        // statement sqlparser chokes on doesn't hide the others
+        let (mut statements, unparsed_statements) = match self.parse_statements(&parseable_sql) {
+            Ok(statements) => (statements, vec![]),
+            Err(e) => self
+                .parse_each_statement(&parseable_sql, &mut raw_statements)
+                .ok_or(e)?,
+        };
+
+        // SQL run by DO blocks is checked like the statements around it
+        statements.extend(self.parse_embedded(sql, embedded_sql::do_block_sql(sql)));
+        statements.sort_by_key(|stmt| (stmt.line, stmt.column));
+
+        let function_statements = self.parse_function_bodies(sql);
+
+        Ok(ParsedSql {
+            statements,
+            raw_statements,
+            unparsed_statements,
+            function_statements,
+            sql: source.to_string(),
+            ignore_ranges,
+            run_in_transaction: None,
+            schema: Arc::default(),
+            database_primary_keys: None,
+        })
+    }
+
+    /// Parse each top-level statement on its own
+    ///
+    /// Returns the statements that parse and those that don't. Those that don't but are
+    /// checked as source text are added to `raw_statements` instead. Returns None when no
+    /// statement is checked and none is a known safe pattern, so the parse error of the
+    /// whole file is reported instead.
+    fn parse_each_statement(
+        &self,
+        sql: &str,
+        raw_statements: &mut Vec<RawStatement>,
+    ) -> Option<(Vec<ParsedStatement>, Vec<RawStatement>)> {
+        let mut statements = vec![];
+        let mut fallback = 0;
+        let mut unparsed = vec![];
+        let mut safe_patterns = 0;
+
+        for (start, end) in raw_statement::statement_spans(sql) {
+            let text = &sql[start..end];
+            // Blank the SQL before the statement so spans and errors keep their location
+            let padded = raw_statement::blank(&sql[..start]) + text;
+            match self.parse_statements(&padded) {
+                Ok(parsed) => statements.extend(parsed),
+                Err(e) => {
+                    let line = sql[..start].matches('\n').count() + 1;
+                    if raw_statement::is_fallback_statement(text) {
+                        raw_statements.push(RawStatement::from_source(text, line));
+                        fallback += 1;
+                        continue;
+                    }
+                    if Self::detect_safe_pattern(text).is_some() {
+                        safe_patterns += 1;
+                    } else {
+                        self.warn_statement_skipped(line, &e.to_string());
+                    }
+                    unparsed.push(RawStatement::from_source(text, line));
                 }
             }
         }
+
+        if statements.is_empty() && fallback == 0 && safe_patterns == 0 {
+            return None;
+        }
+        raw_statements.sort_by_key(|stmt| stmt.line);
+        Some((statements, unparsed))
+    }
+
+    /// Parse SQL embedded in PL/pgSQL code, located in the migration
+    ///
+    /// Snippets that aren't SQL, such as PL/pgSQL assignments, don't parse and are
+    /// dropped, as are queries, which in PL/pgSQL mostly assign variables.
+    fn parse_embedded(
+        &self,
+        sql: &str,
+        embedded: Vec<embedded_sql::EmbeddedSql>,
+    ) -> Vec<ParsedStatement> {
+        embedded
+            .into_iter()
+            .flat_map(|snippet| {
+                // Blank the SQL before the snippet so it keeps its location
+                let padded = raw_statement::blank(&sql[..snippet.offset]) + &snippet.sql;
+                self.parse_statements(&padded).unwrap_or_default()
+            })
+            .filter(|stmt| !matches!(stmt.stmt, Statement::Query(_) | Statement::Declare { .. }))
+            .collect()
+    }
+
+    /// Parse the statements in the bodies of functions and procedures, located in the
+    /// migration
+    ///
+    /// Warns about bodies that can't be analyzed, and about statements of `LANGUAGE sql`
+    /// bodies that don't parse.
+    fn parse_function_bodies(&self, sql: &str) -> Vec<ParsedStatement> {
+        let mut statements = vec![];
+
+        for body in embedded_sql::function_bodies(sql) {
+            let line = sql[..body.offset].matches('\n').count() + 1;
+            match body.sql {
+                embedded_sql::BodySql::Sql(snippets) => {
+                    for snippet in snippets {
+                        let padded = raw_statement::blank(&sql[..snippet.offset]) + &snippet.sql;
+                        match self.parse_statements(&padded) {
+                            Ok(parsed) => statements.extend(parsed),
+                            Err(e) => self.warn_function_skipped(&body.name, line, &e.to_string()),
+                        }
+                    }
+                }
+                embedded_sql::BodySql::PlPgSql(snippets) => {
+                    statements.extend(self.parse_embedded(sql, snippets));
+                }
+                embedded_sql::BodySql::Unsupported(reason) => {
+                    self.warn_function_skipped(&body.name, line, &reason);
+                }
+            }
+        }
+
+        statements.retain(|stmt| !matches!(stmt.stmt, Statement::Query(_)));
+        statements
     }
 
     /// Detect if SQL contains known safe patterns that sqlparser can't parse
     /// Returns the pattern name if detected
     fn detect_safe_pattern(sql: &str) -> Option<&'static str> {
-        if unique_using_index_detector::contains_unique_using_index(sql) {
-            Some("UNIQUE USING INDEX")
-        } else if primary_key_using_index_detector::contains_primary_key_using_index(sql) {
-            Some("PRIMARY KEY USING INDEX")
-        } else if drop_index_concurrently_detector::contains_drop_index_concurrently(sql) {
+        if drop_index_concurrently_detector::contains_drop_index_concurrently(sql) {
             Some("DROP INDEX CONCURRENTLY")
         } else {
             None
         }
     }
 
-    /// Print warning about safe pattern causing other statements to be skipped
-    fn warn_safe_pattern_skipped(pattern_name: &str) {
+    /// Print warning about a statement that isn't checked
+    fn warn_statement_skipped(&self, line: usize, error: &str) {
+        if self.quiet {
+            return;
+        }
+        eprintln!(
+            "Warning: statement at line {} can't be parsed and isn't checked ({}). \
+             Other statements in this file are still checked.",
+            line, error
+        );
+    }
+
+    /// Print warning about a function body that isn't fully checked
+    fn warn_function_skipped(&self, name: &str, line: usize, reason: &str) {
+        if self.quiet {
+            return;
+        }
         eprintln!(
-            "Warning: SQL contains {} (safe pattern) but parser failed. \
-             Other statements in this file may not be checked due to sqlparser limitations.",
-            pattern_name
+            "Warning: body of function '{}' at line {} can't be analyzed and isn't fully checked ({}). \
+             Other statements in this file are still checked.",
+            name, line, reason
         );
     }
 }
@@ -134,6 +344,17 @@ ALTER TABLE users DROP COLUMN email;
         assert!(!result.sql.is_empty());
     }
 
+    #[test]
+    fn test_parse_statements_with_locations() {
+        let parser = SqlParser::new();
+        let sql = "SELECT 'é';  ALTER TABLE users\n  DROP COLUMN email;\n";
+
+        let statements = parser.parse_statements(sql).unwrap();
+        assert_eq!(statements.len(), 2);
+        assert_eq!((statements[0].line, statements[0].column), (1, 1));
+        assert_eq!((statements[1].line, statements[1].column), (1, 14));
+    }
+
     #[test]
     fn test_parse_with_metadata_no_blocks() {
         let parser = SqlParser::new();
@@ -146,23 +367,19 @@ ALTER TABLE users DROP COLUMN email;
     }
 
     #[test]
-    fn test_unique_using_index_returns_empty_statements() {
+    fn test_parse_with_metadata_raw_statements() {
         let parser = SqlParser::new();
-        let sql =
-            "ALTER TABLE users ADD CONSTRAINT users_email_key UNIQUE USING INDEX users_email_idx;";
+        let sql = "ALTER TABLE users DROP COLUMN email;\nCLUSTER users USING users_pkey;";
 
-        // This should succeed (not error) but return empty statements
-        // because sqlparser can't parse UNIQUE USING INDEX
         let result = parser.parse_with_metadata(sql).unwrap();
-        assert_eq!(
-            result.statements.len(),
-            0,
-            "UNIQUE USING INDEX should return empty statements"
-        );
+        assert_eq!(result.statements.len(), 1);
+        assert_eq!(result.raw_statements.len(), 1);
+        assert_eq!(result.raw_statements[0].line, 2);
+        assert_eq!(result.sql, sql);
     }
 
     #[test]
-    fn test_unique_using_index_skips_all_statements() {
+    fn test_unique_using_index_keeps_other_statements() {
         let parser = SqlParser::new();
         // This file has both UNIQUE USING INDEX (safe) and DROP COLUMN (unsafe)
         let sql = r#"
@@ -170,14 +387,11 @@ ALTER TABLE users ADD CONSTRAINT users_email_key UNIQUE USING INDEX users_email_
 ALTER TABLE users DROP COLUMN old_field;
         "#;
 
-        // Due to parser limitation, ALL statements are skipped (returns empty)
-        // This test documents the limitation - the unsafe DROP COLUMN is NOT detected
+        // sqlparser can't parse UNIQUE USING INDEX, so it's kept as source text
         let result = parser.parse_with_metadata(sql).unwrap();
-        assert_eq!(
-            result.statements.len(),
-            0,
-            "When UNIQUE USING INDEX causes parse failure, ALL statements are skipped"
-        );
+        assert_eq!(result.statements.len(), 1);
+        assert_eq!(result.raw_statements.len(), 1);
+        assert!(result.raw_statements[0].using_index_constraint().is_some());
     }
 
     #[test]
@@ -209,7 +423,7 @@ ALTER TABLE users DROP COLUMN old_field;
     }
 
     #[test]
-    fn test_drop_index_concurrently_skips_all_statements() {
+    fn test_drop_index_concurrently_keeps_other_statements() {
         let parser = SqlParser::new();
         // This file has both DROP INDEX CONCURRENTLY (safe) and DROP COLUMN (unsafe)
         let sql = r#"
@@ -217,33 +431,155 @@ DROP INDEX CONCURRENTLY idx_users_email;
 ALTER TABLE users DROP COLUMN old_field;
         "#;
 
-        // Due to parser limitation, ALL statements are skipped (returns empty)
-        // This test documents the limitation - the unsafe DROP COLUMN is NOT detected
+        // Only the statement sqlparser can't parse is skipped
+        let result = parser.parse_with_metadata(sql).unwrap();
+        assert_eq!(result.statements.len(), 1);
+        assert_eq!(result.unparsed_statements.len(), 1);
+        assert_eq!(result.unparsed_statements[0].keyword, "DROP");
+        assert_eq!(result.unparsed_statements[0].line, 2);
+    }
+
+    #[test]
+    fn test_unparseable_statement_keeps_other_statements() {
+        let parser = SqlParser::new();
+        let sql = "ALTER TABLE users ADD COLUMN bio TEXT;
+CREATE STATISTICS FOO BAR;
+ALTER TABLE users DROP COLUMN old_field;";
+
+        let result = parser.parse_with_metadata(sql).unwrap();
+        assert_eq!(result.statements.len(), 2);
+        assert_eq!(result.unparsed_statements.len(), 1);
+        assert_eq!(result.unparsed_statements[0].line, 2);
+    }
+
+    #[test]
+    fn test_role_statements_fall_back_to_source_text() {
+        let parser = SqlParser::new();
+        let sql = "ALTER ROLE app SET statement_timeout = '30s';
+CREATE GROUP admins;
+ALTER TABLE users DROP COLUMN old_field;";
+
+        // Parsed forms stay statements, and only forms sqlparser fails on are raw
+        let result = parser.parse_with_metadata(sql).unwrap();
+        assert_eq!(result.statements.len(), 2);
+        assert!(matches!(
+            result.statements[0].stmt,
+            Statement::AlterRole { .. }
+        ));
+        assert_eq!(result.raw_statements.len(), 1);
+        assert_eq!(result.raw_statements[0].sql, "CREATE GROUP admins");
+        assert_eq!(result.raw_statements[0].line, 2);
+        assert!(result.unparsed_statements.is_empty());
+
+        // A file of only fallback statements isn't a parse error
+        let result = parser.parse_with_metadata("CREATE GROUP admins;").unwrap();
+        assert_eq!(result.raw_statements.len(), 1);
+    }
+
+    #[test]
+    fn test_spans_of_statements_parsed_one_at_a_time() {
+        let parser = SqlParser::new();
+        let sql = "CREATE STATISTICS FOO BAR;\n\n  ALTER TABLE users\n    DROP COLUMN email;";
+
+        let result = parser.parse_with_metadata(sql).unwrap();
+        assert_eq!(result.statements.len(), 1);
+        assert_eq!(result.statements[0].line, 3);
+        assert_eq!(result.statements[0].column, 3);
+    }
+
+    #[test]
+    fn test_checks_sql_in_do_blocks() {
+        let parser = SqlParser::new();
+        let sql = "ALTER TABLE users ADD COLUMN bio TEXT;
+DO $$
+DECLARE
+  n integer := 0;
+BEGIN
+  SELECT count(*) INTO n FROM users;
+  IF n > 0 THEN
+    EXECUTE 'ALTER TABLE users DROP COLUMN legacy';
+  END IF;
+END
+$$;
+ALTER TABLE users ADD COLUMN nickname TEXT;";
+
         let result = parser.parse_with_metadata(sql).unwrap();
+        assert_eq!(result.raw_statements.len(), 1);
+        assert_eq!(result.raw_statements[0].keyword, "DO");
+        assert!(result.unparsed_statements.is_empty());
+
+        let statements: Vec<String> = result
+            .statements
+            .iter()
+            .map(|stmt| stmt.stmt.to_string())
+            .collect();
         assert_eq!(
-            result.statements.len(),
-            0,
-            "When DROP INDEX CONCURRENTLY causes parse failure, ALL statements are skipped"
+            statements,
+            vec![
+                "ALTER TABLE users ADD COLUMN bio TEXT",
+                "ALTER TABLE users DROP COLUMN legacy",
+                "ALTER TABLE users ADD COLUMN nickname TEXT",
+            ]
         );
+        assert_eq!(result.statements[1].line, 8);
     }
 
     #[test]
-    fn test_primary_key_using_index_returns_empty_statements() {
+    fn test_parses_function_and_procedure_bodies() {
         let parser = SqlParser::new();
-        let sql = "ALTER TABLE users ADD CONSTRAINT users_pkey PRIMARY KEY USING INDEX users_pkey;";
+        let sql = "CREATE PROCEDURE add_bio()
+LANGUAGE plpgsql
+AS $$
+BEGIN
+  ALTER TABLE users ADD COLUMN bio TEXT;
+END
+$$;
+CREATE FUNCTION broken() RETURNS void AS $$ NOT SQL; DELETE FROM sessions $$ LANGUAGE sql;
+ALTER TABLE users ADD COLUMN nickname TEXT;";
 
-        // This should succeed (not error) but return empty statements
-        // because sqlparser can't parse PRIMARY KEY USING INDEX
         let result = parser.parse_with_metadata(sql).unwrap();
+        assert_eq!(result.raw_statements.len(), 1);
+        assert_eq!(result.raw_statements[0].keyword, "CREATE");
+        assert!(result.unparsed_statements.is_empty());
+        assert_eq!(result.statements.len(), 2);
+
+        // The unparseable statement of the SQL body is skipped with a warning
+        let statements: Vec<(String, usize)> = result
+            .function_statements
+            .iter()
+            .map(|stmt| (stmt.stmt.to_string(), stmt.line))
+            .collect();
         assert_eq!(
-            result.statements.len(),
-            0,
-            "PRIMARY KEY USING INDEX should return empty statements"
+            statements,
+            vec![
+                ("ALTER TABLE users ADD COLUMN bio TEXT".to_string(), 5),
+                ("DELETE FROM sessions".to_string(), 8),
+            ]
         );
     }
 
     #[test]
-    fn test_primary_key_using_index_skips_all_statements() {
+    fn test_skips_copy_data() {
+        let parser = SqlParser::new();
+        let sql = "COPY countries FROM stdin;\nUS\tUnited States; of America\n\\.\nALTER TABLE users DROP COLUMN email;";
+
+        let result = parser.parse_with_metadata(sql).unwrap();
+        assert_eq!(result.raw_statements.len(), 1);
+        assert_eq!(result.raw_statements[0].sql, "COPY countries FROM stdin");
+        assert!(result.unparsed_statements.is_empty());
+        assert_eq!(result.statements.len(), 1);
+        assert_eq!(result.statements[0].line, 4);
+        assert_eq!(result.sql, sql);
+    }
+
+    #[test]
+    fn test_unparseable_file_is_an_error() {
+        let parser = SqlParser::new();
+        assert!(parser.parse_with_metadata("INVALID SQL HERE;").is_err());
+    }
+
+    #[test]
+    fn test_primary_key_using_index_keeps_other_statements() {
         let parser = SqlParser::new();
         // This file has both PRIMARY KEY USING INDEX (safe) and DROP COLUMN (unsafe)
         let sql = r#"
@@ -251,13 +587,8 @@ ALTER TABLE users ADD CONSTRAINT users_pkey PRIMARY KEY USING INDEX users_pkey;
 ALTER TABLE users DROP COLUMN old_field;
         "#;
 
-        // Due to parser limitation, ALL statements are skipped (returns empty)
-        // This test documents the limitation - the unsafe DROP COLUMN is NOT detected
         let result = parser.parse_with_metadata(sql).unwrap();
-        assert_eq!(
-            result.statements.len(),
-            0,
-            "When PRIMARY KEY USING INDEX causes parse failure, ALL statements are skipped"
-        );
+        assert_eq!(result.statements.len(), 1);
+        assert_eq!(result.raw_statements.len(), 1);
     }
 }
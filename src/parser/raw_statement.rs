@@ -0,0 +1,452 @@
+//! Extraction of statements sqlparser can't parse.
+//!
+//! PostgreSQL maintenance statements such as `CLUSTER`, and `DO` blocks, aren't supported by
+//! sqlparser, and a single unparseable statement fails the whole file. Statements
+//! starting with one of [`RAW_KEYWORDS`], or matching one of [`RAW_PATTERNS`], are
+//! cut out of the SQL before parsing and handed to checks as source text instead.
+//! Statements matching one of [`FALLBACK_PATTERNS`] are only handed over as source
+//! text when sqlparser fails on them.
+
+use crate::schema::name_key;
+use regex::Regex;
+use std::sync::LazyLock;
+
+/// First keywords of statements that are checked as source text
+const RAW_KEYWORDS: &[&str] = &["CLUSTER", "DO", "REFRESH", "REINDEX"];
+
+/// `COPY ... FROM STDIN`, whose data follows the statement
+const COPY_FROM_STDIN: &str = r"(?is)^COPY\s.*\bFROM\s+STDIN\b";
+
+static COPY_FROM_STDIN_PATTERN: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(COPY_FROM_STDIN).expect("valid regex pattern"));
+
+/// Unsupported forms of statements sqlparser otherwise parses
+static RAW_PATTERNS: LazyLock<Vec<Regex>> = LazyLock::new(|| {
+    [
+        r"(?is)^ALTER\s+(?:TABLE|INDEX|MATERIALIZED\s+VIEW)\s.*\bSET\s+TABLESPACE\b",
+        r"(?is)^ALTER\s+TABLE\s.*\bADD\s+(?:CONSTRAINT\s+\S+\s+)?EXCLUDE\b",
+        r"(?is)^ALTER\s+TABLE\s.*\bSET\s+(?:UN)?LOGGED\b",
+        r"(?is)^ALTER\s+TABLE\s.*\bFORCE\s+ROW\s+LEVEL\s+SECURITY\b",
+        r"(?is)^DROP\s+TABLESPACE\b",
+        r"(?is)^CREATE\s+UNLOGGED\s+TABLE\b",
+        r"(?is)^CREATE\s+MATERIALIZED\s+VIEW\s.*\bWITH\s+(?:NO\s+)?DATA\s*;?\s*$",
+        r"(?is)^CREATE\s+TABLE\s.*\bPARTITION\s+OF\b",
+        r"(?is)^ALTER\s+TABLE\s.*\b(?:ATTACH|DETACH)\s+PARTITION\b",
+        r"(?is)^CREATE\s+(?:UNIQUE\s+)?INDEX\s.*\bON\s+ONLY\b",
+        r"(?is)^ALTER\s+INDEX\s.*\bATTACH\s+PARTITION\b",
+        r"(?is)^ALTER\s+TABLE\s.*\bADD\s+CONSTRAINT\s+\S+\s+(?:UNIQUE|PRIMARY\s+KEY)\s+USING\s+INDEX\b",
+        r"(?is)^CREATE\s+(?:OR\s+REPLACE\s+)?PROCEDURE\b",
+        COPY_FROM_STDIN,
+    ]
+    .iter()
+    .map(|pattern| Regex::new(pattern).expect("valid regex pattern"))
+    .collect()
+});
+
+/// Statements sqlparser parses in their common forms but not in every form PostgreSQL
+/// accepts
+static FALLBACK_PATTERNS: LazyLock<Vec<Regex>> = LazyLock::new(|| {
+    [
+        r"(?is)^(?:CREATE|ALTER|DROP)\s+(?:ROLE|USER|GROUP)\b",
+        r"(?is)^(?:GRANT|REVOKE)\b",
+        r"(?is)^DROP\s+DATABASE\b",
+    ]
+    .iter()
+    .map(|pattern| Regex::new(pattern).expect("valid regex pattern"))
+    .collect()
+});
+
+/// `ALTER TABLE ... ADD CONSTRAINT ... { UNIQUE | PRIMARY KEY } USING INDEX ...`
+static USING_INDEX_PATTERN: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?is)^ALTER\s+TABLE\s+(?:IF\s+EXISTS\s+)?(?:ONLY\s+)?(\S+)\s+ADD\s+CONSTRAINT\s+(\S+)\s+(UNIQUE|PRIMARY\s+KEY)\s+USING\s+INDEX\s+([^\s;]+)\s*$",
+    )
+    .expect("valid regex pattern")
+});
+
+/// A constraint attached to an existing unique index
+///
+/// Names are normalized like the keys of [`Schema`](crate::schema::Schema): unquoted
+/// and lowercased.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UsingIndexConstraint {
+    pub table: String,
+    pub constraint: String,
+    pub index: String,
+    /// PRIMARY KEY rather than UNIQUE
+    pub primary_key: bool,
+}
+
+/// A statement kept as source text because sqlparser can't parse it
+#[derive(Debug, Clone, PartialEq)]
+pub struct RawStatement {
+    /// First keyword, uppercased (e.g. `CLUSTER`)
+    pub keyword: String,
+    /// Statement text without the trailing semicolon
+    pub sql: String,
+    /// 1-indexed line the statement starts on
+    pub line: usize,
+}
+
+impl RawStatement {
+    /// Statement starting on the given line, from its source text
+    pub(crate) fn from_source(text: &str, line: usize) -> Self {
+        Self {
+            keyword: text
+                .chars()
+                .take_while(|c| c.is_ascii_alphabetic())
+                .collect::<String>()
+                .to_uppercase(),
+            sql: text.trim_end_matches(';').trim_end().to_string(),
+            line,
+        }
+    }
+
+    /// The constraint this statement attaches to an existing index, if it's an
+    /// `ADD CONSTRAINT ... USING INDEX`
+    pub fn using_index_constraint(&self) -> Option<UsingIndexConstraint> {
+        let captures = USING_INDEX_PATTERN.captures(&self.sql)?;
+        let name = |idx: usize| name_key(&captures[idx]);
+        let unqualified = |idx: usize| {
+            let name = name(idx);
+            name.rsplit('.').next().unwrap_or(&name).to_string()
+        };
+
+        Some(UsingIndexConstraint {
+            table: name(1),
+            constraint: unqualified(2),
+            index: unqualified(4),
+            primary_key: !captures[3].eq_ignore_ascii_case("UNIQUE"),
+        })
+    }
+}
+
+/// Split raw statements out of SQL
+///
+/// Returns the SQL with raw statements blanked out (newlines are kept, so line
+/// numbers of the remaining statements don't change) and the raw statements.
+pub fn extract_raw_statements(sql: &str) -> (String, Vec<RawStatement>) {
+    let mut remaining = sql.to_string();
+    let mut raw_statements = vec![];
+
+    for (start, end) in statement_spans(sql) {
+        let text = &sql[start..end];
+        let raw = RawStatement::from_source(text, sql[..start].matches('\n').count() + 1);
+
+        if !RAW_KEYWORDS.contains(&raw.keyword.as_str())
+            && !RAW_PATTERNS.iter().any(|pattern| pattern.is_match(text))
+        {
+            continue;
+        }
+
+        raw_statements.push(raw);
+
+        // Blank byte for byte so the spans of later statements stay valid
+        remaining.replace_range(start..end, &blank(text));
+    }
+
+    (remaining, raw_statements)
+}
+
+/// Whether a statement sqlparser fails on is checked as source text
+pub fn is_fallback_statement(text: &str) -> bool {
+    FALLBACK_PATTERNS
+        .iter()
+        .any(|pattern| pattern.is_match(text))
+}
+
+/// Blank the data of `COPY ... FROM STDIN` statements
+///
+/// The data runs from the line after the statement through the `\.` line ending it.
+/// Its rows aren't SQL, and their quotes and semicolons would throw off where
+/// statements start and end. Byte offsets and line numbers are kept.
+pub fn blank_copy_data(sql: &str) -> String {
+    let mut sql = sql.to_string();
+    let mut searched = 0;
+
+    // Spans after a COPY are only valid once its data is blanked, so look again each time
+    while let Some((_, end)) = statement_spans(&sql).into_iter().find(|&(start, end)| {
+        start >= searched && COPY_FROM_STDIN_PATTERN.is_match(&sql[start..end])
+    }) {
+        let data_start = sql[end..].find('\n').map_or(sql.len(), |n| end + n + 1);
+        let mut data_end = sql.len();
+        let mut line_start = data_start;
+        for line in sql[data_start..].split_inclusive('\n') {
+            if line.trim_end() == "\\." {
+                data_end = line_start + line.trim_end().len();
+                break;
+            }
+            line_start += line.len();
+        }
+
+        let blanked = blank(&sql[data_start..data_end]);
+        sql.replace_range(data_start..data_end, &blanked);
+        searched = data_end.max(end);
+    }
+
+    sql
+}
+
+/// Text of the same length in bytes and lines, with everything but newlines blanked
+pub(crate) fn blank(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            '\n' => "\n".to_string(),
+            c => " ".repeat(c.len_utf8()),
+        })
+        .collect()
+}
+
+/// Byte spans of statements, each from its first keyword through its semicolon
+///
+/// Quotes, dollar-quoted strings, and comments are skipped so semicolons inside
+/// them don't end a statement. Leading comments aren't part of the span.
+pub(crate) fn statement_spans(sql: &str) -> Vec<(usize, usize)> {
+    let bytes = sql.as_bytes();
+    let mut spans = vec![];
+    let mut start: Option<usize> = None;
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let rest = &sql[i..];
+        let skip_to = if rest.starts_with("--") {
+            rest.find('\n').map_or(bytes.len(), |n| i + n)
+        } else if rest.starts_with("/*") {
+            rest.find("*/").map_or(bytes.len(), |n| i + n + 2)
+        } else if bytes[i] == b'\'' || bytes[i] == b'"' {
+            let quote = bytes[i] as char;
+            rest[1..].find(quote).map_or(bytes.len(), |n| i + n + 2)
+        } else if let Some(tag) = dollar_quote_tag(rest) {
+            rest[tag.len()..]
+                .find(tag)
+                .map_or(bytes.len(), |n| i + tag.len() + n + tag.len())
+        } else {
+            0
+        };
+
+        if skip_to > 0 {
+            // Comments don't start a statement, but quoted text does
+            if start.is_none() && !rest.starts_with("--") && !rest.starts_with("/*") {
+                start = Some(i);
+            }
+            i = skip_to;
+            continue;
+        }
+
+        if bytes[i] == b';' {
+            if let Some(s) = start.take() {
+                spans.push((s, i + 1));
+            }
+        } else if start.is_none() && !bytes[i].is_ascii_whitespace() {
+            start = Some(i);
+        }
+        i += 1;
+    }
+
+    if let Some(s) = start {
+        spans.push((s, sql.trim_end().len().max(s)));
+    }
+
+    spans
+}
+
+/// Opening tag of a dollar-quoted string (`$$` or `$tag$`) at the start of `s`
+pub(crate) fn dollar_quote_tag(s: &str) -> Option<&str> {
+    let body = s.strip_prefix('$')?;
+    let end = body.find('$')?;
+    let tag = &body[..end];
+    if tag.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        Some(&s[..end + 2])
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extracts_cluster() {
+        let sql = "ALTER TABLE users ADD COLUMN bio TEXT;\nCLUSTER users USING users_pkey;\nDROP TABLE old;";
+        let (remaining, raw) = extract_raw_statements(sql);
+
+        assert_eq!(raw.len(), 1);
+        assert_eq!(raw[0].keyword, "CLUSTER");
+        assert_eq!(raw[0].sql, "CLUSTER users USING users_pkey");
+        assert_eq!(raw[0].line, 2);
+        assert_eq!(remaining.lines().count(), 3);
+        assert!(!remaining.contains("CLUSTER"));
+        assert!(remaining.contains("DROP TABLE old;"));
+    }
+
+    #[test]
+    fn test_keeps_line_numbers_of_multiline_statements() {
+        let sql = "-- comment\ncluster\n  users;\nSELECT 1;";
+        let (remaining, raw) = extract_raw_statements(sql);
+
+        assert_eq!(raw[0].line, 2);
+        assert_eq!(raw[0].keyword, "CLUSTER");
+        assert_eq!(remaining.lines().nth(3), Some("SELECT 1;"));
+        assert_eq!(remaining.len(), sql.len());
+    }
+
+    #[test]
+    fn test_ignores_keywords_in_comments_and_strings() {
+        let sql =
+            "-- CLUSTER users;\nINSERT INTO notes VALUES ('a; CLUSTER users;');\n/* CLUSTER x; */";
+        let (remaining, raw) = extract_raw_statements(sql);
+
+        assert!(raw.is_empty());
+        assert_eq!(remaining, sql);
+    }
+
+    #[test]
+    fn test_skips_dollar_quoted_bodies() {
+        let sql = "CREATE FUNCTION f() RETURNS void AS $$ BEGIN PERFORM 1; END $$ LANGUAGE plpgsql;\nCLUSTER users;";
+        let (_, raw) = extract_raw_statements(sql);
+
+        assert_eq!(raw.len(), 1);
+        assert_eq!(raw[0].line, 2);
+    }
+
+    #[test]
+    fn test_blanks_copy_data() {
+        let sql = "COPY countries (code, name) FROM stdin;\nUS\tO'Neil; \"x\nFR\tFrance\n\\.\nCOPY cities FROM STDIN WITH (FORMAT csv);\nParis,FR\n\\.\nCLUSTER users;";
+        let blanked = blank_copy_data(sql);
+
+        assert_eq!(blanked.len(), sql.len());
+        assert_eq!(
+            blanked.lines().collect::<Vec<_>>(),
+            vec![
+                "COPY countries (code, name) FROM stdin;",
+                "             ",
+                "         ",
+                "  ",
+                "COPY cities FROM STDIN WITH (FORMAT csv);",
+                "        ",
+                "  ",
+                "CLUSTER users;",
+            ]
+        );
+
+        let (_, raw) = extract_raw_statements(&blanked);
+        let keywords: Vec<(&str, usize)> =
+            raw.iter().map(|r| (r.keyword.as_str(), r.line)).collect();
+        assert_eq!(keywords, vec![("COPY", 1), ("COPY", 5), ("CLUSTER", 8)]);
+    }
+
+    #[test]
+    fn test_blanks_copy_data_without_terminator() {
+        let sql = "SELECT 1;\nCOPY countries FROM stdin;\nUS\tUnited States\n";
+        assert_eq!(
+            blank_copy_data(sql),
+            "SELECT 1;\nCOPY countries FROM stdin;\n                \n"
+        );
+
+        // COPY from a file has no data in the migration
+        let sql = "COPY countries FROM '/tmp/countries.tsv';\nSELECT 1;";
+        assert_eq!(blank_copy_data(sql), sql);
+    }
+
+    #[test]
+    fn test_extracts_unsupported_forms_of_parsed_statements() {
+        let sql = "ALTER TABLE users SET TABLESPACE fast;\nALTER TABLE users ADD COLUMN bio TEXT;";
+        let (remaining, raw) = extract_raw_statements(sql);
+
+        assert_eq!(raw.len(), 1);
+        assert_eq!(raw[0].keyword, "ALTER");
+        assert_eq!(raw[0].sql, "ALTER TABLE users SET TABLESPACE fast");
+        assert!(remaining.contains("ADD COLUMN bio"));
+
+        let (_, raw) = extract_raw_statements(
+            "ALTER TABLE bookings ADD CONSTRAINT no_overlap EXCLUDE USING gist (room WITH =);",
+        );
+        assert_eq!(raw.len(), 1);
+
+        let (_, raw) = extract_raw_statements("ALTER TABLE events SET UNLOGGED;");
+        assert_eq!(raw.len(), 1);
+
+        let (_, raw) = extract_raw_statements("ALTER TABLE documents FORCE ROW LEVEL SECURITY;");
+        assert_eq!(raw.len(), 1);
+
+        let (_, raw) = extract_raw_statements("DROP TABLESPACE fast;\nDROP TABLE users;");
+        assert_eq!(raw.len(), 1);
+        assert_eq!(raw[0].keyword, "DROP");
+
+        let (_, raw) = extract_raw_statements("CREATE UNLOGGED TABLE page_views (id BIGINT);");
+        assert_eq!(raw.len(), 1);
+
+        let (_, raw) = extract_raw_statements(
+            "CREATE MATERIALIZED VIEW totals AS SELECT * FROM orders WITH NO DATA;",
+        );
+        assert_eq!(raw.len(), 1);
+        assert_eq!(raw[0].keyword, "CREATE");
+
+        let (remaining, raw) = extract_raw_statements(
+            "CREATE TABLE events_2024 PARTITION OF events FOR VALUES FROM ('2024-01-01') TO ('2025-01-01');
+ALTER TABLE events DETACH PARTITION events_2023 CONCURRENTLY;
+CREATE INDEX events_created_at_idx ON ONLY events (created_at);
+ALTER INDEX events_created_at_idx ATTACH PARTITION events_2024_created_at_idx;
+CREATE TABLE events_archive (id BIGINT) PARTITION BY RANGE (id);",
+        );
+        assert_eq!(raw.len(), 4);
+        assert!(remaining.contains("PARTITION BY RANGE"));
+    }
+
+    #[test]
+    fn test_fallback_statements() {
+        assert!(is_fallback_statement(
+            "ALTER USER app WITH PASSWORD 'secret'"
+        ));
+        assert!(is_fallback_statement("create group admins"));
+        assert!(is_fallback_statement("GRANT reporting TO alice"));
+        assert!(is_fallback_statement("REVOKE reporting FROM alice"));
+        assert!(is_fallback_statement("DROP DATABASE app WITH (FORCE)"));
+        assert!(!is_fallback_statement("CREATE TABLE roles (id BIGINT)"));
+
+        // Left to sqlparser first
+        let (_, raw) = extract_raw_statements(
+            "CREATE ROLE reporting NOLOGIN;\nGRANT SELECT ON users TO reporting;\nDROP DATABASE app;",
+        );
+        assert!(raw.is_empty());
+    }
+
+    #[test]
+    fn test_extracts_constraints_using_index() {
+        let sql = "ALTER TABLE users ADD CONSTRAINT users_email_key UNIQUE USING INDEX users_email_idx;
+ALTER TABLE ONLY public.\"Accounts\" ADD CONSTRAINT accounts_pkey PRIMARY KEY USING INDEX accounts_id_idx;
+ALTER TABLE users DROP COLUMN old_field;";
+        let (remaining, raw) = extract_raw_statements(sql);
+
+        assert_eq!(raw.len(), 2);
+        assert!(remaining.contains("DROP COLUMN old_field"));
+        assert_eq!(
+            raw[0].using_index_constraint(),
+            Some(UsingIndexConstraint {
+                table: "users".to_string(),
+                constraint: "users_email_key".to_string(),
+                index: "users_email_idx".to_string(),
+                primary_key: false,
+            })
+        );
+        assert_eq!(
+            raw[1].using_index_constraint(),
+            Some(UsingIndexConstraint {
+                table: "public.Accounts".to_string(),
+                constraint: "accounts_pkey".to_string(),
+                index: "accounts_id_idx".to_string(),
+                primary_key: true,
+            })
+        );
+
+        let (_, raw) = extract_raw_statements("CLUSTER users USING users_pkey;");
+        assert_eq!(raw[0].using_index_constraint(), None);
+    }
+
+    #[test]
+    fn test_statement_without_semicolon() {
+        let (_, raw) = extract_raw_statements("CLUSTER users\n");
+        assert_eq!(raw[0].sql, "CLUSTER users");
+    }
+}
@@ -0,0 +1,177 @@
+//! Ratchet mode: fail only when violation counts grow.
+//!
+//! A state file remembers the per-check violation counts of the last successful
+//! run. A run fails if any count increased; otherwise the stored counts are
+//! replaced by the current (equal or lower) ones, so they can only go down.
+
+use crate::error::{DieselGuardError, Result};
+use crate::violation::Violation;
+use camino::Utf8Path;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+
+/// Per-check violation counts stored between runs
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RatchetState {
+    pub counts: BTreeMap<String, usize>,
+}
+
+/// Change in the violation count of a single check
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CountChange {
+    pub check: String,
+    pub previous: usize,
+    pub current: usize,
+}
+
+/// Result of comparing a run against the stored state
+#[derive(Debug, Clone, Default)]
+pub struct RatchetOutcome {
+    /// True if no state file existed and it was created from this run
+    pub initialized: bool,
+    /// Checks whose count increased (the run fails if non-empty)
+    pub regressions: Vec<CountChange>,
+    /// Checks whose count decreased (stored counts were tightened)
+    pub improvements: Vec<CountChange>,
+}
+
+impl RatchetOutcome {
+    pub fn passed(&self) -> bool {
+        self.regressions.is_empty()
+    }
+}
+
+impl RatchetState {
+    /// Count violations per check
+    pub fn from_results(results: &[(String, Vec<Violation>)]) -> Self {
+        let mut counts = BTreeMap::new();
+        for violation in results.iter().flat_map(|(_, violations)| violations) {
+            *counts.entry(violation.check.clone()).or_insert(0) += 1;
+        }
+        Self { counts }
+    }
+
+    /// Load state from a file, returning `None` if it doesn't exist yet
+    pub fn load(path: &Utf8Path) -> Result<Option<Self>> {
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let content = fs::read_to_string(path)?;
+        serde_json::from_str(&content)
+            .map(Some)
+            .map_err(|e| DieselGuardError::StateError {
+                path: path.to_string(),
+                msg: e.to_string(),
+            })
+    }
+
+    /// Write state to a file
+    pub fn save(&self, path: &Utf8Path) -> Result<()> {
+        let content = serde_json::to_string_pretty(self).unwrap_or_else(|_| "{}".into());
+        fs::write(path, format!("{}\n", content))?;
+        Ok(())
+    }
+
+    /// Compare current counts against this (previous) state
+    pub fn compare(&self, current: &Self) -> RatchetOutcome {
+        let checks: BTreeSet<&String> = self.counts.keys().chain(current.counts.keys()).collect();
+        let mut outcome = RatchetOutcome::default();
+
+        for check in checks {
+            let change = CountChange {
+                check: check.clone(),
+                previous: self.counts.get(check).copied().unwrap_or(0),
+                current: current.counts.get(check).copied().unwrap_or(0),
+            };
+
+            if change.current > change.previous {
+                outcome.regressions.push(change);
+            } else if change.current < change.previous {
+                outcome.improvements.push(change);
+            }
+        }
+
+        outcome
+    }
+}
+
+/// Apply ratchet mode for a run: compare against the state file and update it on success
+pub fn apply(path: &Utf8Path, results: &[(String, Vec<Violation>)]) -> Result<RatchetOutcome> {
+    let current = RatchetState::from_results(results);
+
+    let outcome = match RatchetState::load(path)? {
+        Some(previous) => previous.compare(&current),
+        None => RatchetOutcome {
+            initialized: true,
+            ..Default::default()
+        },
+    };
+
+    if outcome.passed() {
+        current.save(path)?;
+    }
+
+    Ok(outcome)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state(counts: &[(&str, usize)]) -> RatchetState {
+        RatchetState {
+            counts: counts
+                .iter()
+                .map(|(check, count)| (check.to_string(), *count))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_from_results() {
+        let mut drop_column = Violation::new("DROP COLUMN", "", "");
+        drop_column.check = "DropColumnCheck".to_string();
+        let results = vec![
+            ("a/up.sql".to_string(), vec![drop_column.clone()]),
+            ("b/up.sql".to_string(), vec![drop_column]),
+        ];
+
+        assert_eq!(
+            RatchetState::from_results(&results),
+            state(&[("DropColumnCheck", 2)])
+        );
+    }
+
+    #[test]
+    fn test_compare_detects_regression() {
+        let previous = state(&[("DropColumnCheck", 2)]);
+        let current = state(&[("DropColumnCheck", 3)]);
+
+        let outcome = previous.compare(&current);
+        assert!(!outcome.passed());
+        assert_eq!(outcome.regressions[0].previous, 2);
+        assert_eq!(outcome.regressions[0].current, 3);
+    }
+
+    #[test]
+    fn test_compare_new_check_is_regression() {
+        let previous = state(&[("DropColumnCheck", 2)]);
+        let current = state(&[("DropColumnCheck", 2), ("AddIndexCheck", 1)]);
+
+        let outcome = previous.compare(&current);
+        assert_eq!(outcome.regressions.len(), 1);
+        assert_eq!(outcome.regressions[0].check, "AddIndexCheck");
+    }
+
+    #[test]
+    fn test_compare_detects_improvement() {
+        let previous = state(&[("DropColumnCheck", 2), ("AddIndexCheck", 1)]);
+        let current = state(&[("DropColumnCheck", 1)]);
+
+        let outcome = previous.compare(&current);
+        assert!(outcome.passed());
+        assert_eq!(outcome.improvements.len(), 2);
+    }
+}
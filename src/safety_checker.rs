@@ -1,16 +1,52 @@
 use crate::checks::Registry;
 use crate::config::Config;
+use crate::database::{migration_version, DatabasePrimaryKeys};
 use crate::error::Result;
+use crate::fix::{
+    ensure_no_transaction_metadata, requires_no_transaction, runs_in_transaction, MetadataFix,
+};
 use crate::parser::SqlParser;
-use crate::violation::Violation;
+use crate::schema::{Schema, SchemaHistory};
+use crate::suppression::{
+    extract_reason, stale_safety_assured_violation, SuppressedViolation, Suppression,
+    SuppressionMechanism, STALE_SAFETY_ASSURED,
+};
+use crate::timings::{millis, FileTiming, Timings};
+use crate::violation::{Severity, Violation};
 use camino::{Utf8Path, Utf8PathBuf};
+use std::collections::{HashMap, HashSet};
 use std::fs;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use walkdir::WalkDir;
 
 pub struct SafetyChecker {
     parser: SqlParser,
     registry: Registry,
     config: Config,
+    /// Per-file timings, present only when timings are enabled
+    file_timings: Option<Mutex<Vec<FileTiming>>>,
+    /// Versions of migrations already applied to the database, which are skipped
+    applied_versions: HashSet<String>,
+    /// Suppressed violations, present only when tracking is enabled
+    suppressed: Option<Mutex<Vec<SuppressedViolation>>>,
+    files_checked: AtomicUsize,
+    /// Files skipped because of start_after or already-applied migrations
+    files_skipped: AtomicUsize,
+    /// Primary keys of the live database, used to identify dropped primary keys
+    database_primary_keys: Option<Arc<DatabasePrimaryKeys>>,
+    /// Schema history of each migrations directory seen, built on first use
+    schemas: Mutex<HashMap<Utf8PathBuf, Arc<SchemaHistory>>>,
+}
+
+/// Outcome of checking one SQL string
+struct CheckedSql {
+    violations: Vec<Violation>,
+    /// Violations inside safety-assured blocks, with the block's reason
+    assured: Vec<(Option<String>, Violation)>,
+    parse_time: Duration,
+    check_time: Duration,
 }
 
 impl SafetyChecker {
@@ -30,27 +66,263 @@ impl SafetyChecker {
             parser: SqlParser::new(),
             registry: Registry::with_config(&config),
             config,
+            file_timings: None,
+            applied_versions: HashSet::new(),
+            suppressed: None,
+            files_checked: AtomicUsize::new(0),
+            files_skipped: AtomicUsize::new(0),
+            database_primary_keys: None,
+            schemas: Mutex::new(HashMap::new()),
         }
     }
 
+    /// Skip migration directories whose versions are already applied
+    pub fn with_applied_versions(mut self, applied_versions: HashSet<String>) -> Self {
+        self.applied_versions = applied_versions;
+        self
+    }
+
+    /// Identify dropped primary keys from the live database instead of constraint names
+    pub fn with_database_primary_keys(mut self, primary_keys: DatabasePrimaryKeys) -> Self {
+        self.database_primary_keys = Some(Arc::new(primary_keys));
+        self
+    }
+
+    /// Record parse and check timings for every file checked from now on
+    pub fn with_timings(mut self) -> Self {
+        self.registry.enable_timings();
+        self.file_timings = Some(Mutex::new(vec![]));
+        self
+    }
+
+    /// Timings collected so far, if enabled with [`SafetyChecker::with_timings`]
+    pub fn timings(&self) -> Option<Timings> {
+        let file_timings = self.file_timings.as_ref()?;
+        let files = file_timings
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone();
+
+        Some(Timings {
+            total_ms: files.iter().map(|file| file.total_ms).sum(),
+            parse_ms: files.iter().map(|file| file.parse_ms).sum(),
+            checks: self.registry.check_timings(),
+            files,
+        })
+    }
+
     /// Check SQL string for violations
     pub fn check_sql(&self, sql: &str) -> Result<Vec<Violation>> {
-        let parsed = self.parser.parse_with_metadata(sql)?;
+        self.check_sql_detailed(sql, None, Arc::default())
+            .map(|checked| checked.violations)
+    }
+
+    /// Whether the migration a file belongs to runs inside a transaction
+    ///
+    /// Known only for up.sql/down.sql, whose directory can hold a metadata.toml.
+    fn file_runs_in_transaction(path: &Utf8Path) -> Option<bool> {
+        if !matches!(path.file_name(), Some("up.sql") | Some("down.sql")) {
+            return None;
+        }
+        path.parent().map(runs_in_transaction)
+    }
 
-        let violations = self.registry.check_statements_with_context(
-            &parsed.statements,
-            &parsed.sql,
-            &parsed.ignore_ranges,
+    /// Schema declared by the migrations before the one a file belongs to
+    ///
+    /// Known only for up.sql/down.sql, whose migration directory sits in a migrations
+    /// directory; the other migrations there are replayed once and cached.
+    fn file_schema(&self, path: &Utf8Path) -> Arc<Schema> {
+        if !matches!(path.file_name(), Some("up.sql") | Some("down.sql")) {
+            return Arc::default();
+        }
+        let Some(migration_dir) = path.parent() else {
+            return Arc::default();
+        };
+        let (Some(migration), Some(migrations_dir)) =
+            (migration_dir.file_name(), migration_dir.parent())
+        else {
+            return Arc::default();
+        };
+
+        let history = Arc::clone(
+            self.schemas
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .entry(migrations_dir.to_owned())
+                .or_insert_with(|| Arc::new(SchemaHistory::load(migrations_dir))),
         );
+        history.before(migration)
+    }
+
+    /// Check SQL string, keeping suppressed violations and durations
+    fn check_sql_detailed(
+        &self,
+        sql: &str,
+        run_in_transaction: Option<bool>,
+        schema: Arc<Schema>,
+    ) -> Result<CheckedSql> {
+        let started = Instant::now();
+        let mut parsed = self.parser.parse_with_metadata(sql)?;
+        parsed.run_in_transaction = run_in_transaction;
+        parsed.schema = schema;
+        parsed.database_primary_keys = self.database_primary_keys.clone();
+        let parse_time = started.elapsed();
+
+        let started = Instant::now();
+        let mut checked = CheckedSql {
+            violations: vec![],
+            assured: vec![],
+            parse_time,
+            check_time: Duration::ZERO,
+        };
+        let mut used_ranges = HashSet::new();
+        for (line, mut violation) in self.registry.check_parsed(&parsed) {
+            match parsed
+                .ignore_ranges
+                .iter()
+                .position(|range| range.suppresses(line, &violation.check))
+            {
+                Some(idx) => {
+                    let range = &parsed.ignore_ranges[idx];
+                    used_ranges.insert(idx);
+                    // Findings merged into this one are only assured if the block covers them too
+                    checked.violations.extend(
+                        violation.unmerge_unsuppressed(|check| range.suppresses(line, check)),
+                    );
+                    let reason = extract_reason(&parsed.sql, range);
+                    checked.assured.push((reason, violation));
+                }
+                None => checked.violations.push(violation),
+            }
+        }
 
-        Ok(violations)
+        // Blocks that suppress nothing are dead annotations. Skip files without
+        // statements, and blocks around statements that couldn't be parsed, as what
+        // they'd report is unknown.
+        if self.config.is_check_enabled(STALE_SAFETY_ASSURED)
+            && !(parsed.statements.is_empty() && parsed.raw_statements.is_empty())
+        {
+            checked.violations.extend(
+                parsed
+                    .ignore_ranges
+                    .iter()
+                    .enumerate()
+                    .filter(|(idx, _)| !used_ranges.contains(idx))
+                    .filter(|(_, range)| {
+                        !parsed
+                            .unparsed_statements
+                            .iter()
+                            .any(|stmt| range.contains_line(stmt.line))
+                    })
+                    .map(|(_, range)| {
+                        let mut violation = stale_safety_assured_violation(range);
+                        violation.severity = self
+                            .config
+                            .severity_for(STALE_SAFETY_ASSURED, Severity::Warning);
+                        violation
+                    }),
+            );
+        }
+        checked.check_time = started.elapsed();
+
+        Ok(checked)
+    }
+
+    /// Number of files checked so far
+    pub fn files_checked(&self) -> usize {
+        self.files_checked.load(Ordering::Relaxed)
+    }
+
+    /// Number of files skipped so far because of start_after or already-applied migrations
+    pub fn files_skipped(&self) -> usize {
+        self.files_skipped.load(Ordering::Relaxed)
     }
 
     /// Check a single migration file
     pub fn check_file(&self, path: &Utf8Path) -> Result<Vec<Violation>> {
+        self.files_checked.fetch_add(1, Ordering::Relaxed);
+        let started = Instant::now();
         let sql = fs::read_to_string(path)?;
-        self.check_sql(&sql)
-            .map_err(|e| e.with_file_context(path.as_str(), sql.clone()))
+        let checked = self
+            .check_sql_detailed(
+                &sql,
+                Self::file_runs_in_transaction(path),
+                self.file_schema(path),
+            )
+            .map_err(|e| e.with_file_context(path.as_str(), sql.clone()))?;
+
+        if let Some(file_timings) = &self.file_timings {
+            file_timings
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .push(FileTiming {
+                    file: path.to_string(),
+                    parse_ms: millis(checked.parse_time),
+                    check_ms: millis(checked.check_time),
+                    total_ms: millis(started.elapsed()),
+                });
+        }
+
+        for (reason, violation) in checked.assured {
+            self.record_suppressed(path, SuppressionMechanism::SafetyAssured, reason, violation);
+        }
+
+        Ok(checked.violations)
+    }
+
+    /// Track suppressed violations from now on, for [`SafetyChecker::suppressed`]
+    pub fn with_suppressed_tracking(mut self) -> Self {
+        self.suppressed = Some(Mutex::new(vec![]));
+        self
+    }
+
+    /// Violations suppressed so far, if tracking is enabled
+    pub fn suppressed(&self) -> Option<Vec<SuppressedViolation>> {
+        let suppressed = self.suppressed.as_ref()?;
+        Some(suppressed.lock().unwrap_or_else(|e| e.into_inner()).clone())
+    }
+
+    fn record_suppressed(
+        &self,
+        path: &Utf8Path,
+        mechanism: SuppressionMechanism,
+        reason: Option<String>,
+        violation: Violation,
+    ) {
+        if let Some(suppressed) = &self.suppressed {
+            suppressed
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .push(SuppressedViolation {
+                    file: path.to_string(),
+                    mechanism,
+                    reason,
+                    violation,
+                });
+        }
+    }
+
+    /// Record violations of a skipped migration file when tracking suppressions
+    ///
+    /// Skipped migrations are often old and may not parse; errors are ignored.
+    fn record_skipped_file(&self, path: &Utf8Path, mechanism: SuppressionMechanism, reason: &str) {
+        if self.suppressed.is_none() {
+            return;
+        }
+        let Ok(sql) = fs::read_to_string(path) else {
+            return;
+        };
+        let Ok(checked) = self.check_sql_detailed(
+            &sql,
+            Self::file_runs_in_transaction(path),
+            self.file_schema(path),
+        ) else {
+            return;
+        };
+
+        for violation in checked.violations {
+            self.record_suppressed(path, mechanism, Some(reason.to_string()), violation);
+        }
     }
 
     /// Check all migration files in a directory
@@ -109,11 +381,6 @@ impl SafetyChecker {
             None => return vec![],
         };
 
-        // Skip if migration is before start_after threshold
-        if !self.config.should_check_migration(dir_name) {
-            return vec![];
-        }
-
         let mut files = vec![];
 
         // Always check up.sql if it exists
@@ -130,9 +397,102 @@ impl SafetyChecker {
             }
         }
 
+        // Skip if migration is before start_after threshold
+        if !self.config.should_check_migration(dir_name) {
+            let reason = format!(
+                "migration is not after start_after {}",
+                self.config.start_after.as_deref().unwrap_or_default()
+            );
+            for file in &files {
+                self.record_skipped_file(file, SuppressionMechanism::StartAfter, &reason);
+            }
+            self.files_skipped.fetch_add(files.len(), Ordering::Relaxed);
+            return vec![];
+        }
+
+        // Skip if migration has already been applied
+        if self.applied_versions.contains(&migration_version(dir_name)) {
+            for file in &files {
+                self.record_skipped_file(
+                    file,
+                    SuppressionMechanism::AlreadyApplied,
+                    "migration is already applied to the database",
+                );
+            }
+            self.files_skipped.fetch_add(files.len(), Ordering::Relaxed);
+            return vec![];
+        }
+
         files
     }
 
+    /// Migration files that would be checked for a path (file or directory), in order
+    pub fn migration_files(&self, path: &Utf8Path) -> Vec<Utf8PathBuf> {
+        if path.is_dir() {
+            self.collect_files(path)
+        } else {
+            vec![path.to_owned()]
+        }
+    }
+
+    /// Collect all safety-assured blocks in a path (file or directory)
+    pub fn collect_suppressions(&self, path: &Utf8Path) -> Result<Vec<Suppression>> {
+        let mut suppressions = vec![];
+
+        for file_path in &self.migration_files(path) {
+            let sql = fs::read_to_string(file_path)?;
+            let mut parsed = self
+                .parser
+                .parse_with_metadata(&sql)
+                .map_err(|e| e.with_file_context(file_path.as_str(), sql.clone()))?;
+            parsed.run_in_transaction = Self::file_runs_in_transaction(file_path);
+            parsed.schema = self.file_schema(file_path);
+
+            if parsed.ignore_ranges.is_empty() {
+                continue;
+            }
+
+            let violations = self.registry.check_parsed(&parsed);
+
+            suppressions.extend(
+                parsed
+                    .ignore_ranges
+                    .iter()
+                    .map(|range| Suppression::new(file_path, range, &parsed.sql, &violations)),
+            );
+        }
+
+        Ok(suppressions)
+    }
+
+    /// Write `metadata.toml` with `run_in_transaction = false` for migrations that need it
+    ///
+    /// Returns the migration directories that required it and what was done.
+    pub fn fix_metadata(&self, path: &Utf8Path) -> Result<Vec<(Utf8PathBuf, MetadataFix)>> {
+        let mut fixes: Vec<(Utf8PathBuf, MetadataFix)> = vec![];
+
+        for file_path in self.migration_files(path) {
+            // Only up.sql/down.sql belong to a Diesel migration directory
+            if !matches!(file_path.file_name(), Some("up.sql") | Some("down.sql")) {
+                continue;
+            }
+            let Some(migration_dir) = file_path.parent() else {
+                continue;
+            };
+            if fixes.iter().any(|(dir, _)| dir == migration_dir) {
+                continue;
+            }
+
+            let sql = fs::read_to_string(&file_path)?;
+            if requires_no_transaction(&sql) {
+                let fix = ensure_no_transaction_metadata(migration_dir)?;
+                fixes.push((migration_dir.to_owned(), fix));
+            }
+        }
+
+        Ok(fixes)
+    }
+
     /// Check a path (file or directory)
     pub fn check_path(&self, path: &Utf8Path) -> Result<Vec<(String, Vec<Violation>)>> {
         if path.is_dir() {
@@ -174,6 +534,15 @@ mod tests {
         assert_eq!(violations.len(), 1);
     }
 
+    #[test]
+    fn test_checks_statements_around_unparseable_one() {
+        let checker = SafetyChecker::new();
+        let sql = "CREATE STATISTICS FOO BAR;\nALTER TABLE users DROP COLUMN email;";
+        let violations = checker.check_sql(sql).unwrap();
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].operation, "DROP COLUMN");
+    }
+
     #[test]
     fn test_with_disabled_checks() {
         let config = Config {
@@ -187,4 +556,34 @@ mod tests {
         let violations = checker.check_sql(sql).unwrap();
         assert_eq!(violations.len(), 0); // Check is disabled
     }
+
+    #[test]
+    fn test_timings_disabled_by_default() {
+        let checker = SafetyChecker::with_config(Config::default());
+        assert!(checker.timings().is_none());
+    }
+
+    #[test]
+    fn test_skips_applied_migrations() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let dir = Utf8Path::from_path(temp_dir.path()).unwrap();
+        for name in [
+            "2024-01-01-000000_drop_email",
+            "2024-01-02-000000_drop_name",
+        ] {
+            fs::create_dir_all(dir.join(name)).unwrap();
+            fs::write(
+                dir.join(name).join("up.sql"),
+                "ALTER TABLE users DROP COLUMN email;",
+            )
+            .unwrap();
+        }
+
+        let checker = SafetyChecker::with_config(Config::default())
+            .with_applied_versions(HashSet::from(["20240101000000".to_string()]));
+        let results = checker.check_directory(dir).unwrap();
+
+        assert_eq!(results.len(), 1);
+        assert!(results[0].0.contains("2024-01-02-000000_drop_name"));
+    }
 }
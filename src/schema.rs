@@ -0,0 +1,1039 @@
+//! Schema declared by earlier migrations.
+//!
+//! Some checks depend on objects created outside the migration being checked, e.g.
+//! whether an index targets a partitioned table, which constraint is a table's primary
+//! key, which columns an index covers, a column's declared type and nullability or a
+//! domain's underlying type, or whether a column already has a validated
+//! `CHECK (column IS NOT NULL)` constraint. [`SchemaHistory`] replays the
+//! `up.sql` files of a migrations directory in order, and hands each migration the
+//! [`Schema`] left by the migrations before it.
+//!
+//! Only what checks need is tracked. Migrations that don't parse are skipped.
+
+use crate::parser::{RawStatement, SqlParser};
+use camino::Utf8Path;
+use sqlparser::ast::{
+    AlterColumnOperation, AlterIndexOperation, AlterTable, AlterTableOperation, ColumnOption,
+    CreateTable, DataType, Expr, Ident, IndexColumn, ObjectName, ObjectNamePart, ObjectType,
+    RenameTableNameKind, Statement, TableConstraint,
+};
+use sqlparser::dialect::PostgreSqlDialect;
+use sqlparser::parser::Parser;
+use sqlparser::tokenizer::{Token, Tokenizer};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::sync::Arc;
+use walkdir::WalkDir;
+
+/// Objects declared by a sequence of migrations
+#[derive(Debug, Clone, Default)]
+pub struct Schema {
+    /// Tables created by the migrations
+    tables: HashSet<String>,
+    /// Primary key constraint name of each table that has one
+    primary_keys: HashMap<String, String>,
+    /// Tables created with PARTITION BY
+    partitioned_tables: HashSet<String>,
+    /// `CHECK (column IS NOT NULL)` constraints, keyed by (table, constraint)
+    not_null_checks: HashMap<(String, String), NotNullCheck>,
+    /// Declared type of each column, keyed by (table, column)
+    column_types: HashMap<(String, String), DataType>,
+    /// Columns declared NOT NULL or part of a primary key, keyed by (table, column)
+    not_null_columns: HashSet<(String, String)>,
+    /// Underlying type of each domain
+    domains: HashMap<String, DataType>,
+    /// Definition of each named index
+    indexes: HashMap<String, IndexDefinition>,
+}
+
+/// What an index covers and enforces
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IndexDefinition {
+    /// Normalized name of the indexed table
+    pub table: String,
+    /// Normalized key columns or expressions, in order
+    pub columns: Vec<String>,
+    /// Whether the index enforces uniqueness
+    pub unique: bool,
+    /// Access method, `btree` unless given with USING
+    pub method: String,
+    /// WHERE clause of a partial index
+    pub predicate: Option<String>,
+    /// Normalized INCLUDE columns, in order
+    pub include: Vec<String>,
+}
+
+/// A CHECK constraint proving a column has no NULLs once validated
+#[derive(Debug, Clone)]
+struct NotNullCheck {
+    column: String,
+    validated: bool,
+}
+
+/// Identifier for lookups, resolved like PostgreSQL does: unquoted names fold to lower
+/// case, quoted ones keep their case
+pub(crate) fn ident_key(ident: &Ident) -> String {
+    match ident.quote_style {
+        Some(_) => ident.value.clone(),
+        None => ident.value.to_lowercase(),
+    }
+}
+
+/// Object name for lookups, with each part resolved like [`ident_key`]
+pub(crate) fn object_key(name: &ObjectName) -> String {
+    name.0
+        .iter()
+        .map(|part| match part {
+            ObjectNamePart::Identifier(ident) => ident_key(ident),
+            ObjectNamePart::Function(_) => part.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+/// Object name given as SQL text, such as a configured table, resolved like [`object_key`]
+pub(crate) fn name_key(sql: &str) -> String {
+    Parser::new(&PostgreSqlDialect {})
+        .try_with_sql(sql)
+        .and_then(|mut parser| {
+            let name = parser.parse_object_name(false)?;
+            parser.expect_token(&Token::EOF)?;
+            Ok(name)
+        })
+        .map_or_else(|_| sql.to_lowercase(), |name| object_key(&name))
+}
+
+/// Normalized SQL text of an expression, such as an index column or predicate
+///
+/// Unquoted words fold to lower case like PostgreSQL identifiers do, while quoted
+/// identifiers and string literals keep their case.
+pub(crate) fn expr_key(sql: &str) -> String {
+    let Ok(tokens) = Tokenizer::new(&PostgreSqlDialect {}, sql).tokenize() else {
+        return sql.to_string();
+    };
+    tokens
+        .iter()
+        .filter(|token| !matches!(token, Token::Whitespace(_)))
+        .map(|token| match token {
+            Token::Word(word) if word.quote_style.is_none() => word.value.to_lowercase(),
+            Token::Word(word)
+                if word.value == word.value.to_lowercase()
+                    && word.value.chars().all(|c| c.is_alphanumeric() || c == '_') =>
+            {
+                word.value.clone()
+            }
+            Token::Word(word) => format!("\"{}\"", word.value.replace('"', "\"\"")),
+            other => other.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Normalized index name; an index lives in its table's schema, so the prefix is dropped
+pub(crate) fn index_key(name: &ObjectName) -> String {
+    name.0
+        .last()
+        .map(|part| object_key(&ObjectName(vec![part.clone()])))
+        .unwrap_or_default()
+}
+
+/// Name PostgreSQL gives a table's primary key constraint when none is given
+fn default_primary_key_name(table: &str) -> String {
+    let unqualified = table.rsplit('.').next().unwrap_or(table);
+    format!("{}_pkey", unqualified)
+}
+
+/// Column of a `column IS NOT NULL` expression
+fn not_null_column(expr: &Expr) -> Option<&Ident> {
+    match expr {
+        Expr::Nested(inner) => not_null_column(inner),
+        Expr::IsNotNull(inner) => match inner.as_ref() {
+            Expr::Identifier(column) => Some(column),
+            Expr::Nested(nested) => match nested.as_ref() {
+                Expr::Identifier(column) => Some(column),
+                _ => None,
+            },
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+impl Schema {
+    /// Record the objects created and dropped by a migration's statements
+    pub fn apply<'a>(&mut self, statements: impl IntoIterator<Item = &'a Statement>) {
+        for stmt in statements {
+            match stmt {
+                Statement::CreateTable(create) => self.apply_create_table(create),
+                Statement::AlterTable(AlterTable {
+                    name, operations, ..
+                }) => {
+                    let table = object_key(name);
+                    for op in operations {
+                        self.apply_alter_table(&table, op);
+                    }
+                }
+                Statement::CreateDomain(domain) => {
+                    self.domains
+                        .insert(object_key(&domain.name), domain.data_type.clone());
+                }
+                Statement::DropDomain(domain) => {
+                    self.domains.remove(&object_key(&domain.name));
+                }
+                Statement::CreateIndex(index) => {
+                    if let Some(name) = &index.name {
+                        self.indexes.insert(
+                            index_key(name),
+                            IndexDefinition {
+                                table: object_key(&index.table_name),
+                                columns: index
+                                    .columns
+                                    .iter()
+                                    .map(|column| expr_key(&column.to_string()))
+                                    .collect(),
+                                unique: index.unique,
+                                method: index
+                                    .using
+                                    .as_ref()
+                                    .map_or("btree".to_string(), |method| {
+                                        method.to_string().to_lowercase()
+                                    }),
+                                predicate: index.predicate.as_ref().map(ToString::to_string),
+                                include: index.include.iter().map(ident_key).collect(),
+                            },
+                        );
+                    }
+                }
+                Statement::AlterIndex {
+                    name,
+                    operation: AlterIndexOperation::RenameIndex { index_name },
+                } => {
+                    if let Some(index) = self.indexes.remove(&index_key(name)) {
+                        self.indexes.insert(index_key(index_name), index);
+                    }
+                }
+                Statement::Drop {
+                    object_type: ObjectType::Index,
+                    names,
+                    ..
+                } => {
+                    for name in names {
+                        self.indexes.remove(&index_key(name));
+                    }
+                }
+                Statement::Drop {
+                    object_type: ObjectType::Table,
+                    names,
+                    ..
+                } => {
+                    for name in names {
+                        let table = object_key(name);
+                        self.tables.remove(&table);
+                        self.primary_keys.remove(&table);
+                        self.partitioned_tables.remove(&table);
+                        self.not_null_checks.retain(|(t, _), _| *t != table);
+                        self.column_types.retain(|(t, _), _| *t != table);
+                        self.not_null_columns.retain(|(t, _)| *t != table);
+                        self.indexes.retain(|_, index| index.table != table);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn apply_create_table(&mut self, create: &CreateTable) {
+        let table = object_key(&create.name);
+        self.tables.insert(table.clone());
+        self.primary_keys.remove(&table);
+        if create.partition_by.is_some() {
+            self.partitioned_tables.insert(table.clone());
+        }
+        self.column_types.retain(|(t, _), _| *t != table);
+        self.not_null_columns.retain(|(t, _)| *t != table);
+        for column in &create.columns {
+            self.column_types.insert(
+                (table.clone(), ident_key(&column.name)),
+                column.data_type.clone(),
+            );
+            if column.options.iter().any(|opt| {
+                matches!(
+                    opt.option,
+                    ColumnOption::NotNull | ColumnOption::PrimaryKey(_)
+                )
+            }) {
+                self.not_null_columns
+                    .insert((table.clone(), ident_key(&column.name)));
+            }
+        }
+        for constraint in &create.constraints {
+            if let TableConstraint::PrimaryKey(pk) = constraint {
+                self.set_not_null(&table, &pk.columns);
+            }
+        }
+
+        let inline_primary_key = create.columns.iter().find_map(|column| {
+            column.options.iter().find_map(|opt| match &opt.option {
+                ColumnOption::PrimaryKey(pk) => Some(opt.name.as_ref().or(pk.name.as_ref())),
+                _ => None,
+            })
+        });
+        let table_primary_key = create
+            .constraints
+            .iter()
+            .find_map(|constraint| match constraint {
+                TableConstraint::PrimaryKey(pk) => Some(pk.name.as_ref()),
+                _ => None,
+            });
+        if let Some(name) = inline_primary_key.or(table_primary_key) {
+            self.set_primary_key(&table, name);
+        }
+    }
+
+    /// Record the constraints attached to existing indexes by raw statements
+    ///
+    /// PostgreSQL renames the index after the constraint. A primary key also makes the
+    /// index columns NOT NULL.
+    pub fn apply_raw(&mut self, statements: &[RawStatement]) {
+        for constraint in statements
+            .iter()
+            .filter_map(RawStatement::using_index_constraint)
+        {
+            let index = self.indexes.remove(&constraint.index);
+            if constraint.primary_key {
+                self.primary_keys
+                    .insert(constraint.table.clone(), constraint.constraint.clone());
+                for column in index.iter().flat_map(|index| &index.columns) {
+                    self.not_null_columns
+                        .insert((constraint.table.clone(), column.clone()));
+                }
+            }
+            if let Some(index) = index {
+                self.indexes.insert(constraint.constraint, index);
+            }
+        }
+    }
+
+    /// Mark the plain column references among primary key columns as NOT NULL
+    fn set_not_null(&mut self, table: &str, columns: &[IndexColumn]) {
+        for column in columns {
+            if let Expr::Identifier(ident) = &column.column.expr {
+                self.not_null_columns
+                    .insert((table.to_string(), ident_key(ident)));
+            }
+        }
+    }
+
+    fn set_primary_key(&mut self, table: &str, name: Option<&Ident>) {
+        let name = name
+            .map(ident_key)
+            .unwrap_or_else(|| default_primary_key_name(table));
+        self.primary_keys.insert(table.to_string(), name);
+    }
+
+    fn apply_alter_table(&mut self, table: &str, op: &AlterTableOperation) {
+        match op {
+            AlterTableOperation::AddConstraint {
+                constraint: TableConstraint::PrimaryKey(pk),
+                ..
+            } => {
+                self.set_primary_key(table, pk.name.as_ref());
+                self.set_not_null(table, &pk.columns);
+            }
+            AlterTableOperation::AddColumn { column_def, .. } => {
+                self.column_types.insert(
+                    (table.to_string(), ident_key(&column_def.name)),
+                    column_def.data_type.clone(),
+                );
+                if column_def.options.iter().any(|opt| {
+                    matches!(
+                        opt.option,
+                        ColumnOption::NotNull | ColumnOption::PrimaryKey(_)
+                    )
+                }) {
+                    self.not_null_columns
+                        .insert((table.to_string(), ident_key(&column_def.name)));
+                }
+                if let Some(name) = column_def.options.iter().find_map(|opt| match &opt.option {
+                    ColumnOption::PrimaryKey(pk) => Some(opt.name.as_ref().or(pk.name.as_ref())),
+                    _ => None,
+                }) {
+                    self.set_primary_key(table, name);
+                }
+            }
+            AlterTableOperation::AlterColumn {
+                column_name,
+                op: AlterColumnOperation::SetDataType { data_type, .. },
+            } => {
+                self.column_types.insert(
+                    (table.to_string(), ident_key(column_name)),
+                    data_type.clone(),
+                );
+            }
+            AlterTableOperation::AlterColumn {
+                column_name,
+                op: AlterColumnOperation::SetNotNull,
+            } => {
+                self.not_null_columns
+                    .insert((table.to_string(), ident_key(column_name)));
+            }
+            AlterTableOperation::AlterColumn {
+                column_name,
+                op: AlterColumnOperation::DropNotNull,
+            } => {
+                self.not_null_columns
+                    .remove(&(table.to_string(), ident_key(column_name)));
+            }
+            AlterTableOperation::RenameColumn {
+                old_column_name,
+                new_column_name,
+            } => {
+                if let Some(data_type) = self
+                    .column_types
+                    .remove(&(table.to_string(), ident_key(old_column_name)))
+                {
+                    self.column_types
+                        .insert((table.to_string(), ident_key(new_column_name)), data_type);
+                }
+                if self
+                    .not_null_columns
+                    .remove(&(table.to_string(), ident_key(old_column_name)))
+                {
+                    self.not_null_columns
+                        .insert((table.to_string(), ident_key(new_column_name)));
+                }
+            }
+            AlterTableOperation::DropColumn { column_names, .. } => {
+                for column in column_names {
+                    self.column_types
+                        .remove(&(table.to_string(), ident_key(column)));
+                    self.not_null_columns
+                        .remove(&(table.to_string(), ident_key(column)));
+                }
+            }
+            AlterTableOperation::RenameConstraint { old_name, new_name } => {
+                if self.primary_keys.get(table) == Some(&ident_key(old_name)) {
+                    self.primary_keys
+                        .insert(table.to_string(), ident_key(new_name));
+                }
+            }
+            AlterTableOperation::RenameTable { table_name } => {
+                let (RenameTableNameKind::As(new_name) | RenameTableNameKind::To(new_name)) =
+                    table_name;
+                self.rename_table(table, &object_key(new_name));
+            }
+            AlterTableOperation::AddConstraint {
+                constraint: TableConstraint::Check(check),
+                not_valid,
+            } => {
+                if let (Some(name), Some(column)) = (&check.name, not_null_column(&check.expr)) {
+                    self.not_null_checks.insert(
+                        (table.to_string(), ident_key(name)),
+                        NotNullCheck {
+                            column: ident_key(column),
+                            validated: !not_valid,
+                        },
+                    );
+                }
+            }
+            AlterTableOperation::ValidateConstraint { name } => {
+                if let Some(check) = self
+                    .not_null_checks
+                    .get_mut(&(table.to_string(), ident_key(name)))
+                {
+                    check.validated = true;
+                }
+            }
+            AlterTableOperation::DropConstraint { name, .. } => {
+                if self.primary_keys.get(table) == Some(&ident_key(name)) {
+                    self.primary_keys.remove(table);
+                }
+                self.not_null_checks
+                    .remove(&(table.to_string(), ident_key(name)));
+            }
+            _ => {}
+        }
+    }
+
+    /// Move what's known about a table to its new name
+    fn rename_table(&mut self, table: &str, new_table: &str) {
+        if self.tables.remove(table) {
+            self.tables.insert(new_table.to_string());
+        }
+        if let Some(name) = self.primary_keys.remove(table) {
+            self.primary_keys.insert(new_table.to_string(), name);
+        }
+        if self.partitioned_tables.remove(table) {
+            self.partitioned_tables.insert(new_table.to_string());
+        }
+        let checks: Vec<_> = self
+            .not_null_checks
+            .extract_if(|(t, _), _| t == table)
+            .collect();
+        for ((_, constraint), check) in checks {
+            self.not_null_checks
+                .insert((new_table.to_string(), constraint), check);
+        }
+        let columns: Vec<_> = self
+            .column_types
+            .extract_if(|(t, _), _| t == table)
+            .collect();
+        for ((_, column), data_type) in columns {
+            self.column_types
+                .insert((new_table.to_string(), column), data_type);
+        }
+        let not_null_columns: Vec<_> = self
+            .not_null_columns
+            .extract_if(|(t, _)| t == table)
+            .collect();
+        for (_, column) in not_null_columns {
+            self.not_null_columns
+                .insert((new_table.to_string(), column));
+        }
+        for index in self.indexes.values_mut() {
+            if index.table == table {
+                index.table = new_table.to_string();
+            }
+        }
+    }
+
+    /// Whether the constraint is the table's primary key
+    ///
+    /// Returns None for tables the migrations don't create, whose constraints aren't known.
+    pub fn is_primary_key(&self, table: &ObjectName, constraint: &Ident) -> Option<bool> {
+        let table = object_key(table);
+        if !self.tables.contains(&table) {
+            return None;
+        }
+        Some(self.primary_keys.get(&table) == Some(&ident_key(constraint)))
+    }
+
+    /// Whether the column has a validated `CHECK (column IS NOT NULL)` constraint
+    pub fn has_validated_not_null_check(&self, table: &ObjectName, column: &Ident) -> bool {
+        let table = object_key(table);
+        let column = ident_key(column);
+        self.not_null_checks
+            .iter()
+            .any(|((t, _), check)| *t == table && check.column == column && check.validated)
+    }
+
+    /// Declared type of a column, if a tracked statement created or changed it
+    pub fn column_type(&self, table: &ObjectName, column: &Ident) -> Option<&DataType> {
+        self.column_types
+            .get(&(object_key(table), ident_key(column)))
+    }
+
+    /// Whether a column accepts NULLs
+    ///
+    /// Returns None for columns whose declaration isn't known.
+    pub fn is_nullable(&self, table: &ObjectName, column: &Ident) -> Option<bool> {
+        let key = (object_key(table), ident_key(column));
+        if !self.column_types.contains_key(&key) {
+            return None;
+        }
+        Some(!self.not_null_columns.contains(&key))
+    }
+
+    /// Underlying type of a domain created by the migrations
+    pub fn domain_type(&self, name: &ObjectName) -> Option<&DataType> {
+        self.domains.get(&object_key(name))
+    }
+
+    /// Definition of an index created by the migrations
+    pub fn index(&self, name: &ObjectName) -> Option<&IndexDefinition> {
+        self.indexes.get(&index_key(name))
+    }
+
+    /// Number of indexes on a table: its named indexes and its primary key
+    pub fn index_count(&self, table: &ObjectName) -> usize {
+        let table = object_key(table);
+        let named = self
+            .indexes
+            .values()
+            .filter(|index| index.table == table)
+            .count();
+        // A primary key attached with USING INDEX keeps its index among the named ones
+        let primary_key = self
+            .primary_keys
+            .get(&table)
+            .is_some_and(|name| !self.indexes.contains_key(name));
+        named + usize::from(primary_key)
+    }
+
+    /// Other indexes with the same definition as the given index
+    ///
+    /// Table, key columns, uniqueness, access method, predicate and INCLUDE columns all
+    /// have to match, so that either index can stand in for the other.
+    pub fn equivalent_indexes(&self, name: &ObjectName) -> Vec<ObjectName> {
+        let key = index_key(name);
+        let Some(index) = self.indexes.get(&key) else {
+            return vec![];
+        };
+        let mut equivalents: Vec<ObjectName> = self
+            .indexes
+            .iter()
+            .filter(|(other_key, other)| **other_key != key && *other == index)
+            .map(|(other_key, _)| ObjectName::from(vec![Ident::new(other_key.clone())]))
+            .collect();
+        equivalents.sort_by_key(|name| name.to_string());
+        equivalents
+    }
+
+    /// Whether the table was created as a partitioned table
+    pub fn is_partitioned(&self, table: &ObjectName) -> bool {
+        self.partitioned_tables.contains(&object_key(table))
+    }
+}
+
+/// The schema after each migration of a migrations directory
+#[derive(Debug, Default)]
+pub struct SchemaHistory {
+    /// Migration directory names in order, each with the schema after it ran
+    snapshots: Vec<(String, Arc<Schema>)>,
+}
+
+impl SchemaHistory {
+    /// Replay the up.sql of every migration in a directory
+    ///
+    /// Migrations are parsed quietly: the check of each migration already warns about
+    /// the statements it can't parse.
+    pub fn load(dir: &Utf8Path) -> Self {
+        let parser = SqlParser::quiet();
+        let mut migrations: Vec<String> = WalkDir::new(dir)
+            .min_depth(1)
+            .max_depth(1)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|entry| entry.file_type().is_dir())
+            .filter_map(|entry| entry.file_name().to_str().map(str::to_string))
+            .collect();
+        migrations.sort();
+
+        let mut schema = Schema::default();
+        let snapshots = migrations
+            .into_iter()
+            .map(|migration| {
+                let up_sql = dir.join(&migration).join("up.sql");
+                if let Some(parsed) = fs::read_to_string(&up_sql)
+                    .ok()
+                    .and_then(|sql| parser.parse_with_metadata(&sql).ok())
+                {
+                    schema.apply(parsed.statements.iter().map(|stmt| &stmt.stmt));
+                    schema.apply_raw(&parsed.raw_statements);
+                }
+                (migration, Arc::new(schema.clone()))
+            })
+            .collect();
+
+        Self { snapshots }
+    }
+
+    /// Schema left by the migrations sorted before the given one
+    pub fn before(&self, migration: &str) -> Arc<Schema> {
+        let idx = self
+            .snapshots
+            .partition_point(|(name, _)| name.as_str() < migration);
+        match idx {
+            0 => Arc::default(),
+            idx => Arc::clone(&self.snapshots[idx - 1].1),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table(name: &str) -> ObjectName {
+        ObjectName::from(vec![sqlparser::ast::Ident::new(name)])
+    }
+
+    #[test]
+    fn test_tracks_partitioned_tables() {
+        let mut schema = Schema::default();
+        let parser = SqlParser::new();
+
+        schema.apply(
+            &parser
+                .parse(
+                    "CREATE TABLE events (id BIGINT, created_at TIMESTAMPTZ) PARTITION BY RANGE (created_at);
+                     CREATE TABLE users (id BIGINT);",
+                )
+                .unwrap(),
+        );
+        assert!(schema.is_partitioned(&table("events")));
+        assert!(schema.is_partitioned(&table("EVENTS")));
+        assert!(!schema.is_partitioned(&table("users")));
+
+        schema.apply(&parser.parse("DROP TABLE events;").unwrap());
+        assert!(!schema.is_partitioned(&table("events")));
+    }
+
+    #[test]
+    fn test_tracks_validated_not_null_checks() {
+        let mut schema = Schema::default();
+        let parser = SqlParser::new();
+        let email = Ident::new("email");
+
+        schema.apply(
+            &parser
+                .parse("ALTER TABLE users ADD CONSTRAINT users_email_not_null CHECK (email IS NOT NULL) NOT VALID;")
+                .unwrap(),
+        );
+        assert!(!schema.has_validated_not_null_check(&table("users"), &email));
+
+        schema.apply(
+            &parser
+                .parse("ALTER TABLE users VALIDATE CONSTRAINT users_email_not_null;")
+                .unwrap(),
+        );
+        assert!(schema.has_validated_not_null_check(&table("users"), &email));
+        assert!(!schema.has_validated_not_null_check(&table("posts"), &email));
+
+        schema.apply(
+            &parser
+                .parse("ALTER TABLE users DROP CONSTRAINT users_email_not_null;")
+                .unwrap(),
+        );
+        assert!(!schema.has_validated_not_null_check(&table("users"), &email));
+    }
+
+    #[test]
+    fn test_tracks_primary_keys() {
+        let mut schema = Schema::default();
+        let parser = SqlParser::new();
+        let constraint = |name: &str| Ident::new(name);
+
+        schema.apply(
+            &parser
+                .parse(
+                    "CREATE TABLE users (id BIGINT PRIMARY KEY, email TEXT);
+                     CREATE TABLE posts (id BIGINT, CONSTRAINT posts_primary PRIMARY KEY (id));
+                     CREATE TABLE tags (name TEXT);",
+                )
+                .unwrap(),
+        );
+        assert_eq!(
+            schema.is_primary_key(&table("users"), &constraint("users_pkey")),
+            Some(true)
+        );
+        assert_eq!(
+            schema.is_primary_key(&table("posts"), &constraint("posts_primary")),
+            Some(true)
+        );
+        assert_eq!(
+            schema.is_primary_key(&table("posts"), &constraint("posts_pkey")),
+            Some(false)
+        );
+        assert_eq!(
+            schema.is_primary_key(&table("tags"), &constraint("tags_pkey")),
+            Some(false)
+        );
+        assert_eq!(
+            schema.is_primary_key(&table("orders"), &constraint("orders_pkey")),
+            None
+        );
+
+        schema.apply(
+            &parser
+                .parse(
+                    "ALTER TABLE tags ADD CONSTRAINT tags_pk PRIMARY KEY (name);
+                     ALTER TABLE users RENAME CONSTRAINT users_pkey TO users_id_pkey;
+                     ALTER TABLE posts DROP CONSTRAINT posts_primary;
+                     ALTER TABLE users RENAME TO accounts;",
+                )
+                .unwrap(),
+        );
+        assert_eq!(
+            schema.is_primary_key(&table("tags"), &constraint("tags_pk")),
+            Some(true)
+        );
+        assert_eq!(
+            schema.is_primary_key(&table("accounts"), &constraint("users_id_pkey")),
+            Some(true)
+        );
+        assert_eq!(
+            schema.is_primary_key(&table("users"), &constraint("users_id_pkey")),
+            None
+        );
+        assert_eq!(
+            schema.is_primary_key(&table("posts"), &constraint("posts_primary")),
+            Some(false)
+        );
+    }
+
+    #[test]
+    fn test_quoted_names_keep_their_case() {
+        let mut schema = Schema::default();
+        let quoted = |name: &str| Ident::with_quote('"', name);
+
+        schema.apply(
+            &SqlParser::new()
+                .parse(
+                    r#"CREATE TABLE "Users" (id BIGINT PRIMARY KEY);
+                       CREATE TABLE users (id BIGINT, CONSTRAINT users_primary PRIMARY KEY (id));"#,
+                )
+                .unwrap(),
+        );
+        let quoted_table = ObjectName::from(vec![quoted("Users")]);
+        assert_eq!(
+            schema.is_primary_key(&quoted_table, &quoted("Users_pkey")),
+            Some(true)
+        );
+        assert_eq!(
+            schema.is_primary_key(&table("USERS"), &Ident::new("users_primary")),
+            Some(true)
+        );
+        assert_eq!(
+            schema.is_primary_key(&table("users"), &quoted("Users_pkey")),
+            Some(false)
+        );
+
+        assert_eq!(name_key(r#"Billing."Invoices""#), "billing.Invoices");
+        assert_eq!(name_key("public.USERS"), "public.users");
+    }
+
+    #[test]
+    fn test_tracks_column_types() {
+        let statements = SqlParser::new()
+            .parse(
+                "CREATE TABLE users (name VARCHAR(50), age INT);
+                 ALTER TABLE users ALTER COLUMN name TYPE VARCHAR(100);
+                 ALTER TABLE users RENAME COLUMN age TO years;
+                 ALTER TABLE users ADD COLUMN bio TEXT;
+                 ALTER TABLE users RENAME TO members;",
+            )
+            .unwrap();
+        let mut schema = Schema::default();
+        schema.apply(&statements);
+
+        let column_type = |table: &str, column: &str| {
+            schema
+                .column_type(&self::table(table), &Ident::new(column))
+                .map(|data_type| data_type.to_string())
+        };
+        assert_eq!(
+            column_type("members", "name").as_deref(),
+            Some("VARCHAR(100)")
+        );
+        assert_eq!(column_type("members", "years").as_deref(), Some("INT"));
+        assert_eq!(column_type("members", "bio").as_deref(), Some("TEXT"));
+        assert_eq!(column_type("members", "age"), None);
+        assert_eq!(column_type("users", "name"), None);
+    }
+
+    #[test]
+    fn test_tracks_nullability() {
+        let mut schema = Schema::default();
+        let parser = SqlParser::new();
+        let column = |name: &str| Ident::new(name);
+
+        schema.apply(
+            &parser
+                .parse(
+                    "CREATE TABLE users (id BIGINT, tenant_id BIGINT NOT NULL, email TEXT, PRIMARY KEY (id));
+                     ALTER TABLE users ADD COLUMN name TEXT;
+                     ALTER TABLE users ALTER COLUMN email SET NOT NULL;
+                     ALTER TABLE users ALTER COLUMN tenant_id DROP NOT NULL;
+                     ALTER TABLE users RENAME COLUMN email TO email_address;",
+                )
+                .unwrap(),
+        );
+        assert_eq!(
+            schema.is_nullable(&table("users"), &column("id")),
+            Some(false)
+        );
+        assert_eq!(
+            schema.is_nullable(&table("users"), &column("email_address")),
+            Some(false)
+        );
+        assert_eq!(
+            schema.is_nullable(&table("users"), &column("tenant_id")),
+            Some(true)
+        );
+        assert_eq!(
+            schema.is_nullable(&table("users"), &column("name")),
+            Some(true)
+        );
+        assert_eq!(schema.is_nullable(&table("users"), &column("email")), None);
+        assert_eq!(schema.is_nullable(&table("posts"), &column("id")), None);
+    }
+
+    #[test]
+    fn test_tracks_constraints_using_index() {
+        let mut schema = Schema::default();
+        let parsed = SqlParser::new()
+            .parse_with_metadata(
+                "CREATE TABLE users (id BIGINT, email TEXT);
+                 CREATE UNIQUE INDEX CONCURRENTLY users_id_idx ON users (id);
+                 CREATE UNIQUE INDEX CONCURRENTLY users_email_idx ON users (email);
+                 ALTER TABLE users ADD CONSTRAINT users_identity PRIMARY KEY USING INDEX users_id_idx;
+                 ALTER TABLE users ADD CONSTRAINT users_email_key UNIQUE USING INDEX users_email_idx;",
+            )
+            .unwrap();
+        schema.apply(parsed.statements.iter().map(|stmt| &stmt.stmt));
+        schema.apply_raw(&parsed.raw_statements);
+
+        assert_eq!(
+            schema.is_primary_key(&table("users"), &Ident::new("users_identity")),
+            Some(true)
+        );
+        assert_eq!(
+            schema.is_nullable(&table("users"), &Ident::new("id")),
+            Some(false)
+        );
+        assert_eq!(
+            schema.is_nullable(&table("users"), &Ident::new("email")),
+            Some(true)
+        );
+        assert!(schema.index(&table("users_email_idx")).is_none());
+        assert_eq!(
+            schema.index(&table("users_email_key")).unwrap().columns,
+            vec!["email"]
+        );
+    }
+
+    #[test]
+    fn test_expr_key_folds_only_unquoted_words() {
+        assert_eq!(
+            expr_key("Status = 'Active' AND \"Kind\" IS NOT NULL"),
+            "status = 'Active' and \"Kind\" is not null"
+        );
+        assert_eq!(expr_key("lower(\"email\")"), expr_key("LOWER(Email)"));
+    }
+
+    #[test]
+    fn test_tracks_domains() {
+        let mut schema = Schema::default();
+        schema.apply(
+            &SqlParser::new()
+                .parse("CREATE DOMAIN email AS TEXT; CREATE DOMAIN zip AS VARCHAR(10); DROP DOMAIN zip;")
+                .unwrap(),
+        );
+
+        assert_eq!(schema.domain_type(&table("email")), Some(&DataType::Text));
+        assert_eq!(schema.domain_type(&table("zip")), None);
+    }
+
+    #[test]
+    fn test_tracks_indexes() {
+        let mut schema = Schema::default();
+        let parser = SqlParser::new();
+
+        schema.apply(
+            &parser
+                .parse(
+                    "CREATE INDEX users_email_idx ON users (email);
+                     CREATE INDEX CONCURRENTLY users_email_new_idx ON users (\"email\");
+                     CREATE INDEX users_name_idx ON users (name, email);",
+                )
+                .unwrap(),
+        );
+        assert_eq!(
+            schema.index(&table("users_email_idx")),
+            Some(&IndexDefinition {
+                table: "users".to_string(),
+                columns: vec!["email".to_string()],
+                unique: false,
+                method: "btree".to_string(),
+                predicate: None,
+                include: vec![],
+            })
+        );
+        assert_eq!(
+            schema.equivalent_indexes(&table("users_email_idx")),
+            vec![table("users_email_new_idx")]
+        );
+
+        schema.apply(
+            &parser
+                .parse(
+                    "ALTER INDEX users_email_new_idx RENAME TO users_email_key;
+                     ALTER TABLE users RENAME TO people;
+                     DROP INDEX users_email_idx;",
+                )
+                .unwrap(),
+        );
+        assert!(schema.index(&table("users_email_idx")).is_none());
+        assert_eq!(
+            schema.index(&table("users_email_key")).unwrap().table,
+            "people"
+        );
+
+        schema.apply(&parser.parse("DROP TABLE people;").unwrap());
+        assert!(schema.index(&table("users_name_idx")).is_none());
+    }
+
+    #[test]
+    fn test_equivalent_indexes_match_whole_definition() {
+        let mut schema = Schema::default();
+        schema.apply(
+            &SqlParser::new()
+                .parse(
+                    "CREATE UNIQUE INDEX users_email_key ON users (email);
+                     CREATE INDEX users_email_idx ON users (email);
+                     CREATE UNIQUE INDEX users_email_live_key ON users (email) WHERE deleted_at IS NULL;
+                     CREATE UNIQUE INDEX users_email_hash_key ON users USING hash (email);
+                     CREATE UNIQUE INDEX users_email_name_key ON users (email) INCLUDE (name);
+                     CREATE UNIQUE INDEX users_email_copy_key ON users USING btree (email);",
+                )
+                .unwrap(),
+        );
+
+        assert_eq!(
+            schema.equivalent_indexes(&table("users_email_key")),
+            vec![table("users_email_copy_key")]
+        );
+        assert!(schema
+            .equivalent_indexes(&table("users_email_idx"))
+            .is_empty());
+    }
+
+    #[test]
+    fn test_counts_indexes_per_table() {
+        let mut schema = Schema::default();
+        schema.apply(
+            &SqlParser::new()
+                .parse(
+                    "CREATE TABLE users (id BIGINT PRIMARY KEY, email TEXT);
+                     CREATE INDEX users_email_idx ON users (email);
+                     CREATE INDEX posts_user_id_idx ON posts (user_id);",
+                )
+                .unwrap(),
+        );
+
+        assert_eq!(schema.index_count(&table("users")), 2);
+        assert_eq!(schema.index_count(&table("posts")), 1);
+        assert_eq!(schema.index_count(&table("comments")), 0);
+    }
+
+    #[test]
+    fn test_history_before_migration() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = Utf8Path::from_path(dir.path()).unwrap();
+        for (migration, sql) in [
+            (
+                "2024_01_01_000000_create_events",
+                "CREATE TABLE events (id BIGINT) PARTITION BY HASH (id);",
+            ),
+            ("2024_02_01_000000_broken", "NOT SQL"),
+            ("2024_03_01_000000_drop_events", "DROP TABLE events;"),
+        ] {
+            fs::create_dir(root.join(migration)).unwrap();
+            fs::write(root.join(migration).join("up.sql"), sql).unwrap();
+        }
+
+        let history = SchemaHistory::load(root);
+
+        assert!(!history
+            .before("2024_01_01_000000_create_events")
+            .is_partitioned(&table("events")));
+        assert!(history
+            .before("2024_02_01_000000_broken")
+            .is_partitioned(&table("events")));
+        assert!(history
+            .before("2024_03_01_000000_drop_events")
+            .is_partitioned(&table("events")));
+        assert!(!history.before("2025").is_partitioned(&table("events")));
+    }
+}
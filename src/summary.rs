@@ -0,0 +1,94 @@
+//! Aggregate counts of a check run.
+//!
+//! Used by `check --summary-only` to report the shape of a large run without
+//! printing every violation.
+
+use crate::violation::{Severity, Violation};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// Violation counts of a run, broken down by check, severity, and file
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Summary {
+    pub files_checked: usize,
+    pub files_skipped: usize,
+    /// Violations suppressed by safety-assured blocks, start_after, or --skip-applied
+    pub suppressed: usize,
+    pub by_check: BTreeMap<String, usize>,
+    pub by_severity: BTreeMap<String, usize>,
+    /// Files with violations, most violations first
+    pub files: Vec<(String, usize)>,
+}
+
+impl Summary {
+    /// Count the violations of a run; file and suppression counts are left at zero
+    pub fn from_results(results: &[(String, Vec<Violation>)]) -> Self {
+        let mut summary = Self::default();
+
+        for (file, violations) in results {
+            for violation in violations {
+                *summary.by_check.entry(violation.check.clone()).or_insert(0) += 1;
+                *summary
+                    .by_severity
+                    .entry(violation.severity.to_string())
+                    .or_insert(0) += 1;
+            }
+            summary.files.push((file.clone(), violations.len()));
+        }
+
+        // Stable sort keeps files with equal counts in check order
+        summary.files.sort_by(|a, b| b.1.cmp(&a.1));
+        summary
+    }
+
+    pub fn total_violations(&self) -> usize {
+        self.files.iter().map(|(_, count)| count).sum()
+    }
+
+    /// Number of violations with the given severity
+    pub fn count(&self, severity: Severity) -> usize {
+        self.by_severity
+            .get(&severity.to_string())
+            .copied()
+            .unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn violation(check: &str, severity: Severity) -> Violation {
+        let mut violation = Violation::new("OPERATION", "", "");
+        violation.check = check.to_string();
+        violation.severity = severity;
+        violation
+    }
+
+    #[test]
+    fn test_from_results() {
+        let results = vec![
+            (
+                "a/up.sql".to_string(),
+                vec![violation("DropColumnCheck", Severity::Error)],
+            ),
+            (
+                "b/up.sql".to_string(),
+                vec![
+                    violation("DropColumnCheck", Severity::Error),
+                    violation("AddIndexCheck", Severity::Warning),
+                ],
+            ),
+        ];
+
+        let summary = Summary::from_results(&results);
+        assert_eq!(summary.total_violations(), 3);
+        assert_eq!(summary.by_check["DropColumnCheck"], 2);
+        assert_eq!(summary.by_check["AddIndexCheck"], 1);
+        assert_eq!(summary.count(Severity::Error), 2);
+        assert_eq!(summary.count(Severity::Warning), 1);
+        assert_eq!(summary.by_severity["error"], 2);
+        assert_eq!(summary.by_severity["warning"], 1);
+        assert_eq!(summary.files[0], ("b/up.sql".to_string(), 2));
+    }
+}
@@ -0,0 +1,243 @@
+//! Auditing of safety-assured blocks.
+//!
+//! Collects every safety-assured block in a set of migrations together with the
+//! reason written inside it, the age of the migration it lives in, and the checks
+//! whose violations it currently suppresses. Teams can review this list periodically
+//! to keep accumulated exceptions under control.
+
+use crate::config::migration_timestamp;
+use crate::parser::IgnoreRange;
+use crate::violation::Violation;
+use camino::Utf8Path;
+use serde::{Deserialize, Serialize};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Name under which stale safety-assured blocks are reported
+///
+/// Accepted in `disable_checks` and `severity` like a regular check name.
+pub const STALE_SAFETY_ASSURED: &str = "StaleSafetyAssured";
+
+/// A safety-assured block found in a migration file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Suppression {
+    pub file: String,
+    pub start_line: usize,
+    pub end_line: usize,
+    /// Comments written inside the block, usually explaining why it is safe
+    pub reason: Option<String>,
+    /// Days since the migration was created, based on its directory timestamp
+    pub age_days: Option<i64>,
+    /// Checks named in the start directive, or None when the block covers every check
+    pub checks: Option<Vec<String>>,
+    /// Checks whose violations fall inside the block
+    pub suppressed_checks: Vec<String>,
+}
+
+impl Suppression {
+    /// Build a suppression record for one block
+    ///
+    /// `violations` are all violations found in the file (unfiltered), paired with
+    /// the line of the statement that produced them.
+    pub fn new(
+        file: &Utf8Path,
+        range: &IgnoreRange,
+        sql: &str,
+        violations: &[(usize, Violation)],
+    ) -> Self {
+        let mut suppressed_checks: Vec<String> = violations
+            .iter()
+            .filter(|(line, violation)| range.suppresses(*line, &violation.check))
+            .map(|(_, violation)| violation.check.clone())
+            .collect();
+        suppressed_checks.sort();
+        suppressed_checks.dedup();
+
+        Self {
+            file: file.to_string(),
+            start_line: range.start_line,
+            end_line: range.end_line,
+            reason: extract_reason(sql, range),
+            checks: range.checks.clone(),
+            age_days: migration_age_days(file, today_as_days()),
+            suppressed_checks,
+        }
+    }
+}
+
+/// Mechanism that kept a violation out of the results
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SuppressionMechanism {
+    /// Statement inside a safety-assured block
+    SafetyAssured,
+    /// Migration created before the `start_after` threshold
+    StartAfter,
+    /// Migration already applied to the database (`--skip-applied`)
+    AlreadyApplied,
+}
+
+/// A violation that was found but not reported, and why
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SuppressedViolation {
+    pub file: String,
+    pub mechanism: SuppressionMechanism,
+    pub reason: Option<String>,
+    #[serde(flatten)]
+    pub violation: Violation,
+}
+
+/// Finding for a safety-assured block that no longer suppresses any violation
+pub(crate) fn stale_safety_assured_violation(range: &IgnoreRange) -> Violation {
+    let mut violation = Violation::new(
+        "Stale safety-assured block",
+        format!(
+            "The safety-assured block at lines {}-{} doesn't suppress any violation. \
+             The statements inside may have been edited or the check disabled, leaving a dead annotation.",
+            range.start_line, range.end_line
+        ),
+        "Remove the 'safety-assured:start' and 'safety-assured:end' comments.\n\n\
+         Note: keeping the block would silently suppress any unsafe statement added to it later.",
+    )
+    .located(range.start_line, Some(1));
+    violation.check = STALE_SAFETY_ASSURED.to_string();
+    violation
+}
+
+/// Collect the comment lines inside a block into a single reason string
+pub(crate) fn extract_reason(sql: &str, range: &IgnoreRange) -> Option<String> {
+    let comments: Vec<&str> = sql
+        .lines()
+        .enumerate()
+        .filter(|(idx, _)| range.contains_line(idx + 1))
+        .filter_map(|(_, line)| line.trim().strip_prefix("--"))
+        .map(str::trim)
+        .filter(|comment| !comment.is_empty())
+        .collect();
+
+    if comments.is_empty() {
+        None
+    } else {
+        Some(comments.join(" "))
+    }
+}
+
+/// Age in days of the migration containing `file`, if its directory has a timestamp
+fn migration_age_days(file: &Utf8Path, today: i64) -> Option<i64> {
+    let dir_name = file.parent()?.file_name()?;
+    let timestamp = migration_timestamp(dir_name)?;
+
+    let year = timestamp[0..4].parse().ok()?;
+    let month = timestamp[4..6].parse().ok()?;
+    let day = timestamp[6..8].parse().ok()?;
+
+    Some(today - days_from_civil(year, month, day))
+}
+
+/// Current date as days since 1970-01-01 (UTC)
+fn today_as_days() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| (elapsed.as_secs() / 86_400) as i64)
+        .unwrap_or(0)
+}
+
+/// Convert a calendar date to days since 1970-01-01
+///
+/// Uses Howard Hinnant's `days_from_civil` algorithm for the proleptic Gregorian calendar.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let month_index = (month + 9) % 12; // March = 0
+    let day_of_year = (153 * month_index + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_days_from_civil() {
+        assert_eq!(days_from_civil(1970, 1, 1), 0);
+        assert_eq!(days_from_civil(2000, 3, 1), 11_017);
+        assert_eq!(days_from_civil(2024, 1, 1), 19_723);
+    }
+
+    #[test]
+    fn test_migration_age_days() {
+        let file = Utf8Path::new("migrations/2024_01_01_000000_drop_email/up.sql");
+        let today = days_from_civil(2024, 1, 31);
+        assert_eq!(migration_age_days(file, today), Some(30));
+    }
+
+    #[test]
+    fn test_migration_age_without_timestamp() {
+        let file = Utf8Path::new("migrations/drop_email/up.sql");
+        assert_eq!(migration_age_days(file, 0), None);
+    }
+
+    #[test]
+    fn test_extract_reason() {
+        let sql = r#"
+-- safety-assured:start
+-- Safe because: column unused since v2.3
+-- Verified in production logs
+ALTER TABLE users DROP COLUMN email;
+-- safety-assured:end
+        "#;
+        let range = IgnoreRange {
+            start_line: 2,
+            end_line: 6,
+            checks: None,
+        };
+
+        assert_eq!(
+            extract_reason(sql, &range),
+            Some("Safe because: column unused since v2.3 Verified in production logs".to_string())
+        );
+    }
+
+    #[test]
+    fn test_extract_reason_missing() {
+        let sql = r#"
+-- safety-assured:start
+ALTER TABLE users DROP COLUMN email;
+-- safety-assured:end
+        "#;
+        let range = IgnoreRange {
+            start_line: 2,
+            end_line: 4,
+            checks: None,
+        };
+
+        assert_eq!(extract_reason(sql, &range), None);
+    }
+
+    #[test]
+    fn test_suppressed_checks_are_limited_to_block() {
+        let sql = r#"
+ALTER TABLE users ADD COLUMN admin BOOLEAN DEFAULT FALSE;
+-- safety-assured:start
+ALTER TABLE users DROP COLUMN email;
+ALTER TABLE users DROP COLUMN name;
+-- safety-assured:end
+        "#;
+        let range = IgnoreRange {
+            start_line: 3,
+            end_line: 6,
+            checks: None,
+        };
+        let mut drop_column = Violation::new("DROP COLUMN", "", "");
+        drop_column.check = "DropColumnCheck".to_string();
+        let mut add_column = Violation::new("ADD COLUMN with DEFAULT", "", "");
+        add_column.check = "AddColumnCheck".to_string();
+        let violations = vec![(2, add_column), (4, drop_column.clone()), (5, drop_column)];
+
+        let suppression = Suppression::new(Utf8Path::new("up.sql"), &range, sql, &violations);
+        assert_eq!(suppression.suppressed_checks, vec!["DropColumnCheck"]);
+        assert_eq!(suppression.start_line, 3);
+        assert_eq!(suppression.end_line, 6);
+    }
+}
@@ -0,0 +1,45 @@
+//! Performance timings for the tool itself.
+//!
+//! When enabled, the checker records how long each file took to parse and check,
+//! and how much time each check spent in total. The result can be included in
+//! machine-readable output so large repositories can track regressions in CI.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+/// Timings collected during a run, in milliseconds
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Timings {
+    pub total_ms: f64,
+    pub parse_ms: f64,
+    /// Total time spent in each check across all files
+    pub checks: BTreeMap<String, f64>,
+    pub files: Vec<FileTiming>,
+}
+
+/// Timings for a single migration file, in milliseconds
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FileTiming {
+    pub file: String,
+    pub parse_ms: f64,
+    pub check_ms: f64,
+    /// Parse and check time plus reading the file
+    pub total_ms: f64,
+}
+
+/// Convert a duration to fractional milliseconds
+pub(crate) fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_millis() {
+        assert_eq!(millis(Duration::from_micros(1500)), 1.5);
+        assert_eq!(millis(Duration::ZERO), 0.0);
+    }
+}
@@ -1,12 +1,47 @@
 use derive_more::Display;
 use serde::{Deserialize, Serialize};
 
+/// How a violation affects the run: only errors fail it
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, Display)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    #[default]
+    #[display("error")]
+    Error,
+    #[display("warning")]
+    Warning,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Display)]
 #[display("{}: {}", operation, problem)]
 pub struct Violation {
     pub operation: String,
     pub problem: String,
     pub safe_alternative: String,
+    /// Name of the check that produced this violation (filled in by the registry)
+    #[serde(default)]
+    pub check: String,
+    /// Severity configured for the check (filled in by the registry)
+    #[serde(default)]
+    pub severity: Severity,
+    /// Related findings of other checks merged into this one (filled in by the registry)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub notes: Vec<String>,
+    /// 1-indexed source line of the statement reported on (filled in by the registry)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub line: Option<usize>,
+    /// 1-indexed source column of the statement reported on, when known (filled in by
+    /// the registry)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub column: Option<usize>,
+    /// Object the violation is about, such as a table or constraint, identified from
+    /// the statement's AST so findings of different checks about it can be merged
+    #[serde(skip)]
+    pub target: Option<String>,
+    /// Findings behind `notes`, kept so a safety-assured block that names only this
+    /// violation's check can still report them
+    #[serde(skip)]
+    pub merged: Vec<Violation>,
 }
 
 impl Violation {
@@ -19,6 +54,107 @@ impl Violation {
             operation: operation.into(),
             problem: problem.into(),
             safe_alternative: safe_alternative.into(),
+            check: String::new(),
+            severity: Severity::Error,
+            notes: vec![],
+            line: None,
+            column: None,
+            target: None,
+            merged: vec![],
         }
     }
+
+    /// Record the object the violation is about
+    pub fn with_target(mut self, target: impl Into<String>) -> Self {
+        self.target = Some(target.into());
+        self
+    }
+
+    /// Record where the statement reported on starts
+    pub fn located(mut self, line: usize, column: Option<usize>) -> Self {
+        self.line = Some(line);
+        self.column = column;
+        self
+    }
+
+    /// Fold another check's finding about the same change into this one as a note
+    pub fn merge(&mut self, other: Violation) {
+        self.notes.push(other.note());
+        if other.is_error() {
+            self.severity = Severity::Error;
+        }
+        self.merged.push(other);
+    }
+
+    /// Take back the merged findings of checks `suppressed` doesn't cover
+    ///
+    /// They're located where this violation is, to be reported on their own.
+    pub fn unmerge_unsuppressed(&mut self, suppressed: impl Fn(&str) -> bool) -> Vec<Violation> {
+        let (kept, taken): (Vec<_>, Vec<_>) = std::mem::take(&mut self.merged)
+            .into_iter()
+            .partition(|violation| suppressed(&violation.check));
+        self.merged = kept;
+        self.notes
+            .retain(|note| !taken.iter().any(|violation| violation.note() == *note));
+
+        taken
+            .into_iter()
+            .map(|violation| {
+                let (line, column) = (self.line, self.column);
+                Violation {
+                    line,
+                    column,
+                    ..violation
+                }
+            })
+            .collect()
+    }
+
+    /// Text of this violation as a note on another one
+    fn note(&self) -> String {
+        format!("{}: {}", self.operation, self.problem)
+    }
+
+    pub fn is_error(&self) -> bool {
+        self.severity == Severity::Error
+    }
+
+    /// Stable identifier of what was flagged, independent of file and line
+    ///
+    /// Derived from the operation and problem text (which names the affected
+    /// objects) using 64-bit FNV-1a, so it is identical across runs and platforms.
+    pub fn fingerprint(&self) -> String {
+        const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+        const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+        let hash = [self.operation.as_bytes(), b"\0", self.problem.as_bytes()]
+            .concat()
+            .iter()
+            .fold(FNV_OFFSET_BASIS, |hash, byte| {
+                (hash ^ u64::from(*byte)).wrapping_mul(FNV_PRIME)
+            });
+
+        format!("{:016x}", hash)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fingerprint_is_stable() {
+        let violation = Violation::new("DROP COLUMN", "Dropping column 'email'", "");
+        assert_eq!(violation.fingerprint(), violation.clone().fingerprint());
+        assert_eq!(violation.fingerprint().len(), 16);
+    }
+
+    #[test]
+    fn test_fingerprint_ignores_safe_alternative() {
+        let a = Violation::new("DROP COLUMN", "Dropping column 'email'", "one");
+        let b = Violation::new("DROP COLUMN", "Dropping column 'email'", "two");
+        let c = Violation::new("DROP COLUMN", "Dropping column 'name'", "one");
+        assert_eq!(a.fingerprint(), b.fingerprint());
+        assert_ne!(a.fingerprint(), c.fingerprint());
+    }
 }
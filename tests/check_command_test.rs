@@ -0,0 +1,404 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use tempfile::TempDir;
+
+/// Get the path to the diesel-guard binary
+fn diesel_guard_bin() -> PathBuf {
+    // Build the binary first to ensure it exists
+    let status = Command::new("cargo")
+        .args(["build", "--quiet"])
+        .status()
+        .expect("Failed to build diesel-guard");
+    assert!(status.success(), "Failed to build diesel-guard");
+
+    // Get the binary path
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("target");
+    path.push("debug");
+    path.push("diesel-guard");
+    path
+}
+
+#[test]
+fn test_jsonl_format_emits_one_object_per_violation() {
+    let output = Command::new(diesel_guard_bin())
+        .args([
+            "check",
+            "tests/fixtures/drop_multiple_columns/up.sql",
+            "--format",
+            "jsonl",
+        ])
+        .output()
+        .expect("Failed to execute check command");
+
+    assert_eq!(output.status.code(), Some(1));
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let lines: Vec<serde_json::Value> = stdout
+        .lines()
+        .map(|line| serde_json::from_str(line).expect("Each line should be valid JSON"))
+        .collect();
+
+    assert_eq!(lines.len(), 2);
+    for line in &lines {
+        assert_eq!(line["file"], "tests/fixtures/drop_multiple_columns/up.sql");
+        assert_eq!(line["check"], "DropColumnCheck");
+        assert_eq!(line["operation"], "DROP COLUMN");
+        assert_eq!(line["line"], 2);
+        assert_eq!(line["column"], 1);
+    }
+}
+
+#[test]
+fn test_jsonl_format_without_violations() {
+    let output = Command::new(diesel_guard_bin())
+        .args([
+            "check",
+            "tests/fixtures/add_column_safe/up.sql",
+            "--format",
+            "jsonl",
+        ])
+        .output()
+        .expect("Failed to execute check command");
+
+    assert!(output.status.success());
+    assert!(output.stdout.is_empty());
+}
+
+/// Write a migration with the given up.sql into a migrations directory
+fn write_migration(migrations_dir: &Path, name: &str, sql: &str) {
+    let dir = migrations_dir.join(name);
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join("up.sql"), sql).unwrap();
+}
+
+#[test]
+fn test_ratchet_fails_only_when_counts_increase() {
+    let temp_dir = TempDir::new().unwrap();
+    let migrations_dir = temp_dir.path().join("migrations");
+    let state_file = temp_dir.path().join("ratchet.json");
+
+    let run_ratchet = || {
+        Command::new(diesel_guard_bin())
+            .current_dir(temp_dir.path())
+            .args(["check", "migrations", "--ratchet", "ratchet.json"])
+            .output()
+            .expect("Failed to execute check command")
+    };
+
+    write_migration(
+        &migrations_dir,
+        "2024_01_01_000000_drop_email",
+        "ALTER TABLE users DROP COLUMN email;",
+    );
+
+    // First run records the existing violation and passes
+    let output = run_ratchet();
+    assert!(output.status.success());
+    let state: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&state_file).unwrap()).unwrap();
+    assert_eq!(state["counts"]["DropColumnCheck"], 1);
+
+    // A new violation increases the count and fails without touching the state
+    write_migration(
+        &migrations_dir,
+        "2024_01_02_000000_drop_name",
+        "ALTER TABLE users DROP COLUMN name;",
+    );
+    let output = run_ratchet();
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("increased from 1 to 2"));
+
+    // Fixing both violations passes and tightens the stored counts
+    fs::remove_dir_all(&migrations_dir).unwrap();
+    fs::create_dir_all(&migrations_dir).unwrap();
+    let output = run_ratchet();
+    assert!(output.status.success());
+    let state: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&state_file).unwrap()).unwrap();
+    assert!(state["counts"].as_object().unwrap().is_empty());
+}
+
+#[test]
+fn test_check_without_path_uses_configured_roots() {
+    let temp_dir = TempDir::new().unwrap();
+    write_migration(
+        &temp_dir.path().join("billing/migrations"),
+        "2024_01_01_000000_drop_email",
+        "ALTER TABLE users DROP COLUMN email;",
+    );
+    write_migration(
+        &temp_dir.path().join("users/migrations"),
+        "2024_01_01_000000_drop_email",
+        "ALTER TABLE users DROP COLUMN email;",
+    );
+    fs::write(
+        temp_dir.path().join("diesel-guard.toml"),
+        r#"
+[[roots]]
+path = "billing/migrations"
+
+[[roots]]
+path = "users/migrations"
+disable_checks = ["DropColumnCheck"]
+        "#,
+    )
+    .unwrap();
+
+    let output = Command::new(diesel_guard_bin())
+        .current_dir(temp_dir.path())
+        .args(["check", "--format", "json"])
+        .output()
+        .expect("Failed to execute check command");
+
+    assert_eq!(output.status.code(), Some(1));
+
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let roots = report["roots"].as_array().unwrap();
+    assert_eq!(roots.len(), 2);
+    assert_eq!(roots[0]["root"], "billing/migrations");
+    assert_eq!(roots[0]["results"].as_array().unwrap().len(), 1);
+    assert_eq!(roots[1]["root"], "users/migrations");
+    assert!(roots[1]["results"].as_array().unwrap().is_empty());
+}
+
+#[test]
+fn test_check_without_path_or_roots_fails() {
+    let temp_dir = TempDir::new().unwrap();
+
+    let output = Command::new(diesel_guard_bin())
+        .current_dir(temp_dir.path())
+        .arg("check")
+        .output()
+        .expect("Failed to execute check command");
+
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("no [[roots]] configured"));
+}
+
+#[test]
+fn test_check_without_path_uses_diesel_toml() {
+    let temp_dir = TempDir::new().unwrap();
+    write_migration(
+        &temp_dir.path().join("db/migrations"),
+        "2024_01_01_000000_drop_email",
+        "ALTER TABLE users DROP COLUMN email;",
+    );
+    fs::write(
+        temp_dir.path().join("diesel.toml"),
+        "[migrations_directory]\ndir = \"db/migrations\"\n",
+    )
+    .unwrap();
+
+    let output = Command::new(diesel_guard_bin())
+        .current_dir(temp_dir.path())
+        .args(["check", "--format", "json"])
+        .output()
+        .expect("Failed to execute check command");
+
+    assert_eq!(output.status.code(), Some(1));
+
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(report["roots"][0]["root"], "db/migrations");
+    assert_eq!(
+        report["roots"][0]["results"][0][0],
+        "db/migrations/2024_01_01_000000_drop_email/up.sql"
+    );
+}
+
+#[test]
+fn test_fix_writes_metadata_for_concurrent_index() {
+    let temp_dir = TempDir::new().unwrap();
+    let migrations_dir = temp_dir.path().join("migrations");
+    write_migration(
+        &migrations_dir,
+        "2024_01_01_000000_add_index",
+        "CREATE INDEX CONCURRENTLY idx_users_email ON users(email);",
+    );
+    write_migration(
+        &migrations_dir,
+        "2024_01_02_000000_add_column",
+        "ALTER TABLE users ADD COLUMN bio TEXT;",
+    );
+
+    let output = Command::new(diesel_guard_bin())
+        .current_dir(temp_dir.path())
+        .args(["fix", "migrations"])
+        .output()
+        .expect("Failed to execute fix command");
+
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Created"));
+    assert_eq!(
+        fs::read_to_string(migrations_dir.join("2024_01_01_000000_add_index/metadata.toml"))
+            .unwrap(),
+        "run_in_transaction = false\n"
+    );
+    assert!(!migrations_dir
+        .join("2024_01_02_000000_add_column/metadata.toml")
+        .exists());
+}
+
+#[test]
+fn test_env_profile_changes_severity() {
+    let temp_dir = TempDir::new().unwrap();
+    write_migration(
+        &temp_dir.path().join("migrations"),
+        "2024_01_01_000000_drop_email",
+        "ALTER TABLE users DROP COLUMN email;",
+    );
+    fs::write(
+        temp_dir.path().join("diesel-guard.toml"),
+        r#"
+[severity]
+DropColumnCheck = "warning"
+
+[env.production.severity]
+DropColumnCheck = "error"
+        "#,
+    )
+    .unwrap();
+
+    let run_check = |args: &[&str], env: Option<&str>| {
+        let mut command = Command::new(diesel_guard_bin());
+        command
+            .current_dir(temp_dir.path())
+            .args(["check", "migrations"])
+            .args(args)
+            .env_remove("DIESEL_GUARD_ENV");
+        if let Some(env) = env {
+            command.env("DIESEL_GUARD_ENV", env);
+        }
+        command.output().expect("Failed to execute check command")
+    };
+
+    // Warnings are reported but don't fail the run
+    let output = run_check(&[], None);
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("(warning)"));
+
+    let output = run_check(&["--env", "production"], None);
+    assert_eq!(output.status.code(), Some(1));
+
+    let output = run_check(&[], Some("production"));
+    assert_eq!(output.status.code(), Some(1));
+
+    // An unknown profile is an error rather than a silent fallback to the defaults
+    for output in [
+        run_check(&["--env", "staging"], None),
+        run_check(&[], Some("staging")),
+    ] {
+        assert!(!output.status.success());
+        assert!(String::from_utf8_lossy(&output.stderr).contains("Unknown environment: staging"));
+        assert!(output.stdout.is_empty());
+    }
+}
+
+#[test]
+fn test_invalid_config_fails_the_run() {
+    let temp_dir = TempDir::new().unwrap();
+    write_migration(
+        &temp_dir.path().join("migrations"),
+        "2024_01_01_000000_drop_email",
+        "ALTER TABLE users DROP COLUMN email;",
+    );
+    fs::write(
+        temp_dir.path().join("diesel-guard.toml"),
+        "disable_checks = [\"NoSuchCheck\"]\n",
+    )
+    .unwrap();
+
+    let output = Command::new(diesel_guard_bin())
+        .current_dir(temp_dir.path())
+        .args(["check", "migrations"])
+        .env_remove("DIESEL_GUARD_ENV")
+        .output()
+        .expect("Failed to execute check command");
+
+    assert!(!output.status.success());
+    assert!(output.stdout.is_empty());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Invalid check name: NoSuchCheck"));
+}
+
+#[test]
+fn test_warnings_only_run_is_not_reported_as_unsafe() {
+    let output = Command::new(diesel_guard_bin())
+        .args(["check", "tests/fixtures/create_trigger_unsafe/up.sql"])
+        .env("NO_COLOR", "1")
+        .output()
+        .expect("Failed to execute check command");
+
+    assert_eq!(output.status.code(), Some(0));
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("⚠️  Warnings in tests/fixtures/create_trigger_unsafe/up.sql"));
+    assert!(stdout.contains("1 warning(s), no unsafe migrations detected"));
+    assert!(!stdout.contains("❌"));
+}
+
+#[test]
+fn test_summary_only_prints_breakdown() {
+    let output = Command::new(diesel_guard_bin())
+        .args([
+            "check",
+            "tests/fixtures/drop_multiple_columns/up.sql",
+            "--summary-only",
+        ])
+        .output()
+        .expect("Failed to execute check command");
+
+    assert_eq!(output.status.code(), Some(1));
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("2 violation(s) in 1 of 1 checked file(s)"));
+    assert!(stdout.contains("DropColumnCheck"));
+    assert!(stdout.contains("tests/fixtures/drop_multiple_columns/up.sql"));
+    // Per-violation detail is omitted
+    assert!(!stdout.contains("Safe alternative:"));
+}
+
+#[test]
+fn test_summary_only_requires_text_format() {
+    let output = Command::new(diesel_guard_bin())
+        .args([
+            "check",
+            "tests/fixtures/drop_multiple_columns/up.sql",
+            "--summary-only",
+            "--format",
+            "json",
+        ])
+        .output()
+        .expect("Failed to execute check command");
+
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--summary-only"));
+}
+
+#[test]
+fn test_unparseable_statement_is_warned_about_once() {
+    let temp_dir = TempDir::new().unwrap();
+    let migrations_dir = temp_dir.path().join("migrations");
+    write_migration(
+        &migrations_dir,
+        "2024_01_01_000000_add_statistics",
+        "CREATE TABLE users (id BIGINT);\nCREATE STATISTICS foo bar;",
+    );
+
+    let output = Command::new(diesel_guard_bin())
+        .current_dir(temp_dir.path())
+        .args(["check", "migrations"])
+        .output()
+        .expect("Failed to execute check command");
+
+    // The schema replay of the migrations directory parses the file quietly
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_eq!(
+        stderr
+            .matches("statement at line 2 can't be parsed")
+            .count(),
+        1,
+        "{}",
+        stderr
+    );
+}
@@ -385,3 +385,43 @@ fn test_migrations_checked_in_alphanumeric_order() {
         );
     }
 }
+
+#[test]
+fn test_config_roots_apply_overrides() {
+    let temp_dir = TempDir::new().unwrap();
+    let config_path = temp_dir.path().join("diesel-guard.toml");
+
+    fs::write(
+        &config_path,
+        r#"
+disable_checks = ["DropColumnCheck"]
+
+[[roots]]
+path = "tests/fixtures/drop_column"
+
+[[roots]]
+path = "tests/fixtures/drop_column"
+disable_checks = []
+        "#,
+    )
+    .unwrap();
+
+    let config_path_utf8 = Utf8Path::from_path(&config_path).unwrap();
+    let config = Config::load_from_path(config_path_utf8).unwrap();
+    assert_eq!(config.roots.len(), 2);
+
+    let violation_counts: Vec<usize> = config
+        .roots
+        .iter()
+        .map(|root| {
+            let checker = SafetyChecker::with_config(config.for_root(root));
+            checker
+                .check_file(Utf8Path::new(&root.path).join("up.sql").as_path())
+                .unwrap()
+                .len()
+        })
+        .collect();
+
+    // The first root inherits the disabled check, the second re-enables it
+    assert_eq!(violation_counts, vec![0, 1]);
+}
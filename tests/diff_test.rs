@@ -0,0 +1,73 @@
+//! Integration tests for comparing reports produced by `check --format json`.
+
+use camino::Utf8PathBuf;
+use diesel_guard::diff::{load_report, ReportDiff};
+use diesel_guard::output::{OutputFormatter, ReportSections};
+use diesel_guard::timings::Timings;
+use diesel_guard::SafetyChecker;
+use std::fs;
+use tempfile::TempDir;
+
+fn write_report(dir: &TempDir, name: &str, content: &str) -> Utf8PathBuf {
+    let path = Utf8PathBuf::from_path_buf(dir.path().join(name)).unwrap();
+    fs::write(&path, content).unwrap();
+    path
+}
+
+#[test]
+fn test_diff_reports_from_checker() {
+    let temp_dir = TempDir::new().unwrap();
+    let checker = SafetyChecker::new();
+
+    let old_results = checker
+        .check_path("tests/fixtures/drop_column/up.sql".into())
+        .unwrap();
+    let mut new_results = old_results.clone();
+    new_results.extend(
+        checker
+            .check_path("tests/fixtures/rename_table_unsafe/up.sql".into())
+            .unwrap(),
+    );
+
+    // The new report uses the object form produced by --timings
+    let old_path = write_report(
+        &temp_dir,
+        "old.json",
+        &OutputFormatter::format_json(&old_results),
+    );
+    let new_path = write_report(
+        &temp_dir,
+        "new.json",
+        &OutputFormatter::format_json_with_sections(
+            &new_results,
+            &ReportSections {
+                timings: Some(Timings::default()),
+                ..Default::default()
+            },
+        ),
+    );
+
+    let diff = ReportDiff::new(
+        &load_report(&old_path).unwrap(),
+        &load_report(&new_path).unwrap(),
+    );
+
+    assert_eq!(diff.unchanged.len(), 1);
+    assert_eq!(diff.added.len(), 1);
+    assert_eq!(
+        diff.added[0].file,
+        "tests/fixtures/rename_table_unsafe/up.sql"
+    );
+    assert_eq!(diff.added[0].violation.check, "RenameTableCheck");
+    assert!(diff.removed.is_empty());
+    assert!(diff.has_new_violations());
+}
+
+#[test]
+fn test_load_invalid_report() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = write_report(&temp_dir, "report.json", r#"{"unexpected": true}"#);
+
+    let err = load_report(&path).unwrap_err();
+    assert!(err.to_string().contains("Invalid report"));
+}
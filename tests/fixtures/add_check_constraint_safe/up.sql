@@ -0,0 +1,4 @@
+-- Safe: Skip validation of existing rows, then validate without blocking reads or writes
+ALTER TABLE users ADD CONSTRAINT users_age_check CHECK (age >= 0) NOT VALID;
+
+ALTER TABLE users VALIDATE CONSTRAINT users_age_check;
@@ -0,0 +1,2 @@
+-- Unsafe: Scans the whole table under an ACCESS EXCLUSIVE lock
+ALTER TABLE users ADD CONSTRAINT users_age_check CHECK (age >= 0);
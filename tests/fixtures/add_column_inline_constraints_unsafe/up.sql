@@ -0,0 +1,5 @@
+-- Unsafe: Inline UNIQUE builds an index under ACCESS EXCLUSIVE lock
+ALTER TABLE users ADD COLUMN code TEXT UNIQUE;
+
+-- Unsafe: Inline REFERENCES validates the foreign key immediately
+ALTER TABLE posts ADD COLUMN author_id BIGINT REFERENCES users(id);
@@ -0,0 +1,2 @@
+-- Unsafe: Builds the index and checks all rows under ACCESS EXCLUSIVE lock
+ALTER TABLE bookings ADD CONSTRAINT no_overlap EXCLUDE USING gist (room_id WITH =, during WITH &&);
@@ -0,0 +1,7 @@
+-- Safe: Skip validation of existing rows, then validate without blocking writes
+ALTER TABLE posts ADD CONSTRAINT posts_user_id_fkey FOREIGN KEY (user_id) REFERENCES users(id) NOT VALID;
+
+ALTER TABLE posts VALIDATE CONSTRAINT posts_user_id_fkey;
+
+-- Safe: Index the referencing column without blocking writes
+CREATE INDEX CONCURRENTLY posts_user_id_idx ON posts (user_id);
@@ -0,0 +1,2 @@
+-- Unsafe: Validates all existing rows while blocking writes to both tables
+ALTER TABLE posts ADD CONSTRAINT posts_user_id_fkey FOREIGN KEY (user_id) REFERENCES users(id);
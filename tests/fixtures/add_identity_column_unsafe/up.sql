@@ -0,0 +1,2 @@
+-- Unsafe: Rewrites the table to fill identity values for existing rows
+ALTER TABLE events ADD COLUMN id BIGINT GENERATED ALWAYS AS IDENTITY;
@@ -0,0 +1,8 @@
+-- Safe: The table was just created, so nothing is blocked while it's indexed
+CREATE TABLE bookmarks (
+  id BIGINT GENERATED ALWAYS AS IDENTITY PRIMARY KEY,
+  user_id BIGINT NOT NULL,
+  created_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX bookmarks_user_id_idx ON bookmarks (user_id);
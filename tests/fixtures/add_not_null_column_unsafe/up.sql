@@ -0,0 +1,2 @@
+-- Unsafe: Fails on any table with rows, since they'd have NULL in the new column
+ALTER TABLE users ADD COLUMN tier TEXT NOT NULL;
@@ -0,0 +1,7 @@
+-- Safe: The table was just created, so setting NOT NULL scans no rows
+CREATE TABLE invoices (
+  id BIGINT GENERATED ALWAYS AS IDENTITY PRIMARY KEY,
+  number TEXT
+);
+
+ALTER TABLE invoices ALTER COLUMN number SET NOT NULL;
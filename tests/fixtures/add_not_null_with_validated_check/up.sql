@@ -0,0 +1,6 @@
+-- Safe: The validated CHECK constraint lets SET NOT NULL skip the table scan (PostgreSQL 12+)
+ALTER TABLE orders ADD CONSTRAINT orders_customer_id_not_null CHECK (customer_id IS NOT NULL) NOT VALID;
+
+ALTER TABLE orders VALIDATE CONSTRAINT orders_customer_id_not_null;
+
+ALTER TABLE orders ALTER COLUMN customer_id SET NOT NULL;
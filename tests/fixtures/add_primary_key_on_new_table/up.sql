@@ -0,0 +1,7 @@
+-- Safe: The table was just created, so adding its primary key blocks nothing
+CREATE TABLE team_memberships (
+  team_id BIGINT NOT NULL,
+  user_id BIGINT NOT NULL
+);
+
+ALTER TABLE team_memberships ADD CONSTRAINT team_memberships_pkey PRIMARY KEY (team_id, user_id);
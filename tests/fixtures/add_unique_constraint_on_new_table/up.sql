@@ -0,0 +1,7 @@
+-- Safe: The table was just created, so building the unique index blocks nothing
+CREATE TABLE api_keys (
+  id BIGINT GENERATED ALWAYS AS IDENTITY PRIMARY KEY,
+  digest BYTEA NOT NULL
+);
+
+ALTER TABLE api_keys ADD CONSTRAINT api_keys_digest_key UNIQUE (digest);
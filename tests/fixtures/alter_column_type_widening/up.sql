@@ -0,0 +1,12 @@
+-- Safe: Widening a column whose type is declared in the same migration
+-- only updates the catalog, without rewriting the table
+CREATE TABLE products (
+  id BIGINT PRIMARY KEY,
+  name VARCHAR(50) NOT NULL,
+  description VARCHAR(500),
+  price NUMERIC(10, 2) NOT NULL
+);
+
+ALTER TABLE products ALTER COLUMN name TYPE VARCHAR(255);
+ALTER TABLE products ALTER COLUMN description TYPE TEXT;
+ALTER TABLE products ALTER COLUMN price TYPE NUMERIC(14, 2);
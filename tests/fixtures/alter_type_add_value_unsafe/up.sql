@@ -0,0 +1,2 @@
+-- Unsafe: ADD VALUE can't run in a transaction before PostgreSQL 12
+ALTER TYPE order_status ADD VALUE 'refunded';
@@ -0,0 +1,5 @@
+-- Unsafe: Deleting a user silently deletes all of their posts
+CREATE TABLE posts (
+  id BIGINT PRIMARY KEY,
+  user_id BIGINT NOT NULL REFERENCES users (id) ON DELETE CASCADE
+);
@@ -0,0 +1,2 @@
+-- Unsafe (opt-in): CHAR(n) pads values with spaces
+ALTER TABLE users ADD COLUMN locale CHAR(5);
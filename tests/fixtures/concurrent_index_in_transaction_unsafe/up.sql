@@ -0,0 +1,2 @@
+-- Unsafe: No metadata.toml, so Diesel runs this in a transaction and it fails at deploy time
+CREATE INDEX CONCURRENTLY users_email_idx ON users (email);
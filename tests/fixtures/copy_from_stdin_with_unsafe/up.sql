@@ -0,0 +1,10 @@
+-- COPY data isn't SQL, so it's skipped and the other statements are still checked
+
+-- Safe operation
+COPY countries (code, name) FROM stdin;
+US	United States
+GB	United Kingdom; Great Britain
+\.
+
+-- Unsafe operation that is still detected
+ALTER TABLE users DROP COLUMN old_field;
@@ -0,0 +1,13 @@
+-- Unsafe: Runs the view's full query inside the migration
+CREATE MATERIALIZED VIEW order_totals AS
+  SELECT user_id, sum(total) AS total FROM orders GROUP BY user_id;
+
+-- Unsafe: Same, with the default spelled out
+CREATE MATERIALIZED VIEW daily_signups AS
+  SELECT created_at::date AS day, count(*) FROM users GROUP BY 1
+  WITH DATA;
+
+-- Safe: Populated after deploy
+CREATE MATERIALIZED VIEW monthly_revenue AS
+  SELECT date_trunc('month', created_at) AS month, sum(total) FROM orders GROUP BY 1
+  WITH NO DATA;
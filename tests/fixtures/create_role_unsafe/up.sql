@@ -0,0 +1,2 @@
+-- Unsafe: Requires CREATEROLE, and commits the password to the repository
+CREATE USER reporting WITH LOGIN PASSWORD 'change-me';
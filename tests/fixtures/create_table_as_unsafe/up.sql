@@ -0,0 +1,2 @@
+-- Unsafe: Copies the whole query result within the migration transaction
+CREATE TABLE users_archive AS SELECT * FROM users WHERE deleted_at IS NOT NULL;
@@ -0,0 +1,2 @@
+-- Unsafe: Blocks writes while the trigger is created and adds work to every update
+CREATE TRIGGER set_updated_at BEFORE UPDATE ON users FOR EACH ROW EXECUTE FUNCTION set_updated_at();
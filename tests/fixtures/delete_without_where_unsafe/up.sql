@@ -0,0 +1,2 @@
+-- Unsafe: Removes every row of the table
+DELETE FROM events;
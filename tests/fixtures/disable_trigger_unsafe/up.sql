@@ -0,0 +1,2 @@
+-- Unsafe: Also disables foreign key enforcement
+ALTER TABLE orders DISABLE TRIGGER ALL;
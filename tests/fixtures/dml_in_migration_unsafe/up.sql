@@ -0,0 +1,2 @@
+-- Unsafe (with DmlInMigrationCheck enabled): Backfill inside the migration transaction
+UPDATE users SET active = true WHERE active IS NULL;
@@ -0,0 +1,12 @@
+-- Unsafe: DDL inside a DO block is checked like any other statement
+DO $$
+BEGIN
+  IF NOT EXISTS (
+    SELECT 1 FROM information_schema.columns
+    WHERE table_name = 'users' AND column_name = 'legacy_id'
+  ) THEN
+    RETURN;
+  END IF;
+  EXECUTE 'ALTER TABLE users DROP COLUMN legacy_id';
+END
+$$;
@@ -0,0 +1,2 @@
+-- Unsafe: Silently drops foreign keys that reference the constraint
+ALTER TABLE users DROP CONSTRAINT users_email_key CASCADE;
@@ -0,0 +1,2 @@
+-- Unsafe: Destroys every table in the database; never belongs in a migration
+DROP DATABASE IF EXISTS app_production;
@@ -0,0 +1,2 @@
+-- Unsafe: Drop table
+DROP TABLE IF EXISTS legacy_sessions CASCADE;
@@ -0,0 +1,4 @@
+-- Unsafe: Two indexes on the same column, built and maintained twice
+CREATE INDEX CONCURRENTLY users_email_idx ON users (email);
+
+CREATE INDEX CONCURRENTLY index_users_on_email ON users (email);
@@ -0,0 +1,5 @@
+-- Unsafe: Recreating an enum to remove a value rewrites the table and drops the old type
+ALTER TYPE order_status RENAME TO order_status_old;
+CREATE TYPE order_status AS ENUM ('pending', 'paid', 'shipped');
+ALTER TABLE orders ALTER COLUMN status TYPE order_status USING status::text::order_status;
+DROP TYPE order_status_old;
@@ -0,0 +1,10 @@
+-- Unsafe (warning): Every index slows down writes to the table
+CREATE INDEX CONCURRENTLY reports_account_id_idx ON reports (account_id);
+CREATE INDEX CONCURRENTLY reports_kind_idx ON reports (kind);
+CREATE INDEX CONCURRENTLY reports_status_idx ON reports (status);
+CREATE INDEX CONCURRENTLY reports_created_at_idx ON reports (created_at);
+CREATE INDEX CONCURRENTLY reports_updated_at_idx ON reports (updated_at);
+CREATE INDEX CONCURRENTLY reports_author_id_idx ON reports (author_id);
+CREATE INDEX CONCURRENTLY reports_title_idx ON reports (title);
+CREATE INDEX CONCURRENTLY reports_published_at_idx ON reports (published_at);
+CREATE INDEX CONCURRENTLY reports_archived_at_idx ON reports (archived_at);
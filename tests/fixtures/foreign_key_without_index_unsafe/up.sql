@@ -0,0 +1,7 @@
+-- Unsafe: Deleting a user scans all of comments to find references
+CREATE TABLE comments (
+  id BIGINT PRIMARY KEY,
+  user_id BIGINT NOT NULL,
+  body TEXT NOT NULL,
+  CONSTRAINT comments_user_id_fkey FOREIGN KEY (user_id) REFERENCES users (id)
+);
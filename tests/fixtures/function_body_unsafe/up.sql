@@ -0,0 +1,6 @@
+-- Unsafe: DDL in a function body is checked like any other statement
+CREATE OR REPLACE FUNCTION drop_legacy_id() RETURNS void AS $$
+BEGIN
+  EXECUTE 'ALTER TABLE users DROP COLUMN legacy_id';
+END
+$$ LANGUAGE plpgsql;
@@ -0,0 +1,2 @@
+-- Unsafe: Fails where the migration user lacks the grant option or the role doesn't exist
+GRANT SELECT ON users TO reporting;
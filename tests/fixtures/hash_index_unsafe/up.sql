@@ -0,0 +1,2 @@
+-- Unsafe: Hash index instead of btree
+CREATE INDEX CONCURRENTLY idx_users_token ON users USING hash (token);
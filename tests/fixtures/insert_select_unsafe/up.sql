@@ -0,0 +1,2 @@
+-- Unsafe: Copies an unbounded number of rows in one transaction
+INSERT INTO events_archive SELECT * FROM events;
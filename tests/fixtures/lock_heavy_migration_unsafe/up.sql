@@ -0,0 +1,8 @@
+-- Unsafe: Four tables locked in one transaction, each lock held until COMMIT
+ALTER TABLE users ADD COLUMN bio TEXT;
+
+ALTER TABLE posts ADD COLUMN published_at TIMESTAMPTZ;
+
+ALTER TABLE comments ALTER COLUMN body DROP NOT NULL;
+
+ALTER TABLE tags ALTER COLUMN color SET DEFAULT 'gray';
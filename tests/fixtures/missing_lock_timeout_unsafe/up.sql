@@ -0,0 +1,2 @@
+-- Unsafe (opt-in): Waits indefinitely if users is locked by a long-running query
+ALTER TABLE users ADD COLUMN bio TEXT;
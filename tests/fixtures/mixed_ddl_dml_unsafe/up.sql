@@ -0,0 +1,3 @@
+-- Unsafe: The ALTER TABLE lock is held until the backfill finishes
+ALTER TABLE users ADD COLUMN active BOOLEAN;
+UPDATE users SET active = true WHERE active IS NULL;
@@ -0,0 +1,5 @@
+-- Unsafe (opt-in): MONEY depends on the server locale
+CREATE TABLE invoices (
+  id BIGINT PRIMARY KEY,
+  total MONEY NOT NULL
+);
@@ -0,0 +1,5 @@
+-- Unsafe (opt-in): Running this migration a second time fails because the table exists
+CREATE TABLE tags (
+  id BIGINT GENERATED ALWAYS AS IDENTITY PRIMARY KEY,
+  name TEXT NOT NULL
+);
@@ -0,0 +1,8 @@
+-- Unsafe: CONCURRENTLY isn't supported on partitioned tables, so this fails
+CREATE TABLE measurements (
+  id BIGINT GENERATED ALWAYS AS IDENTITY,
+  recorded_at TIMESTAMPTZ NOT NULL,
+  value NUMERIC NOT NULL
+) PARTITION BY RANGE (recorded_at);
+
+CREATE INDEX CONCURRENTLY measurements_recorded_at_idx ON measurements (recorded_at);
@@ -0,0 +1,6 @@
+-- Unsafe (opt-in): Changing the length limit later requires ALTER COLUMN TYPE
+CREATE TABLE newsletters (
+  id BIGINT GENERATED ALWAYS AS IDENTITY PRIMARY KEY,
+  subject VARCHAR(120) NOT NULL,
+  body TEXT NOT NULL
+);
@@ -0,0 +1,2 @@
+-- Unsafe: Blocks all readers of the view during the refresh
+REFRESH MATERIALIZED VIEW user_stats;
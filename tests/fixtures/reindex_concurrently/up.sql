@@ -0,0 +1,2 @@
+-- Safe: Rebuilds the index without blocking (requires run_in_transaction = false)
+REINDEX INDEX CONCURRENTLY idx_users_email;
@@ -0,0 +1,2 @@
+-- Unsafe: Blocks writes and reads through the index while rebuilding
+REINDEX INDEX idx_users_email;
@@ -0,0 +1,2 @@
+-- Unsafe: Breaks running instances that still use the old value
+ALTER TYPE order_status RENAME VALUE 'canceled' TO 'cancelled';
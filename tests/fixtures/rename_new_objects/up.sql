@@ -0,0 +1,11 @@
+-- Safe: The table and column were just created, so nothing else uses them yet
+CREATE TABLE draft_posts (
+  id BIGINT PRIMARY KEY,
+  titel TEXT NOT NULL
+);
+
+ALTER TABLE draft_posts RENAME COLUMN titel TO title;
+ALTER TABLE draft_posts RENAME TO posts;
+
+ALTER TABLE users ADD COLUMN nick TEXT;
+ALTER TABLE users RENAME COLUMN nick TO nickname;
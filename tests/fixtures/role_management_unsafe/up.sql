@@ -0,0 +1,2 @@
+-- Unsafe: Requires CREATEROLE and changes a role shared by the whole cluster
+ALTER ROLE app SET statement_timeout = '30s';
@@ -0,0 +1,4 @@
+-- Unsafe: Without policies, the application instantly loses access to every row
+ALTER TABLE documents ENABLE ROW LEVEL SECURITY;
+
+ALTER TABLE documents FORCE ROW LEVEL SECURITY;
@@ -0,0 +1,5 @@
+-- Unsafe (opt-in, PostgreSQL 10+): Prefer identity columns over SERIAL
+CREATE TABLE users (
+  id BIGSERIAL PRIMARY KEY,
+  email TEXT NOT NULL
+);
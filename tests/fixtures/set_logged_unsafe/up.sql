@@ -0,0 +1,2 @@
+-- Unsafe: Rewrites the table and risks data loss after a crash
+ALTER TABLE events SET UNLOGGED;
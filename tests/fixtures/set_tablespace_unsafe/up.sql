@@ -0,0 +1,2 @@
+-- Unsafe: Copies the whole table under ACCESS EXCLUSIVE lock
+ALTER TABLE users SET TABLESPACE fast_ssd;
@@ -28,3 +28,9 @@ CREATE TABLE products (
 ALTER TABLE products
     ADD COLUMN id INT,
     ADD CONSTRAINT pk_products PRIMARY KEY (id);
+
+-- SERIAL is an INT with a sequence, with the same ~2.1 billion limit
+CREATE TABLE tags (
+    id SERIAL PRIMARY KEY,
+    name TEXT
+);
@@ -0,0 +1,5 @@
+-- Unsafe (opt-in): TIMESTAMP stores wall-clock time without a time zone
+CREATE TABLE events (
+  id BIGINT PRIMARY KEY,
+  created_at TIMESTAMP NOT NULL
+);
@@ -0,0 +1,7 @@
+-- Safe: The staging table was just created, so nothing else uses it yet
+CREATE TABLE staging_currencies (
+  code TEXT NOT NULL,
+  name TEXT NOT NULL
+);
+
+TRUNCATE TABLE staging_currencies;
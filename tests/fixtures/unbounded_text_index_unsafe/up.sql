@@ -0,0 +1,7 @@
+-- Unsafe (warning): Values longer than ~2704 bytes can't be written once indexed
+CREATE TABLE pages (
+  id BIGINT GENERATED ALWAYS AS IDENTITY PRIMARY KEY,
+  url TEXT NOT NULL
+);
+
+CREATE INDEX CONCURRENTLY pages_url_idx ON pages (url);
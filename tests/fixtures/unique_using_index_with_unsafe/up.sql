@@ -1,8 +1,8 @@
--- This migration demonstrates the parser limitation:
--- When UNIQUE USING INDEX causes a parse error, ALL statements are skipped
+-- sqlparser can't parse UNIQUE USING INDEX, so it's checked as source text
+-- and the other statements are still checked
 
--- Safe operation (but causes parse failure)
+-- Safe operation
 ALTER TABLE users ADD CONSTRAINT users_email_key UNIQUE USING INDEX users_email_idx;
 
--- Unsafe operation that SHOULD be detected but WON'T be due to parser limitation
+-- Unsafe operation that is still detected
 ALTER TABLE users DROP COLUMN old_field;
@@ -0,0 +1,6 @@
+-- Unsafe (opt-in): Emptied after a crash and missing on replicas after a failover
+CREATE UNLOGGED TABLE page_views (
+  id BIGINT GENERATED ALWAYS AS IDENTITY PRIMARY KEY,
+  path TEXT NOT NULL,
+  viewed_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
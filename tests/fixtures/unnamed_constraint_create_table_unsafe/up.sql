@@ -0,0 +1,11 @@
+-- Example of unnamed constraints declared in CREATE TABLE
+-- These also receive auto-generated names from PostgreSQL
+
+CREATE TABLE accounts (
+    id BIGINT PRIMARY KEY,
+    -- Unnamed inline UNIQUE constraint
+    email TEXT NOT NULL UNIQUE,
+    balance BIGINT NOT NULL,
+    -- Unnamed table CHECK constraint
+    CHECK (balance >= 0)
+);
@@ -3,7 +3,10 @@
 -- For UNIQUE, use CREATE UNIQUE INDEX CONCURRENTLY instead (see add_unique_constraint_safe)
 
 -- Named CHECK constraint (safe)
-ALTER TABLE users ADD CONSTRAINT users_age_check CHECK (age >= 0);
+ALTER TABLE users ADD CONSTRAINT users_age_check CHECK (age >= 0) NOT VALID;
 
 -- Named FOREIGN KEY constraint (safe)
-ALTER TABLE posts ADD CONSTRAINT posts_user_id_fkey FOREIGN KEY (user_id) REFERENCES users(id);
+ALTER TABLE posts ADD CONSTRAINT posts_user_id_fkey FOREIGN KEY (user_id) REFERENCES users(id) NOT VALID;
+
+-- Index for the foreign key (safe)
+CREATE INDEX CONCURRENTLY posts_user_id_idx ON posts (user_id);
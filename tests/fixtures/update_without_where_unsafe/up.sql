@@ -0,0 +1,2 @@
+-- Unsafe: Rewrites every row of the table in one transaction
+UPDATE users SET active = true;
@@ -0,0 +1,2 @@
+-- Unsafe: Rewrites the table under an ACCESS EXCLUSIVE lock
+VACUUM FULL users;
@@ -0,0 +1,7 @@
+-- Unsafe: Rows accepted today can fail the constraint on a later UPDATE or restore
+CREATE TABLE coupons (
+  id BIGINT GENERATED ALWAYS AS IDENTITY PRIMARY KEY,
+  code TEXT NOT NULL,
+  expires_at TIMESTAMPTZ NOT NULL,
+  CONSTRAINT coupons_expires_in_future CHECK (expires_at > now())
+);
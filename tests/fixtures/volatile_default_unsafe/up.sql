@@ -0,0 +1,2 @@
+-- Unsafe: Volatile default rewrites the table even on PostgreSQL 11+
+ALTER TABLE users ADD COLUMN token UUID DEFAULT gen_random_uuid();
@@ -1,2 +1,5 @@
 -- Safe: Index with 3 columns
 CREATE INDEX CONCURRENTLY idx_users_composite ON users(tenant_id, user_id, email);
+
+-- Safe: INCLUDE columns aren't part of the btree key
+CREATE INDEX CONCURRENTLY idx_users_email_covering ON users(email) INCLUDE (name, status, created_at, tenant_id);
@@ -16,16 +16,26 @@ fn fixture_path(name: &str) -> String {
 fn test_safe_fixtures_pass() {
     let checker = SafetyChecker::new();
     let safe_fixtures = vec![
+        "add_check_constraint_safe",
         "add_column_safe",
+        "add_foreign_key_safe",
+        "add_index_on_new_table",
         "add_index_with_concurrently",
         "add_json_column_safe",
+        "add_not_null_on_new_table",
+        "add_primary_key_on_new_table",
         "add_primary_key_safe",
+        "add_unique_constraint_on_new_table",
         "add_unique_constraint_safe",
+        "alter_column_type_widening",
         "drop_index_concurrently",
         "drop_not_null",
+        "reindex_concurrently",
+        "rename_new_objects",
         "safety_assured_drop",
         "safety_assured_multiple",
         "short_int_pk_safe",
+        "truncate_new_table",
         "unnamed_constraint_safe",
         "wide_index_safe",
     ];
@@ -68,6 +78,29 @@ fn test_add_not_null_detected() {
     assert_eq!(violations[0].operation, "ADD NOT NULL constraint");
 }
 
+#[test]
+fn test_add_not_null_with_validated_check_depends_on_postgres_version() {
+    let path = fixture_path("add_not_null_with_validated_check");
+    let check = |postgres_version| {
+        let config = diesel_guard::Config {
+            postgres_version,
+            ..Default::default()
+        };
+        SafetyChecker::with_config(config)
+            .check_file(Utf8Path::new(&path))
+            .unwrap()
+    };
+
+    assert!(check(Some(12)).is_empty());
+
+    // Before PostgreSQL 12, and when the version is unknown, SET NOT NULL still scans
+    for postgres_version in [Some(11), None] {
+        let violations = check(postgres_version);
+        assert_eq!(violations.len(), 1, "Expected 1 violation");
+        assert_eq!(violations[0].operation, "ADD NOT NULL constraint");
+    }
+}
+
 #[test]
 fn test_add_index_without_concurrently_detected() {
     let checker = SafetyChecker::new();
@@ -142,6 +175,19 @@ fn test_create_extension_detected() {
     assert_eq!(violations[0].operation, "CREATE EXTENSION");
 }
 
+#[test]
+fn test_create_extension_allowed_extensions() {
+    let path = fixture_path("create_extension_unsafe");
+    let mut config = diesel_guard::Config::default();
+    config.checks.create_extension.allowed_extensions = vec!["pg_trgm".to_string()];
+
+    let violations = SafetyChecker::with_config(config)
+        .check_file(Utf8Path::new(&path))
+        .unwrap();
+
+    assert_eq!(violations.len(), 0, "Expected no violations");
+}
+
 #[test]
 fn test_add_unique_constraint_detected() {
     let checker = SafetyChecker::new();
@@ -170,23 +216,28 @@ fn test_unique_using_index_is_safe() {
 }
 
 #[test]
-fn test_unique_using_index_skips_other_statements() {
+fn test_unique_using_index_keeps_other_statements() {
     let checker = SafetyChecker::new();
     let path = fixture_path("unique_using_index_with_unsafe");
 
     // This file contains both UNIQUE USING INDEX (safe) and DROP COLUMN (unsafe)
-    // Due to parser limitation, ALL statements are skipped when UNIQUE USING INDEX
-    // causes a parse error, so even the unsafe DROP COLUMN is not detected
     let violations = checker.check_file(Utf8Path::new(&path)).unwrap();
 
-    // LIMITATION: Should be 1 violation (DROP COLUMN) but is 0 because parser fails
-    // This test documents the known limitation
-    assert_eq!(
-        violations.len(),
-        0,
-        "Parser limitation: UNIQUE USING INDEX causes ALL statements to be skipped, \
-         including the unsafe DROP COLUMN in this file"
-    );
+    assert_eq!(violations.len(), 1);
+    assert_eq!(violations[0].operation, "DROP COLUMN");
+}
+
+#[test]
+fn test_copy_from_stdin_keeps_other_statements() {
+    let checker = SafetyChecker::new();
+    let path = fixture_path("copy_from_stdin_with_unsafe");
+
+    // The COPY data isn't SQL, but doesn't stop the DROP COLUMN after it being checked
+    let violations = checker.check_file(Utf8Path::new(&path)).unwrap();
+
+    assert_eq!(violations.len(), 1);
+    assert_eq!(violations[0].operation, "DROP COLUMN");
+    assert_eq!(violations[0].line, Some(10));
 }
 
 #[test]
@@ -196,12 +247,80 @@ fn test_unnamed_constraint_detected() {
 
     let violations = checker.check_file(Utf8Path::new(&path)).unwrap();
 
-    // Note: Unnamed UNIQUE is caught by both AddUniqueConstraintCheck and UnnamedConstraintCheck
+    // Note: Each unnamed constraint is also caught by the check for adding its constraint type,
+    // merged into one violation with a note
+    // The foreign key also has no index (a warning)
     assert_eq!(violations.len(), 4, "Expected 4 violations");
     assert_eq!(violations[0].operation, "ADD UNIQUE constraint");
+    assert_eq!(violations[1].operation, "ADD CHECK constraint");
+    assert_eq!(violations[2].operation, "ADD FOREIGN KEY");
+    assert!(violations[..3].iter().all(|v| v.notes.len() == 1));
+    assert_eq!(violations[3].operation, "FOREIGN KEY without index");
+}
+
+#[test]
+fn test_unnamed_constraint_reported_separately() {
+    let config = diesel_guard::Config {
+        report_overlapping_violations: true,
+        ..Default::default()
+    };
+    let checker = SafetyChecker::with_config(config);
+    let path = fixture_path("unnamed_constraint_unsafe");
+
+    let violations = checker.check_file(Utf8Path::new(&path)).unwrap();
+
+    assert_eq!(violations.len(), 7, "Expected 7 violations");
+    assert_eq!(violations[0].operation, "ADD UNIQUE constraint");
     assert_eq!(violations[1].operation, "Unnamed constraint");
-    assert_eq!(violations[2].operation, "Unnamed constraint");
-    assert_eq!(violations[3].operation, "Unnamed constraint");
+}
+
+#[test]
+fn test_unnamed_constraint_in_create_table_detected() {
+    let path = fixture_path("unnamed_constraint_create_table_unsafe");
+    let mut config = diesel_guard::Config::default();
+    config.checks.unnamed_constraint.include_create_table = true;
+
+    let violations = SafetyChecker::with_config(config)
+        .check_file(Utf8Path::new(&path))
+        .unwrap();
+
+    assert_eq!(violations.len(), 2, "Expected 2 violations");
+    assert!(violations[0].problem.contains("unnamed UNIQUE constraint"));
+    assert!(violations[1].problem.contains("unnamed CHECK constraint"));
+}
+
+#[test]
+fn test_unnamed_constraint_in_create_table_excluded_by_default() {
+    let checker = SafetyChecker::new();
+    let path = fixture_path("unnamed_constraint_create_table_unsafe");
+
+    let violations = checker.check_file(Utf8Path::new(&path)).unwrap();
+
+    assert_eq!(violations.len(), 0, "Expected no violations");
+}
+
+#[test]
+fn test_add_check_constraint_detected() {
+    let checker = SafetyChecker::new();
+    let path = fixture_path("add_check_constraint_unsafe");
+
+    let violations = checker.check_file(Utf8Path::new(&path)).unwrap();
+
+    assert_eq!(violations.len(), 1, "Expected 1 violation");
+    assert_eq!(violations[0].operation, "ADD CHECK constraint");
+}
+
+#[test]
+fn test_add_foreign_key_detected() {
+    let checker = SafetyChecker::new();
+    let path = fixture_path("add_foreign_key_unsafe");
+
+    let violations = checker.check_file(Utf8Path::new(&path)).unwrap();
+
+    // The missing index on the referencing column is also reported, as a warning
+    assert_eq!(violations.len(), 2, "Expected 2 violations");
+    assert_eq!(violations[0].operation, "ADD FOREIGN KEY");
+    assert_eq!(violations[1].operation, "FOREIGN KEY without index");
 }
 
 #[test]
@@ -309,12 +428,11 @@ fn test_short_int_pk_unsafe_detected() {
 
     let violations = checker.check_file(Utf8Path::new(&path)).unwrap();
 
-    // Expected 5 violations:
-    // - 4 from ShortIntegerPrimaryKeyCheck (INT and SMALLINT PKs)
-    // - 1 from AddPrimaryKeyCheck (ALTER TABLE ADD PRIMARY KEY with INT)
+    // Expected 5 violations from ShortIntegerPrimaryKeyCheck (INT, SMALLINT and SERIAL PKs).
+    // AddPrimaryKeyCheck skips the ALTER TABLE ADD PRIMARY KEY, since products is
+    // created in the same migration.
     assert_eq!(violations.len(), 5, "Expected 5 violations");
 
-    // Check that we have violations from both checks
     let short_int_violations: Vec<_> = violations
         .iter()
         .filter(|v| v.operation == "Short integer primary key")
@@ -326,16 +444,32 @@ fn test_short_int_pk_unsafe_detected() {
 
     assert_eq!(
         short_int_violations.len(),
-        4,
-        "Expected 4 short int PK violations"
+        5,
+        "Expected 5 short int PK violations"
     );
-    assert_eq!(
-        add_pk_violations.len(),
-        1,
-        "Expected 1 ADD PRIMARY KEY violation"
+    assert!(
+        add_pk_violations.is_empty(),
+        "Expected no ADD PRIMARY KEY violation"
     );
 }
 
+#[test]
+fn test_short_int_pk_allowed_tables() {
+    let path = fixture_path("short_int_pk_unsafe");
+    let mut config = diesel_guard::Config::default();
+    config.checks.short_integer_primary_key.allowed_tables =
+        vec!["posts".to_string(), "tags".to_string()];
+
+    let violations = SafetyChecker::with_config(config)
+        .check_file(Utf8Path::new(&path))
+        .unwrap();
+
+    assert_eq!(violations.len(), 3, "Expected 3 violations");
+    assert!(violations
+        .iter()
+        .all(|v| !v.problem.contains("'posts'") && !v.problem.contains("'tags'")));
+}
+
 #[test]
 fn test_truncate_table_detected() {
     let checker = SafetyChecker::new();
@@ -348,57 +482,989 @@ fn test_truncate_table_detected() {
 }
 
 #[test]
-fn test_wide_index_detected() {
+fn test_drop_table_detected() {
     let checker = SafetyChecker::new();
-    let path = fixture_path("wide_index_unsafe");
+    let path = fixture_path("drop_table_unsafe");
 
     let violations = checker.check_file(Utf8Path::new(&path)).unwrap();
 
     assert_eq!(violations.len(), 1, "Expected 1 violation");
-    assert_eq!(violations[0].operation, "Wide index");
+    assert_eq!(violations[0].operation, "DROP TABLE");
 }
 
 #[test]
-fn test_add_primary_key_detected() {
+fn test_vacuum_full_detected() {
     let checker = SafetyChecker::new();
-    let path = fixture_path("add_primary_key_unsafe");
+    let path = fixture_path("vacuum_full_unsafe");
 
     let violations = checker.check_file(Utf8Path::new(&path)).unwrap();
 
     assert_eq!(violations.len(), 1, "Expected 1 violation");
-    assert_eq!(violations[0].operation, "ADD PRIMARY KEY");
+    assert_eq!(violations[0].operation, "VACUUM FULL");
 }
 
 #[test]
-fn test_drop_primary_key_detected() {
+fn test_cluster_detected() {
     let checker = SafetyChecker::new();
-    let path = fixture_path("drop_primary_key_unsafe");
+    let path = fixture_path("cluster_unsafe");
 
     let violations = checker.check_file(Utf8Path::new(&path)).unwrap();
 
     assert_eq!(violations.len(), 1, "Expected 1 violation");
-    assert_eq!(violations[0].operation, "DROP PRIMARY KEY");
+    assert_eq!(violations[0].operation, "CLUSTER");
 }
 
 #[test]
-fn test_check_entire_fixtures_directory() {
+fn test_reindex_detected() {
     let checker = SafetyChecker::new();
-    let results = checker
-        .check_directory(Utf8Path::new("tests/fixtures"))
-        .unwrap();
+    let path = fixture_path("reindex_unsafe");
 
-    let total_violations: usize = results.iter().map(|(_, v)| v.len()).sum();
+    let violations = checker.check_file(Utf8Path::new(&path)).unwrap();
+
+    assert_eq!(violations.len(), 1, "Expected 1 violation");
+    assert_eq!(violations[0].operation, "REINDEX without CONCURRENTLY");
+}
+
+#[test]
+fn test_refresh_materialized_view_detected() {
+    let checker = SafetyChecker::new();
+    let path = fixture_path("refresh_materialized_view_unsafe");
+
+    let violations = checker.check_file(Utf8Path::new(&path)).unwrap();
 
+    assert_eq!(violations.len(), 1, "Expected 1 violation");
     assert_eq!(
-        results.len(),
-        22,
-        "Expected violations in 22 files, got {}",
-        results.len()
+        violations[0].operation,
+        "REFRESH MATERIALIZED VIEW without CONCURRENTLY"
     );
+}
+
+#[test]
+fn test_dml_in_migration_detected_when_enabled() {
+    let path = fixture_path("dml_in_migration_unsafe");
+
+    // Opt-in: nothing is reported by default
+    let violations = SafetyChecker::new()
+        .check_file(Utf8Path::new(&path))
+        .unwrap();
+    assert!(violations.is_empty());
+
+    let config = diesel_guard::Config {
+        enable_checks: vec!["DmlInMigrationCheck".to_string()],
+        ..Default::default()
+    };
+    let violations = SafetyChecker::with_config(config)
+        .check_file(Utf8Path::new(&path))
+        .unwrap();
+
+    assert_eq!(violations.len(), 1, "Expected 1 violation");
+    assert_eq!(violations[0].operation, "UPDATE in migration");
+}
+
+#[test]
+fn test_mixed_ddl_dml_detected() {
+    let checker = SafetyChecker::new();
+    let path = fixture_path("mixed_ddl_dml_unsafe");
+
+    let violations = checker.check_file(Utf8Path::new(&path)).unwrap();
+
+    assert_eq!(violations.len(), 1, "Expected 1 violation");
+    assert_eq!(violations[0].operation, "DDL and DML in one migration");
+}
+
+#[test]
+fn test_set_tablespace_detected() {
+    let checker = SafetyChecker::new();
+    let path = fixture_path("set_tablespace_unsafe");
+
+    let violations = checker.check_file(Utf8Path::new(&path)).unwrap();
+
+    assert_eq!(violations.len(), 1, "Expected 1 violation");
+    assert_eq!(violations[0].operation, "SET TABLESPACE");
+}
+
+#[test]
+fn test_create_table_as_detected() {
+    let checker = SafetyChecker::new();
+    let path = fixture_path("create_table_as_unsafe");
+
+    let violations = checker.check_file(Utf8Path::new(&path)).unwrap();
+
+    assert_eq!(violations.len(), 1, "Expected 1 violation");
+    assert_eq!(violations[0].operation, "CREATE TABLE AS");
+}
+
+#[test]
+fn test_volatile_default_detected() {
+    let checker = SafetyChecker::new();
+    let path = fixture_path("volatile_default_unsafe");
+
+    let violations = checker.check_file(Utf8Path::new(&path)).unwrap();
+
+    // The ADD COLUMN with DEFAULT finding is merged in as a note
+    assert_eq!(violations.len(), 1, "Expected 1 violation");
+    assert_eq!(violations[0].operation, "ADD COLUMN with volatile DEFAULT");
+    assert_eq!(violations[0].notes.len(), 1);
+}
+
+#[test]
+fn test_add_exclusion_constraint_detected() {
+    let checker = SafetyChecker::new();
+    let path = fixture_path("add_exclusion_constraint_unsafe");
+
+    let violations = checker.check_file(Utf8Path::new(&path)).unwrap();
+
+    assert_eq!(violations.len(), 1, "Expected 1 violation");
+    assert_eq!(violations[0].operation, "ADD EXCLUDE constraint");
+    assert!(!violations[0].is_error());
+}
+
+#[test]
+fn test_create_trigger_detected() {
+    let checker = SafetyChecker::new();
+    let path = fixture_path("create_trigger_unsafe");
+
+    let violations = checker.check_file(Utf8Path::new(&path)).unwrap();
+
+    assert_eq!(violations.len(), 1, "Expected 1 violation");
+    assert_eq!(violations[0].operation, "CREATE TRIGGER");
+    assert!(!violations[0].is_error());
+}
+
+#[test]
+fn test_drop_constraint_cascade_detected() {
+    let checker = SafetyChecker::new();
+    let path = fixture_path("drop_constraint_cascade_unsafe");
+
+    let violations = checker.check_file(Utf8Path::new(&path)).unwrap();
+
+    assert_eq!(violations.len(), 1, "Expected 1 violation");
+    assert_eq!(violations[0].operation, "DROP CONSTRAINT CASCADE");
+}
+
+#[test]
+fn test_cascading_delete_detected() {
+    let checker = SafetyChecker::new();
+    let path = fixture_path("cascading_delete_unsafe");
+
+    let violations = checker.check_file(Utf8Path::new(&path)).unwrap();
+
+    // The missing index on the referencing column is also reported, as a warning
+    assert_eq!(violations.len(), 2, "Expected 2 violations");
+    assert_eq!(violations[0].operation, "ON DELETE CASCADE");
+    assert_eq!(violations[1].operation, "FOREIGN KEY without index");
+}
+
+#[test]
+fn test_hash_index_detected() {
+    let checker = SafetyChecker::new();
+    let path = fixture_path("hash_index_unsafe");
+
+    let violations = checker.check_file(Utf8Path::new(&path)).unwrap();
+
+    assert_eq!(violations.len(), 1, "Expected 1 violation");
+    assert_eq!(violations[0].operation, "CREATE INDEX USING hash");
+}
+
+#[test]
+fn test_add_identity_column_detected() {
+    let checker = SafetyChecker::new();
+    let path = fixture_path("add_identity_column_unsafe");
+
+    let violations = checker.check_file(Utf8Path::new(&path)).unwrap();
+
+    assert_eq!(violations.len(), 1, "Expected 1 violation");
+    assert_eq!(violations[0].operation, "ADD COLUMN with IDENTITY");
+}
+
+#[test]
+fn test_set_logged_detected() {
+    let checker = SafetyChecker::new();
+    let path = fixture_path("set_logged_unsafe");
+
+    let violations = checker.check_file(Utf8Path::new(&path)).unwrap();
 
+    assert_eq!(violations.len(), 1, "Expected 1 violation");
+    assert_eq!(violations[0].operation, "SET UNLOGGED");
+}
+
+#[test]
+fn test_alter_type_add_value_detected() {
+    let checker = SafetyChecker::new();
+    let path = fixture_path("alter_type_add_value_unsafe");
+
+    let violations = checker.check_file(Utf8Path::new(&path)).unwrap();
+
+    assert_eq!(violations.len(), 1, "Expected 1 violation");
+    assert_eq!(violations[0].operation, "ALTER TYPE ADD VALUE");
+}
+
+#[test]
+fn test_rename_enum_value_detected() {
+    let checker = SafetyChecker::new();
+    let path = fixture_path("rename_enum_value_unsafe");
+
+    let violations = checker.check_file(Utf8Path::new(&path)).unwrap();
+
+    assert_eq!(violations.len(), 1, "Expected 1 violation");
+    assert_eq!(violations[0].operation, "RENAME enum VALUE");
+}
+
+#[test]
+fn test_enum_rebuild_detected() {
+    let checker = SafetyChecker::new();
+    let path = fixture_path("enum_rebuild_unsafe");
+
+    let violations = checker.check_file(Utf8Path::new(&path)).unwrap();
+
+    // The column change is also reported by AlterColumnTypeCheck
+    assert_eq!(violations.len(), 2, "Expected 2 violations");
+    assert_eq!(violations[0].operation, "ALTER COLUMN TYPE");
+    assert_eq!(violations[1].operation, "enum rebuild");
+}
+
+#[test]
+fn test_add_column_inline_constraints_detected() {
+    let checker = SafetyChecker::new();
+    let path = fixture_path("add_column_inline_constraints_unsafe");
+
+    let violations = checker.check_file(Utf8Path::new(&path)).unwrap();
+
+    // The missing index on author_id is also reported, as a warning
+    assert_eq!(violations.len(), 3, "Expected 3 violations");
+    assert_eq!(violations[0].operation, "ADD UNIQUE constraint");
+    assert_eq!(violations[1].operation, "ADD FOREIGN KEY");
+    assert_eq!(violations[2].operation, "FOREIGN KEY without index");
+}
+
+#[test]
+fn test_timestamp_without_time_zone_detected_when_enabled() {
+    let path = fixture_path("timestamp_without_time_zone_unsafe");
+
+    // Opt-in: nothing is reported by default
+    let violations = SafetyChecker::new()
+        .check_file(Utf8Path::new(&path))
+        .unwrap();
+    assert!(violations.is_empty());
+
+    let config = diesel_guard::Config {
+        enable_checks: vec!["TimestampWithoutTimeZoneCheck".to_string()],
+        ..Default::default()
+    };
+    let violations = SafetyChecker::with_config(config)
+        .check_file(Utf8Path::new(&path))
+        .unwrap();
+
+    assert_eq!(violations.len(), 1, "Expected 1 violation");
     assert_eq!(
-        total_violations, 30,
-        "Expected 30 total violations: 19 files with 1 each, drop_multiple_columns with 2, unnamed_constraint_unsafe with 4, short_int_pk_unsafe with 5 (4 short int + 1 add pk), got {}",
-        total_violations
+        violations[0].operation,
+        "TIMESTAMP WITHOUT TIME ZONE column"
     );
 }
+
+#[test]
+fn test_char_type_detected_when_enabled() {
+    let path = fixture_path("char_type_unsafe");
+
+    // Opt-in: nothing is reported by default
+    let violations = SafetyChecker::new()
+        .check_file(Utf8Path::new(&path))
+        .unwrap();
+    assert!(violations.is_empty());
+
+    let config = diesel_guard::Config {
+        enable_checks: vec!["CharTypeCheck".to_string()],
+        ..Default::default()
+    };
+    let violations = SafetyChecker::with_config(config)
+        .check_file(Utf8Path::new(&path))
+        .unwrap();
+
+    assert_eq!(violations.len(), 1, "Expected 1 violation");
+    assert_eq!(violations[0].operation, "CHAR(n) column");
+}
+
+#[test]
+fn test_money_type_detected_when_enabled() {
+    let path = fixture_path("money_type_unsafe");
+
+    // Opt-in: nothing is reported by default
+    let violations = SafetyChecker::new()
+        .check_file(Utf8Path::new(&path))
+        .unwrap();
+    assert!(violations.is_empty());
+
+    let config = diesel_guard::Config {
+        enable_checks: vec!["MoneyTypeCheck".to_string()],
+        ..Default::default()
+    };
+    let violations = SafetyChecker::with_config(config)
+        .check_file(Utf8Path::new(&path))
+        .unwrap();
+
+    assert_eq!(violations.len(), 1, "Expected 1 violation");
+    assert_eq!(violations[0].operation, "MONEY column");
+}
+
+#[test]
+fn test_serial_vs_identity_detected_when_enabled() {
+    let path = fixture_path("serial_vs_identity_unsafe");
+
+    // Opt-in: nothing is reported by default
+    let violations = SafetyChecker::new()
+        .check_file(Utf8Path::new(&path))
+        .unwrap();
+    assert!(violations.is_empty());
+
+    let config = diesel_guard::Config {
+        enable_checks: vec!["SerialVsIdentityCheck".to_string()],
+        postgres_version: Some(16),
+        ..Default::default()
+    };
+    let violations = SafetyChecker::with_config(config)
+        .check_file(Utf8Path::new(&path))
+        .unwrap();
+
+    assert_eq!(violations.len(), 1, "Expected 1 violation");
+    assert_eq!(violations[0].operation, "SERIAL column");
+}
+
+#[test]
+fn test_delete_without_where_detected() {
+    let checker = SafetyChecker::new();
+    let path = fixture_path("delete_without_where_unsafe");
+
+    let violations = checker.check_file(Utf8Path::new(&path)).unwrap();
+
+    assert_eq!(violations.len(), 1, "Expected 1 violation");
+    assert_eq!(violations[0].operation, "DELETE without WHERE");
+}
+
+#[test]
+fn test_update_without_where_detected() {
+    let checker = SafetyChecker::new();
+    let path = fixture_path("update_without_where_unsafe");
+
+    let violations = checker.check_file(Utf8Path::new(&path)).unwrap();
+
+    assert_eq!(violations.len(), 1, "Expected 1 violation");
+    assert_eq!(violations[0].operation, "UPDATE without WHERE");
+}
+
+#[test]
+fn test_insert_select_detected() {
+    let checker = SafetyChecker::new();
+    let path = fixture_path("insert_select_unsafe");
+
+    let violations = checker.check_file(Utf8Path::new(&path)).unwrap();
+
+    assert_eq!(violations.len(), 1, "Expected 1 violation");
+    assert_eq!(violations[0].operation, "INSERT ... SELECT");
+}
+
+#[test]
+fn test_create_materialized_view_detected() {
+    let checker = SafetyChecker::new();
+    let path = fixture_path("create_materialized_view_unsafe");
+
+    let violations = checker.check_file(Utf8Path::new(&path)).unwrap();
+
+    // Both the implicit and the explicit WITH DATA form, but not WITH NO DATA
+    assert_eq!(violations.len(), 2, "Expected 2 violations");
+    assert!(violations
+        .iter()
+        .all(|v| v.operation == "CREATE MATERIALIZED VIEW WITH DATA"));
+}
+
+#[test]
+fn test_disable_trigger_all_detected() {
+    let checker = SafetyChecker::new();
+    let path = fixture_path("disable_trigger_unsafe");
+
+    let violations = checker.check_file(Utf8Path::new(&path)).unwrap();
+
+    assert_eq!(violations.len(), 1, "Expected 1 violation");
+    assert_eq!(violations[0].operation, "DISABLE TRIGGER ALL");
+    assert!(violations[0].is_error());
+}
+
+#[test]
+fn test_foreign_key_without_index_detected() {
+    let checker = SafetyChecker::new();
+    let path = fixture_path("foreign_key_without_index_unsafe");
+
+    let violations = checker.check_file(Utf8Path::new(&path)).unwrap();
+
+    assert_eq!(violations.len(), 1, "Expected 1 violation");
+    assert_eq!(violations[0].operation, "FOREIGN KEY without index");
+    assert_eq!(violations[0].severity, diesel_guard::Severity::Warning);
+}
+
+#[test]
+fn test_duplicate_index_detected() {
+    let checker = SafetyChecker::new();
+    let path = fixture_path("duplicate_index_unsafe");
+
+    let violations = checker.check_file(Utf8Path::new(&path)).unwrap();
+
+    assert_eq!(violations.len(), 1, "Expected 1 violation");
+    assert_eq!(violations[0].operation, "duplicate index");
+}
+
+#[test]
+fn test_lock_heavy_migration_detected() {
+    let checker = SafetyChecker::new();
+    let path = fixture_path("lock_heavy_migration_unsafe");
+
+    let violations = checker.check_file(Utf8Path::new(&path)).unwrap();
+
+    assert_eq!(violations.len(), 1, "Expected 1 violation");
+    assert_eq!(violations[0].operation, "multiple locking operations");
+
+    // Within the configured limit, nothing is reported
+    let mut config = diesel_guard::Config::default();
+    config.checks.lock_heavy_migration.max_operations = 4;
+    let violations = SafetyChecker::with_config(config)
+        .check_file(Utf8Path::new(&path))
+        .unwrap();
+    assert!(violations.is_empty());
+}
+
+#[test]
+fn test_row_level_security_detected() {
+    let checker = SafetyChecker::new();
+    let path = fixture_path("row_level_security_unsafe");
+
+    let violations = checker.check_file(Utf8Path::new(&path)).unwrap();
+
+    // FORCE is checked as source text, in the context of the whole migration
+    assert_eq!(violations.len(), 2, "Expected 2 violations");
+    assert_eq!(violations[0].operation, "ENABLE ROW LEVEL SECURITY");
+    assert_eq!(violations[1].operation, "FORCE ROW LEVEL SECURITY");
+}
+
+#[test]
+fn test_missing_lock_timeout_detected_when_enabled() {
+    let path = fixture_path("missing_lock_timeout_unsafe");
+
+    // Opt-in: nothing is reported by default
+    let violations = SafetyChecker::new()
+        .check_file(Utf8Path::new(&path))
+        .unwrap();
+    assert!(violations.is_empty());
+
+    let config = diesel_guard::Config {
+        enable_checks: vec!["MissingLockTimeoutCheck".to_string()],
+        ..Default::default()
+    };
+    let violations = SafetyChecker::with_config(config)
+        .check_file(Utf8Path::new(&path))
+        .unwrap();
+
+    assert_eq!(violations.len(), 1, "Expected 1 violation");
+    assert_eq!(violations[0].operation, "missing lock timeout");
+}
+
+#[test]
+fn test_concurrent_index_in_transaction_detected() {
+    let checker = SafetyChecker::new();
+    let path = fixture_path("concurrent_index_in_transaction_unsafe");
+
+    let violations = checker.check_file(Utf8Path::new(&path)).unwrap();
+
+    assert_eq!(violations.len(), 1, "Expected 1 violation");
+    assert_eq!(violations[0].operation, "CONCURRENTLY in transaction");
+
+    // Without a migration directory, whether it runs in a transaction is unknown
+    let sql = std::fs::read_to_string(&path).unwrap();
+    assert!(checker.check_sql(&sql).unwrap().is_empty());
+}
+
+#[test]
+fn test_drop_database_detected() {
+    let path = fixture_path("drop_database_unsafe");
+
+    // Always an error, even when configured as a warning
+    let mut config = diesel_guard::Config::default();
+    config.severity.insert(
+        "DropDatabaseCheck".to_string(),
+        diesel_guard::Severity::Warning,
+    );
+    let violations = SafetyChecker::with_config(config)
+        .check_file(Utf8Path::new(&path))
+        .unwrap();
+
+    assert_eq!(violations.len(), 1, "Expected 1 violation");
+    assert_eq!(violations[0].operation, "DROP DATABASE");
+    assert_eq!(violations[0].severity, diesel_guard::Severity::Error);
+}
+
+#[test]
+fn test_unlogged_table_detected_when_enabled() {
+    let path = fixture_path("unlogged_table_unsafe");
+
+    // Opt-in: nothing is reported by default
+    let violations = SafetyChecker::new()
+        .check_file(Utf8Path::new(&path))
+        .unwrap();
+    assert!(violations.is_empty());
+
+    let config = diesel_guard::Config {
+        enable_checks: vec!["UnloggedTableCheck".to_string()],
+        ..Default::default()
+    };
+    let violations = SafetyChecker::with_config(config)
+        .check_file(Utf8Path::new(&path))
+        .unwrap();
+
+    assert_eq!(violations.len(), 1, "Expected 1 violation");
+    assert_eq!(violations[0].operation, "CREATE UNLOGGED TABLE");
+}
+
+#[test]
+fn test_grant_revoke_detected() {
+    let checker = SafetyChecker::new();
+    let path = fixture_path("grant_revoke_unsafe");
+
+    let violations = checker.check_file(Utf8Path::new(&path)).unwrap();
+
+    assert_eq!(violations.len(), 1, "Expected 1 violation");
+    assert_eq!(violations[0].operation, "GRANT");
+}
+
+#[test]
+fn test_create_role_detected() {
+    let checker = SafetyChecker::new();
+    let path = fixture_path("create_role_unsafe");
+
+    let violations = checker.check_file(Utf8Path::new(&path)).unwrap();
+
+    assert_eq!(violations.len(), 1, "Expected 1 violation");
+    assert_eq!(violations[0].operation, "CREATE USER WITH PASSWORD");
+}
+
+#[test]
+fn test_role_management_detected() {
+    let checker = SafetyChecker::new();
+    let path = fixture_path("role_management_unsafe");
+
+    let violations = checker.check_file(Utf8Path::new(&path)).unwrap();
+
+    assert_eq!(violations.len(), 1, "Expected 1 violation");
+    assert_eq!(violations[0].operation, "ALTER ROLE");
+}
+
+#[test]
+fn test_add_not_null_column_detected() {
+    let checker = SafetyChecker::new();
+    let path = fixture_path("add_not_null_column_unsafe");
+
+    let violations = checker.check_file(Utf8Path::new(&path)).unwrap();
+
+    assert_eq!(violations.len(), 1, "Expected 1 violation");
+    assert_eq!(
+        violations[0].operation,
+        "ADD COLUMN NOT NULL without DEFAULT"
+    );
+}
+
+#[test]
+fn test_non_idempotent_migration_detected_when_enabled() {
+    let path = fixture_path("non_idempotent_migration_unsafe");
+
+    // Opt-in: nothing is reported by default
+    let violations = SafetyChecker::new()
+        .check_file(Utf8Path::new(&path))
+        .unwrap();
+    assert!(violations.is_empty());
+
+    let config = diesel_guard::Config {
+        enable_checks: vec!["NonIdempotentMigrationCheck".to_string()],
+        ..Default::default()
+    };
+    let violations = SafetyChecker::with_config(config)
+        .check_file(Utf8Path::new(&path))
+        .unwrap();
+
+    assert_eq!(violations.len(), 1, "Expected 1 violation");
+    assert_eq!(
+        violations[0].operation,
+        "CREATE TABLE without IF NOT EXISTS"
+    );
+}
+
+#[test]
+fn test_prefer_text_over_varchar_detected_when_enabled() {
+    let path = fixture_path("prefer_text_over_varchar_unsafe");
+
+    // Opt-in: nothing is reported by default
+    let violations = SafetyChecker::new()
+        .check_file(Utf8Path::new(&path))
+        .unwrap();
+    assert!(violations.is_empty());
+
+    let mut config = diesel_guard::Config {
+        enable_checks: vec!["PreferTextOverVarcharCheck".to_string()],
+        ..Default::default()
+    };
+    let violations = SafetyChecker::with_config(config.clone())
+        .check_file(Utf8Path::new(&path))
+        .unwrap();
+
+    assert_eq!(violations.len(), 1, "Expected 1 violation");
+    assert_eq!(violations[0].operation, "VARCHAR(n) column");
+
+    config.checks.prefer_text_over_varchar.allowed_lengths = vec![120];
+    let violations = SafetyChecker::with_config(config)
+        .check_file(Utf8Path::new(&path))
+        .unwrap();
+    assert!(violations.is_empty());
+}
+
+#[test]
+fn test_unbounded_text_index_detected() {
+    let checker = SafetyChecker::new();
+    let path = fixture_path("unbounded_text_index_unsafe");
+
+    let violations = checker.check_file(Utf8Path::new(&path)).unwrap();
+
+    assert_eq!(violations.len(), 1, "Expected 1 violation");
+    assert_eq!(violations[0].operation, "btree index on unbounded text");
+    assert_eq!(violations[0].severity, diesel_guard::Severity::Warning);
+}
+
+#[test]
+fn test_excessive_indexes_detected() {
+    let path = fixture_path("excessive_indexes_unsafe");
+
+    let violations = SafetyChecker::new()
+        .check_file(Utf8Path::new(&path))
+        .unwrap();
+
+    assert_eq!(violations.len(), 1, "Expected 1 violation");
+    assert_eq!(violations[0].operation, "too many indexes");
+    assert_eq!(violations[0].severity, diesel_guard::Severity::Warning);
+
+    let mut config = diesel_guard::Config::default();
+    config.checks.excessive_indexes.max_indexes = 9;
+    let violations = SafetyChecker::with_config(config)
+        .check_file(Utf8Path::new(&path))
+        .unwrap();
+    assert!(violations.is_empty());
+}
+
+#[test]
+fn test_excessive_indexes_counts_earlier_migrations() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let migrations = Utf8Path::from_path(temp_dir.path()).unwrap();
+    for (name, sql) in [
+        (
+            "2024_01_01_000000_create_reports",
+            "CREATE TABLE reports (id BIGINT PRIMARY KEY, account_id BIGINT, kind TEXT);
+CREATE INDEX reports_account_id_idx ON reports (account_id);",
+        ),
+        (
+            "2024_02_01_000000_index_reports",
+            "CREATE INDEX CONCURRENTLY reports_kind_idx ON reports (kind);
+CREATE INDEX CONCURRENTLY reports_account_id_kind_idx ON reports (account_id, kind);",
+        ),
+    ] {
+        std::fs::create_dir(migrations.join(name)).unwrap();
+        std::fs::write(migrations.join(name).join("up.sql"), sql).unwrap();
+    }
+
+    let mut config = diesel_guard::Config::default();
+    config.checks.excessive_indexes.max_indexes = 3;
+    let results = SafetyChecker::with_config(config)
+        .check_directory(migrations)
+        .unwrap();
+
+    assert_eq!(results.len(), 1);
+    assert!(results[0].0.contains("2024_02_01_000000_index_reports"));
+    let excessive: Vec<_> = results[0]
+        .1
+        .iter()
+        .filter(|v| v.operation == "too many indexes")
+        .collect();
+    assert_eq!(excessive.len(), 1);
+    assert!(excessive[0]
+        .problem
+        .contains("has 4 indexes after this migration (2 created by it)"));
+}
+
+#[test]
+fn test_partitioned_table_index_detected() {
+    let checker = SafetyChecker::new();
+    let path = fixture_path("partitioned_table_index_unsafe");
+
+    let violations = checker.check_file(Utf8Path::new(&path)).unwrap();
+
+    assert_eq!(violations.len(), 1, "Expected 1 violation");
+    assert_eq!(
+        violations[0].operation,
+        "CREATE INDEX CONCURRENTLY on partitioned table"
+    );
+}
+
+#[test]
+fn test_partitioned_table_from_earlier_migration_detected() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let migrations = Utf8Path::from_path(temp_dir.path()).unwrap();
+    for (name, sql) in [
+        (
+            "2024_01_01_000000_create_measurements",
+            "CREATE TABLE measurements (recorded_at TIMESTAMPTZ NOT NULL) PARTITION BY RANGE (recorded_at);",
+        ),
+        (
+            "2024_02_01_000000_index_measurements",
+            "CREATE INDEX measurements_recorded_at_idx ON measurements (recorded_at);",
+        ),
+    ] {
+        std::fs::create_dir(migrations.join(name)).unwrap();
+        std::fs::write(migrations.join(name).join("up.sql"), sql).unwrap();
+    }
+
+    let results = SafetyChecker::new().check_directory(migrations).unwrap();
+    assert_eq!(results.len(), 1);
+    assert!(results[0]
+        .0
+        .contains("2024_02_01_000000_index_measurements"));
+    assert!(results[0]
+        .1
+        .iter()
+        .any(|v| v.operation == "CREATE INDEX on partitioned table"));
+
+    // A single file sees the migrations before it, too
+    let violations = SafetyChecker::new()
+        .check_file(&migrations.join("2024_02_01_000000_index_measurements/up.sql"))
+        .unwrap();
+    assert!(violations
+        .iter()
+        .any(|v| v.operation == "CREATE INDEX on partitioned table"));
+}
+
+#[test]
+fn test_not_null_check_from_earlier_migration_allows_set_not_null() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let migrations = Utf8Path::from_path(temp_dir.path()).unwrap();
+    for (name, sql) in [
+        (
+            "2024_01_01_000000_check_email",
+            "ALTER TABLE users ADD CONSTRAINT users_email_not_null CHECK (email IS NOT NULL) NOT VALID;",
+        ),
+        (
+            "2024_01_02_000000_validate_email",
+            "ALTER TABLE users VALIDATE CONSTRAINT users_email_not_null;",
+        ),
+        (
+            "2024_01_03_000000_email_not_null",
+            "ALTER TABLE users ALTER COLUMN email SET NOT NULL;",
+        ),
+    ] {
+        std::fs::create_dir(migrations.join(name)).unwrap();
+        std::fs::write(migrations.join(name).join("up.sql"), sql).unwrap();
+    }
+
+    let checker = || {
+        SafetyChecker::with_config(diesel_guard::Config {
+            postgres_version: Some(16),
+            ..Default::default()
+        })
+    };
+    let results = checker().check_directory(migrations).unwrap();
+    assert!(results.is_empty());
+
+    // Without the VALIDATE migration, the scan is still needed
+    std::fs::remove_dir_all(migrations.join("2024_01_02_000000_validate_email")).unwrap();
+    let violations = checker()
+        .check_file(&migrations.join("2024_01_03_000000_email_not_null/up.sql"))
+        .unwrap();
+    assert_eq!(violations.len(), 1);
+    assert_eq!(violations[0].operation, "ADD NOT NULL constraint");
+}
+
+#[test]
+fn test_unique_index_from_earlier_migration_attached_as_constraint() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let migrations = Utf8Path::from_path(temp_dir.path()).unwrap();
+    for (name, sql) in [
+        (
+            "2024_01_01_000000_create_accounts",
+            "CREATE TABLE accounts (id BIGINT, email TEXT);",
+        ),
+        (
+            "2024_02_01_000000_index_accounts",
+            "CREATE UNIQUE INDEX CONCURRENTLY accounts_id_idx ON accounts (id);
+CREATE UNIQUE INDEX CONCURRENTLY accounts_email_idx ON accounts (email);",
+        ),
+        (
+            "2024_02_02_000000_attach_indexes",
+            "ALTER TABLE accounts ADD CONSTRAINT accounts_identity PRIMARY KEY USING INDEX accounts_id_idx;
+ALTER TABLE accounts ADD CONSTRAINT accounts_email_key UNIQUE USING INDEX accounts_email_idx;",
+        ),
+        (
+            "2024_03_01_000000_drop_identity",
+            "ALTER TABLE accounts DROP CONSTRAINT accounts_identity;",
+        ),
+    ] {
+        std::fs::create_dir(migrations.join(name)).unwrap();
+        std::fs::write(migrations.join(name).join("up.sql"), sql).unwrap();
+    }
+
+    let results = SafetyChecker::new().check_directory(migrations).unwrap();
+    let files: Vec<&str> = results.iter().map(|(file, _)| file.as_str()).collect();
+    assert!(
+        !files
+            .iter()
+            .any(|file| file.contains("2024_02_02_000000_attach_indexes")),
+        "attaching concurrently built indexes is safe: {:?}",
+        results
+    );
+
+    // The constraint attached in the earlier migration is known as the primary key
+    let violations = SafetyChecker::new()
+        .check_file(&migrations.join("2024_03_01_000000_drop_identity/up.sql"))
+        .unwrap();
+    assert!(violations.iter().any(|v| v.operation == "DROP PRIMARY KEY"));
+}
+
+#[test]
+fn test_primary_key_from_earlier_migration_detected_by_name() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let migrations = Utf8Path::from_path(temp_dir.path()).unwrap();
+    for (name, sql) in [
+        (
+            "2024_01_01_000000_create_accounts",
+            "CREATE TABLE accounts (id BIGINT, token_pk TEXT, CONSTRAINT accounts_identity PRIMARY KEY (id), CONSTRAINT accounts_token_pk UNIQUE (token_pk));",
+        ),
+        (
+            "2024_02_01_000000_drop_constraints",
+            "ALTER TABLE accounts DROP CONSTRAINT accounts_token_pk;
+ALTER TABLE accounts DROP CONSTRAINT accounts_identity;",
+        ),
+    ] {
+        std::fs::create_dir(migrations.join(name)).unwrap();
+        std::fs::write(migrations.join(name).join("up.sql"), sql).unwrap();
+    }
+
+    let violations = SafetyChecker::new()
+        .check_file(&migrations.join("2024_02_01_000000_drop_constraints/up.sql"))
+        .unwrap();
+
+    assert_eq!(violations.len(), 1, "Expected 1 violation");
+    assert_eq!(violations[0].operation, "DROP PRIMARY KEY");
+    assert!(violations[0].problem.contains("'accounts_identity'"));
+}
+
+#[test]
+fn test_volatile_check_constraint_detected() {
+    let checker = SafetyChecker::new();
+    let path = fixture_path("volatile_check_constraint_unsafe");
+
+    let violations = checker.check_file(Utf8Path::new(&path)).unwrap();
+
+    assert_eq!(violations.len(), 1, "Expected 1 violation");
+    assert_eq!(
+        violations[0].operation,
+        "CHECK constraint with volatile function"
+    );
+}
+
+#[test]
+fn test_wide_index_detected() {
+    let checker = SafetyChecker::new();
+    let path = fixture_path("wide_index_unsafe");
+
+    let violations = checker.check_file(Utf8Path::new(&path)).unwrap();
+
+    assert_eq!(violations.len(), 1, "Expected 1 violation");
+    assert_eq!(violations[0].operation, "Wide index");
+}
+
+#[test]
+fn test_add_primary_key_detected() {
+    let checker = SafetyChecker::new();
+    let path = fixture_path("add_primary_key_unsafe");
+
+    let violations = checker.check_file(Utf8Path::new(&path)).unwrap();
+
+    assert_eq!(violations.len(), 1, "Expected 1 violation");
+    assert_eq!(violations[0].operation, "ADD PRIMARY KEY");
+}
+
+#[test]
+fn test_do_block_detected() {
+    let checker = SafetyChecker::new();
+    let path = fixture_path("do_block_unsafe");
+
+    let violations = checker.check_file(Utf8Path::new(&path)).unwrap();
+
+    assert_eq!(violations.len(), 1, "Expected 1 violation");
+    assert_eq!(violations[0].operation, "DROP COLUMN");
+    assert_eq!(violations[0].line, Some(10));
+}
+
+#[test]
+fn test_function_body_detected() {
+    let checker = SafetyChecker::new();
+    let path = fixture_path("function_body_unsafe");
+
+    let violations = checker.check_file(Utf8Path::new(&path)).unwrap();
+
+    assert_eq!(violations.len(), 1, "Expected 1 violation");
+    assert_eq!(violations[0].operation, "DROP COLUMN");
+    assert_eq!(violations[0].line, Some(4));
+}
+
+#[test]
+fn test_drop_primary_key_detected() {
+    let checker = SafetyChecker::new();
+    let path = fixture_path("drop_primary_key_unsafe");
+
+    let violations = checker.check_file(Utf8Path::new(&path)).unwrap();
+
+    assert_eq!(violations.len(), 1, "Expected 1 violation");
+    assert_eq!(violations[0].operation, "DROP PRIMARY KEY");
+}
+
+#[test]
+fn test_check_entire_fixtures_directory() {
+    let checker = SafetyChecker::new();
+    let results = checker
+        .check_directory(Utf8Path::new("tests/fixtures"))
+        .unwrap();
+
+    let total_violations: usize = results.iter().map(|(_, v)| v.len()).sum();
+
+    assert_eq!(
+        results.len(),
+        68,
+        "Expected violations in 68 files, got {}",
+        results.len()
+    );
+
+    assert_eq!(
+        total_violations, 83,
+        "Expected 83 total violations: 59 files with 1 each, drop_multiple_columns with 2, row_level_security_unsafe with 2, create_materialized_view_unsafe with 2, enum_rebuild_unsafe with 2, add_foreign_key_unsafe with 2, cascading_delete_unsafe with 2, add_column_inline_constraints_unsafe with 3, unnamed_constraint_unsafe with 4, short_int_pk_unsafe with 5, got {}",
+        total_violations
+    );
+}
+
+#[test]
+fn test_timings_for_fixtures_directory() {
+    let checker = SafetyChecker::new().with_timings();
+    checker
+        .check_directory(Utf8Path::new("tests/fixtures"))
+        .unwrap();
+
+    let timings = checker.timings().expect("timings should be enabled");
+
+    // Every checked file is timed, not only files with violations
+    assert!(timings.files.len() > 22);
+    assert!(timings
+        .files
+        .iter()
+        .any(|file| file.file == "tests/fixtures/drop_column/up.sql"));
+    assert!(timings.checks.contains_key("DropColumnCheck"));
+    assert!(timings.total_ms >= timings.parse_ms);
+}
@@ -289,7 +289,11 @@ ALTER TABLE users DROP COLUMN deprecated_c;
 #[test]
 fn test_interleaved_blocks_and_statements_same_keyword() {
     // Edge case: Same keyword appears in block, outside block, and in another block
-    let checker = SafetyChecker::new();
+    // (the six ALTERs would also exceed LockHeavyMigrationCheck's limit)
+    let checker = SafetyChecker::with_config(diesel_guard::Config {
+        disable_checks: vec!["LockHeavyMigrationCheck".to_string()],
+        ..Default::default()
+    });
     let sql = r#"
 ALTER TABLE users DROP COLUMN a;
 
@@ -333,3 +337,284 @@ fn test_safety_assured_with_leading_whitespace() {
     let violations = checker.check_sql(sql).unwrap();
     assert_eq!(violations.len(), 0, "should handle leading whitespace");
 }
+
+#[test]
+fn test_scoped_safety_assured_block() {
+    let checker = SafetyChecker::new();
+    let sql = r#"
+-- safety-assured:start(DropColumnCheck)
+ALTER TABLE users DROP COLUMN email;
+CREATE INDEX users_name_idx ON users(name);
+-- safety-assured:end
+    "#;
+
+    // Only the named check is suppressed inside the block
+    let violations = checker.check_sql(sql).unwrap();
+    assert_eq!(violations.len(), 1);
+    assert_eq!(violations[0].check, "AddIndexCheck");
+}
+
+#[test]
+fn test_scoped_block_still_reports_merged_findings_of_other_checks() {
+    let checker = SafetyChecker::new();
+    let sql = r#"
+-- safety-assured:start(AddUniqueConstraintCheck)
+ALTER TABLE users ADD UNIQUE (email);
+-- safety-assured:end
+    "#;
+
+    // The unnamed constraint finding is merged into the unique constraint one, but
+    // the block doesn't name its check
+    let violations = checker.check_sql(sql).unwrap();
+    assert_eq!(violations.len(), 1);
+    assert_eq!(violations[0].check, "UnnamedConstraintCheck");
+    assert_eq!(violations[0].line, Some(3));
+
+    let sql = r#"
+-- safety-assured:start(AddUniqueConstraintCheck, UnnamedConstraintCheck)
+ALTER TABLE users ADD UNIQUE (email);
+-- safety-assured:end
+    "#;
+    assert!(checker.check_sql(sql).unwrap().is_empty());
+}
+
+#[test]
+fn test_scoped_block_without_named_violations_is_stale() {
+    let checker = SafetyChecker::new();
+    let sql = r#"
+-- safety-assured:start(RenameColumnCheck)
+ALTER TABLE users DROP COLUMN email;
+-- safety-assured:end
+    "#;
+
+    let violations = checker.check_sql(sql).unwrap();
+    let checks: Vec<&str> = violations.iter().map(|v| v.check.as_str()).collect();
+    assert_eq!(checks, vec!["DropColumnCheck", "StaleSafetyAssured"]);
+}
+
+#[test]
+fn test_scoped_block_with_unknown_check_is_an_error() {
+    let checker = SafetyChecker::new();
+    let sql = r#"
+-- safety-assured:start(DropColumnChecks)
+ALTER TABLE users DROP COLUMN email;
+-- safety-assured:end
+    "#;
+
+    let error = checker.check_sql(sql).unwrap_err().to_string();
+    assert!(error.contains("Unknown check 'DropColumnChecks'"));
+}
+
+#[test]
+fn test_collect_suppressions_from_directory() {
+    let temp_dir = TempDir::new().unwrap();
+    let migration_dir = temp_dir.path().join("2024_01_01_000000_cleanup");
+    fs::create_dir(&migration_dir).unwrap();
+
+    fs::write(
+        migration_dir.join("up.sql"),
+        r#"-- safety-assured:start
+-- Safe because: columns unused since v2.3
+ALTER TABLE users DROP COLUMN email;
+CREATE INDEX users_name_idx ON users(name);
+-- safety-assured:end
+
+-- safety-assured:start
+ALTER TABLE users ADD COLUMN bio TEXT;
+-- safety-assured:end
+
+-- safety-assured:start(AddIndexCheck)
+ALTER TABLE users DROP COLUMN name;
+CREATE INDEX users_bio_idx ON users(bio);
+-- safety-assured:end
+"#,
+    )
+    .unwrap();
+
+    let checker = SafetyChecker::new();
+    let dir_path = Utf8Path::from_path(temp_dir.path()).unwrap();
+    let suppressions = checker.collect_suppressions(dir_path).unwrap();
+
+    assert_eq!(suppressions.len(), 3);
+
+    assert_eq!(suppressions[0].start_line, 1);
+    assert_eq!(suppressions[0].end_line, 5);
+    assert_eq!(
+        suppressions[0].reason.as_deref(),
+        Some("Safe because: columns unused since v2.3")
+    );
+    assert_eq!(
+        suppressions[0].suppressed_checks,
+        vec!["AddIndexCheck", "DropColumnCheck"]
+    );
+    assert!(suppressions[0].age_days.is_some());
+
+    // Block around a safe statement suppresses nothing
+    assert_eq!(suppressions[1].reason, None);
+    assert!(suppressions[1].suppressed_checks.is_empty());
+
+    // Scoped block only suppresses the checks it names
+    assert_eq!(suppressions[0].checks, None);
+    assert_eq!(
+        suppressions[2].checks,
+        Some(vec!["AddIndexCheck".to_string()])
+    );
+    assert_eq!(suppressions[2].suppressed_checks, vec!["AddIndexCheck"]);
+}
+
+#[test]
+fn test_collect_suppressions_from_fixture() {
+    let checker = SafetyChecker::new();
+    let suppressions = checker
+        .collect_suppressions(Utf8Path::new(
+            "tests/fixtures/safety_assured_multiple/up.sql",
+        ))
+        .unwrap();
+
+    assert_eq!(suppressions.len(), 2);
+    assert_eq!(suppressions[0].suppressed_checks, vec!["DropColumnCheck"]);
+    assert_eq!(suppressions[1].suppressed_checks, vec!["AddIndexCheck"]);
+    // Fixture directory has no timestamp
+    assert_eq!(suppressions[0].age_days, None);
+}
+
+#[test]
+fn test_suppressed_violations_are_recorded() {
+    use diesel_guard::suppression::SuppressionMechanism;
+    use diesel_guard::Config;
+
+    let temp_dir = TempDir::new().unwrap();
+    let old_dir = temp_dir.path().join("2023_01_01_000000_old");
+    let new_dir = temp_dir.path().join("2024_06_01_000000_new");
+    fs::create_dir_all(&old_dir).unwrap();
+    fs::create_dir_all(&new_dir).unwrap();
+    fs::write(
+        old_dir.join("up.sql"),
+        "ALTER TABLE users DROP COLUMN legacy;",
+    )
+    .unwrap();
+    fs::write(
+        new_dir.join("up.sql"),
+        r#"-- safety-assured:start
+-- Column unused since v2.3
+ALTER TABLE users DROP COLUMN email;
+-- safety-assured:end
+"#,
+    )
+    .unwrap();
+
+    let config = Config {
+        start_after: Some("2024_01_01_000000".to_string()),
+        ..Default::default()
+    };
+    let checker = SafetyChecker::with_config(config).with_suppressed_tracking();
+    let results = checker
+        .check_directory(Utf8Path::from_path(temp_dir.path()).unwrap())
+        .unwrap();
+    assert!(results.is_empty());
+
+    let suppressed = checker.suppressed().unwrap();
+    assert_eq!(suppressed.len(), 2);
+
+    assert_eq!(suppressed[0].mechanism, SuppressionMechanism::StartAfter);
+    assert!(suppressed[0].file.contains("2023_01_01_000000_old"));
+    assert!(suppressed[0].violation.problem.contains("legacy"));
+
+    assert_eq!(suppressed[1].mechanism, SuppressionMechanism::SafetyAssured);
+    assert_eq!(
+        suppressed[1].reason.as_deref(),
+        Some("Column unused since v2.3")
+    );
+    assert_eq!(suppressed[1].violation.check, "DropColumnCheck");
+}
+
+#[test]
+fn test_suppressed_violations_not_tracked_by_default() {
+    let checker = SafetyChecker::new();
+    checker
+        .check_file(Utf8Path::new("tests/fixtures/safety_assured_drop/up.sql"))
+        .unwrap();
+    assert!(checker.suppressed().is_none());
+}
+
+#[test]
+fn test_stale_safety_assured_block_is_reported() {
+    use diesel_guard::Severity;
+
+    let checker = SafetyChecker::new();
+    let sql = r#"
+-- safety-assured:start
+ALTER TABLE users ADD COLUMN bio TEXT;
+-- safety-assured:end
+    "#;
+
+    let violations = checker.check_sql(sql).unwrap();
+    assert_eq!(violations.len(), 1);
+    assert_eq!(violations[0].check, "StaleSafetyAssured");
+    assert_eq!(violations[0].severity, Severity::Warning);
+    assert!(violations[0].problem.contains("lines 2-4"));
+    assert_eq!(violations[0].line, Some(2));
+    assert_eq!(violations[0].column, Some(1));
+}
+
+#[test]
+fn test_stale_safety_assured_severity_is_configurable() {
+    use diesel_guard::{Config, Severity};
+
+    let sql = r#"
+-- safety-assured:start
+ALTER TABLE users ADD COLUMN bio TEXT;
+-- safety-assured:end
+    "#;
+
+    let config = Config {
+        severity: [("StaleSafetyAssured".to_string(), Severity::Error)].into(),
+        ..Default::default()
+    };
+    let violations = SafetyChecker::with_config(config).check_sql(sql).unwrap();
+    assert_eq!(violations.len(), 1);
+    assert_eq!(violations[0].severity, Severity::Error);
+}
+
+#[test]
+fn test_block_becomes_stale_when_check_is_disabled() {
+    use diesel_guard::Config;
+
+    let sql = r#"
+-- safety-assured:start
+ALTER TABLE users DROP COLUMN email;
+-- safety-assured:end
+    "#;
+
+    let config = Config {
+        disable_checks: vec!["DropColumnCheck".to_string()],
+        ..Default::default()
+    };
+    let violations = SafetyChecker::with_config(config).check_sql(sql).unwrap();
+    assert_eq!(violations.len(), 1);
+    assert_eq!(violations[0].check, "StaleSafetyAssured");
+
+    let config = Config {
+        disable_checks: vec![
+            "DropColumnCheck".to_string(),
+            "StaleSafetyAssured".to_string(),
+        ],
+        ..Default::default()
+    };
+    let violations = SafetyChecker::with_config(config).check_sql(sql).unwrap();
+    assert!(violations.is_empty());
+}
+
+#[test]
+fn test_safety_assured_covers_unparseable_statements() {
+    let checker = SafetyChecker::new();
+    let sql = r#"
+-- safety-assured:start
+-- Safe because: runs in the maintenance window
+CLUSTER users USING users_pkey;
+-- safety-assured:end
+    "#;
+
+    let violations = checker.check_sql(sql).unwrap();
+    assert!(violations.is_empty());
+}